@@ -12,11 +12,11 @@ use crate::{
 };
 use anyhow::{bail, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, BcsBlock, GasEstimation, LedgerInfo, ResourceGroup,
+    AptosErrorCode, AsConverter, BcsBlock, EventCursor, GasEstimation, LedgerInfo, ResourceGroup,
     TransactionOnChainData,
 };
 use aptos_config::config::{NodeConfig, RoleType};
-use aptos_crypto::HashValue;
+use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_gas::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_logger::error;
 use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
@@ -39,7 +39,7 @@ use aptos_types::{
     state_store::{
         state_key::{StateKey, StateKeyInner},
         state_key_prefix::StateKeyPrefix,
-        state_value::StateValue,
+        state_value::{StateValue, StateValueListWithProof},
     },
     transaction::{SignedTransaction, TransactionWithProof, Version},
 };
@@ -48,7 +48,10 @@ use aptos_vm::{
     move_vm_ext::MoveResolverExt,
 };
 use futures::{channel::oneshot, SinkExt};
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -281,6 +284,38 @@ impl Context {
         Ok(kvs)
     }
 
+    /// Returns the values of `state_keys` at the latest state snapshot, with a multi-proof
+    /// anchored to the latest signed ledger info. The values are ordered by state key hash.
+    pub fn get_state_values_with_proof(
+        &self,
+        mut state_keys: Vec<StateKey>,
+    ) -> Result<StateValueListWithProof> {
+        let ledger_info_with_signatures = self.db.get_latest_ledger_info()?;
+        let ledger_version = ledger_info_with_signatures.ledger_info().version();
+        let (version, _root_hash) = self
+            .db
+            .get_state_snapshot_before(ledger_version + 1)?
+            .ok_or_else(|| format_err!("No state snapshot at or before {}.", ledger_version))?;
+        let transaction_info_with_proof = self
+            .db
+            .get_transaction_by_version(version, ledger_version, false)?
+            .proof;
+
+        state_keys.sort_by_cached_key(|state_key| state_key.hash());
+        state_keys.dedup();
+        let (values, proof) = self
+            .db
+            .get_state_values_with_multi_proof_by_version(&state_keys, version)?;
+
+        Ok(StateValueListWithProof {
+            ledger_info_with_signatures,
+            version,
+            transaction_info_with_proof,
+            state_values: state_keys.into_iter().zip(values).collect(),
+            proof,
+        })
+    }
+
     pub fn get_resources_by_pagination(
         &self,
         address: AccountAddress,
@@ -710,6 +745,28 @@ impl Context {
             .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
     }
 
+    pub fn get_transactions_by_entry_function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        self.db
+            .get_transactions_by_entry_function(
+                module,
+                function,
+                start_version,
+                limit as u64,
+                true,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|t| self.convert_into_transaction_on_chain_data(t))
+            .collect()
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
@@ -796,6 +853,27 @@ impl Context {
         }
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start: EventCursor,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        self.db.get_events_by_type(
+            type_tag,
+            start.version,
+            start.event_index,
+            limit,
+            ledger_version,
+        )
+    }
+
+    /// Returns whether the storage maintains the event type and entry function indexes
+    pub fn secondary_indexes_enabled(&self) -> bool {
+        self.db.secondary_indexes_enabled()
+    }
+
     pub fn estimate_gas_price<E: InternalError>(
         &self,
         ledger_info: &LedgerInfo,
@@ -9,17 +9,18 @@ use crate::{
     failpoint::fail_point_poem,
     page::Page,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        api_disabled, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, InternalError,
     },
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
-    MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
+    verify_field_identifier, Address, AptosErrorCode, AsConverter, EventCursor, IdentifierWrapper,
+    LedgerInfo, MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::EventWithVersion, event::EventKey};
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::{
     param::{Path, Query},
     OpenApi,
@@ -140,6 +141,58 @@ impl EventsApi {
         let key = account.find_event_key(event_handle.0, field_name.0.into())?;
         self.list(account.latest_ledger_info, accept_type, page, key)
     }
+
+    /// Get events by event type
+    ///
+    /// This API returns the events of the given Move type, across all accounts,
+    /// in the order they were emitted. It requires the node to maintain the
+    /// optional event type index, otherwise a 403 will be returned.
+    ///
+    /// If there are more events, the position of the next one is returned in
+    /// the X-Aptos-Cursor header, to be passed as `start` for the next page.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_event_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_event_type(
+        &self,
+        accept_type: AcceptType,
+        /// Move type of the events e.g. `0x1::coin::DepositEvent`
+        event_type: Path<MoveStructTag>,
+        /// Position to start the list of events at, as a ledger version or as the
+        /// cursor returned in the X-Aptos-Cursor header of the previous page
+        ///
+        /// If not provided, defaults to version 0
+        start: Query<Option<EventCursor>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        event_type
+            .0
+            .verify(0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_event_type")?;
+        self.context
+            .check_api_output_enabled("Get events by event type", &accept_type)?;
+        if !self.context.secondary_indexes_enabled() {
+            return Err(api_disabled("Get events by event type"));
+        }
+        let page = Page::new(None, limit.0, self.context.max_events_page_size());
+        let type_tag = StructTag::try_from(event_type.0)
+            .map(|struct_tag| TypeTag::Struct(Box::new(struct_tag)))
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        self.list_by_type(accept_type, start.0.unwrap_or_default(), page, type_tag)
+    }
 }
 
 impl EventsApi {
@@ -169,6 +222,51 @@ impl EventsApi {
                 )
             })?;
 
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// List events of a Move type, paging by (version, event index) cursor
+    fn list_by_type(
+        &self,
+        accept_type: AcceptType,
+        start: EventCursor,
+        page: Page,
+        type_tag: TypeTag,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let limit = page.limit(&latest_ledger_info)? as u64;
+        // Fetch one more event than requested, to know where the next page starts
+        let mut events = self
+            .context
+            .get_events_by_type(&type_tag, start, limit + 1, latest_ledger_info.version())
+            .context(format!("Failed to find events by type {}", type_tag))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+        let next_cursor = if events.len() as u64 > limit {
+            events.pop().map(|(event_index, event)| EventCursor {
+                version: event.transaction_version,
+                event_index,
+            })
+        } else {
+            None
+        };
+        let events = events.into_iter().map(|(_, event)| event).collect();
+
+        self.render(latest_ledger_info, accept_type, events)
+            .map(|response| response.with_event_cursor(next_cursor))
+    }
+
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let resolver = self.context.move_resolver_poem(&latest_ledger_info)?;
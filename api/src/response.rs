@@ -471,6 +471,17 @@ macro_rules! generate_success_response {
                 }
                 self
             }
+
+            pub fn with_event_cursor(mut self, new_cursor: Option<aptos_api_types::EventCursor>) -> Self {
+                match self {
+                    $(
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut cursor) => {
+                        *cursor = new_cursor.map(|c| c.to_string());
+                    }
+                    )*
+                }
+                self
+            }
         }
         }
     };
@@ -6,16 +6,16 @@ use crate::{
     failpoint::fail_point_poem,
     response::{
         api_forbidden, build_not_found, module_not_found, resource_not_found, table_item_not_found,
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        BadRequestError, BasicError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResult, BasicResultWith404, InternalError,
     },
     ApiTags, Context,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper,
-    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawTableItemRequest,
-    TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    verify_module_identifier, Address, AptosErrorCode, AsConverter, HexEncodedBytes,
+    IdentifierWrapper, MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue,
+    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use aptos_state_view::TStateView;
 use aptos_types::{
@@ -84,6 +84,51 @@ impl StateApi {
         )
     }
 
+    /// Get account resources with proof
+    ///
+    /// Retrieves a set of resources from a given account, along with a single proof for all of
+    /// them and the latest signed ledger info the proof is anchored to. The resources are read
+    /// at the latest state snapshot of the node, which can be slightly behind the latest ledger
+    /// version. Resources that don't exist come with a proof of their absence.
+    ///
+    /// Only BCS is supported as an AcceptType. The response is a BCS-encoded
+    /// `StateValueListWithProof`.
+    #[oai(
+        path = "/accounts/:address/resources_with_proof",
+        method = "post",
+        operation_id = "get_account_resources_with_proof",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_resources_with_proof(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Names of structs to retrieve e.g. `0x1::account::Account`
+        resource_types: Json<Vec<MoveStructTag>>,
+    ) -> BasicResult<HexEncodedBytes> {
+        for resource_type in resource_types.0.iter() {
+            resource_type
+                .verify(0)
+                .context("'resource_types' invalid")
+                .map_err(|err| {
+                    BasicError::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+                })?;
+        }
+        fail_point_poem("endpoint_get_account_resources_with_proof")?;
+
+        if AcceptType::Json == accept_type {
+            return Err(api_forbidden(
+                "Get account resources with proof",
+                "Only BCS is supported as an AcceptType.",
+            ));
+        }
+        self.context
+            .check_api_output_enabled("Get account resources with proof", &accept_type)?;
+
+        self.resources_with_proof(address.0, resource_types.0)
+    }
+
     /// Get account module
     ///
     /// Retrieves an individual module from a given account and at a specific ledger version. If the
@@ -277,6 +322,57 @@ impl StateApi {
         }
     }
 
+    /// Read resources at the latest state snapshot, with a proof anchored to the latest signed
+    /// ledger info
+    fn resources_with_proof(
+        &self,
+        address: Address,
+        resource_types: Vec<MoveStructTag>,
+    ) -> BasicResult<HexEncodedBytes> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let max_account_resources_page_size = self.context.max_account_resources_page_size();
+        if resource_types.len() > max_account_resources_page_size as usize {
+            return Err(BasicError::bad_request_with_code(
+                format!(
+                    "Too many resource types requested: {}, at most {} are allowed",
+                    resource_types.len(),
+                    max_account_resources_page_size
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let state_keys = resource_types
+            .into_iter()
+            .map(|resource_type| {
+                let resource_type: StructTag = resource_type.try_into()?;
+                Ok(StateKey::access_path(AccessPath::resource_access_path(
+                    address.into(),
+                    resource_type,
+                )?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Failed to parse given resource types")
+            .map_err(|err| {
+                BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
+            })?;
+
+        let state_values_with_proof = self
+            .context
+            .get_state_values_with_proof(state_keys)
+            .context("Failed to get resources with proof from the DB")
+            .map_err(|err| {
+                BasicError::internal_with_code(err, AptosErrorCode::InternalError, &ledger_info)
+            })?;
+
+        BasicResponse::try_from_bcs((
+            state_values_with_proof,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+        ))
+    }
+
     /// Retrieve the module
     ///
     /// JSON: Parse ABI and bytecode
@@ -2,9 +2,12 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_secondary_indexes};
 use aptos_api_test_context::current_function_name;
+use aptos_api_types::EventCursor;
+use aptos_sdk::transaction_builder::aptos_stdlib;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use std::str::FromStr;
 
 static ACCOUNT_ADDRESS: &str = "0xa550c18";
 static CREATION_NUMBER: &str = "0";
@@ -155,3 +158,118 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_event_type_disabled() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get("/events/by_type/0x1::reconfiguration::NewEpochEvent")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_event_type() {
+    let mut context = new_test_context_with_secondary_indexes(current_function_name!());
+    let mut root_account = context.root_account().await;
+    let account = context.gen_account();
+    let txn = context.account_transfer(&mut root_account, &account, 100);
+    context.commit_block(&vec![txn]).await;
+
+    // Genesis emits the first epoch's event
+    let resp = context
+        .get("/events/by_type/0x1::reconfiguration::NewEpochEvent")
+        .await;
+    let events = resp.as_array().unwrap();
+    assert!(!events.is_empty());
+    for event in events {
+        assert_eq!(event["type"], "0x1::reconfiguration::NewEpochEvent");
+    }
+    assert_eq!(events[0]["version"], "0");
+
+    // The transfer withdraws from the root account, and deposits to the new account
+    let resp = context
+        .get("/events/by_type/0x1::coin::WithdrawEvent")
+        .await;
+    let events = resp.as_array().unwrap();
+    assert_eq!(
+        events.last().unwrap()["guid"]["account_address"],
+        root_account.address().to_hex_literal()
+    );
+    let last_version = events.last().unwrap()["version"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    // Pages start at the given version
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/0x1::coin::WithdrawEvent?start={}&limit=1",
+                last_version
+            )
+            .as_str(),
+        )
+        .await;
+    let events = resp.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["version"], last_version);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_event_type_pages_within_transaction() {
+    let mut context = new_test_context_with_secondary_indexes(current_function_name!());
+    let mut root_account = context.root_account().await;
+
+    // A batch transfer withdraws from the root account once per recipient, in one transaction
+    let recipients = (0..3).map(|_| context.gen_account().address()).collect();
+    let txn = root_account.sign_with_transaction_builder(context.transaction_factory().payload(
+        aptos_stdlib::aptos_account_batch_transfer(recipients, vec![100; 3]),
+    ));
+    context.commit_block(&vec![txn.clone()]).await;
+    let resp = context
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            txn.committed_hash().to_hex_literal()
+        ))
+        .await;
+    let version = resp["version"].as_str().unwrap().to_owned();
+
+    // The first page ends within the transaction, and returns a cursor to the next event
+    let req = warp::test::request().method("GET").path(&format!(
+        "/v1/events/by_type/0x1::coin::WithdrawEvent?start={}&limit=2",
+        version
+    ));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    let cursor = resp
+        .headers()
+        .get("X-Aptos-Cursor")
+        .expect("Cursor header was missing");
+    let cursor = EventCursor::from_str(cursor.to_str().unwrap()).unwrap();
+    assert_eq!(cursor.version.to_string(), version);
+    let events: Vec<serde_json::Value> = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(events.len(), 2);
+
+    // The second page starts at the cursor, with the last event of the transaction
+    let req = warp::test::request().method("GET").path(&format!(
+        "/v1/events/by_type/0x1::coin::WithdrawEvent?start={}&limit=2",
+        cursor
+    ));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.headers().contains_key("X-Aptos-Cursor"));
+    let last_events: Vec<serde_json::Value> = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(last_events.len(), 1);
+    assert_eq!(last_events[0]["version"], version);
+    assert!(!events.contains(&last_events[0]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_invalid_event_type() {
+    let context = new_test_context_with_secondary_indexes(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::reconfiguration")
+        .await;
+}
@@ -18,8 +18,18 @@ mod transaction_vector_test;
 mod transactions_test;
 mod view_function;
 
-use aptos_api_test_context::{new_test_context as super_new_test_context, TestContext};
+use aptos_api_test_context::{
+    new_test_context as super_new_test_context, new_test_context_with_rocksdb_configs, TestContext,
+};
+use aptos_config::config::RocksdbConfigs;
 
 fn new_test_context(test_name: String) -> TestContext {
     super_new_test_context(test_name, false)
 }
+
+fn new_test_context_with_secondary_indexes(test_name: String) -> TestContext {
+    new_test_context_with_rocksdb_configs(test_name, false, RocksdbConfigs {
+        enable_secondary_indexes: true,
+        ..Default::default()
+    })
+}
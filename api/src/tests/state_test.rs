@@ -3,8 +3,13 @@
 
 use super::new_test_context;
 use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_api_types::mime_types;
+use aptos_crypto::hash::CryptoHash;
 use aptos_sdk::{transaction_builder::aptos_stdlib::aptos_token_stdlib, types::LocalAccount};
 use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_config::AccountResource, state_store::state_value::StateValueListWithProof,
+};
 use move_core_types::account_address::AccountAddress;
 use move_package::BuildConfig;
 use serde::Serialize;
@@ -298,6 +303,90 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_with_proof() {
+    let context = new_test_context(current_function_name!());
+    let req = warp::test::request()
+        .method("POST")
+        .path(&format!(
+            "/v1{}",
+            get_account_resources_with_proof("0xA550C18")
+        ))
+        .header("Accept", mime_types::BCS)
+        .json(&json!(["0x1::account::Account", "0x1::guid::Generator"]));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    let list_with_proof: StateValueListWithProof = bcs::from_bytes(resp.body()).unwrap();
+
+    // The resources come back in the order of their state key hashes.
+    assert_eq!(list_with_proof.state_values.len(), 2);
+    let mut existing = list_with_proof
+        .state_values
+        .iter()
+        .filter_map(|(_, value)| value.as_ref());
+    let account: AccountResource = bcs::from_bytes(existing.next().unwrap().bytes()).unwrap();
+    assert_eq!(account.sequence_number(), 0);
+    assert!(existing.next().is_none());
+
+    // The genesis ledger info in tests isn't signed, so check everything but the signatures.
+    let ledger_info = list_with_proof.ledger_info_with_signatures.ledger_info();
+    list_with_proof
+        .transaction_info_with_proof
+        .verify(ledger_info, list_with_proof.version)
+        .unwrap();
+    let state_root_hash = list_with_proof
+        .transaction_info_with_proof
+        .transaction_info()
+        .state_checkpoint_hash()
+        .unwrap();
+    let elements: Vec<_> = list_with_proof
+        .state_values
+        .iter()
+        .map(|(key, value)| (key.hash(), value.as_ref().map(|v| v.hash())))
+        .collect();
+    list_with_proof
+        .proof
+        .verify_by_hash(state_root_hash, &elements)
+        .unwrap();
+
+    // Claiming that the missing resource exists doesn't verify.
+    let tampered: Vec<_> = elements
+        .iter()
+        .map(|(key, value_hash)| (*key, Some(value_hash.unwrap_or(state_root_hash))))
+        .collect();
+    assert!(list_with_proof
+        .proof
+        .verify_by_hash(state_root_hash, &tampered)
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_with_proof_json_forbidden() {
+    let mut context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .post(
+            &get_account_resources_with_proof("0xA550C18"),
+            json!(["0x1::account::Account"]),
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_with_proof_by_invalid_struct_tag() {
+    let context = new_test_context(current_function_name!());
+    let req = warp::test::request()
+        .method("POST")
+        .path(&format!(
+            "/v1{}",
+            get_account_resources_with_proof("0xA550C18")
+        ))
+        .header("Accept", mime_types::BCS)
+        .json(&json!(["0x1::GUID_Generator"]));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 400);
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -309,6 +398,10 @@ fn get_account_resource_with_version(address: &str, struct_tag: &str, version: u
     )
 }
 
+fn get_account_resources_with_proof(address: &str) -> String {
+    format!("/accounts/{}/resources_with_proof", address)
+}
+
 fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
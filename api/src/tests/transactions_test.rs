@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_secondary_indexes};
 use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
 use aptos_crypto::{
     ed25519::Ed25519PrivateKey,
@@ -1056,3 +1056,70 @@ fn gen_string(len: u64) -> String {
 fn build_path(path: &str) -> String {
     format!("/v1/transactions{}", path)
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_entry_function_disabled() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get("/transactions/by_entry_function/0x1::aptos_account::create_account")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_entry_function() {
+    let mut context = new_test_context_with_secondary_indexes(current_function_name!());
+    let mut root_account = context.root_account().await;
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let account3 = context.gen_account();
+    let txns = vec![
+        context.create_user_account_by(&mut root_account, &account1),
+        context.account_transfer(&mut root_account, &account2, 100),
+        context.create_user_account_by(&mut root_account, &account3),
+    ];
+    context.commit_block(&txns).await;
+
+    let resp = context
+        .get("/transactions/by_entry_function/0x1::aptos_account::create_account")
+        .await;
+    let txns = resp.as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    for txn in txns {
+        assert_eq!(
+            txn["payload"]["function"],
+            "0x1::aptos_account::create_account"
+        );
+    }
+    let first_version: u64 = txns[0]["version"].as_str().unwrap().parse().unwrap();
+    let second_version: u64 = txns[1]["version"].as_str().unwrap().parse().unwrap();
+    assert!(first_version < second_version);
+
+    // Pages start at the given version
+    let resp = context
+        .get(
+            format!(
+                "/transactions/by_entry_function/0x1::aptos_account::create_account?start={}",
+                first_version + 1
+            )
+            .as_str(),
+        )
+        .await;
+    let txns = resp.as_array().unwrap();
+    assert_eq!(txns.len(), 1);
+    assert_eq!(txns[0]["version"], second_version.to_string());
+
+    let resp = context
+        .get("/transactions/by_entry_function/0x1::aptos_account::transfer?limit=1")
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_invalid_entry_function() {
+    let context = new_test_context_with_secondary_indexes(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/transactions/by_entry_function/0x1::aptos_account")
+        .await;
+}
@@ -21,8 +21,8 @@ use crate::{
 use anyhow::{anyhow, Context as AnyhowContext};
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
+    AsConverter, EncodeSubmissionRequest, EntryFunctionId, GasEstimation, GasEstimationBcs,
+    HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
     Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
@@ -267,6 +267,55 @@ impl TransactionsApi {
         self.list_by_account(&accept_type, page, address.0)
     }
 
+    /// Get transactions by entry function
+    ///
+    /// Retrieves on-chain committed transactions calling the given entry
+    /// function, either directly or through a multisig account. It requires the
+    /// node to maintain the optional entry function index, otherwise a 403 will
+    /// be returned.
+    ///
+    /// If no start version is given, it will start at version 0.
+    #[oai(
+        path = "/transactions/by_entry_function/:function_id",
+        method = "get",
+        operation_id = "get_transactions_by_entry_function",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transactions_by_entry_function(
+        &self,
+        accept_type: AcceptType,
+        /// Entry function e.g. `0x1::aptos_account::transfer`
+        function_id: Path<EntryFunctionId>,
+        /// Ledger version to start list of transactions
+        ///
+        /// If not provided, defaults to 0
+        start: Query<Option<U64>>,
+        /// Max number of transactions to retrieve.
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        function_id
+            .0
+            .verify()
+            .context("'function_id' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_transactions_by_entry_function")?;
+        self.context
+            .check_api_output_enabled("Get transactions by entry function", &accept_type)?;
+        if !self.context.secondary_indexes_enabled() {
+            return Err(api_disabled("Get transactions by entry function"));
+        }
+        let page = Page::new(
+            start.0.map(|v| v.0),
+            limit.0,
+            self.context.max_transactions_page_size(),
+        );
+        self.list_by_entry_function(&accept_type, page, function_id.0)
+    }
+
     /// Submit transaction
     ///
     /// This endpoint accepts transaction submissions in two formats.
@@ -528,7 +577,7 @@ impl TransactionsApi {
     /// Encode submission
     ///
     /// This endpoint accepts an EncodeSubmissionRequest, which internally is a
-    /// UserTransactionRequestInner (and optionally secondary signers) encoded
+    /// UserTransactionRequestInner (and optionally secondary signers and a fee payer) encoded
     /// as JSON, validates the request format, and then returns that request
     /// encoded in BCS. The client can then use this to create a transaction
     /// signature to be used in a SubmitTransactionRequest, which it then
@@ -826,6 +875,48 @@ impl TransactionsApi {
         }
     }
 
+    /// List all transactions calling an entry function, paging by ledger version
+    fn list_by_entry_function(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        function_id: EntryFunctionId,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let data = self
+            .context
+            .get_transactions_by_entry_function(
+                &function_id.module.clone().into(),
+                function_id.name.as_ident_str(),
+                page.start_option().unwrap_or(0),
+                page.limit(&latest_ledger_info)?,
+                latest_ledger_info.version(),
+            )
+            .context(format!(
+                "Failed to read transactions by entry function {}",
+                function_id
+            ))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                self.context
+                    .render_transactions_non_sequential(&latest_ledger_info, data)?,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((data, &latest_ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+
     /// Parses a single signed transaction
     fn get_signed_transaction(
         &self,
@@ -1267,8 +1358,23 @@ impl TransactionsApi {
                 BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
             })?;
 
-        let raw_message = match request.secondary_signers {
-            Some(secondary_signer_addresses) => signing_message(
+        let raw_message = match (request.secondary_signers, request.fee_payer) {
+            (secondary_signer_addresses, Some(fee_payer)) => signing_message(
+                &RawTransactionWithData::new_multi_agent_with_fee_payer(
+                    raw_txn,
+                    secondary_signer_addresses
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| v.into())
+                        .collect(),
+                    fee_payer.into(),
+                ),
+            )
+            .context("Invalid transaction to generate signing message")
+            .map_err(|err| {
+                BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
+            })?,
+            (Some(secondary_signer_addresses), None) => signing_message(
                 &RawTransactionWithData::new_multi_agent(
                     raw_txn,
                     secondary_signer_addresses
@@ -1281,7 +1387,7 @@ impl TransactionsApi {
             .map_err(|err| {
                 BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
             })?,
-            None => raw_txn
+            (None, None) => raw_txn
                 .signing_message()
                 .context("Invalid transaction to generate signing message")
                 .map_err(|err| {
@@ -89,6 +89,16 @@ impl ApiSpecificConfig {
 }
 
 pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestContext {
+    new_test_context_with_rocksdb_configs(test_name, use_db_with_indexer, RocksdbConfigs::default())
+}
+
+/// Like [`new_test_context`], with the given RocksDB configs (e.g., to enable the secondary
+/// indexes). They are ignored if `use_db_with_indexer` is set.
+pub fn new_test_context_with_rocksdb_configs(
+    test_name: String,
+    use_db_with_indexer: bool,
+    rocksdb_configs: RocksdbConfigs,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -115,7 +125,7 @@ pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestCon
                 &tmp_dir,
                 false,                       /* readonly */
                 NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
-                RocksdbConfigs::default(),
+                rocksdb_configs,
                 false, /* indexer */
                 BUFFERED_STATE_TARGET_ITEMS,
                 DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
@@ -20,8 +20,8 @@
 
 use crate::{
     move_types::{MoveAbility, MoveStructValue},
-    Address, EntryFunctionId, HashValue, HexEncodedBytes, IdentifierWrapper, MoveModuleId,
-    MoveStructTag, MoveType, StateKeyWrapper, U128, U256, U64,
+    Address, EntryFunctionId, EventCursor, HashValue, HexEncodedBytes, IdentifierWrapper,
+    MoveModuleId, MoveStructTag, MoveType, StateKeyWrapper, U128, U256, U64,
 };
 use aptos_openapi::{impl_poem_parameter, impl_poem_type};
 use indoc::indoc;
@@ -242,6 +242,19 @@ impl_poem_type!(
     )
 );
 
+impl_poem_type!(
+    EventCursor,
+    "string",
+    (
+        example = Some(serde_json::Value::String("32425224034:2".to_string())),
+        description = Some(indoc! {"
+          A position in the events of a type, as `<version>:<event_index>`, where the event
+          index is the index of the event in the transaction's events. A plain `<version>`
+          starts at the first event of that version. This is used for cursor based pagination.
+        "})
+    )
+);
+
 impl_poem_type!(
     U64,
     "string",
@@ -293,6 +306,8 @@ impl_poem_type!(
 
 impl_poem_parameter!(
     Address,
+    EntryFunctionId,
+    EventCursor,
     HashValue,
     IdentifierWrapper,
     HexEncodedBytes,
@@ -11,6 +11,14 @@ use anyhow::{bail, Context as AnyhowContext};
 use aptos_crypto::{
     ed25519::{self, Ed25519PublicKey, ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH},
     multi_ed25519::{self, MultiEd25519PublicKey, BITMAP_NUM_OF_BYTES, MAX_NUM_OF_KEYS},
+    secp256k1_ecdsa::{
+        self, Secp256k1EcdsaPublicKey, SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPublicKey, SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -18,6 +26,7 @@ use aptos_types::{
     contract_event::{ContractEvent, EventWithVersion},
     transaction::{
         authenticator::{AccountAuthenticator, TransactionAuthenticator, MAX_NUM_OF_SIGS},
+        webauthn::PartialAuthenticatorAssertionResponse,
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
     },
 };
@@ -469,6 +478,9 @@ pub struct EncodeSubmissionRequest {
     /// Secondary signer accounts of the request for Multi-agent
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secondary_signers: Option<Vec<Address>>,
+    /// Account paying for the gas of the request, if not the sender
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<Address>,
 }
 
 impl VerifyInput for EncodeSubmissionRequest {
@@ -848,6 +860,9 @@ pub enum TransactionSignature {
     Ed25519Signature(Ed25519Signature),
     MultiEd25519Signature(MultiEd25519Signature),
     MultiAgentSignature(MultiAgentSignature),
+    FeePayerSignature(FeePayerSignature),
+    Secp256k1EcdsaSignature(Secp256k1EcdsaSignature),
+    WebAuthnSignature(WebAuthnSignature),
 }
 
 impl VerifyInput for TransactionSignature {
@@ -856,6 +871,9 @@ impl VerifyInput for TransactionSignature {
             TransactionSignature::Ed25519Signature(inner) => inner.verify(),
             TransactionSignature::MultiEd25519Signature(inner) => inner.verify(),
             TransactionSignature::MultiAgentSignature(inner) => inner.verify(),
+            TransactionSignature::FeePayerSignature(inner) => inner.verify(),
+            TransactionSignature::Secp256k1EcdsaSignature(inner) => inner.verify(),
+            TransactionSignature::WebAuthnSignature(inner) => inner.verify(),
         }
     }
 }
@@ -868,6 +886,9 @@ impl TryFrom<TransactionSignature> for TransactionAuthenticator {
             TransactionSignature::Ed25519Signature(sig) => sig.try_into()?,
             TransactionSignature::MultiEd25519Signature(sig) => sig.try_into()?,
             TransactionSignature::MultiAgentSignature(sig) => sig.try_into()?,
+            TransactionSignature::FeePayerSignature(sig) => sig.try_into()?,
+            TransactionSignature::Secp256k1EcdsaSignature(sig) => sig.try_into()?,
+            TransactionSignature::WebAuthnSignature(sig) => sig.try_into()?,
         })
     }
 }
@@ -1064,18 +1085,173 @@ impl TryFrom<MultiEd25519Signature> for AccountAuthenticator {
     }
 }
 
+/// A single secp256k1 ECDSA signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct Secp256k1EcdsaSignature {
+    /// The public key, in the uncompressed SEC1 format
+    pub public_key: HexEncodedBytes,
+    pub signature: HexEncodedBytes,
+}
+
+impl VerifyInput for Secp256k1EcdsaSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        let public_key_len = self.public_key.inner().len();
+        let signature_len = self.signature.inner().len();
+        if public_key_len != SECP256K1_ECDSA_PUBLIC_KEY_LENGTH {
+            bail!(
+                "Secp256k1 ECDSA signature's public key is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256K1_ECDSA_PUBLIC_KEY_LENGTH, public_key_len
+            )
+        } else if signature_len != SECP256K1_ECDSA_SIGNATURE_LENGTH {
+            bail!(
+                "Secp256k1 ECDSA signature length is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256K1_ECDSA_SIGNATURE_LENGTH, signature_len
+            )
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl TryFrom<Secp256k1EcdsaSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Secp256k1EcdsaSignature) -> Result<Self, Self::Error> {
+        let Secp256k1EcdsaSignature {
+            public_key,
+            signature,
+        } = value;
+        Ok(TransactionAuthenticator::secp256k1_ecdsa(
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256k1EcdsaPublicKey")?,
+            signature
+                .inner()
+                .try_into()
+                .context("Failed to parse given signature as a Secp256k1EcdsaSignature")?,
+        ))
+    }
+}
+
+impl TryFrom<Secp256k1EcdsaSignature> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Secp256k1EcdsaSignature) -> Result<Self, Self::Error> {
+        let Secp256k1EcdsaSignature {
+            public_key,
+            signature,
+        } = value;
+        Ok(AccountAuthenticator::secp256k1_ecdsa(
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256k1EcdsaPublicKey")?,
+            signature
+                .inner()
+                .try_into()
+                .context("Failed to parse given signature as a Secp256k1EcdsaSignature")?,
+        ))
+    }
+}
+
+/// A single WebAuthn assertion, signed by a secp256r1 ECDSA passkey
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WebAuthnSignature {
+    /// The public key of the passkey, in the uncompressed SEC1 format
+    pub public_key: HexEncodedBytes,
+    /// The signature, with its s-component in the lower half of the curve order
+    pub signature: HexEncodedBytes,
+    /// The `authenticatorData` of the assertion
+    pub authenticator_data: HexEncodedBytes,
+    /// The `clientDataJSON` of the assertion, whose challenge is the signing message
+    pub client_data_json: HexEncodedBytes,
+}
+
+impl VerifyInput for WebAuthnSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        let public_key_len = self.public_key.inner().len();
+        let signature_len = self.signature.inner().len();
+        if public_key_len != SECP256R1_ECDSA_PUBLIC_KEY_LENGTH {
+            bail!(
+                "WebAuthn signature's public key is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256R1_ECDSA_PUBLIC_KEY_LENGTH, public_key_len
+            )
+        } else if signature_len != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            bail!(
+                "WebAuthn signature length is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256R1_ECDSA_SIGNATURE_LENGTH, signature_len
+            )
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl WebAuthnSignature {
+    fn try_into_parts(
+        self,
+    ) -> anyhow::Result<(
+        Secp256r1EcdsaPublicKey,
+        PartialAuthenticatorAssertionResponse,
+    )> {
+        let WebAuthnSignature {
+            public_key,
+            signature,
+            authenticator_data,
+            client_data_json,
+        } = self;
+        Ok((
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256r1EcdsaPublicKey")?,
+            PartialAuthenticatorAssertionResponse::new(
+                signature
+                    .inner()
+                    .try_into()
+                    .context("Failed to parse given signature as a Secp256r1EcdsaSignature")?,
+                authenticator_data.into(),
+                client_data_json.into(),
+            ),
+        ))
+    }
+}
+
+impl TryFrom<WebAuthnSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WebAuthnSignature) -> Result<Self, Self::Error> {
+        let (public_key, assertion) = value.try_into_parts()?;
+        Ok(TransactionAuthenticator::webauthn(public_key, assertion))
+    }
+}
+
+impl TryFrom<WebAuthnSignature> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WebAuthnSignature) -> Result<Self, Self::Error> {
+        let (public_key, assertion) = value.try_into_parts()?;
+        Ok(AccountAuthenticator::webauthn(public_key, assertion))
+    }
+}
+
 /// Account signature scheme
 ///
-/// The account signature scheme allows you to have two types of accounts:
+/// The account signature scheme allows you to have four types of accounts:
 ///
 ///   1. A single Ed25519 key account, one private key
 ///   2. A k-of-n multi-Ed25519 key account, multiple private keys, such that k-of-n must sign a transaction.
+///   3. A single secp256k1 ECDSA key account, one private key
+///   4. A single WebAuthn passkey account, one secp256r1 ECDSA private key held by an authenticator
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
 pub enum AccountSignature {
     Ed25519Signature(Ed25519Signature),
     MultiEd25519Signature(MultiEd25519Signature),
+    Secp256k1EcdsaSignature(Secp256k1EcdsaSignature),
+    WebAuthnSignature(WebAuthnSignature),
 }
 
 impl VerifyInput for AccountSignature {
@@ -1083,6 +1259,8 @@ impl VerifyInput for AccountSignature {
         match self {
             AccountSignature::Ed25519Signature(inner) => inner.verify(),
             AccountSignature::MultiEd25519Signature(inner) => inner.verify(),
+            AccountSignature::Secp256k1EcdsaSignature(inner) => inner.verify(),
+            AccountSignature::WebAuthnSignature(inner) => inner.verify(),
         }
     }
 }
@@ -1094,6 +1272,8 @@ impl TryFrom<AccountSignature> for AccountAuthenticator {
         Ok(match sig {
             AccountSignature::Ed25519Signature(s) => s.try_into()?,
             AccountSignature::MultiEd25519Signature(s) => s.try_into()?,
+            AccountSignature::Secp256k1EcdsaSignature(s) => s.try_into()?,
+            AccountSignature::WebAuthnSignature(s) => s.try_into()?,
         })
     }
 }
@@ -1152,6 +1332,64 @@ impl TryFrom<MultiAgentSignature> for TransactionAuthenticator {
     }
 }
 
+/// Fee payer signature for fee payer transactions
+///
+/// This allows you to have transactions whose gas is paid by an account other than the sender
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct FeePayerSignature {
+    pub sender: AccountSignature,
+    /// The other involved parties' addresses
+    pub secondary_signer_addresses: Vec<Address>,
+    /// The associated signatures, in the same order as the secondary addresses
+    pub secondary_signers: Vec<AccountSignature>,
+    /// The address of the account paying for the gas
+    pub fee_payer_address: Address,
+    /// The signature of the fee payer
+    pub fee_payer_signer: AccountSignature,
+}
+
+impl VerifyInput for FeePayerSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        self.sender.verify()?;
+
+        if self.secondary_signers.len() != self.secondary_signer_addresses.len() {
+            bail!("FeePayer secondary signatures don't match addresses length")
+        }
+
+        for signer in self.secondary_signers.iter() {
+            signer.verify()?;
+        }
+        self.fee_payer_signer.verify()
+    }
+}
+
+impl TryFrom<FeePayerSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: FeePayerSignature) -> Result<Self, Self::Error> {
+        let FeePayerSignature {
+            sender,
+            secondary_signer_addresses,
+            secondary_signers,
+            fee_payer_address,
+            fee_payer_signer,
+        } = value;
+        Ok(TransactionAuthenticator::fee_payer(
+            sender.try_into()?,
+            secondary_signer_addresses
+                .into_iter()
+                .map(|a| a.into())
+                .collect(),
+            secondary_signers
+                .into_iter()
+                .map(|s| s.try_into())
+                .collect::<anyhow::Result<_>>()?,
+            fee_payer_address.into(),
+            fee_payer_signer.try_into()?,
+        ))
+    }
+}
+
 impl From<(&Ed25519PublicKey, &ed25519::Ed25519Signature)> for Ed25519Signature {
     fn from((pk, sig): (&Ed25519PublicKey, &ed25519::Ed25519Signature)) -> Self {
         Self {
@@ -1161,6 +1399,46 @@ impl From<(&Ed25519PublicKey, &ed25519::Ed25519Signature)> for Ed25519Signature
     }
 }
 
+impl
+    From<(
+        &Secp256k1EcdsaPublicKey,
+        &secp256k1_ecdsa::Secp256k1EcdsaSignature,
+    )> for Secp256k1EcdsaSignature
+{
+    fn from(
+        (pk, sig): (
+            &Secp256k1EcdsaPublicKey,
+            &secp256k1_ecdsa::Secp256k1EcdsaSignature,
+        ),
+    ) -> Self {
+        Self {
+            public_key: pk.to_bytes().to_vec().into(),
+            signature: sig.to_bytes().to_vec().into(),
+        }
+    }
+}
+
+impl
+    From<(
+        &Secp256r1EcdsaPublicKey,
+        &PartialAuthenticatorAssertionResponse,
+    )> for WebAuthnSignature
+{
+    fn from(
+        (pk, assertion): (
+            &Secp256r1EcdsaPublicKey,
+            &PartialAuthenticatorAssertionResponse,
+        ),
+    ) -> Self {
+        Self {
+            public_key: pk.to_bytes().to_vec().into(),
+            signature: assertion.signature().to_bytes().to_vec().into(),
+            authenticator_data: assertion.authenticator_data().to_vec().into(),
+            client_data_json: assertion.client_data_json().to_vec().into(),
+        }
+    }
+}
+
 impl
     From<(
         &MultiEd25519PublicKey,
@@ -1202,6 +1480,14 @@ impl From<&AccountAuthenticator> for AccountSignature {
                 public_key,
                 signature,
             } => Self::MultiEd25519Signature((public_key, signature).into()),
+            Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => Self::Secp256k1EcdsaSignature((public_key, signature).into()),
+            WebAuthn {
+                public_key,
+                signature,
+            } => Self::WebAuthnSignature((public_key, signature).into()),
         }
     }
 }
@@ -1247,6 +1533,30 @@ impl From<TransactionAuthenticator> for TransactionSignature {
             } => Self::MultiAgentSignature(
                 (sender, secondary_signer_addresses, secondary_signers).into(),
             ),
+            FeePayer {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                fee_payer_signer,
+            } => Self::FeePayerSignature(FeePayerSignature {
+                sender: sender.into(),
+                secondary_signer_addresses: secondary_signer_addresses
+                    .iter()
+                    .map(|address| (*address).into())
+                    .collect(),
+                secondary_signers: secondary_signers.iter().map(|s| s.into()).collect(),
+                fee_payer_address: (*fee_payer_address).into(),
+                fee_payer_signer: fee_payer_signer.into(),
+            }),
+            Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => Self::Secp256k1EcdsaSignature((public_key, signature).into()),
+            WebAuthn {
+                public_key,
+                signature,
+            } => Self::WebAuthnSignature((public_key, signature).into()),
         }
     }
 }
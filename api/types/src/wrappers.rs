@@ -15,7 +15,7 @@ use anyhow::{bail, Context};
 use aptos_types::{event::EventKey, state_store::state_key::StateKey};
 use move_core_types::identifier::{IdentStr, Identifier};
 use poem_openapi::Object;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::{convert::From, fmt, ops::Deref, str::FromStr};
 
 /// A wrapper of a Move identifier
@@ -140,6 +140,59 @@ impl FromStr for StateKeyWrapper {
     }
 }
 
+/// A position in the events of a Move type, given by the version of the transaction that
+/// emitted the event and the index of the event in that transaction's events. It is
+/// represented as `<version>:<index>`, or just `<version>` for the first event of a version.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EventCursor {
+    pub version: u64,
+    pub event_index: u64,
+}
+
+impl fmt::Display for EventCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.version, self.event_index)
+    }
+}
+
+impl FromStr for EventCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
+        let (version, event_index) = match s.split_once(':') {
+            Some((version, event_index)) => (
+                version,
+                event_index
+                    .parse()
+                    .context("Failed to parse event index of cursor")?,
+            ),
+            None => (s, 0),
+        };
+        Ok(EventCursor {
+            version: version
+                .parse()
+                .context("Failed to parse version of cursor")?,
+            event_index,
+        })
+    }
+}
+
+impl Serialize for EventCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventCursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <String>::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
 impl From<StateKey> for StateKeyWrapper {
     fn from(value: StateKey) -> StateKeyWrapper {
         Self(value)
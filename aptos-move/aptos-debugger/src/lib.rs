@@ -7,23 +7,26 @@ use aptos_gas::{
     LATEST_GAS_FEATURE_VERSION,
 };
 use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
-use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
+use aptos_resource_viewer::{AnnotatedAccountStateBlob, AnnotatedMoveValue, AptosValueAnnotator};
 use aptos_rest_client::Client;
 use aptos_state_view::TStateView;
 use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
+    contract_event::ContractEvent,
     on_chain_config::{Features, OnChainConfig, TimedFeatures},
+    state_store::state_key::StateKey,
     transaction::{
         ChangeSet, SignedTransaction, Transaction, TransactionInfo, TransactionOutput,
         TransactionPayload, Version,
     },
-    vm_status::VMStatus,
+    vm_status::{StatusCode, VMStatus},
 };
 use aptos_validator_interface::{
     AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
 };
 use aptos_vm::{
+    block_executor::{BlockAptosVM, ScheduleRecorder, ScheduleRecording},
     data_cache::StorageAdapter,
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
     AptosVM, VMExecutor,
@@ -61,6 +64,42 @@ impl AptosDebugger {
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    /// Executes the transactions in parallel, recording the schedule of the execution so that
+    /// it can be deterministically replayed by 'replay_transactions_at_version'.
+    pub fn record_transactions_at_version(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        concurrency_level: usize,
+    ) -> Result<(Vec<TransactionOutput>, ScheduleRecording<StateKey>)> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let recorder = Arc::new(ScheduleRecorder::new());
+        let outputs = BlockAptosVM::record_block(
+            txns,
+            &state_view,
+            concurrency_level,
+            None,
+            recorder.clone(),
+        )
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        Ok((outputs, recorder.take_recording()))
+    }
+
+    pub fn replay_transactions_at_version(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        recording: &ScheduleRecording<StateKey>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        BlockAptosVM::replay_block(txns, &state_view, None, recording).map_err(|err| match err {
+            VMStatus::Error(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR, Some(divergence)) => {
+                format_err!("Replay diverged from the recording: {}", divergence)
+            },
+            err => format_err!("Unexpected VM Error: {:?}", err),
+        })
+    }
+
     pub fn execute_transaction_at_version_with_gas_profiler(
         &self,
         version: Version,
@@ -188,6 +227,21 @@ impl AptosDebugger {
         )
     }
 
+    /// Decodes the data of the given events with the types published at `version`.
+    pub fn annotate_events_at_version(
+        &self,
+        version: Version,
+        events: &[ContractEvent],
+    ) -> Result<Vec<AnnotatedMoveValue>> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let remote_storage = StorageAdapter::new(&state_view);
+        let annotator = AptosValueAnnotator::new(&remote_storage);
+        events
+            .iter()
+            .map(|event| annotator.view_contract_event(event))
+            .collect()
+    }
+
     pub async fn annotate_key_accounts_at_version(
         &self,
         version: Version,
@@ -218,7 +272,12 @@ impl AptosDebugger {
             .await
     }
 
-    pub fn run_session_at_version<F>(&self, version: Version, f: F) -> Result<ChangeSet>
+    pub fn run_session_at_version<F>(
+        &self,
+        version: Version,
+        session_id: SessionId,
+        f: F,
+    ) -> Result<ChangeSet>
     where
         F: FnOnce(&mut SessionExt<StorageAdapter<DebuggerStateView>>) -> VMResult<()>,
     {
@@ -234,7 +293,7 @@ impl AptosDebugger {
             TimedFeatures::enable_all(),
         )
         .unwrap();
-        let mut session = move_vm.new_session(&state_view_storage, SessionId::Void);
+        let mut session = move_vm.new_session(&state_view_storage, session_id);
         f(&mut session).map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
         let change_set_ext = session
             .finish(
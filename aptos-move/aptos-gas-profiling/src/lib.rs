@@ -5,5 +5,5 @@ mod flamegraph;
 mod log;
 mod profiler;
 
-pub use log::{FrameName, TransactionGasLog};
+pub use log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog};
 pub use profiler::GasProfiler;
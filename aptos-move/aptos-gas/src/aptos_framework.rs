@@ -67,6 +67,78 @@ crate::natives::define_gas_parameters_for_natives!(GasParameters, "aptos_framewo
     [.algebra.ark_bls12_381_multi_pairing_base, { 8.. => "algebra.ark_bls12_381_multi_pairing_base" }, 8_998_649 * MUL],
     [.algebra.ark_bls12_381_multi_pairing_per_pair, { 8.. => "algebra.ark_bls12_381_multi_pairing_per_pair" }, 4_602_642 * MUL],
     [.algebra.ark_bls12_381_pairing, { 8.. => "algebra.ark_bls12_381_pairing" }, 14_832_220 * MUL],
+    [.algebra.ark_bn254_fq12_add, { 9.. => "algebra.ark_bn254_fq12_add" }, 1_436 * MUL],
+    [.algebra.ark_bn254_fq12_clone, { 9.. => "algebra.ark_bn254_fq12_clone" }, 531 * MUL],
+    [.algebra.ark_bn254_fq12_deser, { 9.. => "algebra.ark_bn254_fq12_deser" }, 5_735 * MUL],
+    [.algebra.ark_bn254_fq12_div, { 9.. => "algebra.ark_bn254_fq12_div" }, 104_740 * MUL],
+    [.algebra.ark_bn254_fq12_eq, { 9.. => "algebra.ark_bn254_fq12_eq" }, 1_045 * MUL],
+    [.algebra.ark_bn254_fq12_from_u64, { 9.. => "algebra.ark_bn254_fq12_from_u64" }, 764 * MUL],
+    [.algebra.ark_bn254_fq12_inv, { 9.. => "algebra.ark_bn254_fq12_inv" }, 75_742 * MUL],
+    [.algebra.ark_bn254_fq12_mul, { 9.. => "algebra.ark_bn254_fq12_mul" }, 30_973 * MUL],
+    [.algebra.ark_bn254_fq12_neg, { 9.. => "algebra.ark_bn254_fq12_neg" }, 997 * MUL],
+    [.algebra.ark_bn254_fq12_one, { 9.. => "algebra.ark_bn254_fq12_one" }, 11 * MUL],
+    [.algebra.ark_bn254_fq12_pow_u256, { 9.. => "algebra.ark_bn254_fq12_pow_u256" }, 12_047_680 * MUL],
+    [.algebra.ark_bn254_fq12_serialize, { 9.. => "algebra.ark_bn254_fq12_serialize" }, 5_077 * MUL],
+    [.algebra.ark_bn254_fq12_square, { 9.. => "algebra.ark_bn254_fq12_square" }, 20_876 * MUL],
+    [.algebra.ark_bn254_fq12_sub, { 9.. => "algebra.ark_bn254_fq12_sub" }, 1_357 * MUL],
+    [.algebra.ark_bn254_fq12_zero, { 9.. => "algebra.ark_bn254_fq12_zero" }, 55 * MUL],
+    [.algebra.ark_bn254_fq_add, { 9.. => "algebra.ark_bn254_fq_add" }, 59 * MUL],
+    [.algebra.ark_bn254_fq_clone, { 9.. => "algebra.ark_bn254_fq_clone" }, 20 * MUL],
+    [.algebra.ark_bn254_fq_deser, { 9.. => "algebra.ark_bn254_fq_deser" }, 466 * MUL],
+    [.algebra.ark_bn254_fq_div, { 9.. => "algebra.ark_bn254_fq_div" }, 25_685 * MUL],
+    [.algebra.ark_bn254_fq_eq, { 9.. => "algebra.ark_bn254_fq_eq" }, 49 * MUL],
+    [.algebra.ark_bn254_fq_from_u64, { 9.. => "algebra.ark_bn254_fq_from_u64" }, 378 * MUL],
+    [.algebra.ark_bn254_fq_inv, { 9.. => "algebra.ark_bn254_fq_inv" }, 24_786 * MUL],
+    [.algebra.ark_bn254_fq_mul, { 9.. => "algebra.ark_bn254_fq_mul" }, 304 * MUL],
+    [.algebra.ark_bn254_fq_neg, { 9.. => "algebra.ark_bn254_fq_neg" }, 55 * MUL],
+    [.algebra.ark_bn254_fq_one, { 9.. => "algebra.ark_bn254_fq_one" }, 11 * MUL],
+    [.algebra.ark_bn254_fq_pow_u256, { 9.. => "algebra.ark_bn254_fq_pow_u256" }, 100_322 * MUL],
+    [.algebra.ark_bn254_fq_serialize, { 9.. => "algebra.ark_bn254_fq_serialize" }, 1_055 * MUL],
+    [.algebra.ark_bn254_fq_square, { 9.. => "algebra.ark_bn254_fq_square" }, 227 * MUL],
+    [.algebra.ark_bn254_fq_sub, { 9.. => "algebra.ark_bn254_fq_sub" }, 69 * MUL],
+    [.algebra.ark_bn254_fq_zero, { 9.. => "algebra.ark_bn254_fq_zero" }, 11 * MUL],
+    [.algebra.ark_bn254_fr_add, { 9.. => "algebra.ark_bn254_fr_add" }, 71 * MUL],
+    [.algebra.ark_bn254_fr_deser, { 9.. => "algebra.ark_bn254_fr_deser" }, 541 * MUL],
+    [.algebra.ark_bn254_fr_div, { 9.. => "algebra.ark_bn254_fr_div" }, 25_809 * MUL],
+    [.algebra.ark_bn254_fr_eq, { 9.. => "algebra.ark_bn254_fr_eq" }, 56 * MUL],
+    [.algebra.ark_bn254_fr_from_u64, { 9.. => "algebra.ark_bn254_fr_from_u64" }, 386 * MUL],
+    [.algebra.ark_bn254_fr_inv, { 9.. => "algebra.ark_bn254_fr_inv" }, 27_113 * MUL],
+    [.algebra.ark_bn254_fr_mul, { 9.. => "algebra.ark_bn254_fr_mul" }, 305 * MUL],
+    [.algebra.ark_bn254_fr_neg, { 9.. => "algebra.ark_bn254_fr_neg" }, 63 * MUL],
+    [.algebra.ark_bn254_fr_one, { 9.. => "algebra.ark_bn254_fr_one" }, 11 * MUL],
+    [.algebra.ark_bn254_fr_serialize, { 9.. => "algebra.ark_bn254_fr_serialize" }, 990 * MUL],
+    [.algebra.ark_bn254_fr_square, { 9.. => "algebra.ark_bn254_fr_square" }, 268 * MUL],
+    [.algebra.ark_bn254_fr_sub, { 9.. => "algebra.ark_bn254_fr_sub" }, 77 * MUL],
+    [.algebra.ark_bn254_fr_zero, { 9.. => "algebra.ark_bn254_fr_zero" }, 11 * MUL],
+    [.algebra.ark_bn254_g1_affine_deser_comp, { 9.. => "algebra.ark_bn254_g1_affine_deser_comp" }, 1_261_625 * MUL],
+    [.algebra.ark_bn254_g1_affine_deser_uncomp, { 9.. => "algebra.ark_bn254_g1_affine_deser_uncomp" }, 1_144_577 * MUL],
+    [.algebra.ark_bn254_g1_affine_serialize_comp, { 9.. => "algebra.ark_bn254_g1_affine_serialize_comp" }, 1_542 * MUL],
+    [.algebra.ark_bn254_g1_affine_serialize_uncomp, { 9.. => "algebra.ark_bn254_g1_affine_serialize_uncomp" }, 2_025 * MUL],
+    [.algebra.ark_bn254_g1_proj_add, { 9.. => "algebra.ark_bn254_g1_proj_add" }, 4_932 * MUL],
+    [.algebra.ark_bn254_g1_proj_double, { 9.. => "algebra.ark_bn254_g1_proj_double" }, 2_248 * MUL],
+    [.algebra.ark_bn254_g1_proj_eq, { 9.. => "algebra.ark_bn254_g1_proj_eq" }, 2_455 * MUL],
+    [.algebra.ark_bn254_g1_proj_generator, { 9.. => "algebra.ark_bn254_g1_proj_generator" }, 11 * MUL],
+    [.algebra.ark_bn254_g1_proj_infinity, { 9.. => "algebra.ark_bn254_g1_proj_infinity" }, 11 * MUL],
+    [.algebra.ark_bn254_g1_proj_neg, { 9.. => "algebra.ark_bn254_g1_proj_neg" }, 11 * MUL],
+    [.algebra.ark_bn254_g1_proj_scalar_mul, { 9.. => "algebra.ark_bn254_g1_proj_scalar_mul" }, 1_467_696 * MUL],
+    [.algebra.ark_bn254_g1_proj_sub, { 9.. => "algebra.ark_bn254_g1_proj_sub" }, 5_553 * MUL],
+    [.algebra.ark_bn254_g1_proj_to_affine, { 9.. => "algebra.ark_bn254_g1_proj_to_affine" }, 30_646 * MUL],
+    [.algebra.ark_bn254_g2_affine_deser_comp, { 9.. => "algebra.ark_bn254_g2_affine_deser_comp" }, 2_568_950 * MUL],
+    [.algebra.ark_bn254_g2_affine_deser_uncomp, { 9.. => "algebra.ark_bn254_g2_affine_deser_uncomp" }, 2_552_331 * MUL],
+    [.algebra.ark_bn254_g2_affine_serialize_comp, { 9.. => "algebra.ark_bn254_g2_affine_serialize_comp" }, 2_362 * MUL],
+    [.algebra.ark_bn254_g2_affine_serialize_uncomp, { 9.. => "algebra.ark_bn254_g2_affine_serialize_uncomp" }, 1_728 * MUL],
+    [.algebra.ark_bn254_g2_proj_add, { 9.. => "algebra.ark_bn254_g2_proj_add" }, 15_881 * MUL],
+    [.algebra.ark_bn254_g2_proj_double, { 9.. => "algebra.ark_bn254_g2_proj_double" }, 7_499 * MUL],
+    [.algebra.ark_bn254_g2_proj_eq, { 9.. => "algebra.ark_bn254_g2_proj_eq" }, 5_068 * MUL],
+    [.algebra.ark_bn254_g2_proj_generator, { 9.. => "algebra.ark_bn254_g2_proj_generator" }, 11 * MUL],
+    [.algebra.ark_bn254_g2_proj_infinity, { 9.. => "algebra.ark_bn254_g2_proj_infinity" }, 11 * MUL],
+    [.algebra.ark_bn254_g2_proj_neg, { 9.. => "algebra.ark_bn254_g2_proj_neg" }, 11 * MUL],
+    [.algebra.ark_bn254_g2_proj_scalar_mul, { 9.. => "algebra.ark_bn254_g2_proj_scalar_mul" }, 3_040_440 * MUL],
+    [.algebra.ark_bn254_g2_proj_sub, { 9.. => "algebra.ark_bn254_g2_proj_sub" }, 17_852 * MUL],
+    [.algebra.ark_bn254_g2_proj_to_affine, { 9.. => "algebra.ark_bn254_g2_proj_to_affine" }, 35_635 * MUL],
+    [.algebra.ark_bn254_multi_pairing_base, { 9.. => "algebra.ark_bn254_multi_pairing_base" }, 9_430_691 * MUL],
+    [.algebra.ark_bn254_multi_pairing_per_pair, { 9.. => "algebra.ark_bn254_multi_pairing_per_pair" }, 2_756_114 * MUL],
+    [.algebra.ark_bn254_pairing, { 9.. => "algebra.ark_bn254_pairing" }, 11_348_312 * MUL],
     [.algebra.ark_h2c_bls12381g1_xmd_sha256_sswu_base, { 8.. => "algebra.ark_h2c_bls12381g1_xmd_sha256_sswu_base" }, 3_251_943 * MUL],
     [.algebra.ark_h2c_bls12381g1_xmd_sha256_sswu_per_msg_byte, { 8.. => "algebra.ark_h2c_bls12381g1_xmd_sha256_sswu_per_msg_byte" }, 48 * MUL],
     [.algebra.ark_h2c_bls12381g2_xmd_sha256_sswu_base, { 8.. => "algebra.ark_h2c_bls12381g2_xmd_sha256_sswu_base" }, 6_773_002 * MUL],
@@ -102,6 +174,10 @@ crate::natives::define_gas_parameters_for_natives!(GasParameters, "aptos_framewo
     [.secp256k1.base, "secp256k1.base", 150 * MUL],
     [.secp256k1.ecdsa_recover, "secp256k1.ecdsa_recover", 1_610_000 * MUL],
 
+    [.secp256r1.base, { 9.. => "secp256r1.base" }, 150 * MUL],
+    [.secp256r1.per_msg_byte_hashing, { 9.. => "secp256r1.per_msg_byte_hashing" }, 50 * MUL],
+    [.secp256r1.ecdsa_verify, { 9.. => "secp256r1.ecdsa_verify" }, 3_200_000 * MUL],
+
     [.ristretto255.basepoint_mul, "ristretto255.basepoint_mul", 128_000 * MUL],
     [.ristretto255.basepoint_double_mul, "ristretto255.basepoint_double_mul", 440_000 * MUL],
 
@@ -33,6 +33,9 @@ use move_vm_types::{
 use std::collections::BTreeMap;
 
 // Change log:
+// - V9
+//   - Added secp256r1 ECDSA signature verification.
+//   - Added BN254 operations.
 // - V8
 //   - Added BLS12-381 operations.
 // - V7
@@ -59,7 +62,7 @@ use std::collections::BTreeMap;
 //       global operations.
 // - V1
 //   - TBA
-pub const LATEST_GAS_FEATURE_VERSION: u64 = 8;
+pub const LATEST_GAS_FEATURE_VERSION: u64 = 9;
 
 pub(crate) const EXECUTION_GAS_MULTIPLIER: u64 = 20;
 
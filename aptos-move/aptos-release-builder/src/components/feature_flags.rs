@@ -35,6 +35,10 @@ pub enum FeatureFlag {
     PeriodicalRewardRateReduction,
     PartialGovernanceVoting,
     SignatureCheckerV2,
+    FeePayerEnabled,
+    Secp256k1EcdsaAuthenticator,
+    WebAuthnAuthenticator,
+    Bn254Structures,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -148,6 +152,12 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             },
             FeatureFlag::PartialGovernanceVoting => AptosFeatureFlag::PARTIAL_GOVERNANCE_VOTING,
             FeatureFlag::SignatureCheckerV2 => AptosFeatureFlag::SIGNATURE_CHECKER_V2,
+            FeatureFlag::FeePayerEnabled => AptosFeatureFlag::FEE_PAYER_ENABLED,
+            FeatureFlag::Secp256k1EcdsaAuthenticator => {
+                AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR
+            },
+            FeatureFlag::WebAuthnAuthenticator => AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR,
+            FeatureFlag::Bn254Structures => AptosFeatureFlag::BN254_STRUCTURES,
         }
     }
 }
@@ -186,6 +196,12 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             },
             AptosFeatureFlag::PARTIAL_GOVERNANCE_VOTING => FeatureFlag::PartialGovernanceVoting,
             AptosFeatureFlag::SIGNATURE_CHECKER_V2 => FeatureFlag::SignatureCheckerV2,
+            AptosFeatureFlag::FEE_PAYER_ENABLED => FeatureFlag::FeePayerEnabled,
+            AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR => {
+                FeatureFlag::Secp256k1EcdsaAuthenticator
+            },
+            AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR => FeatureFlag::WebAuthnAuthenticator,
+            AptosFeatureFlag::BN254_STRUCTURES => FeatureFlag::Bn254Structures,
         }
     }
 }
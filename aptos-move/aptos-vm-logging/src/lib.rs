@@ -16,7 +16,7 @@ use aptos_logger::{prelude::*, Level};
 use aptos_speculative_state_helper::{SpeculativeEvent, SpeculativeEvents};
 use arc_swap::ArcSwapOption;
 use once_cell::sync::Lazy;
-use std::sync::Arc;
+use std::{cell::Cell, sync::Arc};
 
 struct VMLogEntry {
     level: Level,
@@ -55,8 +55,36 @@ impl SpeculativeEvent for VMLogEntry {
 static BUFFERED_LOG_EVENTS: Lazy<ArcSwapOption<SpeculativeEvents<VMLogEntry>>> =
     Lazy::new(|| ArcSwapOption::from(None));
 
+thread_local! {
+    static DIRECT_LOGS: Cell<bool> = Cell::new(false);
+}
+
+fn direct_logs() -> bool {
+    DIRECT_LOGS.with(|direct_logs| direct_logs.get())
+}
+
+/// Runs `f` with the speculative logs of the current thread dispatched directly, leaving the
+/// speculative log storage of a concurrently executing block untouched, e.g. for re-executing
+/// a block in the background. Since the storage is not used, `f` must only execute transactions
+/// on the calling thread, and each of them only once.
+pub fn with_direct_logs<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DIRECT_LOGS.with(|direct_logs| direct_logs.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DIRECT_LOGS.with(|direct_logs| direct_logs.replace(true)));
+    f()
+}
+
 /// Initializes the storage of speculative logs for num_txns many transactions.
 pub fn init_speculative_logs(num_txns: usize) {
+    if direct_logs() {
+        return;
+    }
     BUFFERED_LOG_EVENTS.swap(Some(Arc::new(SpeculativeEvents::new(num_txns))));
 }
 
@@ -64,6 +92,10 @@ pub fn init_speculative_logs(num_txns: usize) {
 /// to speculative buffer. Logs directly and logs a separate (new error) if the speculative
 /// events storage is not initialized or appropriately sized.
 pub fn speculative_log(level: Level, context: &AdapterLogSchema, message: String) {
+    if direct_logs() {
+        VMLogEntry::new(level, context.clone(), message).dispatch();
+        return;
+    }
     let txn_idx = context.get_txn_idx();
     match &*BUFFERED_LOG_EVENTS.load() {
         Some(log_events) => {
@@ -84,6 +116,9 @@ pub fn speculative_log(level: Level, context: &AdapterLogSchema, message: String
 /// Flushes the currently stored logs, and swaps the speculative log / event storage with None.
 /// Must be called after block execution is complete (removes the storage from Arc).
 pub fn flush_speculative_logs() {
+    if direct_logs() {
+        return;
+    }
     match BUFFERED_LOG_EVENTS.swap(None) {
         Some(log_events_ptr) => match Arc::try_unwrap(log_events_ptr) {
             Ok(log_events) => log_events.flush(),
@@ -100,6 +135,9 @@ pub fn flush_speculative_logs() {
 /// Clear speculative logs recorded for a specific transction, useful when transaction
 /// execution fails validation and aborts - setting stage for the re-execution.
 pub fn clear_speculative_txn_logs(txn_idx: usize) {
+    if direct_logs() {
+        return;
+    }
     match &*BUFFERED_LOG_EVENTS.load() {
         Some(log_events) => {
             if let Err(e) = log_events.clear_txn_events(txn_idx) {
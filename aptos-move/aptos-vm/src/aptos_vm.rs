@@ -7,7 +7,7 @@ use crate::{
         discard_error_output, discard_error_vm_status, PreprocessedTransaction, VMAdapter,
     },
     aptos_vm_impl::{get_transaction_output, AptosVMImpl, AptosVMInternals},
-    block_executor::BlockAptosVM,
+    block_executor::{warm_vm_cache, BlockAptosVM},
     counters::*,
     data_cache::{AsMoveResolver, IntoMoveResolver, StorageAdapter},
     delta_state_view::DeltaStateView,
@@ -29,23 +29,27 @@ use aptos_gas::{
     StorageGasParameters,
 };
 use aptos_logger::{enabled, prelude::*, Level};
-use aptos_state_view::StateView;
+use aptos_state_view::{StateView, StateViewId};
 use aptos_types::{
     account_config,
     account_config::new_block_event_key,
     block_metadata::BlockMetadata,
-    on_chain_config::{new_epoch_event_key, FeatureFlag, TimedFeatureOverride},
+    on_chain_config::{
+        new_epoch_event_key, FeatureFlag, OnChainConfig, OnChainExecutionConfig,
+        TimedFeatureOverride,
+    },
     transaction::{
-        ChangeSet, EntryFunction, ExecutionError, ExecutionStatus, ModuleBundle, Multisig,
-        MultisigTransactionPayload, SignatureCheckedTransaction, SignedTransaction, Transaction,
-        TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        authenticator::Scheme, ChangeSet, EntryFunction, ExecutionError, ExecutionStatus,
+        ModuleBundle, Multisig, MultisigTransactionPayload, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{AbortLocation, DiscardedVMStatus, StatusCode, VMStatus},
     write_set::WriteSet,
 };
 use aptos_vm_logging::{
     init_speculative_logs, log_schema::AdapterLogSchema, speculative_error, speculative_log,
+    with_direct_logs,
 };
 use fail::fail_point;
 use move_binary_format::{
@@ -68,7 +72,7 @@ use move_vm_types::gas::UnmeteredGasMeter;
 use num_cpus;
 use once_cell::sync::OnceCell;
 use std::{
-    cmp::min,
+    cmp::{max, min},
     collections::{BTreeMap, BTreeSet},
     convert::{AsMut, AsRef},
     marker::Sync,
@@ -79,6 +83,7 @@ use std::{
 };
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
+static NUM_EXECUTION_SHARDS: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
@@ -135,6 +140,29 @@ impl AptosVM {
         }
     }
 
+    /// Sets the number of shards for the sharded block execution when invoked the first time.
+    /// The concurrency level is divided among the shards.
+    pub fn set_num_shards_once(num_shards: usize) {
+        let num_shards = max(num_shards, 1);
+        // Only the first call succeeds, due to OnceCell semantics.
+        NUM_EXECUTION_SHARDS.set(num_shards).ok();
+    }
+
+    /// Get the number of shards if already set, otherwise return default 1 (the block is
+    /// executed without sharding).
+    pub fn get_num_shards() -> usize {
+        match NUM_EXECUTION_SHARDS.get() {
+            Some(num_shards) => *num_shards,
+            None => 1,
+        }
+    }
+
+    /// Sets whether the VMs (with their loader caches) are reused across blocks when invoked the
+    /// first time. Disabled by default.
+    pub fn set_cross_block_vm_cache_once(enabled: bool) {
+        warm_vm_cache::set_enabled_once(enabled);
+    }
+
     /// Sets runtime config when invoked the first time.
     pub fn set_paranoid_type_checks(enable: bool) {
         // Only the first call succeeds, due to OnceCell semantics.
@@ -1445,7 +1473,11 @@ impl AptosVM {
 
             // Deprecated. Will be removed in the future.
             TransactionPayload::ModuleBundle(_module) => {
-                if MODULE_BUNDLE_DISALLOWED.load(Ordering::Relaxed) {
+                // The module prologue charges the gas to the sender, so fee payers are not
+                // supported.
+                if MODULE_BUNDLE_DISALLOWED.load(Ordering::Relaxed)
+                    || txn_data.fee_payer().is_some()
+                {
                     return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING, None));
                 }
                 self.0.check_gas(storage, txn_data, log_context)?;
@@ -1473,6 +1505,38 @@ impl VMExecutor for AptosVM {
             ))
         });
 
+        Self::execute_block_with_concurrency_level(
+            transactions,
+            state_view,
+            Self::get_concurrency_level(),
+        )
+    }
+
+    fn execute_block_sequential(
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        // The re-executed block may be older than the blocks the cached VMs were used for, and
+        // may run concurrently with the execution of the next block, which owns the speculative
+        // log storage.
+        warm_vm_cache::bypass(|| {
+            with_direct_logs(|| {
+                Self::execute_block_with_concurrency_level(transactions, state_view, 1)
+            })
+        })
+    }
+
+    fn invalidate_cross_block_cache() {
+        warm_vm_cache::invalidate();
+    }
+}
+
+impl AptosVM {
+    fn execute_block_with_concurrency_level(
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+        concurrency_level: usize,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
         info!(
             log_context,
@@ -1480,9 +1544,35 @@ impl VMExecutor for AptosVM {
             transactions.len()
         );
 
+        // The block gas limit is configured on-chain, so all validators observe the same limit.
+        // It only applies to new blocks, replaying committed transactions (e.g. in chunks by
+        // state sync) must not skip any of them.
+        let maybe_block_gas_limit = match state_view.id() {
+            StateViewId::BlockExecution { .. } => {
+                OnChainExecutionConfig::fetch_config(&StorageAdapter::new(state_view))
+                    .and_then(|config| config.block_gas_limit())
+            },
+            _ => None,
+        };
+
         let count = transactions.len();
-        let ret =
-            BlockAptosVM::execute_block(transactions, state_view, Self::get_concurrency_level());
+        let num_shards = Self::get_num_shards();
+        let ret = if num_shards > 1 {
+            BlockAptosVM::execute_block_sharded(
+                transactions,
+                state_view,
+                concurrency_level,
+                num_shards,
+                maybe_block_gas_limit,
+            )
+        } else {
+            BlockAptosVM::execute_block(
+                transactions,
+                state_view,
+                concurrency_level,
+                maybe_block_gas_limit,
+            )
+        };
         if ret.is_ok() {
             // Record the histogram count for transactions per block.
             BLOCK_TRANSACTION_COUNT.observe(count as f64);
@@ -1570,6 +1660,32 @@ impl VMAdapter for AptosVM {
             ));
         }
 
+        if !self
+            .0
+            .get_features()
+            .is_enabled(FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR)
+            && txn
+                .authenticator_ref()
+                .all_signers()
+                .iter()
+                .any(|signer| matches!(signer.scheme(), Scheme::Secp256k1Ecdsa))
+        {
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING, None));
+        }
+
+        if !self
+            .0
+            .get_features()
+            .is_enabled(FeatureFlag::WEBAUTHN_AUTHENTICATOR)
+            && txn
+                .authenticator_ref()
+                .all_signers()
+                .iter()
+                .any(|signer| matches!(signer.scheme(), Scheme::WebAuthn))
+        {
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING, None));
+        }
+
         Ok(())
     }
 
@@ -1591,7 +1707,7 @@ impl VMAdapter for AptosVM {
         )
     }
 
-    fn should_restart_execution(vm_output: &TransactionOutput) -> bool {
+    pub(crate) fn should_restart_execution(vm_output: &TransactionOutput) -> bool {
         let new_epoch_event_key = aptos_types::on_chain_config::new_epoch_event_key();
         vm_output
             .events()
@@ -7,14 +7,17 @@ use crate::{
     data_cache::{MoveResolverWithVMMetadata, StorageAdapter},
     errors::{convert_epilogue_error, convert_prologue_error, expect_only_successful_execution},
     move_vm_ext::{MoveResolverExt, MoveVmExt, SessionExt, SessionId},
-    system_module_names::{MULTISIG_ACCOUNT_MODULE, VALIDATE_MULTISIG_TRANSACTION},
+    system_module_names::{
+        FEE_PAYER_EPILOGUE_NAME, FEE_PAYER_SCRIPT_PROLOGUE_NAME, MULTISIG_ACCOUNT_MODULE,
+        VALIDATE_MULTISIG_TRANSACTION,
+    },
     transaction_metadata::TransactionMetadata,
 };
 use aptos_aggregator::transaction::TransactionOutputExt;
 use aptos_framework::RuntimeModuleMetadataV1;
 use aptos_gas::{
-    AbstractValueSizeGasParameters, AptosGasParameters, ChangeSetConfigs, FromOnChainGasSchedule,
-    Gas, NativeGasParameters, StorageGasParameters,
+    AbstractValueSizeGasParameters, AptosGasParameters, ChangeSetConfigs, FeePerGasUnit,
+    FromOnChainGasSchedule, Gas, NativeGasParameters, StorageGasParameters,
 };
 use aptos_logger::{enabled, prelude::*, Level};
 use aptos_state_view::StateView;
@@ -32,6 +35,7 @@ use aptos_vm_logging::{log_schema::AdapterLogSchema, prelude::*};
 use fail::fail_point;
 use move_binary_format::{errors::VMResult, CompiledModule};
 use move_core_types::{
+    identifier::IdentStr,
     language_storage::ModuleId,
     move_resource::MoveStructType,
     resolver::ResourceResolver,
@@ -364,8 +368,9 @@ impl AptosVMImpl {
         Ok(())
     }
 
-    /// Run the prologue of a transaction by calling into either `SCRIPT_PROLOGUE_NAME` function
-    /// or `MULTI_AGENT_SCRIPT_PROLOGUE_NAME` function stored in the `ACCOUNT_MODULE` on chain.
+    /// Run the prologue of a transaction by calling into either `SCRIPT_PROLOGUE_NAME`,
+    /// `MULTI_AGENT_SCRIPT_PROLOGUE_NAME` or `FEE_PAYER_SCRIPT_PROLOGUE_NAME` function stored in
+    /// the `ACCOUNT_MODULE` on chain.
     pub(crate) fn run_script_prologue<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
@@ -385,7 +390,31 @@ impl AptosVMImpl {
             .iter()
             .map(|auth_key| MoveValue::vector_u8(auth_key.to_vec()))
             .collect();
-        let args = if txn_data.is_multi_agent() {
+        if txn_data.fee_payer().is_some()
+            && !self.features.is_enabled(FeatureFlag::FEE_PAYER_ENABLED)
+        {
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING, None));
+        }
+        let args = if let Some(fee_payer) = txn_data.fee_payer() {
+            vec![
+                MoveValue::Signer(txn_data.sender),
+                MoveValue::U64(txn_sequence_number),
+                MoveValue::vector_u8(txn_authentication_key),
+                MoveValue::vector_address(txn_data.secondary_signers()),
+                MoveValue::Vector(secondary_auth_keys),
+                MoveValue::Address(fee_payer),
+                MoveValue::vector_u8(
+                    txn_data
+                        .fee_payer_authentication_key
+                        .clone()
+                        .unwrap_or_default(),
+                ),
+                MoveValue::U64(txn_gas_price.into()),
+                MoveValue::U64(txn_max_gas_units.into()),
+                MoveValue::U64(txn_expiration_timestamp_secs),
+                MoveValue::U8(chain_id.id()),
+            ]
+        } else if txn_data.is_multi_agent() {
             vec![
                 MoveValue::Signer(txn_data.sender),
                 MoveValue::U64(txn_sequence_number),
@@ -409,10 +438,14 @@ impl AptosVMImpl {
                 MoveValue::vector_u8(txn_data.script_hash.clone()),
             ]
         };
-        let prologue_function_name = if txn_data.is_multi_agent() {
-            &transaction_validation.multi_agent_prologue_name
+        let prologue_function_name = if txn_data.fee_payer().is_some() {
+            FEE_PAYER_SCRIPT_PROLOGUE_NAME
+        } else if txn_data.is_multi_agent() {
+            transaction_validation
+                .multi_agent_prologue_name
+                .as_ident_str()
         } else {
-            &transaction_validation.script_prologue_name
+            transaction_validation.script_prologue_name.as_ident_str()
         };
         session
             .execute_function_bypass_visibility(
@@ -525,19 +558,21 @@ impl AptosVMImpl {
         let txn_sequence_number = txn_data.sequence_number();
         let txn_gas_price = txn_data.gas_unit_price();
         let txn_max_gas_units = txn_data.max_gas_amount();
+        let (epilogue_function_name, args) = epilogue_function_and_args(
+            transaction_validation,
+            txn_data,
+            txn_sequence_number,
+            txn_gas_price,
+            txn_max_gas_units,
+            gas_remaining,
+        );
         session
             .execute_function_bypass_visibility(
                 &transaction_validation.module_id(),
-                &transaction_validation.user_epilogue_name,
+                epilogue_function_name,
                 // TODO: Deprecate this once we remove gas currency on the Move side.
                 vec![],
-                serialize_values(&vec![
-                    MoveValue::Signer(txn_data.sender),
-                    MoveValue::U64(txn_sequence_number),
-                    MoveValue::U64(txn_gas_price.into()),
-                    MoveValue::U64(txn_max_gas_units.into()),
-                    MoveValue::U64(gas_remaining.into()),
-                ]),
+                serialize_values(&args),
                 &mut UnmeteredGasMeter,
             )
             .map(|_return_vals| ())
@@ -558,29 +593,27 @@ impl AptosVMImpl {
         let txn_sequence_number = txn_data.sequence_number();
         let txn_gas_price = txn_data.gas_unit_price();
         let txn_max_gas_units = txn_data.max_gas_amount();
+        let (epilogue_function_name, args) = epilogue_function_and_args(
+            transaction_validation,
+            txn_data,
+            txn_sequence_number,
+            txn_gas_price,
+            txn_max_gas_units,
+            gas_remaining,
+        );
         session
             .execute_function_bypass_visibility(
                 &transaction_validation.module_id(),
-                &transaction_validation.user_epilogue_name,
+                epilogue_function_name,
                 // TODO: Deprecate this once we remove gas currency on the Move side.
                 vec![],
-                serialize_values(&vec![
-                    MoveValue::Signer(txn_data.sender),
-                    MoveValue::U64(txn_sequence_number),
-                    MoveValue::U64(txn_gas_price.into()),
-                    MoveValue::U64(txn_max_gas_units.into()),
-                    MoveValue::U64(gas_remaining.into()),
-                ]),
+                serialize_values(&args),
                 &mut UnmeteredGasMeter,
             )
             .map(|_return_vals| ())
             .map_err(expect_no_verification_errors)
             .or_else(|e| {
-                expect_only_successful_execution(
-                    e,
-                    transaction_validation.user_epilogue_name.as_str(),
-                    log_context,
-                )
+                expect_only_successful_execution(e, epilogue_function_name.as_str(), log_context)
             })
     }
 
@@ -631,6 +664,38 @@ impl AptosVMImpl {
     }
 }
 
+/// Returns the epilogue to run for the transaction, i.e. `FEE_PAYER_EPILOGUE_NAME` if the gas is
+/// paid by a fee payer or the user epilogue otherwise, with its arguments.
+fn epilogue_function_and_args<'a>(
+    transaction_validation: &'a TransactionValidation,
+    txn_data: &TransactionMetadata,
+    txn_sequence_number: u64,
+    txn_gas_price: FeePerGasUnit,
+    txn_max_gas_units: Gas,
+    gas_remaining: Gas,
+) -> (&'a IdentStr, Vec<MoveValue>) {
+    match txn_data.fee_payer() {
+        Some(fee_payer) => (FEE_PAYER_EPILOGUE_NAME, vec![
+            MoveValue::Signer(txn_data.sender),
+            MoveValue::Address(fee_payer),
+            MoveValue::U64(txn_sequence_number),
+            MoveValue::U64(txn_gas_price.into()),
+            MoveValue::U64(txn_max_gas_units.into()),
+            MoveValue::U64(gas_remaining.into()),
+        ]),
+        None => (
+            transaction_validation.user_epilogue_name.as_ident_str(),
+            vec![
+                MoveValue::Signer(txn_data.sender),
+                MoveValue::U64(txn_sequence_number),
+                MoveValue::U64(txn_gas_price.into()),
+                MoveValue::U64(txn_max_gas_units.into()),
+                MoveValue::U64(gas_remaining.into()),
+            ],
+        ),
+    }
+}
+
 /// Internal APIs for the VM, primarily used for testing.
 #[derive(Clone, Copy)]
 pub struct AptosVMInternals<'a>(&'a AptosVMImpl);
@@ -2,18 +2,22 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod partitioner;
 pub(crate) mod vm_wrapper;
+pub(crate) mod warm_vm_cache;
 
 use crate::{
     adapter_common::{preprocess_transaction, PreprocessedTransaction},
-    block_executor::vm_wrapper::AptosExecutorTask,
+    block_executor::{partitioner::partition_by_conflicts, vm_wrapper::AptosExecutorTask},
     counters::{
         BLOCK_EXECUTOR_CONCURRENCY, BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS,
         BLOCK_EXECUTOR_SIGNATURE_VERIFICATION_SECONDS,
     },
+    data_cache::AsMoveResolver,
     AptosVM,
 };
 use aptos_aggregator::{delta_change_set::DeltaOp, transaction::TransactionOutputExt};
+pub use aptos_block_executor::recorder::{ScheduleRecorder, ScheduleRecording};
 use aptos_block_executor::{
     errors::Error,
     executor::{BlockExecutor, RAYON_EXEC_POOL},
@@ -26,14 +30,14 @@ use aptos_infallible::Mutex;
 use aptos_state_view::StateView;
 use aptos_types::{
     state_store::state_key::StateKey,
-    transaction::{Transaction, TransactionOutput, TransactionStatus},
+    transaction::{ExecutionStatus, Transaction, TransactionOutput, TransactionStatus},
     write_set::{WriteOp, WriteSet},
 };
 use aptos_vm_logging::{flush_speculative_logs, init_speculative_logs};
-use move_core_types::vm_status::VMStatus;
+use move_core_types::vm_status::{StatusCode, VMStatus};
 use once_cell::sync::OnceCell;
 use rayon::prelude::*;
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 impl BlockExecutorTransaction for PreprocessedTransaction {
     type Key = StateKey;
@@ -81,6 +85,20 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
         )))
     }
 
+    /// Gas used is not affected by the delta writes, so it can be taken from either output.
+    fn gas_used(&self) -> u64 {
+        match self.committed_output.get() {
+            Some(output) => output.gas_used(),
+            None => self
+                .output_ext
+                .lock()
+                .as_ref()
+                .expect("Output to be set to get gas used")
+                .txn_output()
+                .gas_used(),
+        }
+    }
+
     /// Should never be called after incorporate_delta_writes, as it will consume
     /// output_ext to prepare an output with deltas.
     fn get_writes(&self) -> Vec<(StateKey, WriteOp)> {
@@ -133,6 +151,94 @@ impl BlockAptosVM {
         transactions: Vec<Transaction>,
         state_view: &S,
         concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            |executor, block| executor.execute_block(state_view, block, state_view),
+        )
+    }
+
+    /// Same as 'execute_block', but the block is partitioned into 'num_shards' shards of
+    /// (estimated) conflicting transactions, which are executed concurrently by separate
+    /// parallel executions, each using 'concurrency_level / num_shards' threads.
+    pub fn execute_block_sharded<S: StateView + Sync>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        num_shards: usize,
+        maybe_block_gas_limit: Option<u64>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            |executor, block| {
+                let partition =
+                    partition_by_conflicts(&block, num_shards, &state_view.as_move_resolver());
+                executor.execute_block_sharded(
+                    state_view,
+                    block,
+                    state_view,
+                    &partition.shard_assignment,
+                )
+            },
+        )
+    }
+
+    /// Same as 'execute_block', while recording the schedule of the parallel execution to
+    /// 'recorder', so that it can be reproduced by 'replay_block'.
+    pub fn record_block<S: StateView + Sync>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+        recorder: Arc<ScheduleRecorder<StateKey>>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            |executor, block| {
+                executor
+                    .with_recorder(recorder)
+                    .execute_block(state_view, block, state_view)
+            },
+        )
+    }
+
+    /// Deterministically replays the parallel execution of the block recorded by 'record_block'
+    /// (e.g. against the state view of the debugger, to reproduce a bug offline). A divergence
+    /// from the recording is returned as an invariant violation carrying its description.
+    pub fn replay_block<S: StateView + Sync>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        maybe_block_gas_limit: Option<u64>,
+        recording: &ScheduleRecording<StateKey>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            1,
+            maybe_block_gas_limit,
+            |executor, block| executor.replay_block(state_view, block, state_view, recording),
+        )
+    }
+
+    fn execute_block_impl<'a, S: StateView + Sync>(
+        transactions: Vec<Transaction>,
+        state_view: &'a S,
+        concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+        execute: impl FnOnce(
+            BlockExecutor<PreprocessedTransaction, AptosExecutorTask<'a, S>, S>,
+            Vec<PreprocessedTransaction>,
+        ) -> Result<Vec<AptosTransactionOutput>, Error<VMStatus>>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         // Verify the signatures of all the transactions in parallel.
@@ -149,27 +255,61 @@ impl BlockAptosVM {
                     .collect()
             });
         drop(signature_verification_timer);
+        let ends_with_state_checkpoint = matches!(
+            signature_verified_block.last(),
+            Some(PreprocessedTransaction::StateCheckpoint)
+        );
 
         init_speculative_logs(signature_verified_block.len());
 
         BLOCK_EXECUTOR_CONCURRENCY.set(concurrency_level as i64);
         let executor = BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(
             concurrency_level,
+            maybe_block_gas_limit,
         );
 
-        let ret = executor.execute_block(state_view, signature_verified_block, state_view);
+        let ret = execute(executor, signature_verified_block);
 
         flush_speculative_logs();
 
         match ret {
-            Ok(outputs) => Ok(outputs
-                .into_iter()
-                .map(|output| output.take_output())
-                .collect()),
+            Ok(outputs) => {
+                let mut outputs: Vec<TransactionOutput> = outputs
+                    .into_iter()
+                    .map(|output| output.take_output())
+                    .collect();
+                if maybe_block_gas_limit.is_some() && ends_with_state_checkpoint {
+                    Self::keep_state_checkpoint(&mut outputs);
+                }
+                Ok(outputs)
+            },
             Err(Error::ModulePathReadWrite) => {
                 unreachable!("[Execution]: Must be handled by sequential fallback")
             },
             Err(Error::UserError(err)) => Err(err),
+            Err(Error::ReplayDivergence(divergence)) => Err(VMStatus::Error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some(divergence),
+            )),
+        }
+    }
+
+    /// When the block gas limit is reached, the trailing state checkpoint gets skipped together
+    /// with the rest of the block. Unlike after a reconfiguration (where the block ends early),
+    /// the state checkpoint must be kept, its output is the same as if it was executed.
+    fn keep_state_checkpoint(outputs: &mut [TransactionOutput]) {
+        if outputs.iter().any(AptosVM::should_restart_execution) {
+            return;
+        }
+        if let Some(output) = outputs.last_mut() {
+            if matches!(output.status(), TransactionStatus::Retry) {
+                *output = TransactionOutput::new(
+                    WriteSet::default(),
+                    vec![],
+                    0,
+                    TransactionStatus::Keep(ExecutionStatus::Success),
+                );
+            }
         }
     }
 
@@ -200,6 +340,7 @@ impl BlockAptosVM {
         BLOCK_EXECUTOR_CONCURRENCY.set(concurrency_level as i64);
         let executor = BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(
             concurrency_level,
+            None,
         );
         println!("Parallel execution starts...");
         let timer = Instant::now();
@@ -249,7 +390,7 @@ impl BlockAptosVM {
 
         // sequentially execute the block and check if the results match
         let seq_executor =
-            BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(1);
+            BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(1, None);
         println!("Sequential execution starts...");
         let seq_timer = Instant::now();
         let seq_ret = seq_executor
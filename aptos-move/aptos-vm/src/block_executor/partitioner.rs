@@ -0,0 +1,288 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    adapter_common::PreprocessedTransaction, counters::SHARDED_EXECUTION_LARGEST_CONFLICT_SET,
+};
+use aptos_types::transaction::TransactionPayload;
+use move_binary_format::{access::ModuleAccess, file_format::SignatureToken, CompiledModule};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+    resolver::ModuleResolver,
+};
+use std::collections::HashMap;
+
+/// The result of partitioning a block for the sharded execution.
+#[derive(Debug)]
+pub(crate) struct Partition {
+    /// The shard of each transaction.
+    pub shard_assignment: Vec<usize>,
+    /// The number of transactions in the largest set of (transitively) conflicting transactions.
+    /// It bounds the load of the most loaded shard, e.g. a block in which most transactions
+    /// touch the same hot account is mostly executed by a single shard.
+    pub largest_conflict_set: usize,
+}
+
+/// Partitions the block into 'num_shards' shards for the sharded execution. Transactions that
+/// are estimated to conflict, i.e. share the sender or an address argument of the entry function
+/// (e.g. the recipient of a transfer), are put in the same shard. The sets of (transitively)
+/// conflicting transactions are then assigned to the least loaded shard, largest first.
+///
+/// The estimate doesn't need to be precise, as conflicts across shards are resolved by the
+/// block executor, but every such conflict leads to a re-execution. The address arguments are
+/// told apart by the ABI of the entry function, fetched through 'module_resolver'.
+pub(crate) fn partition_by_conflicts(
+    txns: &[PreprocessedTransaction],
+    num_shards: usize,
+    module_resolver: &impl ModuleResolver,
+) -> Partition {
+    let mut abis = EntryFunctionAbis::new(module_resolver);
+    let conflict_addresses: Vec<_> = txns
+        .iter()
+        .map(|txn| conflict_addresses(txn, &mut abis))
+        .collect();
+    let partition = assign_shards(&conflict_addresses, num_shards);
+    if !txns.is_empty() {
+        SHARDED_EXECUTION_LARGEST_CONFLICT_SET
+            .observe(partition.largest_conflict_set as f64 / txns.len() as f64);
+    }
+    partition
+}
+
+/// Assigns the transactions to shards, given the addresses each of them is likely to write.
+fn assign_shards(conflict_addresses: &[Vec<AccountAddress>], num_shards: usize) -> Partition {
+    let mut conflict_sets = ConflictSets::new(conflict_addresses.len());
+    let mut last_txn_by_address: HashMap<AccountAddress, usize> = HashMap::new();
+    for (idx, addresses) in conflict_addresses.iter().enumerate() {
+        for address in addresses {
+            if let Some(prev_idx) = last_txn_by_address.insert(*address, idx) {
+                conflict_sets.union(prev_idx, idx);
+            }
+        }
+    }
+
+    // Conflict sets by their first transaction, which keeps the assignment deterministic.
+    let mut sets: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut set_by_root = HashMap::new();
+    for idx in 0..conflict_addresses.len() {
+        let root = conflict_sets.find(idx);
+        let pos = *set_by_root.entry(root).or_insert_with(|| {
+            sets.push((idx, Vec::new()));
+            sets.len() - 1
+        });
+        sets[pos].1.push(idx);
+    }
+    sets.sort_by(|(a_first, a), (b_first, b)| b.len().cmp(&a.len()).then(a_first.cmp(b_first)));
+    let largest_conflict_set = sets.first().map_or(0, |(_, set)| set.len());
+
+    let mut shard_loads = vec![0; num_shards.max(1)];
+    let mut shard_assignment = vec![0; conflict_addresses.len()];
+    for (_, set) in sets {
+        let (shard, _) = shard_loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(shard, load)| (**load, *shard))
+            .expect("There is at least one shard");
+        shard_loads[shard] += set.len();
+        for idx in set {
+            shard_assignment[idx] = shard;
+        }
+    }
+    Partition {
+        shard_assignment,
+        largest_conflict_set,
+    }
+}
+
+/// The addresses whose resources the transaction is likely to write.
+fn conflict_addresses(
+    txn: &PreprocessedTransaction,
+    abis: &mut EntryFunctionAbis<impl ModuleResolver>,
+) -> Vec<AccountAddress> {
+    match txn {
+        PreprocessedTransaction::UserTransaction(txn) => {
+            let mut addresses = vec![txn.sender()];
+            if let TransactionPayload::EntryFunction(entry_function) = txn.payload() {
+                if let Some(parameters) =
+                    abis.parameters(entry_function.module(), entry_function.function())
+                {
+                    addresses.extend(address_args(parameters, entry_function.args()));
+                }
+            }
+            addresses
+        },
+        // Other transactions are rare and usually write to the framework, conflicting with
+        // every shard regardless of the assignment.
+        PreprocessedTransaction::WaypointWriteSet(_)
+        | PreprocessedTransaction::BlockMetadata(_)
+        | PreprocessedTransaction::InvalidSignature
+        | PreprocessedTransaction::StateCheckpoint => vec![],
+    }
+}
+
+/// The arguments of type 'address' of an entry function with the given parameters. The signer
+/// parameters come first and aren't passed as arguments.
+fn address_args(parameters: &[SignatureToken], args: &[Vec<u8>]) -> Vec<AccountAddress> {
+    if parameters.len() < args.len() {
+        return vec![];
+    }
+    parameters[parameters.len() - args.len()..]
+        .iter()
+        .zip(args)
+        .filter(|(parameter, _)| **parameter == SignatureToken::Address)
+        .filter_map(|(_, arg)| bcs::from_bytes::<AccountAddress>(arg).ok())
+        .collect()
+}
+
+/// Caches the parameters of the entry functions called in the block.
+struct EntryFunctionAbis<'a, R> {
+    module_resolver: &'a R,
+    parameters: HashMap<(ModuleId, Identifier), Option<Vec<SignatureToken>>>,
+}
+
+impl<'a, R: ModuleResolver> EntryFunctionAbis<'a, R> {
+    fn new(module_resolver: &'a R) -> Self {
+        Self {
+            module_resolver,
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// Returns the parameters of 'module::function', or None if it can't be found.
+    fn parameters(&mut self, module: &ModuleId, function: &IdentStr) -> Option<&[SignatureToken]> {
+        let module_resolver = self.module_resolver;
+        self.parameters
+            .entry((module.clone(), function.to_owned()))
+            .or_insert_with(|| {
+                let bytes = module_resolver.get_module(module).ok()??;
+                let module = CompiledModule::deserialize(&bytes).ok()?;
+                module.function_defs().iter().find_map(|function_def| {
+                    let handle = module.function_handle_at(function_def.function);
+                    (module.identifier_at(handle.name) == function)
+                        .then(|| module.signature_at(handle.parameters).0.clone())
+                })
+            })
+            .as_deref()
+    }
+}
+
+/// Union-find over the transaction indices.
+struct ConflictSets {
+    parents: Vec<usize>,
+}
+
+impl ConflictSets {
+    fn new(num_txns: usize) -> Self {
+        Self {
+            parents: (0..num_txns).collect(),
+        }
+    }
+
+    fn find(&mut self, idx: usize) -> usize {
+        let mut root = idx;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Path compression.
+        let mut idx = idx;
+        while self.parents[idx] != root {
+            let parent = self.parents[idx];
+            self.parents[idx] = root;
+            idx = parent;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            // The root is the earlier transaction, to keep the result independent of the order
+            // of the unions.
+            self.parents[root_a.max(root_b)] = root_a.min(root_b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(indices: &[u8]) -> Vec<AccountAddress> {
+        indices
+            .iter()
+            .map(|idx| AccountAddress::new([*idx; AccountAddress::LENGTH]))
+            .collect()
+    }
+
+    fn shard_loads(partition: &Partition, num_shards: usize) -> Vec<usize> {
+        let mut loads = vec![0; num_shards];
+        for shard in &partition.shard_assignment {
+            loads[*shard] += 1;
+        }
+        loads
+    }
+
+    #[test]
+    fn test_disjoint_block() {
+        let conflict_addresses: Vec<_> = (0..8).map(|idx| addresses(&[idx])).collect();
+        let partition = assign_shards(&conflict_addresses, 4);
+        assert_eq!(partition.shard_assignment, vec![0, 1, 2, 3, 0, 1, 2, 3]);
+        assert_eq!(partition.largest_conflict_set, 1);
+    }
+
+    #[test]
+    fn test_chained_block() {
+        // 0 -> 1 -> 2 -> 3 conflict transitively, 4 and 5 conflict with each other only.
+        let conflict_addresses = vec![
+            addresses(&[0, 1]),
+            addresses(&[10, 4]),
+            addresses(&[1, 2]),
+            addresses(&[11, 4]),
+            addresses(&[2, 3]),
+            addresses(&[3, 12]),
+        ];
+        let partition = assign_shards(&conflict_addresses, 2);
+        assert_eq!(partition.shard_assignment, vec![0, 1, 0, 1, 0, 0]);
+        assert_eq!(partition.largest_conflict_set, 4);
+    }
+
+    #[test]
+    fn test_hot_key_block() {
+        // All but one transaction write the hot address, so a single shard gets most of the block.
+        let mut conflict_addresses: Vec<_> = (1..10).map(|idx| addresses(&[idx, 0])).collect();
+        conflict_addresses.push(addresses(&[10]));
+        let partition = assign_shards(&conflict_addresses, 4);
+        assert_eq!(shard_loads(&partition, 4), vec![9, 1, 0, 0]);
+        assert_eq!(partition.largest_conflict_set, 9);
+    }
+
+    #[test]
+    fn test_empty_block() {
+        let partition = assign_shards(&[], 4);
+        assert!(partition.shard_assignment.is_empty());
+        assert_eq!(partition.largest_conflict_set, 0);
+    }
+
+    #[test]
+    fn test_only_address_args() {
+        let address = AccountAddress::new([1; AccountAddress::LENGTH]);
+        let parameters = vec![
+            SignatureToken::Reference(Box::new(SignatureToken::Signer)),
+            SignatureToken::Address,
+            SignatureToken::U256,
+            SignatureToken::Vector(Box::new(SignatureToken::U8)),
+        ];
+        // The u256 and the bytes are also 32 bytes long, but aren't addresses.
+        let args = vec![
+            bcs::to_bytes(&address).unwrap(),
+            vec![2; 32],
+            bcs::to_bytes(&vec![3u8; 31]).unwrap(),
+        ];
+        assert_eq!(address_args(&parameters, &args), vec![address]);
+
+        // Without a matching ABI no argument is used.
+        assert!(address_args(&parameters[..2], &args).is_empty());
+    }
+}
@@ -5,7 +5,10 @@
 use crate::{
     adapter_common::{PreprocessedTransaction, VMAdapter},
     aptos_vm::AptosVM,
-    block_executor::AptosTransactionOutput,
+    block_executor::{
+        warm_vm_cache::{self, WarmVmTicket},
+        AptosTransactionOutput,
+    },
     data_cache::{AsMoveResolver, StorageAdapter},
 };
 use aptos_aggregator::{delta_change_set::DeltaChangeSet, transaction::TransactionOutputExt};
@@ -13,6 +16,7 @@ use aptos_block_executor::task::{ExecutionStatus, ExecutorTask};
 use aptos_logger::{enabled, Level};
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_state_view::StateView;
+use aptos_types::executable::ModulePath;
 use aptos_vm_logging::{log_schema::AdapterLogSchema, prelude::*};
 use move_core_types::{
     ident_str,
@@ -23,6 +27,7 @@ use move_core_types::{
 pub(crate) struct AptosExecutorTask<'a, S> {
     vm: AptosVM,
     base_view: &'a S,
+    warm_vm_ticket: Option<WarmVmTicket>,
 }
 
 impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
@@ -32,7 +37,7 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
     type Txn = PreprocessedTransaction;
 
     fn init(argument: &'a S) -> Self {
-        let vm = AptosVM::new(argument);
+        let (vm, warm_vm_ticket) = warm_vm_cache::take_vm(argument);
 
         // Loading `0x1::account` and its transitive dependency into the code cache.
         //
@@ -50,6 +55,7 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
         Self {
             vm,
             base_view: argument,
+            warm_vm_ticket,
         }
     }
 
@@ -70,6 +76,16 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
             .execute_single_transaction(txn, &view.as_move_resolver(), &log_context)
         {
             Ok((vm_status, mut output_ext, sender)) => {
+                if output_ext
+                    .txn_output()
+                    .write_set()
+                    .iter()
+                    .any(|(state_key, _)| state_key.module_path().is_some())
+                {
+                    // The VMs executing the block may have loaded the new modules, or the old
+                    // ones, so none of them can be reused.
+                    warm_vm_cache::invalidate();
+                }
                 if materialize_deltas {
                     // Keep TransactionOutputExt type for wrapper.
                     output_ext = TransactionOutputExt::new(
@@ -109,3 +125,11 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
         }
     }
 }
+
+impl<'a, S> Drop for AptosExecutorTask<'a, S> {
+    fn drop(&mut self) {
+        if let Some(ticket) = self.warm_vm_ticket.take() {
+            warm_vm_cache::put_vm(self.vm.clone(), ticket);
+        }
+    }
+}
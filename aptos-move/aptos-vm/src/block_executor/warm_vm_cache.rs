@@ -0,0 +1,300 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of the VMs used to execute the previous blocks, whose Move loader caches are already
+//! populated with the frequently used (e.g. framework) modules, so that they don't have to be
+//! loaded and verified again in every block.
+//!
+//! A VM is only reused if the on-chain configs it was created from are unchanged, and the cache
+//! is cleared whenever a transaction writes a module, since the loaders may have cached the
+//! previous version of the module (or, in case of speculative execution, the new one). Executors
+//! running blocks on forks of the state are responsible for invalidating the cache when switching
+//! to a state with different modules, see `VMExecutor::invalidate_cross_block_cache`.
+//!
+//! To bound the memory used by the loader caches, at most `AptosVM::get_concurrency_level()` VMs
+//! are cached, each reused for at most `MAX_BLOCKS_PER_VM` blocks.
+
+use crate::{counters::CROSS_BLOCK_VM_CACHE, AptosVM};
+use aptos_infallible::Mutex;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{TransactionValidation, CORE_CODE_ADDRESS},
+    on_chain_config::{
+        ChainId, ConfigurationResource, Features, GasSchedule, GasScheduleV2, OnChainConfig,
+        StorageGasSchedule, Version,
+    },
+    state_store::{state_key::StateKey, state_value::StateValue},
+};
+use move_core_types::move_resource::MoveStructType;
+use once_cell::sync::{Lazy, OnceCell};
+use std::cell::Cell;
+
+const MAX_BLOCKS_PER_VM: usize = 100;
+
+static CROSS_BLOCK_VM_CACHE_ENABLED: OnceCell<bool> = OnceCell::new();
+static WARM_VMS: Lazy<Mutex<WarmVms<AptosVM>>> = Lazy::new(|| Mutex::new(WarmVms::default()));
+
+/// The state keys of the configs `AptosVM::new` reads.
+static CONFIG_STATE_KEYS: Lazy<Vec<StateKey>> = Lazy::new(|| {
+    [
+        GasScheduleV2::access_path(),
+        GasSchedule::access_path(),
+        StorageGasSchedule::access_path(),
+        Features::access_path(),
+        ChainId::access_path(),
+        ConfigurationResource::access_path(),
+        Version::access_path(),
+        AccessPath::resource_access_path(CORE_CODE_ADDRESS, TransactionValidation::struct_tag()),
+    ]
+    .into_iter()
+    .map(|access_path| {
+        StateKey::access_path(access_path.expect("Config access paths must be valid"))
+    })
+    .collect()
+});
+
+thread_local! {
+    static BYPASSED: Cell<bool> = Cell::new(false);
+}
+
+pub(crate) fn set_enabled_once(enabled: bool) {
+    CROSS_BLOCK_VM_CACHE_ENABLED.set(enabled).ok();
+}
+
+fn is_enabled() -> bool {
+    CROSS_BLOCK_VM_CACHE_ENABLED.get().copied().unwrap_or(false)
+}
+
+/// Runs `f` without taking VMs from (or putting them to) the cache on the current thread, for the
+/// executions on top of a state that may differ from the state of the cached VMs in modules, e.g.
+/// the re-execution of an older block.
+///
+/// Only the sequential execution runs on the calling thread, so `f` must not execute blocks in
+/// parallel.
+pub(crate) fn bypass<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BYPASSED.with(|bypassed| bypassed.set(self.0));
+        }
+    }
+
+    let _restore = Restore(BYPASSED.with(|bypassed| bypassed.replace(true)));
+    f()
+}
+
+fn is_bypassed() -> bool {
+    BYPASSED.with(|bypassed| bypassed.get())
+}
+
+struct WarmVms<VM> {
+    /// Bumped on every invalidation, the VMs taken out before can't be put back.
+    generation: u64,
+    /// The values of the configs the cached VMs were created from.
+    configs: Vec<Option<StateValue>>,
+    /// The cached VMs, with the number of blocks each was used for.
+    vms: Vec<(VM, usize)>,
+}
+
+impl<VM> Default for WarmVms<VM> {
+    fn default() -> Self {
+        Self {
+            generation: 0,
+            configs: vec![],
+            vms: vec![],
+        }
+    }
+}
+
+impl<VM> WarmVms<VM> {
+    /// Takes out a VM created from `configs`, if any, with a ticket to put it back.
+    fn take(&mut self, configs: Vec<Option<StateValue>>) -> (Option<VM>, WarmVmTicket) {
+        if self.configs != configs {
+            self.vms.clear();
+        }
+        let (vm, num_blocks) = match self.vms.pop() {
+            Some((vm, num_blocks)) => (Some(vm), num_blocks),
+            None => (None, 0),
+        };
+        (vm, WarmVmTicket {
+            generation: self.generation,
+            configs,
+            num_blocks: num_blocks + 1,
+        })
+    }
+
+    /// Puts back a VM taken out with `ticket`, unless the cache was invalidated in the meantime,
+    /// the VM was used for `MAX_BLOCKS_PER_VM` blocks, or `max_vms` VMs are cached already.
+    fn put(&mut self, vm: VM, ticket: WarmVmTicket, max_vms: usize) {
+        if ticket.num_blocks >= MAX_BLOCKS_PER_VM || self.generation != ticket.generation {
+            return;
+        }
+        if self.configs != ticket.configs {
+            self.vms.clear();
+            self.configs = ticket.configs;
+        }
+        if self.vms.len() < max_vms {
+            self.vms.push((vm, ticket.num_blocks));
+        }
+    }
+
+    /// Drops all the cached VMs, returns whether there were any.
+    fn invalidate(&mut self) -> bool {
+        self.generation += 1;
+        let invalidated = !self.vms.is_empty();
+        self.vms.clear();
+        invalidated
+    }
+}
+
+/// Identifies where a VM taken out of the cache can be put back.
+pub(crate) struct WarmVmTicket {
+    generation: u64,
+    configs: Vec<Option<StateValue>>,
+    num_blocks: usize,
+}
+
+/// Returns a VM to execute transactions on top of `state_view`, reusing a cached one if possible.
+pub(crate) fn take_vm(state_view: &impl StateView) -> (AptosVM, Option<WarmVmTicket>) {
+    if !is_enabled() || is_bypassed() {
+        return (AptosVM::new(state_view), None);
+    }
+    let configs = match CONFIG_STATE_KEYS
+        .iter()
+        .map(|state_key| state_view.get_state_value(state_key))
+        .collect::<anyhow::Result<Vec<_>>>()
+    {
+        Ok(configs) => configs,
+        Err(_) => return (AptosVM::new(state_view), None),
+    };
+
+    let (vm, ticket) = WARM_VMS.lock().take(configs);
+    let vm = match vm {
+        Some(vm) => {
+            CROSS_BLOCK_VM_CACHE.with_label_values(&["hit"]).inc();
+            vm
+        },
+        None => {
+            CROSS_BLOCK_VM_CACHE.with_label_values(&["miss"]).inc();
+            AptosVM::new(state_view)
+        },
+    };
+    (vm, Some(ticket))
+}
+
+/// Puts back a VM taken out by `take_vm`, unless the cache was invalidated in the meantime.
+pub(crate) fn put_vm(vm: AptosVM, ticket: WarmVmTicket) {
+    WARM_VMS
+        .lock()
+        .put(vm, ticket, AptosVM::get_concurrency_level());
+}
+
+/// Drops all the cached VMs.
+pub(crate) fn invalidate() {
+    if WARM_VMS.lock().invalidate() {
+        CROSS_BLOCK_VM_CACHE
+            .with_label_values(&["invalidated"])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configs(value: u8) -> Vec<Option<StateValue>> {
+        vec![Some(StateValue::new_legacy(vec![value])), None]
+    }
+
+    #[test]
+    fn test_take_and_put() {
+        let mut warm_vms = WarmVms::default();
+        let (vm, ticket) = warm_vms.take(configs(0));
+        assert_eq!(vm, None);
+        warm_vms.put(1, ticket, 2);
+
+        // The VM is reused for the same configs, with the number of blocks it was used for.
+        let (vm, ticket) = warm_vms.take(configs(0));
+        assert_eq!(vm, Some(1));
+        assert_eq!(ticket.num_blocks, 2);
+        let (vm, other_ticket) = warm_vms.take(configs(0));
+        assert_eq!(vm, None);
+        assert_eq!(other_ticket.num_blocks, 1);
+
+        // At most `max_vms` VMs are cached.
+        warm_vms.put(1, ticket, 1);
+        warm_vms.put(2, other_ticket, 1);
+        assert_eq!(warm_vms.vms, vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_config_change_invalidates() {
+        let mut warm_vms = WarmVms::default();
+        let (_, ticket) = warm_vms.take(configs(0));
+        warm_vms.put(1, ticket, 2);
+
+        // VMs created from other configs are not reused, and dropped.
+        let (vm, ticket) = warm_vms.take(configs(1));
+        assert_eq!(vm, None);
+        assert!(warm_vms.vms.is_empty());
+
+        // Putting back a VM created from other configs replaces the cached ones.
+        let (_, old_ticket) = warm_vms.take(configs(0));
+        warm_vms.put(2, ticket, 2);
+        assert_eq!(warm_vms.configs, configs(1));
+        warm_vms.put(1, old_ticket, 2);
+        assert_eq!(warm_vms.configs, configs(0));
+        assert_eq!(warm_vms.vms, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_generation_invalidates() {
+        let mut warm_vms = WarmVms::default();
+        let (_, ticket) = warm_vms.take(configs(0));
+        warm_vms.put(1, ticket, 2);
+        let (_, ticket) = warm_vms.take(configs(0));
+
+        assert!(!warm_vms.invalidate());
+        // A VM taken out before the invalidation can't be put back.
+        warm_vms.put(1, ticket, 2);
+        assert!(warm_vms.vms.is_empty());
+
+        let (_, ticket) = warm_vms.take(configs(0));
+        warm_vms.put(2, ticket, 2);
+        assert!(warm_vms.invalidate());
+        assert!(warm_vms.vms.is_empty());
+    }
+
+    #[test]
+    fn test_max_blocks_per_vm() {
+        let mut warm_vms = WarmVms::default();
+        for num_blocks in 1..MAX_BLOCKS_PER_VM {
+            let (vm, ticket) = warm_vms.take(configs(0));
+            assert_eq!(vm, (num_blocks > 1).then_some(1));
+            assert_eq!(ticket.num_blocks, num_blocks);
+            warm_vms.put(1, ticket, 2);
+        }
+
+        // The VM is dropped once it was used for `MAX_BLOCKS_PER_VM` blocks.
+        let (vm, ticket) = warm_vms.take(configs(0));
+        assert_eq!(vm, Some(1));
+        assert_eq!(ticket.num_blocks, MAX_BLOCKS_PER_VM);
+        warm_vms.put(1, ticket, 2);
+        assert!(warm_vms.vms.is_empty());
+    }
+
+    #[test]
+    fn test_bypass_restores_previous_value() {
+        bypass(|| {
+            bypass(|| assert!(is_bypassed()));
+            assert!(is_bypassed());
+        });
+        assert!(!is_bypassed());
+
+        let result = std::panic::catch_unwind(|| bypass(|| panic!("Execution failed")));
+        assert!(result.is_err());
+        assert!(!is_bypassed());
+    }
+}
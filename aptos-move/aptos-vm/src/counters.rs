@@ -137,3 +137,22 @@ pub static TXN_GAS_USAGE: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static CROSS_BLOCK_VM_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_cross_block_vm_cache",
+        "Hits, misses and invalidations of the cache of VMs reused across blocks",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static SHARDED_EXECUTION_LARGEST_CONFLICT_SET: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_sharded_execution_largest_conflict_set",
+        "Share of the block taken by its largest set of conflicting transactions in the sharded \
+         execution, which all go to the same shard",
+        vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .unwrap()
+});
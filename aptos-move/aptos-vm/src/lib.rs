@@ -143,16 +143,30 @@ pub trait VMValidator {
 
 /// This trait describes the VM's execution interface.
 pub trait VMExecutor: Send + Sync {
-    // NOTE: At the moment there are no persistent caches that live past the end of a block (that's
-    // why execute_block doesn't take &self.)
-    // There are some cache invalidation issues around transactions publishing code that need to be
-    // sorted out before that's possible.
+    // NOTE: The VM may keep caches that live past the end of a block (e.g. the loaded modules).
+    // The VM invalidates them when executing transactions that publish code, but it doesn't know
+    // which state the next block executes on, see 'invalidate_cross_block_cache'.
 
     /// Executes a block of transactions and returns output for each one of them.
     fn execute_block(
         transactions: Vec<Transaction>,
         state_view: &(impl StateView + Sync),
     ) -> Result<Vec<TransactionOutput>, VMStatus>;
+
+    /// Executes a block of transactions sequentially, e.g. to cross-check the outputs of the
+    /// parallel execution. Same as 'execute_block' for VMs that don't execute in parallel.
+    fn execute_block_sequential(
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block(transactions, state_view)
+    }
+
+    /// Invalidates the caches kept across blocks. Must be called before executing a block on top
+    /// of a state whose modules may differ from the state of the previously executed blocks
+    /// without the VM executing the change, e.g. after applying transaction outputs, or when
+    /// switching between forks with published modules.
+    fn invalidate_cross_block_cache() {}
 }
 
 /*
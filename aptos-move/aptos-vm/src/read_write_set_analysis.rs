@@ -4,25 +4,30 @@
 
 use crate::{
     adapter_common::PreprocessedTransaction,
+    data_cache::AsMoveResolver,
     move_vm_ext::MoveResolverExt,
     system_module_names::{BLOCK_MODULE, BLOCK_PROLOGUE, SCRIPT_PROLOGUE_NAME, USER_EPILOGUE_NAME},
 };
 use anyhow::{anyhow, bail, Result};
+use aptos_framework::natives::code::PackageRegistry;
+use aptos_state_view::StateView;
 use aptos_types::{
     account_config,
     transaction::{SignedTransaction, TransactionPayload},
 };
-use move_bytecode_utils::module_cache::SyncModuleCache;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::{GetModule, SyncModuleCache};
 use move_core_types::{
+    account_address::AccountAddress,
     ident_str,
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
-    resolver::ModuleResolver,
+    resolver::{ModuleResolver, ResourceResolver},
     value::{serialize_values, MoveValue},
 };
 use once_cell::sync::Lazy;
 use read_write_set_dynamic::{ConcretizedFormals, NormalizedReadWriteSetAnalysis};
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref, sync::Arc};
 
 pub struct ReadWriteSetAnalysis<'a, R: ModuleResolver> {
     normalized_analysis_result: &'a NormalizedReadWriteSetAnalysis,
@@ -38,6 +43,20 @@ static TRANSACTION_VALIDATION: Lazy<ModuleId> = Lazy::new(|| {
     )
 });
 
+static FRAMEWORK_ADDRESSES: Lazy<Vec<AccountAddress>> = Lazy::new(|| {
+    vec![
+        account_config::CORE_CODE_ADDRESS,
+        AccountAddress::from_hex_literal("0x3").unwrap(),
+        AccountAddress::from_hex_literal("0x4").unwrap(),
+    ]
+});
+static PACKAGE_REGISTRY_TAG: Lazy<StructTag> = Lazy::new(|| StructTag {
+    address: account_config::CORE_CODE_ADDRESS,
+    module: ident_str!("code").to_owned(),
+    name: ident_str!("PackageRegistry").to_owned(),
+    type_params: vec![],
+});
+
 const TRANSACTION_FEES_MODULE_NAME: &IdentStr = ident_str!("transaction_fee");
 const TRANSACTION_FEES_NAME: &IdentStr = ident_str!("TransactionFee");
 
@@ -72,6 +91,14 @@ impl<'a, R: MoveResolverExt> ReadWriteSetAnalysis<'a, R> {
         Ok(self.get_keys_user_transaction(tx)?.1)
     }
 
+    /// Returns an overapproximation of the `ResourceKey`'s in global storage that will be written
+    /// by the entry function embedded in `tx`, leaving out the prologue/epilogue that every
+    /// transaction runs. Only formals and type arguments are binded, secondary indexes remain
+    /// unresolved. Returns an empty vector if the payload cannot be analyzed.
+    pub fn get_keys_written_hint(&self, tx: &SignedTransaction) -> Vec<ResourceKey> {
+        keys_written_hint(self.normalized_analysis_result, tx, &self.module_cache)
+    }
+
     /// Returns an overapproximation of the `ResourceKey`'s in global storage that will be read
     /// by `tx`
     pub fn get_keys_read(&self, tx: &SignedTransaction) -> Result<Vec<ResourceKey>> {
@@ -280,6 +307,28 @@ impl<'a, R: MoveResolverExt> ReadWriteSetAnalysis<'a, R> {
     }
 }
 
+fn keys_written_hint(
+    normalized_analysis_result: &NormalizedReadWriteSetAnalysis,
+    tx: &SignedTransaction,
+    module_cache: &impl GetModule,
+) -> Vec<ResourceKey> {
+    match tx.payload() {
+        TransactionPayload::EntryFunction(s) => normalized_analysis_result
+            .get_partially_concretized_summary(
+                s.module(),
+                s.function(),
+                &[tx.sender()],
+                s.args(),
+                s.ty_args(),
+                module_cache,
+            )
+            .ok()
+            .and_then(|accesses| accesses.get_keys_written())
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
 impl<'a, R: MoveResolverExt> Deref for ReadWriteSetAnalysis<'a, R> {
     type Target = NormalizedReadWriteSetAnalysis;
 
@@ -287,3 +336,85 @@ impl<'a, R: MoveResolverExt> Deref for ReadWriteSetAnalysis<'a, R> {
         self.normalized_analysis_result
     }
 }
+
+/// Static hints of the resources written by user transactions, e.g. used to spread conflicting
+/// transactions apart when ordering a block.
+pub trait WriteHints: Send + Sync {
+    /// Returns, for each of `txns`, the resources its payload may write. An empty vector means
+    /// there is no hint for the transaction.
+    fn get_write_hints(&self, txns: &[SignedTransaction]) -> Vec<Vec<ResourceKey>>;
+}
+
+/// `WriteHints` derived from a read/write set analysis of a fixed set of modules. The hints only
+/// depend on the payloads and these modules, never on the state a node happens to have, so nodes
+/// that build them from the same modules agree on them.
+pub struct ReadWriteSetWriteHints {
+    normalized_analysis_result: NormalizedReadWriteSetAnalysis,
+    modules: ModuleSnapshot,
+}
+
+impl ReadWriteSetWriteHints {
+    /// Analyzes `modules` to hint the writes of transactions calling into them.
+    pub fn new(modules: Vec<CompiledModule>) -> Result<Self> {
+        let normalized_analysis_result =
+            read_write_set::analyze(&modules)?.normalize_all_scripts(add_on_functions_list());
+        Ok(Self {
+            normalized_analysis_result,
+            modules: ModuleSnapshot(
+                modules
+                    .into_iter()
+                    .map(|module| (module.self_id(), Arc::new(module)))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// Returns the modules of all packages registered at the framework addresses in
+    /// `state_view`.
+    pub fn framework_modules(state_view: &impl StateView) -> Result<Vec<CompiledModule>> {
+        let resolver = state_view.as_move_resolver();
+        let mut modules = vec![];
+        for address in FRAMEWORK_ADDRESSES.iter() {
+            let registry = match resolver.get_resource(address, &PACKAGE_REGISTRY_TAG)? {
+                Some(bytes) => bcs::from_bytes::<PackageRegistry>(&bytes)?,
+                None => continue,
+            };
+            for module in registry
+                .packages
+                .iter()
+                .flat_map(|package| &package.modules)
+            {
+                let module_id = ModuleId::new(*address, Identifier::new(module.name.as_str())?);
+                let bytes = resolver
+                    .get_module(&module_id)?
+                    .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+                modules.push(
+                    CompiledModule::deserialize(&bytes).map_err(|e| {
+                        anyhow!("Failed to deserialize module {}: {:?}", module_id, e)
+                    })?,
+                );
+            }
+        }
+        Ok(modules)
+    }
+}
+
+impl WriteHints for ReadWriteSetWriteHints {
+    fn get_write_hints(&self, txns: &[SignedTransaction]) -> Vec<Vec<ResourceKey>> {
+        txns.iter()
+            .map(|txn| keys_written_hint(&self.normalized_analysis_result, txn, &self.modules))
+            .collect()
+    }
+}
+
+/// The modules analyzed by `ReadWriteSetWriteHints`, which binds formals against these only.
+struct ModuleSnapshot(BTreeMap<ModuleId, Arc<CompiledModule>>);
+
+impl GetModule for ModuleSnapshot {
+    type Error = anyhow::Error;
+    type Item = Arc<CompiledModule>;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Arc<CompiledModule>>> {
+        Ok(self.0.get(id).cloned())
+    }
+}
@@ -24,6 +24,39 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of speculative aborts per transaction in a block executed in parallel.
+pub static PARALLEL_EXECUTION_ABORT_RATE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_par_abort_rate",
+        // metric description
+        "Number of speculative aborts divided by the number of transactions in a block",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 15).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Number of re-executions per transaction when validating the outputs of the shards of a block.
+pub static CROSS_SHARD_ABORT_RATE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_cross_shard_abort_rate",
+        // metric description
+        "Number of aborts in the cross-shard validation divided by the number of transactions in a block",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 15).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Count of times the block gas limit was reached, skipping the remaining transactions.
+pub static BLOCK_GAS_LIMIT_REACHED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_block_gas_limit_reached_count",
+        "Number of blocks in which the block gas limit was reached (remaining txns skipped)"
+    )
+    .unwrap()
+});
+
 pub static PARALLEL_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -35,6 +68,17 @@ pub static PARALLEL_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static SHARDED_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_sharded_execution_seconds",
+        // metric description
+        "The time spent in seconds in sharded execution (incl. the cross-shard validation)",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static RAYON_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -13,6 +13,9 @@ pub enum Error<E> {
     /// Execution of a thread yields a non-recoverable error, such error will be propagated back to
     /// the caller.
     UserError(E),
+    /// Replaying a recorded parallel execution diverged from the recording, e.g. because it was
+    /// recorded for another block or state.
+    ReplayDivergence(String),
 }
 
 pub type Result<T, E> = ::std::result::Result<T, Error<E>>;
@@ -5,14 +5,15 @@
 use crate::{
     counters,
     counters::{
-        PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
-        TASK_VALIDATE_SECONDS, VM_INIT_SECONDS, WORK_WITH_TASK_SECONDS,
+        PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, SHARDED_EXECUTION_SECONDS,
+        TASK_EXECUTE_SECONDS, TASK_VALIDATE_SECONDS, VM_INIT_SECONDS, WORK_WITH_TASK_SECONDS,
     },
     errors::*,
+    recorder::{ScheduleEvent, ScheduleRecorder, ScheduleRecording},
     scheduler::{Scheduler, SchedulerTask, Wave},
     task::{ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
-    txn_last_input_output::TxnLastInputOutput,
-    view::{LatestView, MVHashMapView},
+    txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
+    view::{LatestView, MVHashMapView, ReplayView},
 };
 use aptos_aggregator::delta_change_set::{deserialize, serialize};
 use aptos_logger::debug;
@@ -23,17 +24,18 @@ use aptos_mvhashmap::{
 use aptos_state_view::TStateView;
 use aptos_types::{
     executable::ExecutableTestType, // TODO: fix up with the proper generics.
-    write_set::WriteOp,
+    write_set::{TransactionWrite, WriteOp},
 };
 use aptos_vm_logging::{clear_speculative_txn_logs, init_speculative_logs};
 use num_cpus;
 use once_cell::sync::Lazy;
 use std::{
-    collections::btree_map::BTreeMap,
+    collections::{btree_map::BTreeMap, HashMap},
     marker::PhantomData,
     sync::{
         mpsc,
         mpsc::{Receiver, Sender},
+        Arc,
     },
 };
 
@@ -55,6 +57,14 @@ pub struct BlockExecutor<T, E, S> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     concurrency_level: usize,
+    // if set, the execution stops (and the remaining transactions are skipped) once the
+    // accumulated gas of the committed transactions reaches the limit.
+    maybe_block_gas_limit: Option<u64>,
+    // if set, the schedule of the parallel execution is recorded, see 'replay_block'.
+    maybe_recorder: Option<Arc<ScheduleRecorder<T::Key>>>,
+    // if set, the executor executes a shard of the block (see 'execute_block_sharded'), given
+    // by the indices of the shard's transactions in the block.
+    maybe_shard_txn_indices: Option<Vec<TxnIndex>>,
     phantom: PhantomData<(T, E, S)>,
 }
 
@@ -66,7 +76,7 @@ where
 {
     /// The caller needs to ensure that concurrency_level > 1 (0 is illegal and 1 should
     /// be handled by sequential execution) and that concurrency_level <= num_cpus.
-    pub fn new(concurrency_level: usize) -> Self {
+    pub fn new(concurrency_level: usize, maybe_block_gas_limit: Option<u64>) -> Self {
         assert!(
             concurrency_level > 0 && concurrency_level <= num_cpus::get(),
             "Parallel execution concurrency level {} should be between 1 and number of CPUs",
@@ -74,10 +84,38 @@ where
         );
         Self {
             concurrency_level,
+            maybe_block_gas_limit,
+            maybe_recorder: None,
+            maybe_shard_txn_indices: None,
             phantom: PhantomData,
         }
     }
 
+    /// Creates an executor for a shard of the block, i.e. the transactions at 'txn_indices'.
+    /// The deltas are not materialized when the shard's transactions are committed, as the
+    /// outputs are validated (and committed) in the whole block afterwards.
+    fn new_shard(concurrency_level: usize, txn_indices: Vec<TxnIndex>) -> Self {
+        Self {
+            maybe_shard_txn_indices: Some(txn_indices),
+            ..Self::new(concurrency_level, None)
+        }
+    }
+
+    /// Maps the index of a transaction in the executed (shard of the) block to its index in
+    /// the block, which is also used by the speculative logs.
+    fn block_txn_idx(&self, txn_idx: TxnIndex) -> TxnIndex {
+        match &self.maybe_shard_txn_indices {
+            Some(txn_indices) => txn_indices[txn_idx as usize],
+            None => txn_idx,
+        }
+    }
+
+    /// Records the schedule of the parallel execution of the next block to 'recorder'.
+    pub fn with_recorder(mut self, recorder: Arc<ScheduleRecorder<T::Key>>) -> Self {
+        self.maybe_recorder = Some(recorder);
+        self
+    }
+
     fn execute(
         &self,
         version: Version,
@@ -90,7 +128,8 @@ where
     ) -> SchedulerTask {
         let _timer = TASK_EXECUTE_SECONDS.start_timer();
         let (idx_to_execute, incarnation) = version;
-        let txn = &signature_verified_block[idx_to_execute as usize];
+        let block_txn_idx = self.block_txn_idx(idx_to_execute);
+        let txn = &signature_verified_block[block_txn_idx as usize];
 
         let speculative_view = MVHashMapView::new(versioned_cache, scheduler);
 
@@ -98,21 +137,57 @@ where
         let execute_result = executor.execute_transaction(
             &LatestView::<T, S>::new_mv_view(base_view, &speculative_view, idx_to_execute),
             txn,
-            idx_to_execute,
+            block_txn_idx,
             false,
         );
+        if scheduler.done() {
+            // The execution was halted (a transaction can't be executing once all transactions
+            // are committed), so the output will never be committed and needn't be recorded.
+            return SchedulerTask::Done;
+        }
+        let reads = speculative_view.take_reads();
+        if let Some(recorder) = &self.maybe_recorder {
+            // Recorded before the writes are applied, so that any read of the writes is
+            // recorded after this event.
+            recorder.record(ScheduleEvent::Execution {
+                version,
+                reads: reads.clone(),
+            });
+        }
+
+        let updates_outside = Self::apply_execution_result(
+            version,
+            execute_result,
+            reads,
+            last_input_output,
+            versioned_cache,
+        );
+        scheduler.finish_execution(idx_to_execute, incarnation, updates_outside)
+    }
+
+    /// Applies the writes and deltas of the execution of 'version' to the versioned cache,
+    /// removes the entries of the previous incarnation that were not overwritten, and records
+    /// the reads and the result. Returns whether the execution wrote outside of the previous
+    /// write/delta set.
+    fn apply_execution_result(
+        version: Version,
+        execute_result: ExecutionStatus<E::Output, E::Error>,
+        reads: Vec<ReadDescriptor<T::Key>>,
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        versioned_cache: &MVHashMap<T::Key, T::Value, ExecutableTestType>,
+    ) -> bool {
+        let (idx_to_execute, _) = version;
         let mut prev_modified_keys = last_input_output.modified_keys(idx_to_execute);
 
         // For tracking whether the recent execution wrote outside of the previous write/delta set.
         let mut updates_outside = false;
         let mut apply_updates = |output: &E::Output| {
             // First, apply writes.
-            let write_version = (idx_to_execute, incarnation);
             for (k, v) in output.get_writes().into_iter() {
                 if !prev_modified_keys.remove(&k) {
                     updates_outside = true;
                 }
-                versioned_cache.write(&k, write_version, v);
+                versioned_cache.write(&k, version, v);
             }
 
             // Then, apply deltas.
@@ -150,8 +225,8 @@ where
             versioned_cache.delete(&k, idx_to_execute);
         }
 
-        last_input_output.record(idx_to_execute, speculative_view.take_reads(), result);
-        scheduler.finish_execution(idx_to_execute, incarnation, updates_outside)
+        last_input_output.record(idx_to_execute, reads, result);
+        updates_outside
     }
 
     fn validate(
@@ -191,12 +266,18 @@ where
         });
 
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
+        if let Some(recorder) = &self.maybe_recorder {
+            recorder.record(ScheduleEvent::Validation {
+                version: version_to_validate,
+                aborted,
+            });
+        }
 
         if aborted {
             counters::SPECULATIVE_ABORT_COUNT.inc();
 
             // Any logs from the aborted execution should be cleared and not reported.
-            clear_speculative_txn_logs(idx_to_validate as usize);
+            clear_speculative_txn_logs(self.block_txn_idx(idx_to_validate) as usize);
 
             // Not valid and successfully aborted, mark the latest write/delta sets as estimates.
             for k in last_input_output.modified_keys(idx_to_validate) {
@@ -269,27 +350,54 @@ where
         drop(init_timer);
 
         let committing = matches!(role, CommitRole::Coordinator(_, _));
+        let num_txns = self
+            .maybe_shard_txn_indices
+            .as_ref()
+            .map_or(block.len(), |txn_indices| txn_indices.len());
 
         let _timer = WORK_WITH_TASK_SECONDS.start_timer();
         let mut scheduler_task = SchedulerTask::NoTask;
+        let mut accumulated_gas = 0;
         loop {
             // Only one thread does try_commit to avoid contention.
             match &role {
-                CommitRole::Coordinator(post_commit_txs, mut idx) => {
+                // Once the execution is halted, no more transactions may be committed.
+                CommitRole::Coordinator(post_commit_txs, mut idx) if !scheduler.done() => {
                     while let Some(txn_idx) = scheduler.try_commit() {
-                        post_commit_txs[idx]
-                            .send(txn_idx)
-                            .expect("Worker must be available");
-                        // Iterate round robin over workers to do commit_hook.
-                        idx = (idx + 1) % post_commit_txs.len();
+                        if let Some(recorder) = &self.maybe_recorder {
+                            recorder.record(ScheduleEvent::Commit(txn_idx));
+                        }
+                        accumulated_gas += last_input_output.gas_used(txn_idx);
+
+                        // The deltas of a shard are materialized after the validation in the
+                        // whole block.
+                        if self.maybe_shard_txn_indices.is_none() {
+                            post_commit_txs[idx]
+                                .send(txn_idx)
+                                .expect("Worker must be available");
+                            // Iterate round robin over workers to do commit_hook.
+                            idx = (idx + 1) % post_commit_txs.len();
+                        }
 
-                        if txn_idx as usize + 1 == block.len() {
+                        if txn_idx as usize + 1 == num_txns {
                             // Committed the last transaction / everything.
                             scheduler_task = SchedulerTask::Done;
                             break;
                         }
+
+                        if let Some(block_gas_limit) = self.maybe_block_gas_limit {
+                            if accumulated_gas >= block_gas_limit {
+                                // Stop committing, the remaining transactions are skipped. The
+                                // pending task is still handled (e.g. to notify a condvar), and
+                                // the scheduler returns Done afterwards.
+                                counters::BLOCK_GAS_LIMIT_REACHED_COUNT.inc();
+                                scheduler.halt();
+                                break;
+                            }
+                        }
                     }
                 },
+                CommitRole::Coordinator(_, _) => {},
                 CommitRole::Worker(rx) => {
                     while let Ok(txn_idx) = rx.try_recv() {
                         self.commit_hook(txn_idx, versioned_cache, last_input_output, base_view);
@@ -362,10 +470,51 @@ where
             return Ok(vec![]);
         }
 
+        if let Some(recorder) = &self.maybe_recorder {
+            recorder.clear();
+        }
+
         let num_txns = signature_verified_block.len() as u32;
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
 
+        self.run_workers(
+            &executor_initial_arguments,
+            signature_verified_block,
+            &last_input_output,
+            &versioned_cache,
+            &scheduler,
+            base_view,
+        );
+
+        counters::PARALLEL_EXECUTION_ABORT_RATE
+            .observe(scheduler.num_aborts() as f64 / num_txns as f64);
+
+        // Fewer than num_txns transactions are committed if the block gas limit was reached.
+        let (num_committed, _) = scheduler.commit_state();
+        let ret = Self::take_committed_outputs(&last_input_output, num_committed, num_txns);
+
+        RAYON_EXEC_POOL.spawn(move || {
+            // Explicit async drops.
+            drop(last_input_output);
+            drop(scheduler);
+            // TODO: re-use the code cache.
+            drop(versioned_cache);
+        });
+
+        ret
+    }
+
+    /// Runs 'concurrency_level' worker tasks on the block until the scheduler is done.
+    fn run_workers(
+        &self,
+        executor_arguments: &E::Argument,
+        block: &[T],
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        versioned_cache: &MVHashMap<T::Key, T::Value, ExecutableTestType>,
+        scheduler: &Scheduler,
+        base_view: &S,
+    ) {
         let mut roles: Vec<CommitRole> = vec![];
         let mut senders = Vec::with_capacity(self.concurrency_level - 1);
         for _ in 0..(self.concurrency_level - 1) {
@@ -380,65 +529,306 @@ where
         // executors are running concurrently, they will all havean active coordinator.
         roles.push(CommitRole::Coordinator(senders, 0));
 
-        let timer = RAYON_EXECUTION_SECONDS.start_timer();
+        let _timer = RAYON_EXECUTION_SECONDS.start_timer();
         RAYON_EXEC_POOL.scope(|s| {
             for _ in 0..self.concurrency_level {
                 let role = roles.pop().expect("Role must be set for all threads");
                 s.spawn(|_| {
                     self.work_task_with_scope(
-                        &executor_initial_arguments,
-                        signature_verified_block,
-                        &last_input_output,
-                        &versioned_cache,
-                        &scheduler,
+                        executor_arguments,
+                        block,
+                        last_input_output,
+                        versioned_cache,
+                        scheduler,
                         base_view,
                         role,
                     );
                 });
             }
         });
-        drop(timer);
+    }
 
-        let num_txns = num_txns as usize;
-        // TODO: for large block sizes and many cores, extract outputs in parallel.
-        let mut final_results = Vec::with_capacity(num_txns);
+    /// Executes the shards of the block concurrently, each by a separate parallel execution
+    /// over the base state, and then validates the outputs of all shards in the whole block,
+    /// re-executing the transactions that observed a conflicting write of another shard. The
+    /// validation, as well as the commit (incl. the materialization of deltas and the block gas
+    /// limit) are performed by a parallel execution seeded with the outputs of the shards, which
+    /// guarantees the same outputs as when executing the block without shards.
+    pub(crate) fn execute_transactions_sharded(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+        shard_assignment: &[usize],
+    ) -> Result<Vec<E::Output>, E::Error> {
+        let _timer = SHARDED_EXECUTION_SECONDS.start_timer();
+        assert_eq!(
+            signature_verified_block.len(),
+            shard_assignment.len(),
+            "Each transaction must be assigned to a shard"
+        );
+        let num_shards = shard_assignment.iter().max().map_or(0, |shard| shard + 1);
+        let shard_concurrency_level = self.concurrency_level / num_shards.max(1);
+        assert!(shard_concurrency_level > 1, "Must use fewer shards");
+
+        if signature_verified_block.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut shard_txn_indices = vec![Vec::new(); num_shards];
+        for (txn_idx, shard) in shard_assignment.iter().enumerate() {
+            shard_txn_indices[*shard].push(txn_idx as TxnIndex);
+        }
+
+        // Each shard is executed from a separate thread, as the parallel execution blocks the
+        // calling thread until it is done (the shards together use 'concurrency_level' threads).
+        let executor_arguments_ref = &executor_arguments;
+        let shard_outputs = std::thread::scope(|s| {
+            let handles: Vec<_> = shard_txn_indices
+                .into_iter()
+                .filter(|txn_indices| !txn_indices.is_empty())
+                .map(|txn_indices| {
+                    s.spawn(move || {
+                        Self::new_shard(shard_concurrency_level, txn_indices).execute_shard(
+                            executor_arguments_ref,
+                            signature_verified_block,
+                            base_view,
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Shard execution must not panic"))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let num_txns = signature_verified_block.len() as TxnIndex;
+        let last_input_output = TxnLastInputOutput::new(num_txns);
+        let versioned_cache = MVHashMap::new(None);
+        for (txn_idx, reads, output) in shard_outputs.into_iter().flatten() {
+            let execute_result = match output {
+                ExecutionStatus::Success(output) => ExecutionStatus::Success(output),
+                ExecutionStatus::SkipRest(output) => ExecutionStatus::SkipRest(output),
+                ExecutionStatus::Abort(Error::UserError(err)) => ExecutionStatus::Abort(err),
+                ExecutionStatus::Abort(Error::ModulePathReadWrite) => {
+                    unreachable!("Only user errors are recorded as outputs")
+                },
+            };
+            Self::apply_execution_result(
+                (txn_idx, 0),
+                execute_result,
+                reads,
+                &last_input_output,
+                &versioned_cache,
+            );
+        }
+        let scheduler = Scheduler::new_with_executed_txns(num_txns);
+
+        self.run_workers(
+            &executor_arguments,
+            signature_verified_block,
+            &last_input_output,
+            &versioned_cache,
+            &scheduler,
+            base_view,
+        );
+
+        counters::CROSS_SHARD_ABORT_RATE.observe(scheduler.num_aborts() as f64 / num_txns as f64);
+
+        let (num_committed, _) = scheduler.commit_state();
+        let ret = Self::take_committed_outputs(&last_input_output, num_committed, num_txns);
+
+        RAYON_EXEC_POOL.spawn(move || {
+            // Explicit async drops.
+            drop(last_input_output);
+            drop(scheduler);
+            drop(versioned_cache);
+        });
+
+        ret
+    }
+
+    /// Executes the transactions of the shard in parallel, and returns the last read-set and
+    /// output of each transaction, by its index in the block (the deltas are not materialized).
+    #[allow(clippy::type_complexity)]
+    fn execute_shard(
+        &self,
+        executor_arguments: &E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+    ) -> Result<
+        Vec<(
+            TxnIndex,
+            Vec<ReadDescriptor<T::Key>>,
+            ExecutionStatus<E::Output, Error<E::Error>>,
+        )>,
+        E::Error,
+    > {
+        let txn_indices = self
+            .maybe_shard_txn_indices
+            .as_ref()
+            .expect("Must be a shard executor");
+        let num_txns = txn_indices.len() as TxnIndex;
+        let last_input_output = TxnLastInputOutput::new(num_txns);
+        let versioned_cache = MVHashMap::new(None);
+        let scheduler = Scheduler::new(num_txns);
+
+        self.run_workers(
+            executor_arguments,
+            signature_verified_block,
+            &last_input_output,
+            &versioned_cache,
+            &scheduler,
+            base_view,
+        );
 
-        let maybe_err = if last_input_output.module_publishing_may_race() {
+        // The module reads and writes of the aborted incarnations are not validated in the
+        // block, so the fallback must be checked for each shard.
+        if last_input_output.module_publishing_may_race() {
             counters::MODULE_PUBLISHING_FALLBACK_COUNT.inc();
-            Some(Error::ModulePathReadWrite)
-        } else {
-            let mut ret = None;
-            for idx in 0..num_txns {
-                match last_input_output.take_output(idx as TxnIndex) {
-                    ExecutionStatus::Success(t) => final_results.push(t),
-                    ExecutionStatus::SkipRest(t) => {
-                        final_results.push(t);
-                        break;
-                    },
-                    ExecutionStatus::Abort(err) => {
-                        ret = Some(err);
-                        break;
-                    },
-                };
-            }
-            ret
-        };
+            return Err(Error::ModulePathReadWrite);
+        }
+
+        let ret = (0..num_txns)
+            .map(|idx| {
+                let (reads, output) = last_input_output.take_input_output(idx);
+                let reads = reads
+                    .into_iter()
+                    .map(|read| read.into_block_read(txn_indices))
+                    .collect();
+                (txn_indices[idx as usize], reads, output)
+            })
+            .collect();
 
         RAYON_EXEC_POOL.spawn(move || {
             // Explicit async drops.
             drop(last_input_output);
             drop(scheduler);
-            // TODO: re-use the code cache.
             drop(versioned_cache);
         });
 
-        match maybe_err {
-            Some(err) => Err(err),
-            None => {
-                final_results.resize_with(num_txns, E::Output::skip_output);
-                Ok(final_results)
-            },
+        Ok(ret)
+    }
+
+    /// Takes the outputs of the first 'num_committed' transactions, padded with skipped outputs
+    /// to 'num_txns', unless the execution failed.
+    fn take_committed_outputs(
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        num_committed: TxnIndex,
+        num_txns: TxnIndex,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        if last_input_output.module_publishing_may_race() {
+            counters::MODULE_PUBLISHING_FALLBACK_COUNT.inc();
+            return Err(Error::ModulePathReadWrite);
+        }
+
+        // TODO: for large block sizes and many cores, extract outputs in parallel.
+        let mut final_results = Vec::with_capacity(num_txns as usize);
+        for idx in 0..num_committed {
+            match last_input_output.take_output(idx) {
+                ExecutionStatus::Success(t) => final_results.push(t),
+                ExecutionStatus::SkipRest(t) => {
+                    final_results.push(t);
+                    break;
+                },
+                ExecutionStatus::Abort(err) => return Err(err),
+            };
+        }
+
+        final_results.resize_with(num_txns as usize, E::Output::skip_output);
+        Ok(final_results)
+    }
+
+    /// Replays the parallel execution of a block recorded by a 'ScheduleRecorder'
+    /// deterministically, e.g. to reproduce a bug offline. The recorded events are applied in
+    /// order by a single thread, with each execution observing exactly the recorded reads.
+    /// Returns 'Error::ReplayDivergence' if the replay diverges from the recording (e.g. it was
+    /// recorded for another block).
+    pub(crate) fn replay_transactions(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+        recording: &ScheduleRecording<T::Key>,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        if signature_verified_block.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let executor = E::init(executor_arguments);
+        let num_txns = signature_verified_block.len() as TxnIndex;
+        let last_input_output = TxnLastInputOutput::new(num_txns);
+        let versioned_cache = MVHashMap::new(None);
+        let mut replayed_writes = HashMap::new();
+        let mut num_committed = 0;
+
+        for event in recording {
+            match event {
+                ScheduleEvent::Execution { version, reads } => {
+                    let (idx_to_execute, _) = *version;
+                    let txn = signature_verified_block
+                        .get(idx_to_execute as usize)
+                        .ok_or_else(|| {
+                            Error::ReplayDivergence(format!(
+                                "Recorded execution of {:?} out of {} transactions",
+                                version, num_txns
+                            ))
+                        })?;
+                    let execute_result = {
+                        let replay_view = ReplayView::new(base_view, &replayed_writes, reads);
+                        let execute_result =
+                            executor.execute_transaction(&replay_view, txn, idx_to_execute, false);
+                        if let Some(divergence) = replay_view.divergence() {
+                            return Err(Error::ReplayDivergence(format!(
+                                "Execution of {:?}: {}",
+                                version, divergence
+                            )));
+                        }
+                        execute_result
+                    };
+
+                    if let ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) =
+                        &execute_result
+                    {
+                        let writes = output
+                            .get_writes()
+                            .into_iter()
+                            .map(|(k, v)| (k, v.as_state_value()))
+                            .collect();
+                        replayed_writes.insert(*version, writes);
+                    }
+                    Self::apply_execution_result(
+                        *version,
+                        execute_result,
+                        reads.clone(),
+                        &last_input_output,
+                        &versioned_cache,
+                    );
+                },
+                ScheduleEvent::Validation { version, aborted } => {
+                    if *aborted {
+                        let (idx_to_validate, _) = *version;
+                        clear_speculative_txn_logs(idx_to_validate as usize);
+                        for k in last_input_output.modified_keys(idx_to_validate) {
+                            versioned_cache.mark_estimate(&k, idx_to_validate);
+                        }
+                    }
+                },
+                ScheduleEvent::Commit(txn_idx) => {
+                    if *txn_idx != num_committed {
+                        return Err(Error::ReplayDivergence(format!(
+                            "Recorded commit of {} while {} transactions are committed",
+                            txn_idx, num_committed
+                        )));
+                    }
+                    self.commit_hook(*txn_idx, &versioned_cache, &last_input_output, base_view);
+                    num_committed += 1;
+                },
+            }
         }
+
+        Self::take_committed_outputs(&last_input_output, num_committed, num_txns)
     }
 
     pub(crate) fn execute_transactions_sequential(
@@ -452,6 +842,7 @@ where
         let mut data_map = BTreeMap::new();
 
         let mut ret = Vec::with_capacity(num_txns);
+        let mut accumulated_gas = 0;
         for (idx, txn) in signature_verified_block.iter().enumerate() {
             let res = executor.execute_transaction(
                 &LatestView::<T, S>::new_btree_view(base_view, &data_map, idx as TxnIndex),
@@ -460,7 +851,7 @@ where
                 true,
             );
 
-            let must_skip = matches!(res, ExecutionStatus::SkipRest(_));
+            let mut must_skip = matches!(res, ExecutionStatus::SkipRest(_));
 
             match res {
                 ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
@@ -469,6 +860,7 @@ where
                         0,
                         "Sequential execution must materialize deltas"
                     );
+                    accumulated_gas += output.gas_used();
                     // Apply the writes.
                     for (ap, write_op) in output.get_writes().into_iter() {
                         data_map.insert(ap, write_op);
@@ -481,6 +873,14 @@ where
                 },
             }
 
+            if let Some(block_gas_limit) = self.maybe_block_gas_limit {
+                if accumulated_gas >= block_gas_limit && idx + 1 < num_txns {
+                    // Same as in parallel execution, the remaining transactions are skipped.
+                    counters::BLOCK_GAS_LIMIT_REACHED_COUNT.inc();
+                    must_skip = true;
+                }
+            }
+
             if must_skip {
                 break;
             }
@@ -496,7 +896,7 @@ where
         signature_verified_block: Vec<T>,
         base_view: &S,
     ) -> Result<Vec<E::Output>, E::Error> {
-        let mut ret = if self.concurrency_level > 1 {
+        let ret = if self.concurrency_level > 1 {
             self.execute_transactions_parallel(
                 executor_arguments,
                 &signature_verified_block,
@@ -510,6 +910,81 @@ where
             )
         };
 
+        self.sequential_fallback_if_needed(
+            ret,
+            executor_arguments,
+            signature_verified_block,
+            base_view,
+        )
+    }
+
+    /// Same as 'execute_block', but the block is partitioned into shards, given by the shard of
+    /// each transaction in 'shard_assignment', that are executed concurrently by separate
+    /// parallel executions (see 'execute_transactions_sharded'). The partitioning only affects
+    /// the performance, i.e. transactions estimated to conflict should be in the same shard.
+    /// Falls back to 'execute_block' if there are not enough threads for at least two per shard,
+    /// or if the schedule is recorded.
+    pub fn execute_block_sharded(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        base_view: &S,
+        shard_assignment: &[usize],
+    ) -> Result<Vec<E::Output>, E::Error> {
+        let num_shards = shard_assignment.iter().max().map_or(0, |shard| shard + 1);
+        if num_shards < 2
+            || self.concurrency_level / num_shards < 2
+            || self.maybe_recorder.is_some()
+        {
+            return self.execute_block(executor_arguments, signature_verified_block, base_view);
+        }
+
+        let ret = self.execute_transactions_sharded(
+            executor_arguments,
+            &signature_verified_block,
+            base_view,
+            shard_assignment,
+        );
+
+        self.sequential_fallback_if_needed(
+            ret,
+            executor_arguments,
+            signature_verified_block,
+            base_view,
+        )
+    }
+
+    /// Same as 'execute_block', but the parallel execution is replayed from the recording of the
+    /// 'ScheduleRecorder' instead (see 'replay_transactions').
+    pub fn replay_block(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        base_view: &S,
+        recording: &ScheduleRecording<T::Key>,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        let ret = self.replay_transactions(
+            executor_arguments,
+            &signature_verified_block,
+            base_view,
+            recording,
+        );
+
+        self.sequential_fallback_if_needed(
+            ret,
+            executor_arguments,
+            signature_verified_block,
+            base_view,
+        )
+    }
+
+    fn sequential_fallback_if_needed(
+        &self,
+        mut ret: Result<Vec<E::Output>, E::Error>,
+        executor_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        base_view: &S,
+    ) -> Result<Vec<E::Output>, E::Error> {
         if matches!(ret, Err(Error::ModulePathReadWrite)) {
             debug!("[Execution]: Module read & written, sequential fallback");
 
@@ -141,6 +141,7 @@ pub mod errors;
 pub mod executor;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
+pub mod recorder;
 mod scheduler;
 pub mod task;
 mod txn_last_input_output;
@@ -9,6 +9,7 @@ use crate::{
         DeltaDataView, EmptyDataView, ExpectedOutput, KeyType, Task, Transaction, TransactionGen,
         TransactionGenParams, ValueType,
     },
+    recorder::{ScheduleEvent, ScheduleRecorder},
 };
 use claims::assert_ok;
 use num_cpus;
@@ -19,7 +20,7 @@ use proptest::{
     strategy::{Strategy, ValueTree},
     test_runner::TestRunner,
 };
-use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+use std::{cmp::min, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
 
 fn run_transactions<K, V>(
     key_universe: &[K],
@@ -55,7 +56,7 @@ fn run_transactions<K, V>(
             Transaction<KeyType<K>, ValueType<V>>,
             Task<KeyType<K>, ValueType<V>>,
             EmptyDataView<KeyType<K>, ValueType<V>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view);
 
         if module_access.0 && module_access.1 {
@@ -179,7 +180,7 @@ fn deltas_writes_mixed() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view);
 
         let baseline = ExpectedOutput::generate_baseline(&transactions, None);
@@ -187,6 +188,50 @@ fn deltas_writes_mixed() {
     }
 }
 
+#[test]
+fn sharded_deltas_writes_mixed() {
+    let mut runner = TestRunner::default();
+    let num_txns = 1000;
+
+    let universe = vec(any::<[u8; 32]>(), 50)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+        num_txns,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+
+    // Do not allow deletions as resolver can't apply delta to a deleted aggregator.
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize_with_deltas(&universe, 15, false))
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    // Arbitrary shards, so that the outputs must be fixed by the cross-shard validation.
+    let num_shards = (num_cpus::get() / 2).clamp(1, 4);
+    let shard_assignment: Vec<_> = (0..num_txns).map(|idx| idx % num_shards).collect();
+
+    for _ in 0..20 {
+        let output = BlockExecutor::<
+            Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+            Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+            DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        >::new((num_shards * 2).min(num_cpus::get()), None)
+        .execute_transactions_sharded((), &transactions, &data_view, &shard_assignment);
+
+        let baseline = ExpectedOutput::generate_baseline(&transactions, None);
+        baseline.assert_output(&output);
+    }
+}
+
 #[test]
 fn deltas_resolver() {
     let mut runner = TestRunner::default();
@@ -219,7 +264,7 @@ fn deltas_resolver() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view);
 
         let delta_writes = output
@@ -261,6 +306,118 @@ fn dynamic_read_writes_contended() {
     );
 }
 
+#[test]
+fn block_gas_limit() {
+    let mut runner = TestRunner::default();
+    let num_txns = 1000;
+
+    // A small universe, so that many executions wait on read dependencies when halted.
+    let universe = vec(any::<[u8; 32]>(), 10)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+        num_txns,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize(&universe, (false, false)))
+        .collect();
+
+    let data_view = EmptyDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    // Every transaction uses 1 gas, so exactly block_gas_limit transactions get committed.
+    for block_gas_limit in [1, 10, 100, 999, 1000, 2000] {
+        let num_committed = min(block_gas_limit as usize, num_txns);
+        for _ in 0..10 {
+            let output = BlockExecutor::<
+                Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+                Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+                EmptyDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+            >::new(num_cpus::get(), Some(block_gas_limit))
+            .execute_transactions_parallel((), &transactions, &data_view);
+
+            // Only committed outputs incorporate the (here empty) delta writes, the rest
+            // must be skip outputs.
+            let results = output.as_ref().expect("Must be success");
+            assert_eq!(results.len(), num_txns);
+            for (idx, result) in results.iter().enumerate() {
+                assert_eq!(result.3.get().is_some(), idx < num_committed);
+            }
+
+            let baseline = ExpectedOutput::generate_baseline(&transactions[..num_committed], None);
+            baseline.assert_output(&output);
+        }
+    }
+}
+
+#[test]
+fn record_and_replay() {
+    let mut runner = TestRunner::default();
+    let num_txns = 1000;
+
+    let universe = vec(any::<[u8; 32]>(), 50)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    // Static transactions, as the replay executes each incarnation again.
+    let transaction_gen = vec(any::<TransactionGen<[u8; 32]>>(), num_txns)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize_with_deltas(&universe, 15, false))
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let baseline = ExpectedOutput::generate_baseline(&transactions, None);
+
+    let recorder = Arc::new(ScheduleRecorder::new());
+    let block_executor = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    >::new(num_cpus::get(), None)
+    .with_recorder(recorder.clone());
+
+    for _ in 0..5 {
+        let output = block_executor.execute_transactions_parallel((), &transactions, &data_view);
+        baseline.assert_output(&output);
+
+        let recording = recorder.take_recording();
+        assert!(recording.len() >= num_txns);
+        let output = block_executor.replay_transactions((), &transactions, &data_view, &recording);
+        baseline.assert_output(&output);
+    }
+
+    // Replaying the recording against another block, or with a commit missing, must diverge.
+    let _ = block_executor.execute_transactions_parallel((), &transactions, &data_view);
+    let recording = recorder.take_recording();
+    let reversed_transactions: Vec<_> = transactions.iter().rev().cloned().collect();
+    let output =
+        block_executor.replay_transactions((), &reversed_transactions, &data_view, &recording);
+    assert!(matches!(output, Err(Error::ReplayDivergence(_))));
+
+    let mut missing_commit = recording;
+    let first_commit = missing_commit
+        .iter()
+        .position(|event| matches!(event, ScheduleEvent::Commit(_)))
+        .unwrap();
+    missing_commit.remove(first_commit);
+    let output = block_executor.replay_transactions((), &transactions, &data_view, &missing_commit);
+    assert!(matches!(output, Err(Error::ReplayDivergence(_))));
+}
+
 #[test]
 fn module_publishing_fallback() {
     let mut runner = TestRunner::default();
@@ -360,7 +517,7 @@ fn publishing_fixed_params() {
         Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
         Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
         DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-    >::new(num_cpus::get())
+    >::new(num_cpus::get(), None)
     .execute_transactions_parallel((), &transactions, &data_view);
     assert_ok!(output);
 
@@ -396,7 +553,7 @@ fn publishing_fixed_params() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view);
 
         assert_eq!(output.unwrap_err(), Error::ModulePathReadWrite);
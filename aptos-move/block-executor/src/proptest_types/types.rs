@@ -502,6 +502,10 @@ where
         Self(vec![], vec![], vec![], OnceCell::new())
     }
 
+    fn gas_used(&self) -> u64 {
+        1
+    }
+
     fn incorporate_delta_writes(&self, delta_writes: Vec<(K, WriteOp)>) {
         assert_ok!(self.3.set(delta_writes));
     }
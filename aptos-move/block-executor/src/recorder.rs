@@ -0,0 +1,62 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::txn_last_input_output::ReadDescriptor;
+use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::{TxnIndex, Version};
+use serde::{Deserialize, Serialize};
+
+/// A step of the parallel execution that affected the outcome of the block. The steps are
+/// recorded in the order in which they took effect, which is sufficient to deterministically
+/// replay the execution (see 'BlockExecutor::replay_block').
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ScheduleEvent<K> {
+    /// An incarnation finished executing, with all of the reads it performed (in order).
+    /// Recorded before the writes of the incarnation become visible to other transactions.
+    Execution {
+        version: Version,
+        reads: Vec<ReadDescriptor<K>>,
+    },
+    /// An incarnation was validated, and aborted if the validation failed.
+    Validation { version: Version, aborted: bool },
+    /// A transaction was committed.
+    Commit(TxnIndex),
+}
+
+/// The schedule of a parallel execution of a block, as recorded by the 'ScheduleRecorder'.
+pub type ScheduleRecording<K> = Vec<ScheduleEvent<K>>;
+
+/// Opt-in recorder of the schedule of a parallel execution, used to reproduce rare bugs that
+/// depend on the (non-deterministic) interleaving of the scheduler tasks. Recording serializes
+/// the threads on a lock, so it should not be enabled on the critical path.
+pub struct ScheduleRecorder<K> {
+    events: Mutex<ScheduleRecording<K>>,
+}
+
+impl<K> ScheduleRecorder<K> {
+    pub fn new() -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn record(&self, event: ScheduleEvent<K>) {
+        self.events.lock().push(event);
+    }
+
+    /// Clears the events of a previous block.
+    pub(crate) fn clear(&self) {
+        self.events.lock().clear();
+    }
+
+    /// Returns the events recorded during the last parallel execution.
+    pub fn take_recording(&self) -> ScheduleRecording<K> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+impl<K> Default for ScheduleRecorder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -23,6 +23,17 @@ pub type Wave = u32;
 
 type DependencyCondvar = Arc<(Mutex<bool>, Condvar)>;
 
+/// The outcome of registering a read dependency of a transaction on another transaction.
+#[derive(Debug)]
+pub enum DependencyResult {
+    /// The dependency was recorded, the caller must wait on the condition variable.
+    Dependency(DependencyCondvar),
+    /// The dependency got resolved in the meantime, the caller should repeat the read.
+    Resolved,
+    /// The execution was halted, the caller should finish the ongoing execution early.
+    ExecutionHalted,
+}
+
 /// A holder for potential task returned from the Scheduler. ExecutionTask and ValidationTask
 /// each contain a version of transaction that must be executed or validated, respectively.
 /// NoTask holds no task (similar None if we wrapped tasks in Option), and Done implies that
@@ -204,6 +215,9 @@ pub struct Scheduler {
 
     /// Shared marker that is set when a thread detects that all txns can be committed.
     done_marker: CachePadded<AtomicBool>,
+
+    /// Number of successful aborts (each leading to a re-execution), for metrics.
+    num_aborts: AtomicU32,
 }
 
 /// Public Interfaces for the Scheduler
@@ -229,7 +243,20 @@ impl Scheduler {
             execution_idx: AtomicU32::new(0),
             validation_idx: AtomicU64::new(0),
             done_marker: CachePadded::new(AtomicBool::new(false)),
+            num_aborts: AtomicU32::new(0),
+        }
+    }
+
+    /// Creates a scheduler for transactions that were all already executed (incarnation 0),
+    /// e.g. when the outputs of the shards of a block are validated against the whole block.
+    /// Only the transactions that fail the validation are re-executed.
+    pub fn new_with_executed_txns(num_txns: TxnIndex) -> Self {
+        let scheduler = Self::new(num_txns);
+        for txn_status in scheduler.txn_status.iter() {
+            *txn_status.0.write() = ExecutionStatus::Executed(0);
         }
+        scheduler.execution_idx.store(num_txns, Ordering::SeqCst);
+        scheduler
     }
 
     /// If successful, returns Some(TxnIndex), the index of committed transaction.
@@ -276,13 +303,17 @@ impl Scheduler {
         None
     }
 
-    #[cfg(test)]
     /// Return the TxnIndex and Wave of current commit index
     pub fn commit_state(&self) -> (TxnIndex, u32) {
         let commit_state = self.commit_state.lock();
         (commit_state.0, commit_state.1)
     }
 
+    /// Return the number of successful aborts so far.
+    pub fn num_aborts(&self) -> u32 {
+        self.num_aborts.load(Ordering::Relaxed)
+    }
+
     /// Try to abort version = (txn_idx, incarnation), called upon validation failure.
     /// When the invocation manages to update the status of the transaction, it changes
     /// Executed(incarnation) => Aborting(incarnation), it returns true. Otherwise,
@@ -297,12 +328,34 @@ impl Scheduler {
 
         if *status == ExecutionStatus::Executed(incarnation) {
             *status = ExecutionStatus::Aborting(incarnation);
+            self.num_aborts.fetch_add(1, Ordering::Relaxed);
             true
         } else {
             false
         }
     }
 
+    /// Stop the parallel execution early, i.e. when the block gas limit is reached. Sets the
+    /// done marker so that no more tasks are handed out, and wakes up all executions that are
+    /// waiting on a read dependency (the dependency may never be resolved otherwise).
+    /// The caller (the committing thread) must not call try_commit afterwards.
+    pub fn halt(&self) {
+        self.done_marker.store(true, Ordering::SeqCst);
+
+        // Executions that get suspended after this point observe the done marker (checked
+        // in suspend while holding the status lock) and do not wait.
+        for txn_status in self.txn_status.iter() {
+            let status = txn_status.0.read();
+            if let ExecutionStatus::Suspended(_, dep_condvar)
+            | ExecutionStatus::ReadyToExecute(_, Some(dep_condvar)) = &*status
+            {
+                let (lock, cvar) = &**dep_condvar;
+                *lock.lock() = true;
+                cvar.notify_one();
+            }
+        }
+    }
+
     /// Return the next task for the thread.
     pub fn next_task(&self, committing: bool) -> SchedulerTask {
         let _timer = GET_NEXT_TASK_SECONDS.start_timer();
@@ -351,16 +404,17 @@ impl Scheduler {
     }
 
     /// When a txn depends on another txn, adds it to the dependency list of the other txn.
-    /// Returns true if successful, or false, if the dependency got resolved in the meantime.
-    /// If true is returned, Scheduler guarantees that later (dep_txn_idx will finish execution)
-    /// transaction txn_idx will be resumed, and corresponding execution task created.
-    /// If false is returned, it is caller's responsibility to repeat the read that caused the
-    /// dependency and continue the ongoing execution of txn_idx.
+    /// Returns Dependency if successful, or Resolved, if the dependency got resolved in the
+    /// meantime. If Dependency is returned, Scheduler guarantees that later (dep_txn_idx will
+    /// finish execution) transaction txn_idx will be resumed, and corresponding execution task
+    /// created. If Resolved is returned, it is caller's responsibility to repeat the read that
+    /// caused the dependency and continue the ongoing execution of txn_idx. ExecutionHalted is
+    /// returned if the execution was halted, as the dependency may never get resolved.
     pub fn wait_for_dependency(
         &self,
         txn_idx: TxnIndex,
         dep_txn_idx: TxnIndex,
-    ) -> Option<DependencyCondvar> {
+    ) -> DependencyResult {
         // Note: Could pre-check that txn dep_txn_idx isn't in an executed state, but the caller
         // usually has just observed the read dependency.
 
@@ -378,9 +432,11 @@ impl Scheduler {
             // To avoid zombie dependency (and losing liveness), must return here and
             // not add a (stale) dependency.
 
-            return None;
+            return DependencyResult::Resolved;
+        }
+        if !self.suspend(txn_idx, dep_condvar.clone()) {
+            return DependencyResult::ExecutionHalted;
         }
-        self.suspend(txn_idx, dep_condvar.clone());
 
         // Safe to add dependency here (still holding the lock) - finish_execution of txn
         // dep_txn_idx is guaranteed to acquire the same lock later and clear the dependency.
@@ -388,7 +444,7 @@ impl Scheduler {
 
         // Stored deps gets unlocked here.
 
-        Some(dep_condvar)
+        DependencyResult::Dependency(dep_condvar)
     }
 
     pub fn finish_validation(&self, txn_idx: TxnIndex, wave: Wave) {
@@ -677,12 +733,18 @@ impl Scheduler {
     }
 
     /// Put a transaction in a suspended state, with a condition variable that can be
-    /// used to wake it up after the dependency is resolved.
-    fn suspend(&self, txn_idx: TxnIndex, dep_condvar: DependencyCondvar) {
+    /// used to wake it up after the dependency is resolved. Returns false (and does not
+    /// suspend) if the execution was halted.
+    fn suspend(&self, txn_idx: TxnIndex, dep_condvar: DependencyCondvar) -> bool {
         let mut status = self.txn_status[txn_idx as usize].0.write();
 
+        if self.done() {
+            return false;
+        }
+
         if let ExecutionStatus::Executing(incarnation) = *status {
             *status = ExecutionStatus::Suspended(incarnation, dep_condvar);
+            true
         } else {
             unreachable!();
         }
@@ -722,8 +784,9 @@ impl Scheduler {
         *status = ExecutionStatus::ReadyToExecute(incarnation + 1, None);
     }
 
-    /// Checks whether the done marker is set. The marker can only be set by 'try_commit'.
-    fn done(&self) -> bool {
+    /// Checks whether the done marker is set. The marker can only be set by 'try_commit'
+    /// (once all transactions are committed) or by 'halt'.
+    pub fn done(&self) -> bool {
         self.done_marker.load(Ordering::Acquire)
     }
 }
@@ -85,6 +85,9 @@ pub trait TransactionOutput: Send + Sync + Debug {
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self;
 
+    /// Gas charged for the transaction, accumulated against the block gas limit.
+    fn gas_used(&self) -> u64;
+
     /// In parallel execution, will be called once per transaction when the output is
     /// ready to be committed. In sequential execution, won't be called (deltas are
     /// materialized and incorporated during execution).
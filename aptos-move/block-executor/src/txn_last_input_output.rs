@@ -10,6 +10,7 @@ use aptos_types::{access_path::AccessPath, executable::ModulePath, write_set::Wr
 use arc_swap::ArcSwapOption;
 use crossbeam::utils::CachePadded;
 use dashmap::DashSet;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashSet,
     fmt::Debug,
@@ -36,8 +37,8 @@ impl<T: TransactionOutput, E: Debug> TxnOutput<T, E> {
 }
 
 /// Information about the read which is used by validation.
-#[derive(Clone, PartialEq)]
-enum ReadKind {
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) enum ReadKind {
     /// Read returned a value from the multi-version data-structure, with index
     /// and incarnation number of the execution associated with the write of
     /// that entry.
@@ -50,7 +51,7 @@ enum ReadKind {
     DeltaApplicationFailure,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ReadDescriptor<K> {
     access_path: K,
 
@@ -94,6 +95,24 @@ impl<K: ModulePath> ReadDescriptor<K> {
         &self.access_path
     }
 
+    pub(crate) fn kind(&self) -> &ReadKind {
+        &self.kind
+    }
+
+    /// Translates a read of a committed transaction in the execution of a shard (see
+    /// 'BlockExecutor::execute_block_sharded') to the corresponding read in the whole block,
+    /// given the indices of the shard's transactions in the block. A committed read observed
+    /// the last incarnation of the writer, which is seeded as incarnation 0 in the block.
+    pub(crate) fn into_block_read(self, shard_txn_indices: &[TxnIndex]) -> Self {
+        match self.kind {
+            ReadKind::Version(txn_idx, _) => Self {
+                access_path: self.access_path,
+                kind: ReadKind::Version(shard_txn_indices[txn_idx as usize], 0),
+            },
+            _ => self,
+        }
+    }
+
     // Does the read descriptor describe a read from MVHashMap w. a specified version.
     pub fn validate_version(&self, version: Version) -> bool {
         let (txn_idx, incarnation) = version;
@@ -254,6 +273,18 @@ impl<K: ModulePath, T: TransactionOutput, E: Debug + Send + Clone> TxnLastInputO
         )
     }
 
+    // Gas used by the committed transaction, accumulated against the block gas limit.
+    pub(crate) fn gas_used(&self, txn_idx: TxnIndex) -> u64 {
+        match &self.outputs[txn_idx as usize]
+            .load_full()
+            .expect("Output must exist")
+            .output_status
+        {
+            ExecutionStatus::Success(t) | ExecutionStatus::SkipRest(t) => t.gas_used(),
+            ExecutionStatus::Abort(_) => 0,
+        }
+    }
+
     // Called when a transaction is committed to record WriteOps for materialized aggregator values
     // corresponding to the (deltas) in the recorded final output of the transaction.
     pub(crate) fn record_delta_writes(
@@ -273,6 +304,22 @@ impl<K: ModulePath, T: TransactionOutput, E: Debug + Send + Clone> TxnLastInputO
         };
     }
 
+    // Must be executed after parallel execution is done, grabs the last read-set and output.
+    // Will panic if other outstanding references to the recorded output exist.
+    pub(crate) fn take_input_output(
+        &self,
+        txn_idx: TxnIndex,
+    ) -> (Vec<ReadDescriptor<K>>, ExecutionStatus<T, Error<E>>)
+    where
+        K: Clone,
+    {
+        let input = self.inputs[txn_idx as usize]
+            .swap(None)
+            .expect("Input must be recorded after execution");
+        let input = Arc::try_unwrap(input).unwrap_or_else(|input| input.as_ref().clone());
+        (input, self.take_output(txn_idx))
+    }
+
     // Must be executed after parallel execution is done, grabs outputs. Will panic if
     // other outstanding references to the recorded outputs exist.
     pub(crate) fn take_output(&self, txn_idx: TxnIndex) -> ExecutionStatus<T, Error<E>> {
@@ -5,7 +5,7 @@
 use crate::{
     executor::BlockExecutor,
     proptest_types::types::{DeltaDataView, ExpectedOutput, KeyType, Task, Transaction, ValueType},
-    scheduler::{Scheduler, SchedulerTask},
+    scheduler::{DependencyResult, Scheduler, SchedulerTask},
 };
 use aptos_aggregator::delta_change_set::{delta_add, delta_sub, DeltaOp, DeltaUpdate};
 use aptos_mvhashmap::types::TxnIndex;
@@ -30,9 +30,11 @@ where
         phantom: PhantomData,
     };
 
-    let output =
-        BlockExecutor::<Transaction<K, V>, Task<K, V>, DeltaDataView<K, V>>::new(num_cpus::get())
-            .execute_transactions_parallel((), &transactions, &data_view);
+    let output = BlockExecutor::<Transaction<K, V>, Task<K, V>, DeltaDataView<K, V>>::new(
+        num_cpus::get(),
+        None,
+    )
+    .execute_transactions_parallel((), &transactions, &data_view);
 
     let baseline = ExpectedOutput::generate_baseline(&transactions, None);
     baseline.assert_output(&output);
@@ -418,9 +420,9 @@ fn scheduler_dependency() {
     ));
 
     // Current status of 0 is executed - hence, no dependency added.
-    assert!(s.wait_for_dependency(3, 0).is_none());
+    assert_matches!(s.wait_for_dependency(3, 0), DependencyResult::Resolved);
     // Dependency added for transaction 4 on transaction 2.
-    assert!(s.wait_for_dependency(4, 2).is_some());
+    assert_matches!(s.wait_for_dependency(4, 2), DependencyResult::Dependency(_));
 
     assert!(matches!(
         s.finish_execution(2, 0, false),
@@ -434,6 +436,34 @@ fn scheduler_dependency() {
     ));
 }
 
+#[test]
+fn scheduler_halt() {
+    let s = Scheduler::new(10);
+
+    for i in 0..3 {
+        assert!(matches!(
+            s.next_task(false),
+            SchedulerTask::ExecutionTask((j, 0), None) if j == i
+        ));
+    }
+
+    // Dependency added for transaction 1 on transaction 0.
+    let dep_condvar = match s.wait_for_dependency(1, 0) {
+        DependencyResult::Dependency(dep_condvar) => dep_condvar,
+        _ => unreachable!(),
+    };
+
+    s.halt();
+
+    // The waiting execution is woken up, new dependencies are not waited on.
+    assert!(*dep_condvar.0.lock());
+    assert_matches!(
+        s.wait_for_dependency(2, 0),
+        DependencyResult::ExecutionHalted
+    );
+    assert!(matches!(s.next_task(false), SchedulerTask::Done));
+}
+
 // Will return a scheduler in a state where all transactions are scheduled for
 // for execution, validation index = num_txns, and wave = 0.
 fn incarnation_one_scheduler(num_txns: TxnIndex) -> Scheduler {
@@ -468,8 +498,8 @@ fn scheduler_incarnation() {
     let s = incarnation_one_scheduler(5);
 
     // execution/validation index = 5, wave = 0.
-    assert!(s.wait_for_dependency(1, 0).is_some());
-    assert!(s.wait_for_dependency(3, 0).is_some());
+    assert_matches!(s.wait_for_dependency(1, 0), DependencyResult::Dependency(_));
+    assert_matches!(s.wait_for_dependency(3, 0), DependencyResult::Dependency(_));
 
     // Because validation index is higher, return validation task to caller (even with
     // revalidate_suffix = true) - because now we always decrease validation idx to txn_idx + 1
@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters, scheduler::Scheduler, task::Transaction, txn_last_input_output::ReadDescriptor,
+    counters,
+    scheduler::{DependencyResult, Scheduler},
+    task::Transaction,
+    txn_last_input_output::{ReadDescriptor, ReadKind},
 };
 use anyhow::Result;
 use aptos_aggregator::delta_change_set::{deserialize, serialize};
 use aptos_logger::error;
 use aptos_mvhashmap::{
-    types::{MVCodeError, MVCodeOutput, MVDataError, MVDataOutput, TxnIndex},
+    types::{MVCodeError, MVCodeOutput, MVDataError, MVDataOutput, TxnIndex, Version},
     MVHashMap,
 };
 use aptos_state_view::{StateViewId, TStateView};
@@ -19,7 +22,13 @@ use aptos_types::{
     write_set::TransactionWrite,
 };
 use aptos_vm_logging::{log_schema::AdapterLogSchema, prelude::*};
-use std::{cell::RefCell, collections::BTreeMap, fmt::Debug, hash::Hash, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    sync::Arc,
+};
 
 /// A struct that is always used by a single thread performing an execution task. The struct is
 /// passed to the VM and acts as a proxy to resolve reads first in the shared multi-version
@@ -120,7 +129,7 @@ impl<
                 Err(Dependency(dep_idx)) => {
                     // `self.txn_idx` estimated to depend on a write from `dep_idx`.
                     match self.scheduler.wait_for_dependency(txn_idx, dep_idx) {
-                        Some(dep_condition) => {
+                        DependencyResult::Dependency(dep_condition) => {
                             let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
                             // Wait on a condition variable corresponding to the encountered
                             // read dependency. Once the dep_idx finishes re-execution, scheduler
@@ -142,7 +151,10 @@ impl<
                                 dep_resolved = cvar.wait(dep_resolved).unwrap();
                             }
                         },
-                        None => continue,
+                        DependencyResult::Resolved => continue,
+                        // The output of the ongoing execution will not be committed, so
+                        // the read result does not matter, but it must not block.
+                        DependencyResult::ExecutionHalted => return ReadResult::None,
                     }
                 },
                 Err(DeltaApplicationFailure) => {
@@ -159,6 +171,115 @@ impl<
     }
 }
 
+/// A view used to replay a recorded execution of a transaction, see 'BlockExecutor::replay_block'.
+/// Each read observes the recorded outcome: the value written by the recorded version (as
+/// replayed), the resolved aggregator value, or storage. The first read that deviates from the
+/// recorded reads is captured as a divergence, after which the reads are served from storage.
+pub(crate) struct ReplayView<'a, K, S> {
+    base_view: &'a S,
+    // The values written by the replayed incarnations.
+    replayed_writes: &'a HashMap<Version, HashMap<K, Option<StateValue>>>,
+    recorded_reads: &'a [ReadDescriptor<K>],
+    next_read: RefCell<usize>,
+    divergence: RefCell<Option<String>>,
+}
+
+impl<'a, K: ModulePath + Debug + Hash + Eq, S: TStateView<Key = K>> ReplayView<'a, K, S> {
+    pub(crate) fn new(
+        base_view: &'a S,
+        replayed_writes: &'a HashMap<Version, HashMap<K, Option<StateValue>>>,
+        recorded_reads: &'a [ReadDescriptor<K>],
+    ) -> Self {
+        Self {
+            base_view,
+            replayed_writes,
+            recorded_reads,
+            next_read: RefCell::new(0),
+            divergence: RefCell::new(None),
+        }
+    }
+
+    /// Returns the divergence from the recorded reads, if any, including missing reads.
+    pub(crate) fn divergence(&self) -> Option<String> {
+        let next_read = *self.next_read.borrow();
+        self.divergence.borrow().clone().or_else(|| {
+            (next_read < self.recorded_reads.len()).then(|| {
+                format!(
+                    "{} out of {} recorded reads performed",
+                    next_read,
+                    self.recorded_reads.len()
+                )
+            })
+        })
+    }
+
+    /// Returns the value observed by the recorded read of 'state_key', or None if it was read
+    /// from storage, or the divergence if the read was not recorded.
+    fn replay_read(&self, state_key: &K) -> Result<Option<Option<StateValue>>, String> {
+        let mut next_read = self.next_read.borrow_mut();
+        let read = match self.recorded_reads.get(*next_read) {
+            Some(read) if read.path() == state_key => read,
+            read => {
+                return Err(format!(
+                    "read {:?}, recorded {:?}",
+                    state_key,
+                    read.map(ReadDescriptor::path)
+                ))
+            },
+        };
+        *next_read += 1;
+
+        match read.kind() {
+            ReadKind::Version(txn_idx, incarnation) => self
+                .replayed_writes
+                .get(&(*txn_idx, *incarnation))
+                .and_then(|writes| writes.get(state_key))
+                .map(|value| Some(value.clone()))
+                .ok_or_else(|| {
+                    format!(
+                        "{:?} not written by ({}, {}) in the replay",
+                        state_key, txn_idx, incarnation
+                    )
+                }),
+            ReadKind::Resolved(value) => Ok(Some(Some(StateValue::new_legacy(serialize(value))))),
+            // Same as in the parallel execution, see 'MVHashMapView::fetch_data'.
+            ReadKind::DeltaApplicationFailure => {
+                Ok(Some(Some(StateValue::new_legacy(serialize(&0)))))
+            },
+            ReadKind::Storage => Ok(None),
+        }
+    }
+}
+
+impl<'a, K: ModulePath + Debug + Hash + Eq, S: TStateView<Key = K>> TStateView
+    for ReplayView<'a, K, S>
+{
+    type Key = K;
+
+    fn get_state_value(&self, state_key: &K) -> anyhow::Result<Option<StateValue>> {
+        if self.divergence.borrow().is_none() {
+            match self.replay_read(state_key) {
+                Ok(Some(value)) => return Ok(value),
+                Ok(None) => {},
+                Err(divergence) => *self.divergence.borrow_mut() = Some(divergence),
+            }
+        }
+        self.base_view.get_state_value(state_key)
+    }
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base_view.is_genesis()
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base_view.get_usage()
+    }
+}
+
 enum ViewMapKind<'a, T: Transaction> {
     MultiVersion(&'a MVHashMapView<'a, T::Key, T::Value>),
     BTree(&'a BTreeMap<T::Key, T::Value>),
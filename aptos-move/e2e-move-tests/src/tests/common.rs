@@ -1,6 +1,23 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    secp256k1_ecdsa::Secp256k1EcdsaPrivateKey, secp256r1_ecdsa::Secp256r1EcdsaPrivateKey,
+    PrivateKey, Uniform,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    on_chain_config::FeatureFlag,
+    transaction::{
+        authenticator::AuthenticationKey, webauthn::PartialAuthenticatorAssertionResponse,
+        RawTransaction, SignedTransaction, TransactionStatus,
+    },
+    vm_status::StatusCode,
+};
+use rand::rngs::OsRng;
 use std::{collections::BTreeMap, path::PathBuf};
 
 pub fn test_dir_path(s: &str) -> PathBuf {
@@ -31,3 +48,119 @@ pub fn build_scripts(package_folder: &str, package_names: Vec<&str>) -> BTreeMap
     }
     scripts
 }
+
+/// A signature scheme accounts can authenticate transactions with.
+pub trait AccountScheme {
+    type PrivateKey: PrivateKey + Uniform;
+
+    fn authentication_key(private_key: &Self::PrivateKey) -> AuthenticationKey;
+
+    fn sign(raw_txn: RawTransaction, private_key: &Self::PrivateKey) -> SignedTransaction;
+}
+
+pub struct Secp256k1Ecdsa;
+
+impl AccountScheme for Secp256k1Ecdsa {
+    type PrivateKey = Secp256k1EcdsaPrivateKey;
+
+    fn authentication_key(private_key: &Self::PrivateKey) -> AuthenticationKey {
+        AuthenticationKey::secp256k1_ecdsa(&private_key.public_key())
+    }
+
+    fn sign(raw_txn: RawTransaction, private_key: &Self::PrivateKey) -> SignedTransaction {
+        raw_txn
+            .sign_secp256k1_ecdsa(private_key, private_key.public_key())
+            .unwrap()
+            .into_inner()
+    }
+}
+
+/// A passkey, signing with the assertion of a software authenticator.
+pub struct WebAuthn;
+
+impl AccountScheme for WebAuthn {
+    type PrivateKey = Secp256r1EcdsaPrivateKey;
+
+    fn authentication_key(private_key: &Self::PrivateKey) -> AuthenticationKey {
+        AuthenticationKey::webauthn(&private_key.public_key())
+    }
+
+    fn sign(raw_txn: RawTransaction, private_key: &Self::PrivateKey) -> SignedTransaction {
+        let assertion =
+            PartialAuthenticatorAssertionResponse::sign_for_testing(private_key, &raw_txn);
+        SignedTransaction::new_webauthn(raw_txn, private_key.public_key(), assertion)
+    }
+}
+
+/// Creates and funds an account whose authentication key is derived from a key of scheme `S`.
+pub fn new_account_with_scheme<S: AccountScheme>(
+    harness: &mut MoveHarness,
+) -> (AccountAddress, S::PrivateKey) {
+    let private_key = S::PrivateKey::generate_for_testing();
+    let address = S::authentication_key(&private_key).derived_address();
+    let funder = harness.new_account_with_key_pair();
+    assert_success!(harness.run_transaction_payload(
+        &funder,
+        aptos_stdlib::aptos_account_transfer(address, 1_000_000_000),
+    ));
+    (address, private_key)
+}
+
+/// An unsigned transfer of one octa from `sender` to `to`.
+pub fn transfer_raw_transaction(
+    harness: &MoveHarness,
+    sender: AccountAddress,
+    to: AccountAddress,
+) -> RawTransaction {
+    RawTransaction::new(
+        sender,
+        harness.sequence_number(&sender),
+        aptos_stdlib::aptos_account_transfer(to, 1),
+        100_000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    )
+}
+
+/// A transfer of one octa from `sender` to `to`, signed with scheme `S`.
+pub fn transfer_transaction<S: AccountScheme>(
+    harness: &MoveHarness,
+    sender: AccountAddress,
+    private_key: &S::PrivateKey,
+    to: AccountAddress,
+) -> SignedTransaction {
+    S::sign(transfer_raw_transaction(harness, sender, to), private_key)
+}
+
+/// Checks that an account of scheme `S` can transfer once `feature` is enabled, and that a
+/// transaction signed with another key of the same scheme is rejected.
+pub fn check_transfer_with_scheme<S: AccountScheme>(feature: FeatureFlag) {
+    let mut harness = MoveHarness::new_with_features(vec![feature], vec![]);
+    let (sender, private_key) = new_account_with_scheme::<S>(&mut harness);
+    let receiver = harness.new_account_with_key_pair();
+
+    let txn = transfer_transaction::<S>(&harness, sender, &private_key, *receiver.address());
+    assert_success!(harness.run(txn));
+    assert_eq!(harness.sequence_number(&sender), 1);
+
+    let other_key = S::PrivateKey::generate(&mut OsRng);
+    let txn = transfer_transaction::<S>(&harness, sender, &other_key, *receiver.address());
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+}
+
+/// Checks that transactions of scheme `S` are rejected while `feature` is disabled.
+pub fn check_scheme_not_enabled<S: AccountScheme>(feature: FeatureFlag) {
+    let mut harness = MoveHarness::new_with_features(vec![], vec![feature]);
+    let (sender, private_key) = new_account_with_scheme::<S>(&mut harness);
+    let receiver = harness.new_account_with_key_pair();
+
+    let txn = transfer_transaction::<S>(&harness, sender, &private_key, *receiver.address());
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    );
+}
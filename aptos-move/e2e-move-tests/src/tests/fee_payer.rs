@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_language_e2e_tests::account::Account;
+use aptos_types::{
+    on_chain_config::FeatureFlag,
+    transaction::{SignedTransaction, TransactionStatus},
+    vm_status::StatusCode,
+};
+
+fn fee_payer_transaction(
+    harness: &MoveHarness,
+    sender: &Account,
+    fee_payer: &Account,
+) -> SignedTransaction {
+    sender
+        .transaction()
+        .fee_payer(fee_payer.clone())
+        .sequence_number(harness.sequence_number(sender.address()))
+        .max_gas_amount(100_000)
+        .gas_unit_price(100)
+        .payload(aptos_stdlib::aptos_account_transfer(
+            *fee_payer.address(),
+            0,
+        ))
+        .sign_fee_payer()
+}
+
+fn balance(harness: &MoveHarness, account: &Account) -> u64 {
+    harness
+        .executor
+        .read_coin_store_resource(account)
+        .unwrap()
+        .coin()
+}
+
+#[test]
+fn test_fee_payer_pays_gas() {
+    let mut harness = MoveHarness::new_with_features(vec![FeatureFlag::FEE_PAYER_ENABLED], vec![]);
+    let sender = harness.new_account_with_balance_and_sequence_number(0, 0);
+    let fee_payer = harness.new_account_with_key_pair();
+    let fee_payer_balance = balance(&harness, &fee_payer);
+
+    let txn = fee_payer_transaction(&harness, &sender, &fee_payer);
+    let output = harness.run_raw(txn);
+    assert_success!(output.status().to_owned());
+
+    assert_eq!(balance(&harness, &sender), 0);
+    assert_eq!(
+        balance(&harness, &fee_payer),
+        fee_payer_balance - output.gas_used() * 100
+    );
+    // The sequence number of the sender is incremented, not the fee payer's.
+    assert_eq!(harness.sequence_number(sender.address()), 1);
+    assert_eq!(harness.sequence_number(fee_payer.address()), 0);
+}
+
+#[test]
+fn test_fee_payer_not_enabled() {
+    let mut harness = MoveHarness::new_with_features(vec![], vec![FeatureFlag::FEE_PAYER_ENABLED]);
+    let sender = harness.new_account_with_balance_and_sequence_number(0, 0);
+    let fee_payer = harness.new_account_with_key_pair();
+
+    let txn = fee_payer_transaction(&harness, &sender, &fee_payer);
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    );
+}
+
+#[test]
+fn test_fee_payer_wrong_signer() {
+    let mut harness = MoveHarness::new_with_features(vec![FeatureFlag::FEE_PAYER_ENABLED], vec![]);
+    let sender = harness.new_account_with_balance_and_sequence_number(0, 0);
+    let fee_payer = harness.new_account_with_key_pair();
+
+    // Signed with another key on behalf of the fee payer.
+    let mut impostor = fee_payer.clone();
+    let other = Account::new();
+    impostor.rotate_key(other.privkey, other.pubkey);
+    let txn = fee_payer_transaction(&harness, &sender, &impostor);
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+}
@@ -0,0 +1,17 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::common::{self, Secp256k1Ecdsa};
+use aptos_types::on_chain_config::FeatureFlag;
+
+#[test]
+fn test_secp256k1_ecdsa_transaction() {
+    common::check_transfer_with_scheme::<Secp256k1Ecdsa>(
+        FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR,
+    );
+}
+
+#[test]
+fn test_secp256k1_ecdsa_not_enabled() {
+    common::check_scheme_not_enabled::<Secp256k1Ecdsa>(FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR);
+}
@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    tests::common::{self, WebAuthn},
+    MoveHarness,
+};
+use aptos_crypto::{secp256r1_ecdsa::Secp256r1EcdsaPrivateKey, PrivateKey};
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::FeatureFlag,
+    transaction::{
+        webauthn::PartialAuthenticatorAssertionResponse, RawTransaction, SignedTransaction,
+        TransactionStatus,
+    },
+    vm_status::StatusCode,
+};
+
+/// The offset of the flags byte in the authenticator data, after the relying party id hash.
+const FLAGS_INDEX: usize = 32;
+const USER_PRESENT_FLAG: u8 = 0x01;
+
+#[test]
+fn test_webauthn_transaction() {
+    common::check_transfer_with_scheme::<WebAuthn>(FeatureFlag::WEBAUTHN_AUTHENTICATOR);
+}
+
+#[test]
+fn test_webauthn_not_enabled() {
+    common::check_scheme_not_enabled::<WebAuthn>(FeatureFlag::WEBAUTHN_AUTHENTICATOR);
+}
+
+/// A passkey account with a transfer that still needs to be signed.
+fn new_unsigned_transfer() -> (
+    MoveHarness,
+    AccountAddress,
+    Secp256r1EcdsaPrivateKey,
+    RawTransaction,
+) {
+    let mut harness =
+        MoveHarness::new_with_features(vec![FeatureFlag::WEBAUTHN_AUTHENTICATOR], vec![]);
+    let (sender, private_key) = common::new_account_with_scheme::<WebAuthn>(&mut harness);
+    let receiver = harness.new_account_with_key_pair();
+    let raw_txn = common::transfer_raw_transaction(&harness, sender, *receiver.address());
+    (harness, sender, private_key, raw_txn)
+}
+
+/// Rewrites the fields of a valid assertion for `raw_txn` and signs them again, so only the
+/// WebAuthn checks of the rewritten fields can reject the transaction.
+fn resign_assertion(
+    raw_txn: &RawTransaction,
+    private_key: &Secp256r1EcdsaPrivateKey,
+    rewrite: impl FnOnce(&mut Vec<u8>, &mut Vec<u8>),
+) -> PartialAuthenticatorAssertionResponse {
+    let assertion = PartialAuthenticatorAssertionResponse::sign_for_testing(private_key, raw_txn);
+    let mut authenticator_data = assertion.authenticator_data().to_vec();
+    let mut client_data_json = assertion.client_data_json().to_vec();
+    rewrite(&mut authenticator_data, &mut client_data_json);
+    PartialAuthenticatorAssertionResponse::sign_data_for_testing(
+        private_key,
+        authenticator_data,
+        client_data_json,
+    )
+}
+
+#[test]
+fn test_webauthn_wrong_challenge() {
+    let (mut harness, sender, private_key, raw_txn) = new_unsigned_transfer();
+
+    // A valid assertion, but for a transfer to another receiver.
+    let other_txn = common::transfer_raw_transaction(&harness, sender, sender);
+    let assertion =
+        PartialAuthenticatorAssertionResponse::sign_for_testing(&private_key, &other_txn);
+    let txn = SignedTransaction::new_webauthn(raw_txn, private_key.public_key(), assertion);
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+    );
+}
+
+#[test]
+fn test_webauthn_create_type() {
+    let (mut harness, _, private_key, raw_txn) = new_unsigned_transfer();
+
+    let assertion = resign_assertion(&raw_txn, &private_key, |_, client_data_json| {
+        *client_data_json = String::from_utf8(client_data_json.clone())
+            .unwrap()
+            .replace("webauthn.get", "webauthn.create")
+            .into_bytes();
+    });
+    let txn = SignedTransaction::new_webauthn(raw_txn, private_key.public_key(), assertion);
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+    );
+}
+
+#[test]
+fn test_webauthn_user_not_present() {
+    let (mut harness, _, private_key, raw_txn) = new_unsigned_transfer();
+
+    let assertion = resign_assertion(&raw_txn, &private_key, |authenticator_data, _| {
+        authenticator_data[FLAGS_INDEX] &= !USER_PRESENT_FLAG;
+    });
+    let txn = SignedTransaction::new_webauthn(raw_txn, private_key.public_key(), assertion);
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+    );
+}
@@ -0,0 +1,104 @@
+/// This module implements ECDSA signatures based on the prime-order secp256r1 (NIST P-256) elliptic curve, as produced
+/// by WebAuthn passkeys and other platform authenticators.
+
+module aptos_std::secp256r1 {
+    /// An error occurred while deserializing, for example due to wrong input size.
+    const E_DESERIALIZE: u64 = 1;
+
+    /// The size of a secp256r1-based ECDSA public key, in bytes.
+    const RAW_PUBLIC_KEY_NUM_BYTES: u64 = 64;
+
+    /// The size of a secp256r1-based ECDSA signature, in bytes.
+    const SIGNATURE_NUM_BYTES: u64 = 64;
+
+    /// A 64-byte ECDSA public key: the concatenation of the x and y coordinates of the curve point.
+    struct ECDSARawPublicKey has copy, drop, store {
+        bytes: vector<u8>
+    }
+
+    /// A 64-byte ECDSA signature: the concatenation of its r and s components.
+    struct ECDSASignature has copy, drop, store {
+        bytes: vector<u8>
+    }
+
+    /// Constructs an ECDSASignature struct from the given 64 bytes.
+    public fun ecdsa_signature_from_bytes(bytes: vector<u8>): ECDSASignature {
+        assert!(std::vector::length(&bytes) == SIGNATURE_NUM_BYTES, std::error::invalid_argument(E_DESERIALIZE));
+        ECDSASignature { bytes }
+    }
+
+    /// Constructs an ECDSARawPublicKey struct, given a 64-byte raw representation.
+    public fun ecdsa_raw_public_key_from_64_bytes(bytes: vector<u8>): ECDSARawPublicKey {
+        assert!(std::vector::length(&bytes) == RAW_PUBLIC_KEY_NUM_BYTES, std::error::invalid_argument(E_DESERIALIZE));
+        ECDSARawPublicKey { bytes }
+    }
+
+    /// Serializes an ECDSARawPublicKey struct to 64-bytes.
+    public fun ecdsa_raw_public_key_to_bytes(pk: &ECDSARawPublicKey): vector<u8> {
+        pk.bytes
+    }
+
+    /// Serializes an ECDSASignature struct to 64-bytes.
+    public fun ecdsa_signature_to_bytes(sig: &ECDSASignature): vector<u8> {
+        sig.bytes
+    }
+
+    /// Returns `true` if `signature` is a valid secp256r1 ECDSA signature of `message`, hashed with SHA2-256, under
+    /// `public_key`.
+    ///
+    /// Signatures whose s-component is in the upper half of the curve order are rejected, so that they cannot be
+    /// mauled. Public keys that are not on the curve never verify.
+    public fun ecdsa_verify(
+        message: vector<u8>,
+        public_key: &ECDSARawPublicKey,
+        signature: &ECDSASignature,
+    ): bool {
+        ecdsa_verify_internal(message, public_key.bytes, signature.bytes)
+    }
+
+    //
+    // Native functions
+    //
+
+    /// Returns `true` if `signature` verifies on the SHA2-256 hash of `message` under `public_key` and returns `false`
+    /// otherwise.
+    native fun ecdsa_verify_internal(
+        message: vector<u8>,
+        public_key: vector<u8>,
+        signature: vector<u8>
+    ): bool;
+
+    //
+    // Tests
+    //
+
+    #[test]
+    /// Test on a valid secp256r1 ECDSA signature created using sk = x"0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
+    fun test_ecdsa_verify() {
+        let pk = ecdsa_raw_public_key_from_64_bytes(x"d8cd12ea5c67f2f8a00c1124893edcfa6754c4d6cede6be13bdf2295c810a97fa5a89d2d2a360c0ca9a4d6c7c9ed4b28d3e199d6627f2e696d689c310a5b0f48");
+        let sig = ecdsa_signature_from_bytes(x"9534789e509b92e5896b0fb0ad27e1efdd6fa90ecffab823175ef35fc155b3585be410363147f7db7e7788f8ea904c617254ffdca440f215ee5a179dbee11e47");
+        assert!(ecdsa_verify(b"test aptos secp256r1", &pk, &sig), 1);
+
+        // Wrong message
+        assert!(!ecdsa_verify(b"test aptos secp256k1", &pk, &sig), 1);
+
+        // Flipped bits; Signature becomes invalid
+        let sig = ecdsa_signature_from_bytes(x"9534789e509b92e5896b0fb0ad27e1efdd6fa90ecffab823175ef35fc155b3585be410363147f7db7e7788f8ea904c617254ffdca440f215ee5a179dbee11e4f");
+        assert!(!ecdsa_verify(b"test aptos secp256r1", &pk, &sig), 1);
+
+        // Same signature with a high s-component; rejected
+        let sig = ecdsa_signature_from_bytes(x"9534789e509b92e5896b0fb0ad27e1efdd6fa90ecffab823175ef35fc155b358a41befc8ceb8082581887707156fb39e4a91fad102d6ac6f055fb3253d82070a");
+        assert!(!ecdsa_verify(b"test aptos secp256r1", &pk, &sig), 1);
+
+        // Public key not on the curve
+        let pk = ecdsa_raw_public_key_from_64_bytes(x"d8cd12ea5c67f2f8a00c1124893edcfa6754c4d6cede6be13bdf2295c810a97fa5a89d2d2a360c0ca9a4d6c7c9ed4b28d3e199d6627f2e696d689c310a5b0f49");
+        let sig = ecdsa_signature_from_bytes(x"9534789e509b92e5896b0fb0ad27e1efdd6fa90ecffab823175ef35fc155b3585be410363147f7db7e7788f8ea904c617254ffdca440f215ee5a179dbee11e47");
+        assert!(!ecdsa_verify(b"test aptos secp256r1", &pk, &sig), 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0x10001, location = Self)]
+    fun test_wrong_public_key_size() {
+        ecdsa_raw_public_key_from_64_bytes(x"04d8cd12");
+    }
+}
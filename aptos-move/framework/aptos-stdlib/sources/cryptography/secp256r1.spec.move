@@ -0,0 +1,6 @@
+spec aptos_std::secp256r1 {
+    spec ecdsa_verify_internal {
+        // TODO: temporary mockup.
+        pragma opaque;
+    }
+}
@@ -182,6 +182,39 @@ module std::features {
         is_enabled(PARTIAL_GOVERNANCE_VOTING)
     }
 
+    /// Whether the gas of a transaction can be paid by a fee payer other than the sender.
+    /// Lifetime: transient
+    const FEE_PAYER_ENABLED: u64 = 19;
+    public fun get_fee_payer_feature(): u64 { FEE_PAYER_ENABLED }
+    public fun fee_payer_enabled(): bool acquires Features {
+        is_enabled(FEE_PAYER_ENABLED)
+    }
+
+    /// Whether transactions can be signed with secp256k1 ECDSA keys.
+    /// Lifetime: transient
+    const SECP256K1_ECDSA_AUTHENTICATOR: u64 = 20;
+    public fun get_secp256k1_ecdsa_authenticator_feature(): u64 { SECP256K1_ECDSA_AUTHENTICATOR }
+    public fun secp256k1_ecdsa_authenticator_enabled(): bool acquires Features {
+        is_enabled(SECP256K1_ECDSA_AUTHENTICATOR)
+    }
+
+    /// Whether transactions can be signed with WebAuthn passkeys.
+    /// Lifetime: transient
+    const WEBAUTHN_AUTHENTICATOR: u64 = 21;
+    public fun get_webauthn_authenticator_feature(): u64 { WEBAUTHN_AUTHENTICATOR }
+    public fun webauthn_authenticator_enabled(): bool acquires Features {
+        is_enabled(WEBAUTHN_AUTHENTICATOR)
+    }
+
+    /// Whether the generic algebra implementation for BN254 operations are enabled.
+    ///
+    /// Lifetime: transient
+    const BN254_STRUCTURES: u64 = 22;
+    public fun get_bn254_structures_feature(): u64 { BN254_STRUCTURES }
+    public fun bn254_structures_enabled(): bool acquires Features {
+        is_enabled(BN254_STRUCTURES)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
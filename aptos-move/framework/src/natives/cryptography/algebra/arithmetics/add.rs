@@ -66,6 +66,48 @@ pub fn add_internal(
             mul,
             gas_params.ark_bls12_381_fq12_mul * NumArgs::one()
         ),
+        Some(Structure::BN254Fr) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fr,
+            add,
+            gas_params.ark_bn254_fr_add * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq,
+            add,
+            gas_params.ark_bn254_fq_add * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq12) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            add,
+            gas_params.ark_bn254_fq12_add * NumArgs::one()
+        ),
+        Some(Structure::BN254G1) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::G1Projective,
+            add,
+            gas_params.ark_bn254_g1_proj_add * NumArgs::one()
+        ),
+        Some(Structure::BN254G2) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::G2Projective,
+            add,
+            gas_params.ark_bn254_g2_proj_add * NumArgs::one()
+        ),
+        Some(Structure::BN254Gt) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            mul,
+            gas_params.ark_bn254_fq12_mul * NumArgs::one()
+        ),
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
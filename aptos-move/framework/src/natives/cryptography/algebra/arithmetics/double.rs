@@ -50,6 +50,27 @@ pub fn double_internal(
             square,
             gas_params.ark_bls12_381_fq12_square * NumArgs::one()
         ),
+        Some(Structure::BN254G1) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::G1Projective,
+            double,
+            gas_params.ark_bn254_g1_proj_double * NumArgs::one()
+        ),
+        Some(Structure::BN254G2) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::G2Projective,
+            double,
+            gas_params.ark_bn254_g2_proj_double * NumArgs::one()
+        ),
+        Some(Structure::BN254Gt) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            square,
+            gas_params.ark_bn254_fq12_square * NumArgs::one()
+        ),
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -64,6 +64,49 @@ pub fn neg_internal(
             let new_handle = store_element!(context, new_element);
             Ok(smallvec![Value::u64(new_handle as u64)])
         },
+        Some(Structure::BN254Fr) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fr,
+            neg,
+            gas_params.ark_bn254_fr_neg * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq,
+            neg,
+            gas_params.ark_bn254_fq_neg * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq12) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            neg,
+            gas_params.ark_bn254_fq12_neg * NumArgs::one()
+        ),
+        Some(Structure::BN254G1) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::G1Projective,
+            neg,
+            gas_params.ark_bn254_g1_proj_neg * NumArgs::one()
+        ),
+        Some(Structure::BN254G2) => ark_unary_op_internal!(
+            context,
+            args,
+            ark_bn254::G2Projective,
+            neg,
+            gas_params.ark_bn254_g2_proj_neg * NumArgs::one()
+        ),
+        Some(Structure::BN254Gt) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fq12, element_ptr, element);
+            context.charge(gas_params.ark_bn254_fq12_inv * NumArgs::one())?;
+            let new_element = element.inverse().ok_or_else(abort_invariant_violated)?;
+            let new_handle = store_element!(context, new_element);
+            Ok(smallvec![Value::u64(new_handle as u64)])
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -66,6 +66,48 @@ pub fn sub_internal(
             div,
             gas_params.ark_bls12_381_fq12_div * NumArgs::one()
         ),
+        Some(Structure::BN254Fr) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fr,
+            sub,
+            gas_params.ark_bn254_fr_sub * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq,
+            sub,
+            gas_params.ark_bn254_fq_sub * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq12) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            sub,
+            gas_params.ark_bn254_fq12_sub * NumArgs::one()
+        ),
+        Some(Structure::BN254G1) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::G1Projective,
+            sub,
+            gas_params.ark_bn254_g1_proj_sub * NumArgs::one()
+        ),
+        Some(Structure::BN254G2) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::G2Projective,
+            sub,
+            gas_params.ark_bn254_g2_proj_sub * NumArgs::one()
+        ),
+        Some(Structure::BN254Gt) => ark_binary_op_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            div,
+            gas_params.ark_bn254_fq12_div * NumArgs::one()
+        ),
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
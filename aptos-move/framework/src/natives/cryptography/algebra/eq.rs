@@ -68,6 +68,42 @@ pub fn eq_internal(
             ark_bls12_381::Fq12,
             gas_params.ark_bls12_381_fq12_eq * NumArgs::one()
         ),
+        Some(Structure::BN254Fr) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::Fr,
+            gas_params.ark_bn254_fr_eq * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::Fq,
+            gas_params.ark_bn254_fq_eq * NumArgs::one()
+        ),
+        Some(Structure::BN254Fq12) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            gas_params.ark_bn254_fq12_eq * NumArgs::one()
+        ),
+        Some(Structure::BN254G1) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::G1Projective,
+            gas_params.ark_bn254_g1_proj_eq * NumArgs::one()
+        ),
+        Some(Structure::BN254G2) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::G2Projective,
+            gas_params.ark_bn254_g2_proj_eq * NumArgs::one()
+        ),
+        Some(Structure::BN254Gt) => ark_eq_internal!(
+            context,
+            args,
+            ark_bn254::Fq12,
+            gas_params.ark_bn254_fq12_eq * NumArgs::one()
+        ),
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
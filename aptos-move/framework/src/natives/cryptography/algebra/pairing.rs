@@ -27,6 +27,9 @@ fn feature_flag_of_pairing(
         (Some(Structure::BLS12381G1), Some(Structure::BLS12381G2), Some(Structure::BLS12381Gt)) => {
             Some(FeatureFlag::BLS12_381_STRUCTURES)
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            Some(FeatureFlag::BN254_STRUCTURES)
+        },
         _ => None,
     }
 }
@@ -100,6 +103,56 @@ pub fn multi_pairing_internal(
             let new_handle = store_element!(context, new_element);
             Ok(smallvec![Value::u64(new_handle as u64)])
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            let g2_element_handles = safely_pop_arg!(args, Vec<u64>);
+            let g1_element_handles = safely_pop_arg!(args, Vec<u64>);
+            let num_entries = g1_element_handles.len();
+            if num_entries != g2_element_handles.len() {
+                return Err(SafeNativeError::Abort {
+                    abort_code: MOVE_ABORT_CODE_INPUT_VECTOR_SIZES_NOT_MATCHING,
+                });
+            }
+
+            context.charge(
+                gas_params.ark_bn254_g1_proj_to_affine * NumArgs::from(num_entries as u64),
+            )?;
+            let mut g1_elements_affine = Vec::with_capacity(num_entries);
+            for handle in g1_element_handles {
+                safe_borrow_element!(
+                    context,
+                    handle as usize,
+                    ark_bn254::G1Projective,
+                    ptr,
+                    element
+                );
+                g1_elements_affine.push(element.into_affine());
+            }
+
+            context.charge(
+                gas_params.ark_bn254_g2_proj_to_affine * NumArgs::from(num_entries as u64),
+            )?;
+            let mut g2_elements_affine = Vec::with_capacity(num_entries);
+            for handle in g2_element_handles {
+                safe_borrow_element!(
+                    context,
+                    handle as usize,
+                    ark_bn254::G2Projective,
+                    ptr,
+                    element
+                );
+                g2_elements_affine.push(element.into_affine());
+            }
+
+            context.charge(
+                gas_params.ark_bn254_multi_pairing_base * NumArgs::one()
+                    + gas_params.ark_bn254_multi_pairing_per_pair
+                        * NumArgs::from(num_entries as u64),
+            )?;
+            let new_element =
+                ark_bn254::Bn254::multi_pairing(g1_elements_affine, g2_elements_affine).0;
+            let new_handle = store_element!(context, new_element);
+            Ok(smallvec![Value::u64(new_handle as u64)])
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -145,6 +198,32 @@ pub fn pairing_internal(
             let new_handle = store_element!(context, new_element);
             Ok(smallvec![Value::u64(new_handle as u64)])
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            let g2_element_handle = safely_pop_arg!(args, u64) as usize;
+            let g1_element_handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(
+                context,
+                g1_element_handle,
+                ark_bn254::G1Projective,
+                g1_element_ptr,
+                g1_element
+            );
+            context.charge(gas_params.ark_bn254_g1_proj_to_affine * NumArgs::one())?;
+            let g1_element_affine = g1_element.into_affine();
+            safe_borrow_element!(
+                context,
+                g2_element_handle,
+                ark_bn254::G2Projective,
+                g2_element_ptr,
+                g2_element
+            );
+            context.charge(gas_params.ark_bn254_g2_proj_to_affine * NumArgs::one())?;
+            let g2_element_affine = g2_element.into_affine();
+            context.charge(gas_params.ark_bn254_pairing * NumArgs::one())?;
+            let new_element = ark_bn254::Bn254::pairing(g1_element_affine, g2_element_affine).0;
+            let new_handle = store_element!(context, new_element);
+            Ok(smallvec![Value::u64(new_handle as u64)])
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -2,7 +2,9 @@
 
 #[cfg(feature = "testing")]
 use crate::{
-    natives::cryptography::algebra::{AlgebraContext, Structure, BLS12381_GT_GENERATOR},
+    natives::cryptography::algebra::{
+        AlgebraContext, Structure, BLS12381_GT_GENERATOR, BN254_GT_GENERATOR,
+    },
     store_element, structure_from_ty_arg,
 };
 #[cfg(feature = "testing")]
@@ -62,6 +64,30 @@ pub fn rand_insecure_internal(
                 Value::u64(handle as u64)
             ]))
         },
+        Some(Structure::BN254Fr) => {
+            ark_rand_internal!(context, ark_bn254::Fr)
+        },
+        Some(Structure::BN254Fq) => {
+            ark_rand_internal!(context, ark_bn254::Fq)
+        },
+        Some(Structure::BN254Fq12) => {
+            ark_rand_internal!(context, ark_bn254::Fq12)
+        },
+        Some(Structure::BN254G1) => {
+            ark_rand_internal!(context, ark_bn254::G1Projective)
+        },
+        Some(Structure::BN254G2) => {
+            ark_rand_internal!(context, ark_bn254::G2Projective)
+        },
+        Some(Structure::BN254Gt) => {
+            let k = ark_bn254::Fr::rand(&mut test_rng());
+            let k_bigint: ark_ff::BigInteger256 = k.into();
+            let element = BN254_GT_GENERATOR.pow(k_bigint);
+            let handle = store_element!(context, element);
+            Ok(NativeResult::ok(InternalGas::zero(), smallvec![
+                Value::u64(handle as u64)
+            ]))
+        },
         _ => unreachable!(),
     }
 }
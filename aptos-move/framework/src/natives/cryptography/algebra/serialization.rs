@@ -5,7 +5,7 @@ use crate::{
     natives::{
         cryptography::algebra::{
             abort_invariant_violated, gas::GasParameters, AlgebraContext, SerializationFormat,
-            Structure, BLS12381_R_SCALAR, MOVE_ABORT_CODE_NOT_IMPLEMENTED,
+            Structure, BLS12381_R_SCALAR, BN254_R_SCALAR, MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         },
         helpers::{SafeNativeContext, SafeNativeError, SafeNativeResult},
     },
@@ -36,6 +36,16 @@ pub fn feature_flag_of_serialization_format(
         | Some(SerializationFormat::BLS12381G2Uncompressed)
         | Some(SerializationFormat::BLS12381G2Compressed)
         | Some(SerializationFormat::BLS12381Gt) => Some(FeatureFlag::BLS12_381_STRUCTURES),
+        Some(SerializationFormat::BN254FrLsb)
+        | Some(SerializationFormat::BN254FrMsb)
+        | Some(SerializationFormat::BN254FqLsb)
+        | Some(SerializationFormat::BN254FqMsb)
+        | Some(SerializationFormat::BN254Fq12LscLsb)
+        | Some(SerializationFormat::BN254G1Uncompressed)
+        | Some(SerializationFormat::BN254G1Compressed)
+        | Some(SerializationFormat::BN254G2Uncompressed)
+        | Some(SerializationFormat::BN254G2Compressed)
+        | Some(SerializationFormat::BN254Gt) => Some(FeatureFlag::BN254_STRUCTURES),
         _ => None,
     }
 }
@@ -174,6 +184,136 @@ pub fn serialize_internal(
                 .map_err(|_e| abort_invariant_violated())?;
             Ok(smallvec![Value::vector_u8(buf)])
         },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrLsb)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fr, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fr_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrMsb)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fr, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fr_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            buf.reverse();
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqLsb)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fq, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fq_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqMsb)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fq, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fq_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            buf.reverse();
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq12), Some(SerializationFormat::BN254Fq12LscLsb)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fq12, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fq12_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Uncompressed)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(
+                context,
+                handle,
+                ark_bn254::G1Projective,
+                element_ptr,
+                element
+            );
+            let element_affine = element.into_affine();
+            let mut buf = Vec::new();
+            context.charge(gas_params.ark_bn254_g1_affine_serialize_uncomp * NumArgs::one())?;
+            element_affine
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Compressed)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(
+                context,
+                handle,
+                ark_bn254::G1Projective,
+                element_ptr,
+                element
+            );
+            let element_affine = element.into_affine();
+            let mut buf = Vec::new();
+            context.charge(gas_params.ark_bn254_g1_affine_serialize_comp * NumArgs::one())?;
+            element_affine
+                .serialize_compressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Uncompressed)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(
+                context,
+                handle,
+                ark_bn254::G2Projective,
+                element_ptr,
+                element
+            );
+            let element_affine = element.into_affine();
+            let mut buf = Vec::new();
+            context.charge(gas_params.ark_bn254_g2_affine_serialize_uncomp * NumArgs::one())?;
+            element_affine
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Compressed)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(
+                context,
+                handle,
+                ark_bn254::G2Projective,
+                element_ptr,
+                element
+            );
+            let element_affine = element.into_affine();
+            let mut buf = Vec::new();
+            context.charge(gas_params.ark_bn254_g2_affine_serialize_comp * NumArgs::one())?;
+            element_affine
+                .serialize_compressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Gt), Some(SerializationFormat::BN254Gt)) => {
+            let handle = safely_pop_arg!(args, u64) as usize;
+            safe_borrow_element!(context, handle, ark_bn254::Fq12, element_ptr, element);
+            let mut buf = vec![];
+            context.charge(gas_params.ark_bn254_fq12_serialize * NumArgs::one())?;
+            element
+                .serialize_uncompressed(&mut buf)
+                .map_err(|_e| abort_invariant_violated())?;
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -352,6 +492,151 @@ pub fn deserialize_internal(
                 _ => Ok(smallvec![Value::bool(false), Value::u64(0)]),
             }
         },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrLsb)) => {
+            // Valid BN254FrLsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fr,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_fr_deser * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrMsb)) => {
+            // Valid BN254FrMsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            let mut bytes_copy: Vec<u8> = bytes.to_vec();
+            bytes_copy.reverse();
+            let bytes = bytes_copy.as_slice();
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fr,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_fr_deser * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqLsb)) => {
+            // Valid BN254FqLsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_fq_deser * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqMsb)) => {
+            // Valid BN254FqMsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            let mut bytes_copy: Vec<u8> = bytes.to_vec();
+            bytes_copy.reverse();
+            let bytes = bytes_copy.as_slice();
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_fq_deser * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254Fq12), Some(SerializationFormat::BN254Fq12LscLsb)) => {
+            // Valid BN254Fq12LscLsb serialization should be 384-byte.
+            if bytes.len() != 384 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq12,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_fq12_deser * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Uncompressed)) => {
+            // Valid BN254G1AffineUncompressed serialization should be 64-byte.
+            if bytes.len() != 64 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G1Affine,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_g1_affine_deser_uncomp * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Compressed)) => {
+            // Valid BN254G1AffineCompressed serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G1Affine,
+                deserialize_compressed,
+                gas_params.ark_bn254_g1_affine_deser_comp * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Uncompressed)) => {
+            // Valid BN254G2AffineUncompressed serialization should be 128-byte.
+            if bytes.len() != 128 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G2Affine,
+                deserialize_uncompressed,
+                gas_params.ark_bn254_g2_affine_deser_uncomp * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Compressed)) => {
+            // Valid BN254G2AffineCompressed serialization should be 64-byte.
+            if bytes.len() != 64 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G2Affine,
+                deserialize_compressed,
+                gas_params.ark_bn254_g2_affine_deser_comp * NumArgs::one()
+            )
+        },
+        (Some(Structure::BN254Gt), Some(SerializationFormat::BN254Gt)) => {
+            // Valid BN254Gt serialization should be 384-byte.
+            if bytes.len() != 384 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            context.charge(gas_params.ark_bn254_fq12_deser * NumArgs::one())?;
+            match <ark_bn254::Fq12>::deserialize_uncompressed(bytes) {
+                Ok(element) => {
+                    context.charge(
+                        (gas_params.ark_bn254_fq12_pow_u256 + gas_params.ark_bn254_fq12_eq)
+                            * NumArgs::one(),
+                    )?;
+                    if element.pow(BN254_R_SCALAR.0) == ark_bn254::Fq12::one() {
+                        let handle = store_element!(context, element);
+                        Ok(smallvec![Value::bool(true), Value::u64(handle as u64)])
+                    } else {
+                        Ok(smallvec![Value::bool(false), Value::u64(0)])
+                    }
+                },
+                _ => Ok(smallvec![Value::bool(false), Value::u64(0)]),
+            }
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -9,3 +9,4 @@ pub mod ristretto255;
 pub mod ristretto255_point;
 pub mod ristretto255_scalar;
 pub mod secp256k1;
+pub mod secp256r1;
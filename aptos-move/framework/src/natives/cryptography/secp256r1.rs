@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/***************************************************************************************************
+ * native fun ecdsa_verify_internal
+ *
+ *   gas cost: base_cost + per_msg_byte_hashing * msg_size +? ecdsa_verify
+ *
+ **************************************************************************************************/
+use crate::{
+    natives::helpers::{make_safe_native, SafeNativeContext, SafeNativeResult},
+    safely_pop_arg,
+};
+use aptos_crypto::{
+    secp256r1_ecdsa::{Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature},
+    traits::Signature,
+};
+use aptos_types::on_chain_config::{Features, TimedFeatures};
+use move_core_types::gas_algebra::{
+    InternalGas, InternalGasPerArg, InternalGasPerByte, NumArgs, NumBytes,
+};
+use move_vm_runtime::native_functions::NativeFunction;
+use move_vm_types::{loaded_data::runtime_types::Type, values::Value};
+use smallvec::{smallvec, SmallVec};
+use std::{collections::VecDeque, convert::TryFrom, sync::Arc};
+
+/// The tag of the uncompressed SEC1 encoding of a public key, which the Move raw public keys omit.
+const SEC1_UNCOMPRESSED_TAG: u8 = 0x04;
+
+fn native_ecdsa_verify(
+    gas_params: &GasParameters,
+    context: &mut SafeNativeContext,
+    _ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 3);
+
+    let signature = safely_pop_arg!(arguments, Vec<u8>);
+    let public_key = safely_pop_arg!(arguments, Vec<u8>);
+    let msg = safely_pop_arg!(arguments, Vec<u8>);
+
+    context.charge(gas_params.base)?;
+
+    // NOTE(Gas): O(1) deserialization cost, the public key is 64 bytes and has to be on the curve.
+    let mut sec1_public_key = vec![SEC1_UNCOMPRESSED_TAG];
+    sec1_public_key.extend_from_slice(&public_key);
+    let public_key = match Secp256r1EcdsaPublicKey::try_from(sec1_public_key.as_slice()) {
+        Ok(public_key) => public_key,
+        Err(_) => return Ok(smallvec![Value::bool(false)]),
+    };
+
+    // NOTE(Gas): O(1) deserialization cost, which rejects signatures with a high s-component.
+    let sig = match Secp256r1EcdsaSignature::try_from(signature.as_slice()) {
+        Ok(sig) => sig,
+        Err(_) => return Ok(smallvec![Value::bool(false)]),
+    };
+
+    // The message is hashed with SHA2-256 before the verification.
+    context.charge(
+        gas_params.per_msg_byte_hashing * NumBytes::new(msg.len() as u64)
+            + gas_params.ecdsa_verify * NumArgs::one(),
+    )?;
+
+    let verify_result = sig.verify_arbitrary_msg(&msg, &public_key).is_ok();
+    Ok(smallvec![Value::bool(verify_result)])
+}
+
+/***************************************************************************************************
+ * module
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub base: InternalGas,
+    pub per_msg_byte_hashing: InternalGasPerByte,
+    pub ecdsa_verify: InternalGasPerArg,
+}
+
+pub fn make_all(
+    gas_params: GasParameters,
+    timed_features: TimedFeatures,
+    features: Arc<Features>,
+) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [(
+        "ecdsa_verify_internal",
+        make_safe_native(gas_params, timed_features, features, native_ecdsa_verify),
+    )];
+
+    crate::natives::helpers::make_module_natives(natives)
+}
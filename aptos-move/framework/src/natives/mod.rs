@@ -44,6 +44,7 @@ pub struct GasParameters {
     pub ed25519: ed25519::GasParameters,
     pub bls12381: cryptography::bls12381::GasParameters,
     pub secp256k1: cryptography::secp256k1::GasParameters,
+    pub secp256r1: cryptography::secp256r1::GasParameters,
     pub ristretto255: cryptography::ristretto255::GasParameters,
     pub hash: hash::GasParameters,
     pub type_info: type_info::GasParameters,
@@ -137,6 +138,78 @@ impl GasParameters {
                 ark_bls12_381_pairing: 0.into(),
                 ark_bls12_381_multi_pairing_base: 0.into(),
                 ark_bls12_381_multi_pairing_per_pair: 0.into(),
+                ark_bn254_fr_add: 0.into(),
+                ark_bn254_fr_deser: 0.into(),
+                ark_bn254_fr_div: 0.into(),
+                ark_bn254_fr_eq: 0.into(),
+                ark_bn254_fr_from_u64: 0.into(),
+                ark_bn254_fr_inv: 0.into(),
+                ark_bn254_fr_mul: 0.into(),
+                ark_bn254_fr_neg: 0.into(),
+                ark_bn254_fr_one: 0.into(),
+                ark_bn254_fr_serialize: 0.into(),
+                ark_bn254_fr_square: 0.into(),
+                ark_bn254_fr_sub: 0.into(),
+                ark_bn254_fr_zero: 0.into(),
+                ark_bn254_fq_add: 0.into(),
+                ark_bn254_fq_clone: 0.into(),
+                ark_bn254_fq_deser: 0.into(),
+                ark_bn254_fq_div: 0.into(),
+                ark_bn254_fq_eq: 0.into(),
+                ark_bn254_fq_from_u64: 0.into(),
+                ark_bn254_fq_inv: 0.into(),
+                ark_bn254_fq_mul: 0.into(),
+                ark_bn254_fq_neg: 0.into(),
+                ark_bn254_fq_one: 0.into(),
+                ark_bn254_fq_pow_u256: 0.into(),
+                ark_bn254_fq_serialize: 0.into(),
+                ark_bn254_fq_square: 0.into(),
+                ark_bn254_fq_sub: 0.into(),
+                ark_bn254_fq_zero: 0.into(),
+                ark_bn254_fq12_add: 0.into(),
+                ark_bn254_fq12_clone: 0.into(),
+                ark_bn254_fq12_deser: 0.into(),
+                ark_bn254_fq12_div: 0.into(),
+                ark_bn254_fq12_eq: 0.into(),
+                ark_bn254_fq12_from_u64: 0.into(),
+                ark_bn254_fq12_inv: 0.into(),
+                ark_bn254_fq12_mul: 0.into(),
+                ark_bn254_fq12_neg: 0.into(),
+                ark_bn254_fq12_one: 0.into(),
+                ark_bn254_fq12_pow_u256: 0.into(),
+                ark_bn254_fq12_serialize: 0.into(),
+                ark_bn254_fq12_square: 0.into(),
+                ark_bn254_fq12_sub: 0.into(),
+                ark_bn254_fq12_zero: 0.into(),
+                ark_bn254_g1_affine_deser_comp: 0.into(),
+                ark_bn254_g1_affine_deser_uncomp: 0.into(),
+                ark_bn254_g1_affine_serialize_comp: 0.into(),
+                ark_bn254_g1_affine_serialize_uncomp: 0.into(),
+                ark_bn254_g1_proj_add: 0.into(),
+                ark_bn254_g1_proj_double: 0.into(),
+                ark_bn254_g1_proj_eq: 0.into(),
+                ark_bn254_g1_proj_generator: 0.into(),
+                ark_bn254_g1_proj_infinity: 0.into(),
+                ark_bn254_g1_proj_neg: 0.into(),
+                ark_bn254_g1_proj_scalar_mul: 0.into(),
+                ark_bn254_g1_proj_sub: 0.into(),
+                ark_bn254_g1_proj_to_affine: 0.into(),
+                ark_bn254_g2_affine_deser_comp: 0.into(),
+                ark_bn254_g2_affine_deser_uncomp: 0.into(),
+                ark_bn254_g2_affine_serialize_comp: 0.into(),
+                ark_bn254_g2_affine_serialize_uncomp: 0.into(),
+                ark_bn254_g2_proj_add: 0.into(),
+                ark_bn254_g2_proj_double: 0.into(),
+                ark_bn254_g2_proj_eq: 0.into(),
+                ark_bn254_g2_proj_generator: 0.into(),
+                ark_bn254_g2_proj_infinity: 0.into(),
+                ark_bn254_g2_proj_neg: 0.into(),
+                ark_bn254_g2_proj_scalar_mul: 0.into(),
+                ark_bn254_g2_proj_sub: 0.into(),
+                ark_bn254_g2_proj_to_affine: 0.into(),
+                ark_bn254_multi_pairing_base: 0.into(),
+                ark_bn254_multi_pairing_per_pair: 0.into(),
+                ark_bn254_pairing: 0.into(),
                 ark_h2c_bls12381g1_xmd_sha256_sswu_base: 0.into(),
                 ark_h2c_bls12381g1_xmd_sha256_sswu_per_msg_byte: 0.into(),
                 ark_h2c_bls12381g2_xmd_sha256_sswu_base: 0.into(),
@@ -155,6 +228,11 @@ impl GasParameters {
                 base: 0.into(),
                 ecdsa_recover: 0.into(),
             },
+            secp256r1: cryptography::secp256r1::GasParameters {
+                base: 0.into(),
+                per_msg_byte_hashing: 0.into(),
+                ecdsa_verify: 0.into(),
+            },
             ristretto255: cryptography::ristretto255::GasParameters {
                 basepoint_mul: 0.into(),
                 basepoint_double_mul: 0.into(),
@@ -348,6 +426,14 @@ pub fn all_natives(
             features.clone()
         )
     );
+    add_natives_from_module!(
+        "secp256r1",
+        cryptography::secp256r1::make_all(
+            gas_params.secp256r1,
+            timed_features.clone(),
+            features.clone()
+        )
+    );
     add_natives_from_module!(
         "aptos_hash",
         hash::make_all(gas_params.hash, timed_features.clone(), features.clone())
@@ -434,6 +434,10 @@ pub fn default_features() -> Vec<FeatureFlag> {
         FeatureFlag::STRUCT_CONSTRUCTORS,
         FeatureFlag::CRYPTOGRAPHY_ALGEBRA_NATIVES,
         FeatureFlag::BLS12_381_STRUCTURES,
+        FeatureFlag::FEE_PAYER_ENABLED,
+        FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR,
+        FeatureFlag::WEBAUTHN_AUTHENTICATOR,
+        FeatureFlag::BN254_STRUCTURES,
     ]
 }
 
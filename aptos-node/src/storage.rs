@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
-use aptos_db::AptosDB;
+use aptos_backup_cli::ledger_archive::BackupStorageArchive;
+use aptos_config::{
+    config::{NodeConfig, StorageConfig},
+    utils::get_genesis_txn,
+};
+use aptos_db::{ledger_archive::LedgerArchive, AptosDB};
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_logger::{debug, info};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
@@ -51,17 +55,23 @@ fn create_rocksdb_checkpoint_and_change_working_dir(
 ) {
     // Update the source and checkpoint directories
     let source_dir = node_config.storage.dir();
+    let source_dir_paths = node_config.storage.get_dir_paths();
     node_config.set_data_dir(working_dir.as_ref().to_path_buf());
     let checkpoint_dir = node_config.storage.dir();
     assert!(source_dir != checkpoint_dir);
 
+    // The checkpoints of all DBs are stored under the checkpoint directory, so the
+    // DB path overrides (which still point at the source DBs) must not be used anymore.
+    node_config.storage.db_path_overrides = None;
+    let checkpoint_dir_paths = node_config.storage.get_dir_paths();
+
     // Create rocksdb checkpoint directory
     fs::create_dir_all(&checkpoint_dir).unwrap();
 
     // Open the database and create a checkpoint
     AptosDB::create_checkpoint(
-        &source_dir,
-        &checkpoint_dir,
+        &source_dir_paths,
+        &checkpoint_dir_paths,
         node_config
             .storage
             .rocksdb_configs
@@ -81,6 +91,22 @@ fn create_rocksdb_checkpoint_and_change_working_dir(
         .expect("StateSyncDB checkpoint creation failed.");
 }
 
+/// Opens the ledger archive, either in a local directory or in a backup storage, if configured.
+fn open_ledger_archive(
+    storage_config: &StorageConfig,
+) -> anyhow::Result<Option<Arc<LedgerArchive>>> {
+    let ledger_archive = if let Some(archive_dir) = storage_config.ledger_archive_dir() {
+        LedgerArchive::open_local(archive_dir)?
+    } else if let Some(config_path) = &storage_config.ledger_archive_command_adapter_config {
+        LedgerArchive::open(Box::new(
+            BackupStorageArchive::new_with_command_adapter_config(config_path)?,
+        ))?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Arc::new(ledger_archive)))
+}
+
 /// Creates any rocksdb checkpoints, opens the storage database,
 /// starts the backup service, handles genesis initialization and returns
 /// the various handles.
@@ -93,16 +119,19 @@ pub fn initialize_database_and_checkpoints(
         create_rocksdb_checkpoint_and_change_working_dir(node_config, working_dir);
     }
 
-    // Open the database
+    // Open the ledger archive and the database
     let instant = Instant::now();
-    let aptos_db = AptosDB::open(
-        &node_config.storage.dir(),
+    let ledger_archive = open_ledger_archive(&node_config.storage)
+        .map_err(|err| anyhow!("Ledger archive failed to open {}", err))?;
+    let aptos_db = AptosDB::open_with_dir_paths(
+        &node_config.storage.get_dir_paths(),
         false, /* readonly */
         node_config.storage.storage_pruner_config,
         node_config.storage.rocksdb_configs,
         node_config.storage.enable_indexer,
         node_config.storage.buffered_state_target_items,
         node_config.storage.max_num_nodes_per_lru_cache_shard,
+        ledger_archive,
     )
     .map_err(|err| anyhow!("DB failed to open {}", err))?;
     let (aptos_db, db_rw, backup_service) =
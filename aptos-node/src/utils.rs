@@ -3,6 +3,7 @@
 
 use anyhow::anyhow;
 use aptos_config::config::NodeConfig;
+use aptos_executor::differential_execution::set_differential_execution_config_once;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
@@ -50,9 +51,11 @@ pub fn fetch_chain_id(db: &DbReaderWriter) -> anyhow::Result<ChainId> {
 pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     AptosVM::set_paranoid_type_checks(node_config.execution.paranoid_type_verification);
     AptosVM::set_concurrency_level_once(node_config.execution.concurrency_level as usize);
+    AptosVM::set_num_shards_once(node_config.execution.num_executor_shards as usize);
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
+    AptosVM::set_cross_block_vm_cache_once(node_config.execution.cross_block_vm_cache);
 
     if node_config
         .execution
@@ -60,4 +63,12 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     {
         AptosVM::set_processed_transactions_detailed_counters();
     }
+
+    set_differential_execution_config_once(
+        node_config.execution.differential_execution_sample_interval,
+        node_config
+            .execution
+            .differential_execution_dump_dir
+            .clone(),
+    );
 }
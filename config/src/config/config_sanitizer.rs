@@ -5,8 +5,8 @@ use crate::config::{
     node_config_loader::NodeType,
     utils::{are_failpoints_enabled, get_config_name},
     ApiConfig, BaseConfig, ConsensusConfig, Error, ExecutionConfig, IndexerConfig,
-    IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig, NodeConfig,
-    PeerMonitoringServiceConfig, StateSyncConfig, StorageConfig,
+    IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig, NetworkConfig,
+    NodeConfig, PeerMonitoringServiceConfig, StateSyncConfig, StorageConfig,
 };
 use aptos_types::chain_id::ChainId;
 use std::collections::HashSet;
@@ -144,6 +144,9 @@ fn sanitize_fullnode_network_configs(
             ));
         }
 
+        // Verify the outbound protocol rate limits
+        sanitize_outbound_protocol_rate_limits(&sanitizer_name, fullnode_network_config)?;
+
         // Prepare the network id
         fullnode_network_config.set_listen_address_and_prepare_identity()?;
     }
@@ -194,6 +197,9 @@ fn sanitize_validator_network_config(
             ));
         }
 
+        // Verify the outbound protocol rate limits
+        sanitize_outbound_protocol_rate_limits(&sanitizer_name, validator_network_config)?;
+
         // Prepare the network id
         validator_network_config.set_listen_address_and_prepare_identity()?;
     }
@@ -201,10 +207,32 @@ fn sanitize_validator_network_config(
     Ok(())
 }
 
+/// Sanitize the outbound protocol rate limits of the given network config
+fn sanitize_outbound_protocol_rate_limits(
+    sanitizer_name: &str,
+    network_config: &NetworkConfig,
+) -> Result<(), Error> {
+    for (protocol_name, rate_limit_config) in &network_config.outbound_protocol_rate_limits {
+        // Verify that the bucket can hold a second worth of bytes
+        if rate_limit_config.byte_bucket_size < rate_limit_config.byte_rate {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_string(),
+                format!(
+                    "The byte_bucket_size of protocol {} must be >= its byte_rate!",
+                    protocol_name
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{config::NetworkConfig, network_id::NetworkId};
+    use crate::{config::ProtocolRateLimitConfig, network_id::NetworkId};
+    use std::collections::HashMap;
 
     #[test]
     fn test_sanitize_missing_pfn_network_configs() {
@@ -372,4 +400,44 @@ mod tests {
         .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_rate_limited_protocol() {
+        // Create a validator config that rate limits a known protocol
+        let mut node_config = NodeConfig {
+            validator_network: Some(NetworkConfig {
+                network_id: NetworkId::Validator,
+                mutual_authentication: true,
+                outbound_protocol_rate_limits: HashMap::from([(
+                    "StorageServiceRpc".to_string(),
+                    ProtocolRateLimitConfig::default(),
+                )]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it succeeds
+        sanitize_validator_network_config(
+            &mut node_config,
+            NodeType::Validator,
+            ChainId::testnet(),
+        )
+        .unwrap();
+
+        // Shrink the bucket below the byte rate and verify that sanitization fails
+        let network_config = node_config.validator_network.as_mut().unwrap();
+        network_config
+            .outbound_protocol_rate_limits
+            .get_mut("StorageServiceRpc")
+            .unwrap()
+            .byte_bucket_size = 1;
+        let error = sanitize_validator_network_config(
+            &mut node_config,
+            NodeType::Validator,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
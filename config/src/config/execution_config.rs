@@ -26,14 +26,25 @@ pub struct ExecutionConfig {
     pub genesis_file_location: PathBuf,
     /// Number of threads to run execution
     pub concurrency_level: u16,
+    /// Number of shards the blocks are partitioned into for execution, each executed in parallel
+    /// with concurrency_level / num_executor_shards threads (1 disables sharding)
+    pub num_executor_shards: u16,
     /// Number of threads to read proofs
     pub num_proof_reading_threads: u16,
+    /// Reuses the VMs, with the modules they loaded, across blocks
+    pub cross_block_vm_cache: bool,
     /// Enables paranoid mode for types, which adds extra runtime VM checks
     pub paranoid_type_verification: bool,
     /// Enables paranoid mode for hot potatoes, which adds extra runtime VM checks
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// Re-executes every n-th block or chunk sequentially in the background and compares the
+    /// result with the parallel execution (0 disables the check). Meant for fullnodes, as it
+    /// costs an extra execution thread.
+    pub differential_execution_sample_interval: u64,
+    /// Directory to dump the blocks and chunks with differential execution mismatches to, if set
+    pub differential_execution_dump_dir: Option<PathBuf>,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -59,10 +70,14 @@ impl Default for ExecutionConfig {
             genesis_file_location: PathBuf::new(),
             // Parallel execution by default.
             concurrency_level: 8,
+            num_executor_shards: 1,
             num_proof_reading_threads: 32,
+            cross_block_vm_cache: false,
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            differential_execution_sample_interval: 0,
+            differential_execution_dump_dir: None,
         }
     }
 }
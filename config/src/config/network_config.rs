@@ -53,6 +53,8 @@ pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const CONNECTION_BACKOFF_BASE: u64 = 2;
 pub const IP_BYTE_BUCKET_RATE: usize = 102400 /* 100 KiB */;
 pub const IP_BYTE_BUCKET_SIZE: usize = IP_BYTE_BUCKET_RATE;
+pub const PROTOCOL_BYTE_BUCKET_RATE: usize = 10 * 1024 * 1024 /* 10 MiB */;
+pub const PROTOCOL_BYTE_BUCKET_SIZE: usize = PROTOCOL_BYTE_BUCKET_RATE;
 pub const INBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with 500ms latency
 pub const INBOUND_TCP_TX_BUFFER_SIZE: u32 = 512 * 1024; // 1MB use a bigger spoon
 pub const OUTBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with 500ms latency
@@ -117,6 +119,10 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     /// Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    /// Outbound byte rate limits for individual protocols (keyed by protocol name, e.g.,
+    /// "StorageServiceRpc"). The limits apply to each connection separately. Unknown protocol
+    /// names are rejected when the network is built.
+    pub outbound_protocol_rate_limits: HashMap<String, ProtocolRateLimitConfig>,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
 }
@@ -154,6 +160,7 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            outbound_protocol_rate_limits: HashMap::new(),
             max_message_size: MAX_MESSAGE_SIZE,
             inbound_rx_buffer_size_bytes: Some(INBOUND_TCP_RX_BUFFER_SIZE),
             inbound_tx_buffer_size_bytes: Some(INBOUND_TCP_TX_BUFFER_SIZE),
@@ -367,6 +374,27 @@ impl Default for RateLimitConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProtocolRateLimitConfig {
+    /// Maximum number of bytes/s sent for the protocol on a connection
+    pub byte_rate: usize,
+    /// Maximum burst of bytes for the protocol on a connection (must be >= `byte_rate`)
+    pub byte_bucket_size: usize,
+    /// Initial amount of tokens initially in the bucket
+    pub initial_bucket_fill_percentage: u8,
+}
+
+impl Default for ProtocolRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            byte_rate: PROTOCOL_BYTE_BUCKET_RATE,
+            byte_bucket_size: PROTOCOL_BYTE_BUCKET_SIZE,
+            initial_bucket_fill_percentage: 100,
+        }
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
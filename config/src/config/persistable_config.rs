@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::{Error, NodeConfig, SafetyRulesConfig};
+use crate::config::{DbPathConfig, Error, NodeConfig, SafetyRulesConfig};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     fs::{read_to_string, File},
@@ -54,3 +54,4 @@ pub trait PersistableConfig: Serialize + DeserializeOwned {
 // We only implement PersistableConfig for the configs that should be read/written to disk
 impl PersistableConfig for NodeConfig {}
 impl PersistableConfig for SafetyRulesConfig {}
+impl PersistableConfig for DbPathConfig {}
@@ -3,14 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig},
+    config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+        PersistableConfig,
+    },
     utils,
 };
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 // Lru cache will consume about 2G RAM based on this default value.
@@ -18,6 +22,9 @@ pub const DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD: usize = 1 << 13;
 
 pub const BUFFERED_STATE_TARGET_ITEMS: usize = 100_000;
 
+/// The number of shards of the state KV DB and the state merkle DB
+pub const NUM_STATE_SHARDS: usize = 16;
+
 /// Port selected RocksDB options for tuning underlying rocksdb instance of AptosDB.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
@@ -70,6 +77,9 @@ pub struct RocksdbConfigs {
     pub use_sharded_state_merkle_db: bool,
     pub state_kv_db_config: RocksdbConfig,
     pub index_db_config: RocksdbConfig,
+    /// Whether to maintain the optional event-by-type and transaction-by-entry-function indexes
+    /// in the ledger db. Only data committed while this is enabled will be indexed.
+    pub enable_secondary_indexes: bool,
 }
 
 impl Default for RocksdbConfigs {
@@ -84,6 +94,7 @@ impl Default for RocksdbConfigs {
                 max_open_files: 1000,
                 ..Default::default()
             },
+            enable_secondary_indexes: false,
         }
     }
 }
@@ -114,6 +125,125 @@ pub struct StorageConfig {
     /// since genesis. To recover operation after data loss, or to bootstrap a node in fast sync
     /// mode, the indexer db needs to be copied in from another node.
     pub enable_indexer: bool,
+    /// Optional root directories for individual DBs (and DB shards), e.g., to place them on
+    /// separate disks. DBs without an override are stored under `dir`.
+    pub db_path_overrides: Option<DbPathConfig>,
+    /// If set, the ledger pruner archives transactions, write sets and events into immutable
+    /// compressed segments in this directory before deleting them, and historical reads of them
+    /// fall back to the archive. A relative path is resolved against `dir`.
+    pub ledger_archive_dir: Option<PathBuf>,
+    /// Alternatively to `ledger_archive_dir`, keeps the ledger archive in the backup storage that
+    /// the backup command adapter config at this path talks to. The storage must be dedicated to
+    /// the archive, i.e., not be shared with backups.
+    pub ledger_archive_command_adapter_config: Option<PathBuf>,
+}
+
+/// Root directories of the individual DBs, overriding the default (`StorageConfig::dir`).
+/// Every override is a root directory, i.e., the DB is stored in the same sub-directory (e.g.,
+/// `state_kv_db/shard_3`) of the override as it would be under the default directory.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DbPathConfig {
+    pub ledger_db_path: Option<PathBuf>,
+    pub state_kv_db_path: Option<ShardedDbPathConfig>,
+    pub state_merkle_db_path: Option<ShardedDbPathConfig>,
+}
+
+/// Root directories of a sharded DB.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShardedDbPathConfig {
+    /// Root directory of the metadata DB, also used by all shards without their own override
+    pub metadata_path: Option<PathBuf>,
+    /// Root directories of individual shards, by shard id
+    pub shard_paths: BTreeMap<u8, PathBuf>,
+}
+
+/// The resolved root directories of all DBs, see [`DbPathConfig`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageDirPaths {
+    default_path: PathBuf,
+    ledger_db_path: Option<PathBuf>,
+    state_kv_db_paths: ShardedDbPathConfig,
+    state_merkle_db_paths: ShardedDbPathConfig,
+}
+
+impl StorageDirPaths {
+    /// All DBs are stored under the given root directory.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        Self::new(path, None)
+    }
+
+    /// DBs are stored under the given root directory, unless overridden.
+    pub fn new<P: AsRef<Path>>(default_path: P, overrides: Option<&DbPathConfig>) -> Self {
+        let mut dir_paths = Self {
+            default_path: default_path.as_ref().to_path_buf(),
+            ledger_db_path: None,
+            state_kv_db_paths: ShardedDbPathConfig::default(),
+            state_merkle_db_paths: ShardedDbPathConfig::default(),
+        };
+        if let Some(overrides) = overrides {
+            dir_paths.ledger_db_path = overrides.ledger_db_path.clone();
+            if let Some(state_kv_db_path) = &overrides.state_kv_db_path {
+                dir_paths.state_kv_db_paths = state_kv_db_path.clone();
+            }
+            if let Some(state_merkle_db_path) = &overrides.state_merkle_db_path {
+                dir_paths.state_merkle_db_paths = state_merkle_db_path.clone();
+            }
+        }
+        dir_paths
+    }
+
+    /// DBs are stored under the given root directory, unless overridden in the given YAML file
+    /// (in the format of [`DbPathConfig`]).
+    pub fn from_path_and_overrides_file<P: AsRef<Path>>(
+        path: P,
+        overrides_file: Option<&Path>,
+    ) -> Result<Self, Error> {
+        let overrides = overrides_file.map(DbPathConfig::load_config).transpose()?;
+        Ok(Self::new(path, overrides.as_ref()))
+    }
+
+    pub fn default_root_path(&self) -> &Path {
+        &self.default_path
+    }
+
+    pub fn ledger_db_root_path(&self) -> &Path {
+        self.ledger_db_path
+            .as_deref()
+            .unwrap_or_else(|| self.default_root_path())
+    }
+
+    pub fn state_kv_db_metadata_root_path(&self) -> &Path {
+        self.metadata_root_path(&self.state_kv_db_paths)
+    }
+
+    pub fn state_kv_db_shard_root_path(&self, shard_id: u8) -> &Path {
+        self.shard_root_path(&self.state_kv_db_paths, shard_id)
+    }
+
+    pub fn state_merkle_db_metadata_root_path(&self) -> &Path {
+        self.metadata_root_path(&self.state_merkle_db_paths)
+    }
+
+    pub fn state_merkle_db_shard_root_path(&self, shard_id: u8) -> &Path {
+        self.shard_root_path(&self.state_merkle_db_paths, shard_id)
+    }
+
+    fn metadata_root_path<'a>(&'a self, paths: &'a ShardedDbPathConfig) -> &'a Path {
+        paths
+            .metadata_path
+            .as_deref()
+            .unwrap_or_else(|| self.default_root_path())
+    }
+
+    fn shard_root_path<'a>(&'a self, paths: &'a ShardedDbPathConfig, shard_id: u8) -> &'a Path {
+        paths
+            .shard_paths
+            .get(&shard_id)
+            .map(PathBuf::as_path)
+            .unwrap_or_else(|| self.metadata_root_path(paths))
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -262,6 +392,9 @@ impl Default for StorageConfig {
             enable_indexer: false,
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            db_path_overrides: None,
+            ledger_archive_dir: None,
+            ledger_archive_command_adapter_config: None,
         }
     }
 }
@@ -275,6 +408,18 @@ impl StorageConfig {
         }
     }
 
+    /// Returns the root directories of all DBs, applying the configured overrides (if any)
+    pub fn get_dir_paths(&self) -> StorageDirPaths {
+        StorageDirPaths::new(self.dir(), self.db_path_overrides.as_ref())
+    }
+
+    /// Returns the directory of the ledger archive, if the archive is enabled
+    pub fn ledger_archive_dir(&self) -> Option<PathBuf> {
+        self.ledger_archive_dir
+            .as_ref()
+            .map(|archive_dir| self.dir().join(archive_dir))
+    }
+
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.data_dir = data_dir;
     }
@@ -287,17 +432,66 @@ impl StorageConfig {
 
 impl ConfigSanitizer for StorageConfig {
     fn sanitize(
-        _node_config: &mut NodeConfig,
+        node_config: &mut NodeConfig,
         _node_type: NodeType,
         _chain_id: ChainId,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add validation of higher-level properties once we have variable configs
+        let sanitizer_name = Self::get_sanitizer_name();
+        let storage_config = &node_config.storage;
+
+        // Verify that all DB path overrides are absolute and refer to existing shards
+        if let Some(overrides) = &storage_config.db_path_overrides {
+            let mut paths: Vec<&PathBuf> = overrides.ledger_db_path.iter().collect();
+            for sharded_paths in [&overrides.state_kv_db_path, &overrides.state_merkle_db_path]
+                .into_iter()
+                .flatten()
+            {
+                paths.extend(sharded_paths.metadata_path.iter());
+                if let Some(shard_id) = sharded_paths
+                    .shard_paths
+                    .keys()
+                    .find(|shard_id| **shard_id as usize >= NUM_STATE_SHARDS)
+                {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "Invalid shard id {} in the DB path overrides, there are only {} shards!",
+                            shard_id, NUM_STATE_SHARDS
+                        ),
+                    ));
+                }
+                paths.extend(sharded_paths.shard_paths.values());
+            }
+            if let Some(path) = paths.into_iter().find(|path| path.is_relative()) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!("DB path overrides must be absolute, found: {:?}", path),
+                ));
+            }
+        }
+
+        // Verify that the ledger archive is configured in one place at most
+        if storage_config.ledger_archive_dir.is_some()
+            && storage_config
+                .ledger_archive_command_adapter_config
+                .is_some()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "ledger_archive_dir and ledger_archive_command_adapter_config can't both be set!"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
     use crate::config::PrunerConfig;
+    use aptos_temppath::TempPath;
 
     #[test]
     pub fn test_default_prune_window() {
@@ -308,4 +502,137 @@ mod test {
         assert!(config.state_merkle_pruner_config.prune_window >= 100_000);
         assert!(config.epoch_snapshot_pruner_config.prune_window > 50_000_000);
     }
+
+    #[test]
+    fn test_get_dir_paths() {
+        let storage_config = StorageConfig {
+            dir: PathBuf::from("/opt/aptos/db"),
+            db_path_overrides: Some(DbPathConfig {
+                ledger_db_path: Some(PathBuf::from("/disk0")),
+                state_kv_db_path: Some(ShardedDbPathConfig {
+                    metadata_path: Some(PathBuf::from("/disk1")),
+                    shard_paths: [(3, PathBuf::from("/disk2"))].into_iter().collect(),
+                }),
+                state_merkle_db_path: None,
+            }),
+            ..Default::default()
+        };
+
+        let dir_paths = storage_config.get_dir_paths();
+        assert_eq!(dir_paths.default_root_path(), Path::new("/opt/aptos/db"));
+        assert_eq!(dir_paths.ledger_db_root_path(), Path::new("/disk0"));
+        assert_eq!(
+            dir_paths.state_kv_db_metadata_root_path(),
+            Path::new("/disk1")
+        );
+        assert_eq!(
+            dir_paths.state_kv_db_shard_root_path(3),
+            Path::new("/disk2")
+        );
+        assert_eq!(
+            dir_paths.state_kv_db_shard_root_path(4),
+            Path::new("/disk1")
+        );
+        assert_eq!(
+            dir_paths.state_merkle_db_metadata_root_path(),
+            Path::new("/opt/aptos/db")
+        );
+        assert_eq!(
+            dir_paths.state_merkle_db_shard_root_path(3),
+            Path::new("/opt/aptos/db")
+        );
+    }
+
+    #[test]
+    fn test_dir_paths_from_overrides_file() {
+        let overrides = DbPathConfig {
+            state_merkle_db_path: Some(ShardedDbPathConfig {
+                metadata_path: None,
+                shard_paths: [(5, PathBuf::from("/disk1"))].into_iter().collect(),
+            }),
+            ..Default::default()
+        };
+        let overrides_file = TempPath::new();
+        overrides.save_config(overrides_file.path()).unwrap();
+
+        let dir_paths = StorageDirPaths::from_path_and_overrides_file(
+            "/opt/aptos/db",
+            Some(overrides_file.path()),
+        )
+        .unwrap();
+        assert_eq!(
+            dir_paths,
+            StorageDirPaths::new("/opt/aptos/db", Some(&overrides))
+        );
+        assert_eq!(
+            dir_paths.state_merkle_db_shard_root_path(5),
+            Path::new("/disk1")
+        );
+    }
+
+    #[test]
+    fn test_sanitize_relative_db_path_override() {
+        // Create a node config with a relative ledger db path
+        let mut node_config = NodeConfig {
+            storage: StorageConfig {
+                db_path_overrides: Some(DbPathConfig {
+                    ledger_db_path: Some(PathBuf::from("ledger")),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            StorageConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::mainnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_invalid_shard_path_override() {
+        // Create a node config with a path for a shard that doesn't exist
+        let mut node_config = NodeConfig {
+            storage: StorageConfig {
+                db_path_overrides: Some(DbPathConfig {
+                    state_merkle_db_path: Some(ShardedDbPathConfig {
+                        metadata_path: None,
+                        shard_paths: [(NUM_STATE_SHARDS as u8, PathBuf::from("/disk1"))]
+                            .into_iter()
+                            .collect(),
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            StorageConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::mainnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_ledger_archive_configured_twice() {
+        // Create a node config with both a local and a backup storage ledger archive
+        let mut node_config = NodeConfig {
+            storage: StorageConfig {
+                ledger_archive_dir: Some(PathBuf::from("ledger_archive")),
+                ledger_archive_command_adapter_config: Some(PathBuf::from("/archive.yaml")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            StorageConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::mainnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
@@ -0,0 +1,360 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    counters::{NUM_SENDERS_IN_BLOCK, TXN_SHUFFLE_SECONDS, TXN_SHUFFLE_UNRESOLVED_CONFLICTS},
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_types::transaction::{SignedTransaction, TransactionPayload};
+use aptos_vm::read_write_set_analysis::WriteHints;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, ResourceKey},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// An implementation of transaction shuffler, which tries to spread transactions that are likely
+/// to conflict in a block. It generalizes `SenderAwareShuffler` from senders to conflict keys:
+/// a transaction conflicts with its sender and with the resources it writes according to the
+/// static `WriteHints`. When no hint is available for an entry function transaction, the module
+/// of the entry function is used as a coarse stand-in for the resources it writes.
+///
+/// The shuffler maintains the conflict keys of the last `conflict_window_size` transactions added
+/// to the block. To select the next transaction, it looks at the next `conflict_window_size`
+/// remaining transactions in the original order and picks the first one that has no conflict key
+/// in the window and no earlier remaining transaction from the same sender. If there is none, it
+/// keeps the order and picks the first remaining transaction. Hence
+/// 1. Relative ordering of all transactions from the same sender is preserved.
+/// 2. If no transactions conflict, the ordering is unchanged.
+///
+/// The shuffling algorithm is O(n * conflict_window_size * number of conflict keys).
+pub struct ConflictAwareShuffler {
+    conflict_window_size: usize,
+    write_hints: Option<Arc<dyn WriteHints>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum ConflictKey {
+    Sender(AccountAddress),
+    Module(ModuleId),
+    Resource(ResourceKey),
+}
+
+impl ConflictAwareShuffler {
+    pub fn new(conflict_window_size: usize, write_hints: Option<Arc<dyn WriteHints>>) -> Self {
+        Self {
+            conflict_window_size,
+            write_hints,
+        }
+    }
+
+    fn conflict_keys(&self, txns: &[SignedTransaction]) -> Vec<Vec<ConflictKey>> {
+        let write_hints = match &self.write_hints {
+            Some(write_hints) => write_hints.get_write_hints(txns),
+            None => vec![vec![]; txns.len()],
+        };
+        txns.iter()
+            .zip(write_hints)
+            .map(|(txn, resources)| {
+                let mut keys = vec![ConflictKey::Sender(txn.sender())];
+                if !resources.is_empty() {
+                    keys.extend(resources.into_iter().map(ConflictKey::Resource));
+                } else if let TransactionPayload::EntryFunction(entry_function) = txn.payload() {
+                    keys.push(ConflictKey::Module(entry_function.module().clone()));
+                }
+                keys.sort();
+                keys.dedup();
+                keys
+            })
+            .collect()
+    }
+}
+
+impl TransactionShuffler for ConflictAwareShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let _timer = TXN_SHUFFLE_SECONDS.start_timer();
+
+        let conflict_keys = self.conflict_keys(&txns);
+        let mut sliding_window = SlidingWindowState::new(self.conflict_window_size);
+        let mut remaining: VecDeque<usize> = (0..txns.len()).collect();
+        let mut order = Vec::with_capacity(txns.len());
+        let mut num_unresolved_conflicts = 0;
+
+        while !remaining.is_empty() {
+            let mut blocked_senders = HashSet::new();
+            let pos = remaining
+                .iter()
+                .take(self.conflict_window_size.max(1))
+                .position(|&idx| {
+                    let sender = txns[idx].sender();
+                    if blocked_senders.contains(&sender) {
+                        return false;
+                    }
+                    if sliding_window.has_conflict(&conflict_keys[idx]) {
+                        // Later transactions from the sender can't move ahead of this one.
+                        blocked_senders.insert(sender);
+                        return false;
+                    }
+                    true
+                })
+                .unwrap_or_else(|| {
+                    num_unresolved_conflicts += 1;
+                    0
+                });
+            let idx = remaining.remove(pos).expect("position must be in range");
+            sliding_window.add(idx, &conflict_keys);
+            order.push(idx);
+        }
+
+        NUM_SENDERS_IN_BLOCK.set(
+            txns.iter()
+                .map(|txn| txn.sender())
+                .collect::<HashSet<_>>()
+                .len() as f64,
+        );
+        TXN_SHUFFLE_UNRESOLVED_CONFLICTS.inc_by(num_unresolved_conflicts);
+
+        let mut txns: Vec<_> = txns.into_iter().map(Some).collect();
+        order
+            .into_iter()
+            .map(|idx| txns[idx].take().expect("each transaction is added once"))
+            .collect()
+    }
+}
+
+/// The conflict keys of the last `window_size` transactions added to the block.
+struct SlidingWindowState {
+    window_size: usize,
+    window: VecDeque<usize>,
+    key_counts: HashMap<ConflictKey, usize>,
+}
+
+impl SlidingWindowState {
+    fn new(window_size: usize) -> Self {
+        Self {
+            window_size,
+            window: VecDeque::with_capacity(window_size + 1),
+            key_counts: HashMap::new(),
+        }
+    }
+
+    fn has_conflict(&self, keys: &[ConflictKey]) -> bool {
+        keys.iter().any(|key| self.key_counts.contains_key(key))
+    }
+
+    fn add(&mut self, idx: usize, conflict_keys: &[Vec<ConflictKey>]) {
+        if self.window_size == 0 {
+            return;
+        }
+        for key in &conflict_keys[idx] {
+            *self.key_counts.entry(key.clone()).or_insert(0) += 1;
+        }
+        self.window.push_back(idx);
+        if self.window.len() > self.window_size {
+            let dropped = self.window.pop_front().expect("window is not empty");
+            for key in &conflict_keys[dropped] {
+                let count = self
+                    .key_counts
+                    .get_mut(key)
+                    .expect("key of a transaction in the window must be counted");
+                *count -= 1;
+                if *count == 0 {
+                    self.key_counts.remove(key);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        conflict_aware_shuffler::ConflictAwareShuffler, transaction_shuffler::TransactionShuffler,
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{EntryFunction, RawTransaction, SignedTransaction, TransactionPayload},
+    };
+    use aptos_vm::read_write_set_analysis::WriteHints;
+    use move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, ResourceKey, StructTag},
+    };
+    use std::sync::Arc;
+
+    fn create_signed_transaction(
+        num_transactions: usize,
+        module: &ModuleId,
+    ) -> Vec<SignedTransaction> {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let sender = AccountAddress::random();
+
+        (0..num_transactions)
+            .map(|i| {
+                let transaction_payload = TransactionPayload::EntryFunction(EntryFunction::new(
+                    module.clone(),
+                    Identifier::new("f").unwrap(),
+                    vec![],
+                    vec![],
+                ));
+                let raw_transaction = RawTransaction::new(
+                    sender,
+                    i as u64,
+                    transaction_payload,
+                    0,
+                    0,
+                    0,
+                    ChainId::new(10),
+                );
+                SignedTransaction::new(
+                    raw_transaction.clone(),
+                    public_key.clone(),
+                    private_key.sign(&raw_transaction).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn module(name: &str) -> ModuleId {
+        ModuleId::new(AccountAddress::ONE, Identifier::new(name).unwrap())
+    }
+
+    /// Hints that every transaction writes a resource under its sender's account.
+    struct SenderResourceWriteHints;
+
+    impl WriteHints for SenderResourceWriteHints {
+        fn get_write_hints(&self, txns: &[SignedTransaction]) -> Vec<Vec<ResourceKey>> {
+            txns.iter()
+                .map(|txn| {
+                    vec![ResourceKey::new(txn.sender(), StructTag {
+                        address: AccountAddress::ONE,
+                        module: Identifier::new("a").unwrap(),
+                        name: Identifier::new("R").unwrap(),
+                        type_params: vec![],
+                    })]
+                })
+                .collect()
+        }
+    }
+
+    /// Hints that transactions calling module `x` write a resource under their sender's account
+    /// and all other transactions write the same global resource.
+    struct SharedResourceWriteHints;
+
+    impl WriteHints for SharedResourceWriteHints {
+        fn get_write_hints(&self, txns: &[SignedTransaction]) -> Vec<Vec<ResourceKey>> {
+            txns.iter()
+                .map(|txn| {
+                    let (address, module_name) = match txn.payload() {
+                        TransactionPayload::EntryFunction(entry_function)
+                            if entry_function.module() == &module("x") =>
+                        {
+                            (txn.sender(), "x")
+                        },
+                        _ => (AccountAddress::ONE, "shared"),
+                    };
+                    vec![ResourceKey::new(address, StructTag {
+                        address: AccountAddress::ONE,
+                        module: Identifier::new(module_name).unwrap(),
+                        name: Identifier::new("R").unwrap(),
+                        type_params: vec![],
+                    })]
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_non_conflicting_txns() {
+        let mut txns = Vec::new();
+        for i in 0..50 {
+            txns.extend(create_signed_transaction(1, &module(&format!("m{}", i))));
+        }
+        let txn_shuffler = ConflictAwareShuffler::new(10, None);
+        // Assert that the ordering is unchanged if no transactions conflict.
+        assert_eq!(txn_shuffler.shuffle(txns.clone()), txns);
+    }
+
+    #[test]
+    // A1, B1, C1, D1 all calling module a, X1, Y1 calling modules x and y
+    // with conflict_window_size=3, should return
+    // (X1 is only within the lookahead once B1 is added without resolving the conflict):
+    // A1, B1, X1, Y1, C1, D1
+    fn test_module_conflict_shuffling() {
+        let module_a = module("a");
+        let txns_a: Vec<_> = (0..4)
+            .flat_map(|_| create_signed_transaction(1, &module_a))
+            .collect();
+        let txn_x = create_signed_transaction(1, &module("x"));
+        let txn_y = create_signed_transaction(1, &module("y"));
+        let mut orig_txns = txns_a.clone();
+        orig_txns.extend(txn_x.clone());
+        orig_txns.extend(txn_y.clone());
+
+        let txn_shuffler = ConflictAwareShuffler::new(3, None);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(optimized_txns.len(), orig_txns.len());
+        assert_eq!(optimized_txns[0], txns_a[0]);
+        assert_eq!(optimized_txns[1], txns_a[1]);
+        assert_eq!(optimized_txns[2], txn_x[0]);
+        assert_eq!(optimized_txns[3], txn_y[0]);
+        assert_eq!(optimized_txns[4], txns_a[2]);
+        assert_eq!(optimized_txns[5], txns_a[3]);
+    }
+
+    #[test]
+    // A1, B1 calling module a, X1 calling module x
+    // with conflict_window_size=2, should return A1, X1, B1 without hints, and keep the order if
+    // the hints tell that A1 and B1 write disjoint resources.
+    fn test_write_hints_override_module() {
+        let module_a = module("a");
+        let txn_a = create_signed_transaction(1, &module_a);
+        let txn_b = create_signed_transaction(1, &module_a);
+        let txn_x = create_signed_transaction(1, &module("x"));
+        let mut orig_txns = txn_a.clone();
+        orig_txns.extend(txn_b.clone());
+        orig_txns.extend(txn_x.clone());
+
+        let txn_shuffler = ConflictAwareShuffler::new(2, None);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(optimized_txns, vec![
+            txn_a[0].clone(),
+            txn_x[0].clone(),
+            txn_b[0].clone()
+        ]);
+
+        let txn_shuffler = ConflictAwareShuffler::new(2, Some(Arc::new(SenderResourceWriteHints)));
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(optimized_txns, orig_txns);
+    }
+
+    #[test]
+    // A1 calling module a, B1 calling module b, both writing the same resource, X1 calling
+    // module x with conflict_window_size=2, should keep the order without hints as no modules
+    // conflict, and return A1, X1, B1 once the hints tell that A1 and B1 conflict.
+    fn test_write_hints_conflict_shuffling() {
+        let txn_a = create_signed_transaction(1, &module("a"));
+        let txn_b = create_signed_transaction(1, &module("b"));
+        let txn_x = create_signed_transaction(1, &module("x"));
+        let mut orig_txns = txn_a.clone();
+        orig_txns.extend(txn_b.clone());
+        orig_txns.extend(txn_x.clone());
+
+        let txn_shuffler = ConflictAwareShuffler::new(2, None);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(optimized_txns, orig_txns);
+
+        let txn_shuffler = ConflictAwareShuffler::new(2, Some(Arc::new(SharedResourceWriteHints)));
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(optimized_txns, vec![
+            txn_a[0].clone(),
+            txn_x[0].clone(),
+            txn_b[0].clone()
+        ]);
+    }
+}
@@ -116,6 +116,16 @@ pub static NUM_SENDERS_IN_BLOCK: Lazy<Gauge> = Lazy::new(|| {
     register_gauge!("num_senders_in_block", "Total number of senders in a block").unwrap()
 });
 
+/// Number of transactions the conflict aware shuffler could not place without a conflict in the
+/// conflict window
+pub static TXN_SHUFFLE_UNRESOLVED_CONFLICTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_txn_shuffle_unresolved_conflicts",
+        "Number of transactions placed within the conflict window of a conflicting transaction"
+    )
+    .unwrap()
+});
+
 /// Transaction shuffling call latency
 pub static TXN_SHUFFLE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
@@ -678,8 +678,12 @@ impl EpochManager {
         };
 
         let (payload_manager, quorum_store_msg_tx) = quorum_store_builder.init_payload_manager();
-        let transaction_shuffler =
-            create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
+        let transaction_shuffler = create_transaction_shuffler(
+            onchain_execution_config.transaction_shuffler_type(),
+            self.storage.aptos_db(),
+            epoch,
+        )
+        .expect("Failed to create the transaction shuffler");
         self.quorum_store_msg_tx = quorum_store_msg_tx;
 
         let payload_client = QuorumStoreClient::new(
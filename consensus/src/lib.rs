@@ -41,6 +41,7 @@ mod twins;
 mod txn_notifier;
 mod util;
 
+mod conflict_aware_shuffler;
 /// AptosBFT implementation
 pub mod consensus_provider;
 /// Required by the telemetry service
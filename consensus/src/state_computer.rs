@@ -298,11 +298,11 @@ impl StateComputer for ExecutionProxy {
 
 #[tokio::test]
 async fn test_commit_sync_race() {
-    use crate::{error::MempoolError, transaction_shuffler::create_transaction_shuffler};
+    use crate::{error::MempoolError, transaction_shuffler::NoOpShuffler};
     use aptos_consensus_notifications::Error;
     use aptos_types::{
         aggregate_signature::AggregateSignature, block_info::BlockInfo, ledger_info::LedgerInfo,
-        on_chain_config::TransactionShufflerType, transaction::SignedTransaction,
+        transaction::SignedTransaction,
     };
 
     struct RecordedCommit {
@@ -401,7 +401,7 @@ async fn test_commit_sync_race() {
     executor.new_epoch(
         &EpochState::empty(),
         Arc::new(PayloadManager::DirectMempool),
-        create_transaction_shuffler(TransactionShufflerType::NoShuffling),
+        Arc::new(NoOpShuffler {}),
     );
     executor
         .commit(&[], generate_li(1, 1), callback.clone())
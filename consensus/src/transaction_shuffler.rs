@@ -1,15 +1,20 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sender_aware_shuffler::SenderAwareShuffler;
-use aptos_logger::info;
+use crate::{
+    conflict_aware_shuffler::ConflictAwareShuffler, sender_aware_shuffler::SenderAwareShuffler,
+};
+use anyhow::{format_err, Result};
+use aptos_logger::{info, warn};
+use aptos_storage_interface::{state_view::DbStateViewAtVersion, DbReader};
 use aptos_types::{
     on_chain_config::{
         TransactionShufflerType,
-        TransactionShufflerType::{NoShuffling, SenderAwareV1},
+        TransactionShufflerType::{ConflictAwareV1, NoShuffling, SenderAwareV1},
     },
     transaction::SignedTransaction,
 };
+use aptos_vm::read_write_set_analysis::{ReadWriteSetWriteHints, WriteHints};
 use std::sync::Arc;
 
 /// Interface to shuffle transactions
@@ -26,10 +31,14 @@ impl TransactionShuffler for NoOpShuffler {
     }
 }
 
+/// Creates the shuffler of `epoch`. The shuffled order must be the same on all nodes, so
+/// everything it depends on is read at the version that started the epoch.
 pub fn create_transaction_shuffler(
     shuffler_type: TransactionShufflerType,
-) -> Arc<dyn TransactionShuffler> {
-    match shuffler_type {
+    aptos_db: Arc<dyn DbReader>,
+    epoch: u64,
+) -> Result<Arc<dyn TransactionShuffler>> {
+    Ok(match shuffler_type {
         NoShuffling => Arc::new(NoOpShuffler {}),
         SenderAwareV1(confict_window_size) => {
             info!(
@@ -38,5 +47,157 @@ pub fn create_transaction_shuffler(
             );
             Arc::new(SenderAwareShuffler::new(confict_window_size as usize))
         },
+        ConflictAwareV1(conflict_window_size) => {
+            info!(
+                "Using conflict aware transaction shuffling with conflict window size {}",
+                conflict_window_size
+            );
+            Arc::new(ConflictAwareShuffler::new(
+                conflict_window_size as usize,
+                create_write_hints(aptos_db, epoch)?,
+            ))
+        },
+    })
+}
+
+/// Builds write hints from a read/write set analysis of the framework at the version that started
+/// `epoch`. Failing to read the framework is an error, as falling back would make this node order
+/// blocks differently from the others. The analysis itself only depends on the framework, so all
+/// nodes agree on falling back to module level conflicts if it fails.
+fn create_write_hints(
+    aptos_db: Arc<dyn DbReader>,
+    epoch: u64,
+) -> Result<Option<Arc<dyn WriteHints>>> {
+    let previous_epoch = epoch
+        .checked_sub(1)
+        .ok_or_else(|| format_err!("Epoch {} has no start version.", epoch))?;
+    let version = aptos_db
+        .get_epoch_ending_ledger_infos(previous_epoch, epoch)?
+        .ledger_info_with_sigs
+        .last()
+        .ok_or_else(|| {
+            format_err!(
+                "Missing the ledger info that ended epoch {}.",
+                previous_epoch
+            )
+        })?
+        .ledger_info()
+        .version();
+    let modules =
+        ReadWriteSetWriteHints::framework_modules(&aptos_db.state_view_at_version(Some(version))?)?;
+
+    Ok(match ReadWriteSetWriteHints::new(modules) {
+        Ok(write_hints) => Some(Arc::new(write_hints)),
+        Err(e) => {
+            warn!(
+                "Failed to analyze read/write sets, shuffling by modules instead: {:?}",
+                e
+            );
+            None
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transaction_shuffler::create_transaction_shuffler;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
+    use aptos_executor_test_helpers::{
+        gen_block_id, gen_ledger_info_with_sigs, integration_test_impl::create_db_and_executor,
+    };
+    use aptos_executor_types::BlockExecutorTrait;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        block_metadata::BlockMetadata,
+        chain_id::ChainId,
+        on_chain_config::TransactionShufflerType,
+        transaction::{
+            EntryFunction, RawTransaction, SignedTransaction, Transaction, TransactionPayload,
+            WriteSetPayload,
+        },
+        validator_signer::ValidatorSigner,
+    };
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    fn create_transfer(recipient: AccountAddress) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let raw_transaction = RawTransaction::new(
+            AccountAddress::random(),
+            0,
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(
+                    AccountAddress::ONE,
+                    Identifier::new("aptos_account").unwrap(),
+                ),
+                Identifier::new("transfer").unwrap(),
+                vec![],
+                vec![
+                    bcs::to_bytes(&recipient).unwrap(),
+                    bcs::to_bytes(&1u64).unwrap(),
+                ],
+            )),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        SignedTransaction::new(
+            raw_transaction.clone(),
+            private_key.public_key(),
+            private_key.sign(&raw_transaction).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_shuffling_does_not_depend_on_db_version() {
+        let (genesis, validators) =
+            aptos_vm_genesis::test_genesis_change_set_and_validators(Some(1));
+        let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+        let path = TempPath::new();
+        path.create_as_dir().unwrap();
+        let (_aptos_db, db, executor, _waypoint) =
+            create_db_and_executor(path.path(), &genesis_txn);
+        let shuffler_type = TransactionShufflerType::ConflictAwareV1(2);
+        let shuffler_at_genesis =
+            create_transaction_shuffler(shuffler_type.clone(), db.reader.clone(), 1).unwrap();
+
+        // Commit a block in the same epoch, so the DB is at a later version.
+        let signer = ValidatorSigner::new(
+            validators[0].data.owner_address,
+            validators[0].consensus_key.clone(),
+        );
+        let block_id = gen_block_id(1);
+        let block = vec![
+            Transaction::BlockMetadata(BlockMetadata::new(
+                block_id,
+                1,
+                1,
+                signer.author(),
+                vec![0],
+                vec![],
+                1,
+            )),
+            Transaction::StateCheckpoint(HashValue::random()),
+        ];
+        let output = executor
+            .execute_block((block_id, block), executor.committed_block_id())
+            .unwrap();
+        let ledger_info = gen_ledger_info_with_sigs(1, &output, block_id, &[signer]);
+        executor.commit_blocks(vec![block_id], ledger_info).unwrap();
+        assert!(db.reader.get_latest_version().unwrap() > 0);
+        let shuffler_after_block =
+            create_transaction_shuffler(shuffler_type, db.reader.clone(), 1).unwrap();
+
+        let (alice, bob) = (AccountAddress::random(), AccountAddress::random());
+        let txns: Vec<_> = [alice, alice, bob, alice, bob, alice]
+            .into_iter()
+            .map(create_transfer)
+            .collect();
+        assert_eq!(
+            shuffler_at_genesis.shuffle(txns.clone()),
+            shuffler_after_block.shuffle(txns),
+        );
     }
 }
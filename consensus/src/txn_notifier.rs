@@ -69,6 +69,9 @@ impl TxnNotifier for MempoolNotifier {
             ).into());
         }
         let user_txn_status = &compute_status[1..txns.len() + 1];
+        // Only discarded txns are rejected. Txns with the Retry status (e.g. skipped once the
+        // block gas limit is reached) are neither committed nor rejected, so they stay in
+        // mempool and get pulled into a later block.
         for (txn, status) in txns.iter().zip_eq(user_txn_status) {
             if let TransactionStatus::Discard(reason) = status {
                 rejected_txns.push(RejectedTransactionSummary {
@@ -0,0 +1,417 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the ECDSA signature scheme over the secp256k1 curve, as used
+//! by Bitcoin and Ethereum, so that keys managed by their tooling (or by HSMs that only support
+//! secp256k1) can be used to sign transactions.
+//!
+//! Messages are hashed with SHA3-256 before signing. Signature verification rejects signatures
+//! whose s-component is not in the lower half of the curve order, so that a third party cannot
+//! produce another valid signature for the same message and key.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use aptos_crypto::{
+//!     secp256k1_ecdsa::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//!     test_utils::KeyPair
+//! };
+//! use rand_core::OsRng;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng = OsRng;
+//! let kp = KeyPair::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>::generate(&mut rng);
+//!
+//! let signature = kp.private_key.sign(&message).unwrap();
+//! assert!(signature.verify(&message, &kp.public_key).is_ok());
+//! ```
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+use crate::{hash::CryptoHash, traits::*, HashValue};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use core::convert::TryFrom;
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// The length of the Secp256k1EcdsaPrivateKey
+pub const SECP256K1_ECDSA_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Secp256k1EcdsaPublicKey, in the uncompressed SEC1 format
+pub const SECP256K1_ECDSA_PUBLIC_KEY_LENGTH: usize = 65;
+/// The length of the Secp256k1EcdsaSignature, the concatenation of its r and s components
+pub const SECP256K1_ECDSA_SIGNATURE_LENGTH: usize = 64;
+
+/// A secp256k1 ECDSA private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Secp256k1EcdsaPrivateKey(pub(crate) libsecp256k1::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Secp256k1EcdsaPrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Secp256k1EcdsaPrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        Secp256k1EcdsaPrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A secp256k1 ECDSA public key
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1EcdsaPublicKey(pub(crate) libsecp256k1::PublicKey);
+
+/// A secp256k1 ECDSA signature
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1EcdsaSignature(pub(crate) libsecp256k1::Signature);
+
+/// Hashes an arbitrary message into the digest that is actually signed.
+fn to_ecdsa_message(message: &[u8]) -> libsecp256k1::Message {
+    libsecp256k1::Message::parse(HashValue::sha3_256_of(message).as_ref())
+}
+
+impl Secp256k1EcdsaPrivateKey {
+    /// The length of the Secp256k1EcdsaPrivateKey
+    pub const LENGTH: usize = SECP256K1_ECDSA_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaPrivateKey.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256k1EcdsaSignature {
+        // The returned signature is already normalized to a low s-component.
+        let (signature, _recovery_id) = libsecp256k1::sign(&to_ecdsa_message(message), &self.0);
+        Secp256k1EcdsaSignature(signature)
+    }
+}
+
+impl Secp256k1EcdsaPublicKey {
+    /// The length of the Secp256k1EcdsaPublicKey
+    pub const LENGTH: usize = SECP256K1_ECDSA_PUBLIC_KEY_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaPublicKey in the uncompressed SEC1 format.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_PUBLIC_KEY_LENGTH] {
+        self.0.serialize()
+    }
+}
+
+impl Secp256k1EcdsaSignature {
+    /// The length of the Secp256k1EcdsaSignature
+    pub const LENGTH: usize = SECP256K1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_SIGNATURE_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// return an all-zero signature (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Secp256k1EcdsaSignature(
+            libsecp256k1::Signature::parse_standard_slice(&[0u8; Self::LENGTH]).unwrap(),
+        )
+    }
+
+    /// Check for correct size and signature malleability issues.
+    ///
+    /// Given a valid signature {r, s}, {r, n - s} (where n is the order of the curve) is also a
+    /// valid signature for the same message and key. To prevent this, we only accept signatures
+    /// whose s-component is in the lower half of the curve order, as produced by
+    /// [Secp256k1EcdsaPrivateKey](Secp256k1EcdsaPrivateKey)'s signing.
+    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let signature = libsecp256k1::Signature::parse_standard_slice(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        if signature.s.is_high() {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Secp256k1EcdsaPrivateKey {
+    type PublicKeyMaterial = Secp256k1EcdsaPublicKey;
+}
+
+impl SigningKey for Secp256k1EcdsaPrivateKey {
+    type SignatureMaterial = Secp256k1EcdsaSignature;
+    type VerifyingKeyMaterial = Secp256k1EcdsaPublicKey;
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        Ok(Secp256k1EcdsaPrivateKey::sign_arbitrary_message(
+            self,
+            signing_message(message)?.as_ref(),
+        ))
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256k1EcdsaSignature {
+        Secp256k1EcdsaPrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Secp256k1EcdsaPrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        // Rejection sampling, the probability of a random 32-byte string not being a valid
+        // secret key (i.e. zero, or not smaller than the curve order) is negligible.
+        loop {
+            let mut bytes = [0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH];
+            rng.fill_bytes(&mut bytes);
+            if let Ok(secret_key) = libsecp256k1::SecretKey::parse(&bytes) {
+                return Secp256k1EcdsaPrivateKey(secret_key);
+            }
+        }
+    }
+}
+
+impl PartialEq<Self> for Secp256k1EcdsaPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256k1EcdsaPrivateKey {}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaPrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1EcdsaPrivateKey. This method will check for private key validity:
+    /// i.e., correct key length, and a non-zero scalar smaller than the curve order.
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1EcdsaPrivateKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::SecretKey::parse_slice(bytes)
+            .map(Secp256k1EcdsaPrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256k1EcdsaPrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaPrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Secp256k1EcdsaPrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH];
+        buf[SECP256K1_ECDSA_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&Secp256k1EcdsaPrivateKey> for Secp256k1EcdsaPublicKey {
+    fn from(private_key: &Secp256k1EcdsaPrivateKey) -> Self {
+        Secp256k1EcdsaPublicKey(libsecp256k1::PublicKey::from_secret_key(&private_key.0))
+    }
+}
+
+impl PublicKey for Secp256k1EcdsaPublicKey {
+    type PrivateKeyMaterial = Secp256k1EcdsaPrivateKey;
+}
+
+impl std::hash::Hash for Secp256k1EcdsaPublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+impl PartialEq for Secp256k1EcdsaPublicKey {
+    fn eq(&self, other: &Secp256k1EcdsaPublicKey) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256k1EcdsaPublicKey {}
+
+impl VerifyingKey for Secp256k1EcdsaPublicKey {
+    type SignatureMaterial = Secp256k1EcdsaSignature;
+    type SigningKeyMaterial = Secp256k1EcdsaPrivateKey;
+}
+
+impl fmt::Display for Secp256k1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Secp256k1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1EcdsaPublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaPublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1EcdsaPublicKey from the uncompressed SEC1 format. This method
+    /// checks that the key is a valid curve point.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1EcdsaPublicKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::PublicKey::parse_slice(bytes, Some(libsecp256k1::PublicKeyFormat::Full))
+            .map(Secp256k1EcdsaPublicKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256k1EcdsaPublicKey {
+    fn length(&self) -> usize {
+        SECP256K1_ECDSA_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256k1EcdsaSignature {
+    type SigningKeyMaterial = Secp256k1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256k1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message, rejecting
+    /// signatures with a high s-component.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        Secp256k1EcdsaSignature::check_s_malleability(&self.to_bytes())?;
+        if libsecp256k1::verify(&to_ecdsa_message(message), &self.0, &public_key.0) {
+            Ok(())
+        } else {
+            Err(anyhow!("Secp256k1 ECDSA signature verification failed"))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Length for Secp256k1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256K1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256k1EcdsaSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        // As for Ed25519, mauled signatures are detected early. (This check is performed again in
+        // Secp256k1EcdsaSignature::verify_arbitrary_msg.)
+        Secp256k1EcdsaSignature::check_s_malleability(bytes)?;
+        libsecp256k1::Signature::parse_standard_slice(bytes)
+            .map(Secp256k1EcdsaSignature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Secp256k1EcdsaSignature {
+    fn eq(&self, other: &Secp256k1EcdsaSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1EcdsaSignature {}
+
+impl fmt::Display for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1EcdsaSignature({})", self)
+    }
+}
+
+/////////////
+// Fuzzing //
+/////////////
+
+/// Produces a uniformly random secp256k1 ECDSA keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy(
+) -> impl Strategy<Value = KeyPair<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>> {
+    test_utils::uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+}
+
+/// Produces a uniformly random secp256k1 ECDSA public key
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Secp256k1EcdsaPublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        keypair_strategy().prop_map(|v| v.public_key).boxed()
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the ECDSA signature scheme over the secp256r1 (NIST P-256)
+//! curve, which is the curve supported by platform authenticators such as passkeys (WebAuthn)
+//! and secure enclaves.
+//!
+//! Messages are hashed with SHA2-256 before signing, as mandated by the ES256 algorithm of
+//! WebAuthn. Signature verification rejects signatures whose s-component is not in the lower
+//! half of the curve order, so that a third party cannot produce another valid signature for the
+//! same message and key. Since authenticators do not normalize their signatures, clients must do
+//! so before submitting them.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use aptos_crypto::{
+//!     secp256r1_ecdsa::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//!     test_utils::KeyPair
+//! };
+//! use rand_core::OsRng;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng = OsRng;
+//! let kp = KeyPair::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>::generate(&mut rng);
+//!
+//! let signature = kp.private_key.sign(&message).unwrap();
+//! assert!(signature.verify(&message, &kp.public_key).is_ok());
+//! ```
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+use crate::{hash::CryptoHash, traits::*};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use core::convert::TryFrom;
+use p256::ecdsa::signature::{Signer, Verifier};
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// The length of the Secp256r1EcdsaPrivateKey
+pub const SECP256R1_ECDSA_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Secp256r1EcdsaPublicKey, in the uncompressed SEC1 format
+pub const SECP256R1_ECDSA_PUBLIC_KEY_LENGTH: usize = 65;
+/// The length of the Secp256r1EcdsaSignature, the concatenation of its r and s components
+pub const SECP256R1_ECDSA_SIGNATURE_LENGTH: usize = 64;
+
+/// A secp256r1 ECDSA private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Secp256r1EcdsaPrivateKey(pub(crate) p256::ecdsa::SigningKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Secp256r1EcdsaPrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Secp256r1EcdsaPrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        Secp256r1EcdsaPrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A secp256r1 ECDSA public key
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256r1EcdsaPublicKey(pub(crate) p256::ecdsa::VerifyingKey);
+
+/// A secp256r1 ECDSA signature
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256r1EcdsaSignature(pub(crate) p256::ecdsa::Signature);
+
+impl Secp256r1EcdsaPrivateKey {
+    /// The length of the Secp256r1EcdsaPrivateKey
+    pub const LENGTH: usize = SECP256R1_ECDSA_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaPrivateKey.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH] {
+        let mut bytes = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+        bytes.copy_from_slice(&self.0.to_bytes());
+        bytes
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256r1EcdsaSignature {
+        let signature: p256::ecdsa::Signature = self.0.sign(message);
+        // Unlike secp256k1, the signatures are not normalized to a low s-component.
+        Secp256r1EcdsaSignature(signature).normalize()
+    }
+}
+
+impl Secp256r1EcdsaPublicKey {
+    /// The length of the Secp256r1EcdsaPublicKey
+    pub const LENGTH: usize = SECP256R1_ECDSA_PUBLIC_KEY_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaPublicKey in the uncompressed SEC1 format.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH] {
+        let mut bytes = [0u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH];
+        bytes.copy_from_slice(self.0.to_encoded_point(false).as_bytes());
+        bytes
+    }
+}
+
+impl Secp256r1EcdsaSignature {
+    /// The length of the Secp256r1EcdsaSignature
+    pub const LENGTH: usize = SECP256R1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SECP256R1_ECDSA_SIGNATURE_LENGTH];
+        bytes.copy_from_slice(self.0.as_ref());
+        bytes
+    }
+
+    /// Returns the signature with its s-component normalized to the lower half of the curve
+    /// order, which is the only one accepted by the verification.
+    pub(crate) fn normalize(self) -> Self {
+        match self.0.normalize_s() {
+            Some(signature) => Secp256r1EcdsaSignature(signature),
+            None => self,
+        }
+    }
+
+    /// Check for correct size and signature malleability issues.
+    ///
+    /// Given a valid signature {r, s}, {r, n - s} (where n is the order of the curve) is also a
+    /// valid signature for the same message and key. To prevent this, we only accept signatures
+    /// whose s-component is in the lower half of the curve order.
+    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let signature = p256::ecdsa::Signature::try_from(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        if signature.normalize_s().is_some() {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Secp256r1EcdsaPrivateKey {
+    type PublicKeyMaterial = Secp256r1EcdsaPublicKey;
+}
+
+impl SigningKey for Secp256r1EcdsaPrivateKey {
+    type SignatureMaterial = Secp256r1EcdsaSignature;
+    type VerifyingKeyMaterial = Secp256r1EcdsaPublicKey;
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        Ok(Secp256r1EcdsaPrivateKey::sign_arbitrary_message(
+            self,
+            signing_message(message)?.as_ref(),
+        ))
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256r1EcdsaSignature {
+        Secp256r1EcdsaPrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Secp256r1EcdsaPrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        // Rejection sampling, the probability of a random 32-byte string not being a valid
+        // secret key (i.e. zero, or not smaller than the curve order) is negligible.
+        loop {
+            let mut bytes = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+            rng.fill_bytes(&mut bytes);
+            if let Ok(signing_key) = p256::ecdsa::SigningKey::from_bytes(&bytes) {
+                return Secp256r1EcdsaPrivateKey(signing_key);
+            }
+        }
+    }
+}
+
+impl PartialEq<Self> for Secp256r1EcdsaPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256r1EcdsaPrivateKey {}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaPrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256r1EcdsaPrivateKey. This method will check for private key validity:
+    /// i.e., correct key length, and a non-zero scalar smaller than the curve order.
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256r1EcdsaPrivateKey, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        p256::ecdsa::SigningKey::from_bytes(bytes)
+            .map(Secp256r1EcdsaPrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256r1EcdsaPrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaPrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Secp256r1EcdsaPrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+        buf[SECP256R1_ECDSA_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&Secp256r1EcdsaPrivateKey> for Secp256r1EcdsaPublicKey {
+    fn from(private_key: &Secp256r1EcdsaPrivateKey) -> Self {
+        Secp256r1EcdsaPublicKey(private_key.0.verifying_key())
+    }
+}
+
+impl PublicKey for Secp256r1EcdsaPublicKey {
+    type PrivateKeyMaterial = Secp256r1EcdsaPrivateKey;
+}
+
+impl std::hash::Hash for Secp256r1EcdsaPublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+impl PartialEq for Secp256r1EcdsaPublicKey {
+    fn eq(&self, other: &Secp256r1EcdsaPublicKey) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256r1EcdsaPublicKey {}
+
+impl VerifyingKey for Secp256r1EcdsaPublicKey {
+    type SignatureMaterial = Secp256r1EcdsaSignature;
+    type SigningKeyMaterial = Secp256r1EcdsaPrivateKey;
+}
+
+impl fmt::Display for Secp256r1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Secp256r1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1EcdsaPublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaPublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256r1EcdsaPublicKey from the uncompressed SEC1 format. This method
+    /// checks that the key is a valid curve point.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256r1EcdsaPublicKey, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+            .map(Secp256r1EcdsaPublicKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256r1EcdsaPublicKey {
+    fn length(&self) -> usize {
+        SECP256R1_ECDSA_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256r1EcdsaSignature {
+    type SigningKeyMaterial = Secp256r1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256r1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message, rejecting
+    /// signatures with a high s-component.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Secp256r1EcdsaSignature::check_s_malleability(&self.to_bytes())?;
+        public_key
+            .0
+            .verify(message, &self.0)
+            .map_err(|_| anyhow!("Secp256r1 ECDSA signature verification failed"))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Length for Secp256r1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256R1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256r1EcdsaSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        // As for Ed25519, mauled signatures are detected early. (This check is performed again in
+        // Secp256r1EcdsaSignature::verify_arbitrary_msg.)
+        Secp256r1EcdsaSignature::check_s_malleability(bytes)?;
+        p256::ecdsa::Signature::try_from(bytes)
+            .map(Secp256r1EcdsaSignature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Secp256r1EcdsaSignature {
+    fn eq(&self, other: &Secp256r1EcdsaSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256r1EcdsaSignature {}
+
+impl fmt::Display for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1EcdsaSignature({})", self)
+    }
+}
+
+/////////////
+// Fuzzing //
+/////////////
+
+/// Produces a uniformly random secp256r1 ECDSA keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy(
+) -> impl Strategy<Value = KeyPair<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>> {
+    test_utils::uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+}
+
+/// Produces a uniformly random secp256r1 ECDSA public key
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Secp256r1EcdsaPublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        keypair_strategy().prop_map(|v| v.public_key).boxed()
+    }
+}
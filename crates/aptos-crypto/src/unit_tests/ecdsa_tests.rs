@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/// Generates the tests shared by the ECDSA schemes over different curves. The caller imports
+/// `TryFrom`, `proptest::prelude::*`, the traits and the test utils.
+macro_rules! ecdsa_tests {
+    (
+        $private_key:ty,
+        $public_key:ty,
+        $signature:ty,
+        $public_key_length:expr,
+        $signature_length:expr $(,)?
+    ) => {
+        proptest! {
+            #[test]
+            fn test_sign_and_verify(
+                keypair in uniform_keypair_strategy::<$private_key, $public_key>(),
+                message in random_serializable_struct(),
+            ) {
+                let signature = keypair.private_key.sign(&message).unwrap();
+                prop_assert!(signature.verify(&message, &keypair.public_key).is_ok());
+
+                let other_message = TestAptosCrypto(format!("{}!", message.0));
+                prop_assert!(signature.verify(&other_message, &keypair.public_key).is_err());
+            }
+
+            #[test]
+            fn test_verify_with_wrong_key(
+                keypair in uniform_keypair_strategy::<$private_key, $public_key>(),
+                other_keypair in uniform_keypair_strategy::<$private_key, $public_key>(),
+                message in random_serializable_struct(),
+            ) {
+                prop_assume!(keypair.public_key != other_keypair.public_key);
+                let signature = keypair.private_key.sign(&message).unwrap();
+                prop_assert!(signature.verify(&message, &other_keypair.public_key).is_err());
+            }
+
+            #[test]
+            fn test_serialization_roundtrip(
+                keypair in uniform_keypair_strategy::<$private_key, $public_key>(),
+                message in random_serializable_struct(),
+            ) {
+                let private_key_bytes = keypair.private_key.to_bytes();
+                prop_assert_eq!(
+                    <$private_key>::try_from(&private_key_bytes[..]).unwrap(),
+                    keypair.private_key
+                );
+
+                let public_key_bytes = keypair.public_key.to_bytes();
+                prop_assert_eq!(public_key_bytes.len(), $public_key_length);
+                prop_assert_eq!(
+                    <$public_key>::try_from(&public_key_bytes[..]).unwrap(),
+                    keypair.public_key
+                );
+
+                let signature = keypair.private_key.sign(&message).unwrap();
+                let signature_bytes = signature.to_bytes();
+                prop_assert_eq!(signature_bytes.len(), $signature_length);
+                prop_assert_eq!(
+                    <$signature>::try_from(&signature_bytes[..]).unwrap(),
+                    signature
+                );
+
+                let serialized = bcs::to_bytes(&keypair.public_key).unwrap();
+                let deserialized: $public_key = bcs::from_bytes(&serialized).unwrap();
+                prop_assert_eq!(deserialized, keypair.public_key);
+            }
+        }
+
+        #[test]
+        fn test_wrong_lengths_are_rejected() {
+            assert_eq!(
+                <$private_key>::try_from(&[1u8; 31][..]),
+                Err(CryptoMaterialError::WrongLengthError)
+            );
+            assert_eq!(
+                <$public_key>::try_from(&[4u8; 33][..]),
+                Err(CryptoMaterialError::WrongLengthError)
+            );
+            assert_eq!(
+                <$signature>::try_from(&[1u8; 63][..]),
+                Err(CryptoMaterialError::WrongLengthError)
+            );
+            // Zero is not a valid secret key.
+            assert_eq!(
+                <$private_key>::try_from(&[0u8; 32][..]),
+                Err(CryptoMaterialError::DeserializationError)
+            );
+        }
+    };
+}
@@ -6,8 +6,12 @@ mod bls12381_test;
 mod compat_test;
 mod cross_test;
 mod cryptohasher;
+#[macro_use]
+mod ecdsa_tests;
 mod ed25519_test;
 mod hash_test;
 mod hkdf_test;
 mod multi_ed25519_test;
 mod noise_test;
+mod secp256k1_ecdsa_test;
+mod secp256r1_ecdsa_test;
@@ -0,0 +1,44 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256k1_ecdsa::{
+        Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature,
+        SECP256K1_ECDSA_PUBLIC_KEY_LENGTH, SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy, TestAptosCrypto},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::prelude::*;
+
+ecdsa_tests!(
+    Secp256k1EcdsaPrivateKey,
+    Secp256k1EcdsaPublicKey,
+    Secp256k1EcdsaSignature,
+    SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+    SECP256K1_ECDSA_SIGNATURE_LENGTH,
+);
+
+proptest! {
+    #[test]
+    fn test_high_s_signature_is_rejected(
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>(),
+        message in random_serializable_struct(),
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+
+        // {r, n - s} is a valid ECDSA signature for the same message and key.
+        let mut mauled = signature.0;
+        mauled.s = -mauled.s;
+        let mauled_bytes = mauled.serialize();
+        prop_assert_eq!(
+            Secp256k1EcdsaSignature::check_s_malleability(&mauled_bytes),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        prop_assert!(Secp256k1EcdsaSignature::try_from(&mauled_bytes[..]).is_err());
+        prop_assert!(Secp256k1EcdsaSignature(mauled)
+            .verify(&message, &keypair.public_key)
+            .is_err());
+    }
+}
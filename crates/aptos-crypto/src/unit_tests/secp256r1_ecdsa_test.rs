@@ -0,0 +1,44 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature,
+        SECP256R1_ECDSA_PUBLIC_KEY_LENGTH, SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy, TestAptosCrypto},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::prelude::*;
+
+ecdsa_tests!(
+    Secp256r1EcdsaPrivateKey,
+    Secp256r1EcdsaPublicKey,
+    Secp256r1EcdsaSignature,
+    SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+    SECP256R1_ECDSA_SIGNATURE_LENGTH,
+);
+
+proptest! {
+    #[test]
+    fn test_high_s_signature_is_rejected(
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>(),
+        message in random_serializable_struct(),
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+
+        // {r, n - s} is a valid ECDSA signature for the same message and key.
+        let mauled = p256::ecdsa::Signature::from_scalars(signature.0.r(), -signature.0.s()).unwrap();
+        let mauled_bytes = mauled.as_ref().to_vec();
+        prop_assert_eq!(
+            Secp256r1EcdsaSignature::check_s_malleability(&mauled_bytes),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        prop_assert!(Secp256r1EcdsaSignature::try_from(&mauled_bytes[..]).is_err());
+        prop_assert!(Secp256r1EcdsaSignature(mauled)
+            .verify(&message, &keypair.public_key)
+            .is_err());
+        prop_assert_eq!(Secp256r1EcdsaSignature(mauled).normalize(), signature);
+    }
+}
@@ -251,9 +251,18 @@ impl Bucket {
         }
     }
 
-    /// Refill tokens based on how many seconds have passed since last refresh
-    pub(crate) fn refill(&mut self) {
-        let num_intervals = self.last_refresh_time.elapsed().as_secs();
+    /// Starts counting refill intervals from the given time instead of the creation time.
+    /// Used with the `_at` methods when time is provided by the caller (e.g., a mock clock).
+    pub fn starting_at(mut self, now: Instant) -> Self {
+        self.last_refresh_time = now;
+        self
+    }
+
+    /// Refill tokens based on how many seconds have passed between the last refresh and `now`
+    pub(crate) fn refill_at(&mut self, now: Instant) {
+        let num_intervals = now
+            .saturating_duration_since(self.last_refresh_time)
+            .as_secs();
         if num_intervals > 0 {
             // Log how many were throttled in the period before refill
             if self.allowed_in_period > 0 || self.throttled_in_period > 0 {
@@ -296,13 +305,22 @@ impl Bucket {
     /// to make it through, or else it must be rejected.  A result of `None` means it cannot
     /// ever be allowed through, as it's bigger than the size of the bucket.
     pub fn acquire_all_tokens(&mut self, requested: usize) -> Result<(), Option<Instant>> {
+        self.acquire_all_tokens_at(requested, Instant::now())
+    }
+
+    /// Same as `acquire_all_tokens`, but refills the bucket as of the given time
+    pub fn acquire_all_tokens_at(
+        &mut self,
+        requested: usize,
+        now: Instant,
+    ) -> Result<(), Option<Instant>> {
         // Skip over if we purposely have an open throttle
         if !self.enabled || requested == 0 {
             return Ok(());
         }
 
         // Refill if needed
-        self.refill();
+        self.refill_at(now);
 
         if self.tokens >= requested {
             self.deduct_tokens(requested);
@@ -318,13 +336,18 @@ impl Bucket {
     /// Returns `usize` of tokens allowed.  May be less than requested.
     /// For best effort, caller should return unused tokens with `add_tokens`
     pub fn acquire_tokens(&mut self, requested: usize) -> Result<usize, Instant> {
+        self.acquire_tokens_at(requested, Instant::now())
+    }
+
+    /// Same as `acquire_tokens`, but refills the bucket as of the given time
+    pub fn acquire_tokens_at(&mut self, requested: usize, now: Instant) -> Result<usize, Instant> {
         // Skip over if we purposely have an open throttle
         if !self.enabled || requested == 0 {
             return Ok(requested);
         }
 
         // Refill if needed
-        self.refill();
+        self.refill_at(now);
 
         let allowed = self.deduct_tokens(requested);
         if allowed > 0 {
@@ -467,7 +490,7 @@ mod tests {
         // After 1 refill period, we should be at least 1 rate change if not more
         // TODO: Put in a mock time service
         sleep(bucket.time_of_next_refill().duration_since(Instant::now()));
-        bucket.refill();
+        bucket.refill_at(Instant::now());
         let num_tokens = bucket.tokens;
         assert!(num_tokens >= bucket_rate);
 
@@ -3,7 +3,9 @@
 
 use crate::{
     account::derive_resource_account::ResourceAccountSeed,
-    common::types::{CliCommand, CliTypedResult, TransactionOptions, TransactionSummary},
+    common::types::{
+        CliCommand, CliTypedResult, TransactionOptions, TransactionOutcome, TransactionSummary,
+    },
 };
 use aptos_cached_packages::aptos_stdlib::resource_account_create_resource_account;
 use aptos_rest_client::{
@@ -69,12 +71,12 @@ impl From<Transaction> for CreateResourceAccountSummary {
 }
 
 #[async_trait]
-impl CliCommand<CreateResourceAccountSummary> for CreateResourceAccount {
+impl CliCommand<TransactionOutcome<CreateResourceAccountSummary>> for CreateResourceAccount {
     fn command_name(&self) -> &'static str {
         "CreateResourceAccount"
     }
 
-    async fn execute(self) -> CliTypedResult<CreateResourceAccountSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<CreateResourceAccountSummary>> {
         let authentication_key: Vec<u8> = if let Some(key) = self.authentication_key {
             bcs::to_bytes(&key)?
         } else {
@@ -86,6 +88,6 @@ impl CliCommand<CreateResourceAccountSummary> for CreateResourceAccount {
                 authentication_key,
             ))
             .await
-            .map(CreateResourceAccountSummary::from)
+            .map(|outcome| outcome.map(CreateResourceAccountSummary::from))
     }
 }
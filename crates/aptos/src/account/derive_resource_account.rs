@@ -76,7 +76,7 @@ pub struct ResourceAccountSeed {
 }
 
 impl ResourceAccountSeed {
-    pub fn seed(self) -> CliTypedResult<Vec<u8>> {
+    pub fn seed(&self) -> CliTypedResult<Vec<u8>> {
         match self.seed_encoding {
             SeedEncoding::Bcs => Ok(bcs::to_bytes(self.seed.as_str())?),
             SeedEncoding::Utf8 => Ok(self.seed.as_bytes().to_vec()),
@@ -126,7 +126,7 @@ impl CliCommand<RotateSummary> for RotateKey {
 
         let txn_summary = self
             .txn_options
-            .submit_transaction(aptos_stdlib::account_rotate_authentication_key(
+            .sign_and_submit_transaction(aptos_stdlib::account_rotate_authentication_key(
                 0,
                 // Existing public key
                 current_private_key.public_key().to_bytes().to_vec(),
@@ -3,7 +3,7 @@
 
 use crate::common::types::{
     CliCommand, CliTypedResult, EntryFunctionArguments, MultisigAccount, TransactionOptions,
-    TransactionSummary,
+    TransactionOutcome, TransactionSummary,
 };
 use aptos_cached_packages::aptos_stdlib;
 use aptos_rest_client::{
@@ -75,12 +75,12 @@ impl From<Transaction> for CreateSummary {
 }
 
 #[async_trait]
-impl CliCommand<CreateSummary> for Create {
+impl CliCommand<TransactionOutcome<CreateSummary>> for Create {
     fn command_name(&self) -> &'static str {
         "CreateMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<CreateSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<CreateSummary>> {
         self.txn_options
             .submit_transaction(aptos_stdlib::multisig_account_create_with_owners(
                 self.additional_owners,
@@ -90,7 +90,7 @@ impl CliCommand<CreateSummary> for Create {
                 vec![],
             ))
             .await
-            .map(CreateSummary::from)
+            .map(|outcome| outcome.map(CreateSummary::from))
     }
 }
 
@@ -110,14 +110,17 @@ pub struct CreateTransaction {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateTransaction {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for CreateTransaction {
     fn command_name(&self) -> &'static str {
         "CreateTransactionMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        let client = self.txn_options.rest_client()?;
         let payload = MultisigTransactionPayload::EntryFunction(
-            self.entry_function_args.create_entry_function_payload()?,
+            self.entry_function_args
+                .create_entry_function_payload(&client)
+                .await?,
         );
         self.txn_options
             .submit_transaction(aptos_stdlib::multisig_account_create_transaction(
@@ -125,7 +128,7 @@ impl CliCommand<TransactionSummary> for CreateTransaction {
                 to_bytes(&payload)?,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -147,19 +150,19 @@ pub struct Approve {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for Approve {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for Approve {
     fn command_name(&self) -> &'static str {
         "ApproveMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         self.txn_options
             .submit_transaction(aptos_stdlib::multisig_account_approve_transaction(
                 self.multisig_account.multisig_address,
                 self.sequence_number,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -181,19 +184,19 @@ pub struct Reject {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for Reject {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for Reject {
     fn command_name(&self) -> &'static str {
         "RejectMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         self.txn_options
             .submit_transaction(aptos_stdlib::multisig_account_reject_transaction(
                 self.multisig_account.multisig_address,
                 self.sequence_number,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -210,12 +213,12 @@ pub struct Execute {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for Execute {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for Execute {
     fn command_name(&self) -> &'static str {
         "ExecuteMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let payload = TransactionPayload::Multisig(Multisig {
             multisig_address: self.multisig_account.multisig_address,
             // TODO: Support passing an explicit payload
@@ -224,7 +227,7 @@ impl CliCommand<TransactionSummary> for Execute {
         self.txn_options
             .submit_transaction(payload)
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -241,17 +244,17 @@ pub struct ExecuteReject {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for ExecuteReject {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for ExecuteReject {
     fn command_name(&self) -> &'static str {
         "ExecuteRejectMultisig"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         self.txn_options
             .submit_transaction(aptos_stdlib::multisig_account_execute_rejected_transaction(
                 self.multisig_account.multisig_address,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
@@ -7,24 +7,28 @@ use crate::{
         utils::{
             check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
             get_account_with_state, get_auth_key, get_sequence_number, prompt_yes_with_override,
-            read_from_file, start_logger, to_common_result, to_common_success_result,
-            write_to_file, write_to_file_with_opts, write_to_user_only_file,
+            read_from_file, save_gas_profile, start_logger, to_common_result,
+            to_common_success_result, write_to_file, write_to_file_with_opts,
+            write_to_user_only_file,
         },
     },
     config::GlobalConfig,
     genesis::git::from_yaml,
     move_tool::{ArgWithType, MemberId},
+    transaction::{OfflineTransaction, OfflineTransactionSummary},
 };
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     x25519, PrivateKey, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
 use aptos_debugger::AptosDebugger;
-use aptos_gas_profiling::FrameName;
 use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
 use aptos_rest_client::{
-    aptos_api_types::{HashValue, MoveType, ViewRequest},
+    aptos_api_types::{
+        EntryFunctionId, EntryFunctionPayload, HashValue, HexEncodedBytes, MoveFunction,
+        MoveModuleBytecode, MoveType, ViewRequest,
+    },
     error::RestError,
     Client, Transaction,
 };
@@ -32,14 +36,18 @@ use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_types::{
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, SignedTransaction, TransactionPayload,
-        TransactionStatus,
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        EntryFunction, SignedTransaction, TransactionPayload, TransactionStatus,
     },
 };
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
 use hex::FromHexError;
-use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TypeTag},
+    value::{MoveStruct, MoveValue},
+};
 use serde::{Deserialize, Serialize};
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -81,6 +89,8 @@ pub enum CliError {
     ConfigLoadError(String, String),
     #[error("Unable to find config {0}, have you run `aptos init`?")]
     ConfigNotFoundError(String),
+    #[error("Package upgrade is incompatible: {0}")]
+    IncompatibleUpgradeError(String),
     #[error("Error accessing '{0}': {1}")]
     IO(String, #[source] std::io::Error),
     #[error("Move compilation failed: {0}")]
@@ -110,6 +120,7 @@ impl CliError {
             CliError::CommandArgumentError(_) => "CommandArgumentError",
             CliError::ConfigLoadError(_, _) => "ConfigLoadError",
             CliError::ConfigNotFoundError(_) => "ConfigNotFoundError",
+            CliError::IncompatibleUpgradeError(_) => "IncompatibleUpgradeError",
             CliError::IO(_, _) => "IO",
             CliError::MoveCompilationError(_) => "MoveCompilationError",
             CliError::MoveTestError => "MoveTestError",
@@ -1088,6 +1099,41 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
     }
 }
 
+/// The outcome of a transaction command, which either submits the transaction or, with
+/// `--output-unsigned`, writes it to a file to be signed offline
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum TransactionOutcome<T = Transaction> {
+    /// The transaction was signed and submitted, or simulated locally with `--profile-gas`
+    Submitted(T),
+    /// The transaction was written to a file instead of being submitted
+    WrittenUnsigned(OfflineTransactionSummary),
+}
+
+impl<T> TransactionOutcome<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> TransactionOutcome<U> {
+        match self {
+            TransactionOutcome::Submitted(inner) => TransactionOutcome::Submitted(f(inner)),
+            TransactionOutcome::WrittenUnsigned(summary) => {
+                TransactionOutcome::WrittenUnsigned(summary)
+            },
+        }
+    }
+
+    /// Returns the submitted transaction, or an error if it was written to a file instead
+    pub fn into_submitted(self) -> CliTypedResult<T> {
+        match self {
+            TransactionOutcome::Submitted(inner) => Ok(inner),
+            TransactionOutcome::WrittenUnsigned(summary) => {
+                Err(CliError::UnexpectedError(format!(
+                    "The transaction was written to '{}' instead of being submitted",
+                    summary.transaction_file
+                )))
+            },
+        }
+    }
+}
+
 /// A shortened transaction output
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TransactionSummary {
@@ -1304,6 +1350,30 @@ pub struct TransactionOptions {
     /// flamegraphs that reflect the gas usage.
     #[clap(long)]
     pub(crate) profile_gas: bool,
+
+    /// Profile of the account paying for the gas of the transaction instead of the sender
+    ///
+    /// The transaction is signed by both the sender and the fee payer, using the private key of
+    /// the profile.
+    #[clap(long)]
+    pub(crate) fee_payer_profile: Option<String>,
+
+    /// Write the unsigned transaction to this file instead of signing and submitting it
+    ///
+    /// The file can be signed offline with `aptos transaction sign` and then sent with
+    /// `aptos transaction submit`.  No private key is needed: the sender is `--sender-account`
+    /// or the account of the profile.  `--max-gas` has to be given, as the transaction can't be
+    /// simulated without signatures, and `--expiration-secs` should leave enough time to collect
+    /// the signatures.  Commands which submit several transactions, or which need the result of
+    /// the transaction, e.g. `aptos governance propose`, don't support it.
+    #[clap(long, parse(from_os_str), conflicts_with = "profile_gas")]
+    pub(crate) output_unsigned: Option<PathBuf>,
+
+    /// Addresses of the additional signers of a multi-agent transaction
+    ///
+    /// Only used with `--output-unsigned`, each of them has to sign the transaction file.
+    #[clap(long, multiple_values = true, requires = "output_unsigned", parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) secondary_signer_addresses: Vec<AccountAddress>,
 }
 
 impl TransactionOptions {
@@ -1323,9 +1393,34 @@ impl TransactionOptions {
     }
 
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
+        // An unsigned transaction is built without access to the private key
+        if self.output_unsigned.is_some() {
+            return match self.sender_account {
+                Some(sender_address) => Ok(sender_address),
+                None => self.profile_options.account_address(),
+            };
+        }
         Ok(self.get_key_and_address()?.1)
     }
 
+    /// Retrieves the private key and the address of the fee payer, if any
+    pub fn get_fee_payer_key_and_address(
+        &self,
+    ) -> CliTypedResult<Option<(Ed25519PrivateKey, AccountAddress)>> {
+        self.fee_payer_profile
+            .as_ref()
+            .map(|profile| {
+                PrivateKeyInputOptions::default().extract_private_key_and_address(
+                    self.encoding_options.encoding,
+                    &ProfileOptions {
+                        profile: Some(profile.clone()),
+                    },
+                    None,
+                )
+            })
+            .transpose()
+    }
+
     /// Gets the auth key by account address. We need to fetch the auth key from Rest API rather than creating an
     /// auth key out of the public key.
     pub(crate) async fn auth_key(
@@ -1347,12 +1442,39 @@ impl TransactionOptions {
     }
 
     /// Submit a transaction
+    ///
+    /// With `--output-unsigned`, the transaction is written to a file instead.
     pub async fn submit_transaction(
         &self,
         payload: TransactionPayload,
+    ) -> CliTypedResult<TransactionOutcome> {
+        match self.output_unsigned {
+            Some(ref output_file) => self
+                .write_unsigned_transaction(payload, output_file)
+                .await
+                .map(TransactionOutcome::WrittenUnsigned),
+            None => self
+                .sign_and_submit_transaction(payload)
+                .await
+                .map(TransactionOutcome::Submitted),
+        }
+    }
+
+    /// Sign and submit a transaction, for commands which need the committed transaction and so
+    /// can't write it to a file with `--output-unsigned`
+    pub async fn sign_and_submit_transaction(
+        &self,
+        payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
+        if self.output_unsigned.is_some() {
+            return Err(CliError::CommandArgumentError(
+                "--output-unsigned is not supported by this command".to_string(),
+            ));
+        }
+
         let client = self.rest_client()?;
         let (sender_key, sender_address) = self.get_key_and_address()?;
+        let fee_payer = self.get_fee_payer_key_and_address()?;
 
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
@@ -1406,11 +1528,22 @@ impl TransactionOptions {
                 .expiration_timestamp_secs(expiration_time_secs)
                 .build();
 
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_key.public_key(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
+            let empty_signature = Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap();
+            let signed_transaction = match &fee_payer {
+                Some((fee_payer_key, fee_payer_address)) => SignedTransaction::new_fee_payer(
+                    unsigned_transaction,
+                    AccountAuthenticator::ed25519(sender_key.public_key(), empty_signature.clone()),
+                    vec![],
+                    vec![],
+                    *fee_payer_address,
+                    AccountAuthenticator::ed25519(fee_payer_key.public_key(), empty_signature),
+                ),
+                None => SignedTransaction::new(
+                    unsigned_transaction,
+                    sender_key.public_key(),
+                    empty_signature,
+                ),
+            };
 
             let txns = client
                 .simulate_with_gas_estimation(&signed_transaction, true, false)
@@ -1448,8 +1581,17 @@ impl TransactionOptions {
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
         let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let transaction = match fee_payer {
+            Some((fee_payer_key, fee_payer_address)) => sender_account
+                .sign_fee_payer_with_transaction_builder(
+                    vec![],
+                    &LocalAccount::new(fee_payer_address, fee_payer_key, 0),
+                    transaction_factory.payload(payload),
+                ),
+            None => {
+                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload))
+            },
+        };
         let response = client
             .submit_and_wait(&transaction)
             .await
@@ -1458,6 +1600,54 @@ impl TransactionOptions {
         Ok(response.into_inner())
     }
 
+    /// Builds the transaction without signing it, and writes it to `output_file`
+    async fn write_unsigned_transaction(
+        &self,
+        payload: TransactionPayload,
+        output_file: &Path,
+    ) -> CliTypedResult<OfflineTransactionSummary> {
+        let max_gas = self.gas_options.max_gas.ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "--max-gas must be set with --output-unsigned, as the transaction can't be simulated without signatures".to_string(),
+            )
+        })?;
+        let sender_address = self.sender_address()?;
+        let fee_payer_address = self
+            .fee_payer_profile
+            .as_ref()
+            .map(|profile| {
+                ProfileOptions {
+                    profile: Some(profile.clone()),
+                }
+                .account_address()
+            })
+            .transpose()?;
+        check_if_file_exists(output_file, self.prompt_options)?;
+
+        let client = self.rest_client()?;
+        let gas_unit_price = match self.gas_options.gas_unit_price {
+            Some(gas_unit_price) => gas_unit_price,
+            None => client.estimate_gas_price().await?.into_inner().gas_estimate,
+        };
+        let (account, state) = get_account_with_state(&client, sender_address).await?;
+
+        let raw_txn = TransactionFactory::new(ChainId::new(state.chain_id))
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .with_transaction_expiration_time(self.gas_options.expiration_secs)
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(account.sequence_number)
+            .build();
+        let txn = OfflineTransaction::new(
+            raw_txn,
+            self.secondary_signer_addresses.clone(),
+            fee_payer_address,
+        );
+        txn.save(output_file)?;
+        Ok(txn.summary(output_file))
+    }
+
     /// Simulate the transaction locally using the debugger, with the gas profiler enabled.
     pub async fn profile_gas(
         &self,
@@ -1515,90 +1705,7 @@ impl TransactionOptions {
             CliError::UnexpectedError(format!("failed to simulate txn with gas profiler: {}", err))
         })?;
 
-        // Generate the file name for the flamegraphs
-        let entry_point = gas_log.entry_point();
-
-        let human_readable_name = match entry_point {
-            FrameName::Script => "script".to_string(),
-            FrameName::Function {
-                module_id, name, ..
-            } => {
-                let addr_short = module_id.address().short_str_lossless();
-                let addr_truncated = if addr_short.len() > 4 {
-                    &addr_short[..4]
-                } else {
-                    addr_short.as_str()
-                };
-                format!("0x{}-{}-{}", addr_truncated, module_id.name(), name)
-            },
-        };
-        let raw_file_name = format!("txn-{}-{}", hash, human_readable_name);
-
-        // Create the directory if it does not exist yet.
-        let dir: &Path = Path::new("gas-profiling");
-
-        macro_rules! create_dir {
-            () => {
-                if let Err(err) = std::fs::create_dir(dir) {
-                    if err.kind() != std::io::ErrorKind::AlreadyExists {
-                        return Err(CliError::UnexpectedError(format!(
-                            "failed to create directory {}",
-                            dir.display()
-                        )));
-                    }
-                }
-            };
-        }
-
-        // Generate the execution & IO flamegraph.
-        println!();
-        match gas_log.to_flamegraph(format!("Transaction {} -- Execution & IO", hash))? {
-            Some(graph_bytes) => {
-                create_dir!();
-                let graph_file_path = Path::join(dir, format!("{}.exec_io.svg", raw_file_name));
-                std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
-                    CliError::UnexpectedError(format!(
-                        "Failed to write flamegraph to file {} : {:?}",
-                        graph_file_path.display(),
-                        err
-                    ))
-                })?;
-                println!(
-                    "Execution & IO Gas flamegraph saved to {}",
-                    graph_file_path.display()
-                );
-            },
-            None => {
-                println!("Skipped generating execution & IO flamegraph");
-            },
-        }
-
-        // Generate the storage fee flamegraph.
-        match gas_log
-            .storage
-            .to_flamegraph(format!("Transaction {} -- Storage Fee", hash))?
-        {
-            Some(graph_bytes) => {
-                create_dir!();
-                let graph_file_path = Path::join(dir, format!("{}.storage.svg", raw_file_name));
-                std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
-                    CliError::UnexpectedError(format!(
-                        "Failed to write flamegraph to file {} : {:?}",
-                        graph_file_path.display(),
-                        err
-                    ))
-                })?;
-                println!(
-                    "Storage fee flamegraph saved to {}",
-                    graph_file_path.display()
-                );
-            },
-            None => {
-                println!("Skipped generating storage fee flamegraph");
-            },
-        }
-
-        println!();
+        save_gas_profile(hash, &gas_log)?;
 
         // Generate the transaction summary
 
@@ -1684,6 +1791,9 @@ pub struct ArgWithTypeVec {
     ///
     /// Example: `address:0x1 bool:true u8:0 u256:1234 "bool:[true, false]" 'address:[["0xace", "0xbee"], []]'`
     ///
+    /// `Option<T>` is specified as a vector of at most one value, e.g. `u64:[5]` or `u64:[]`,
+    /// `Object<T>` as an address and `String` as a string.
+    ///
     /// Vector is wrapped in a reusable struct for uniform CLI documentation.
     #[clap(long, multiple_values = true)]
     pub(crate) args: Vec<ArgWithType>,
@@ -1695,8 +1805,8 @@ pub struct EntryFunctionArguments {
     /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
     ///
     /// Example: `0x842ed41fad9640a2ad08fdd7d3e4f7f505319aac7d67e1c0dd6a7cce8732c7e3::message::set_message`
-    #[clap(long)]
-    pub function_id: MemberId,
+    #[clap(long, required_unless_present = "json_file")]
+    pub function_id: Option<MemberId>,
 
     #[clap(flatten)]
     pub(crate) arg_vec: ArgWithTypeVec,
@@ -1706,11 +1816,47 @@ pub struct EntryFunctionArguments {
     /// Example: `u8 u16 u32 u64 u128 u256 bool address vector signer`
     #[clap(long, multiple_values = true)]
     pub type_args: Vec<MoveType>,
+
+    /// JSON file specifying the function, type arguments and arguments
+    ///
+    /// The file has the format of an entry function payload in the REST API, e.g.
+    /// `{"function": "0x1::coin::transfer", "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+    /// "arguments": ["0x1", "100"]}`. Arguments are parsed with the types of the function's
+    /// parameters on-chain, which supports `Option<T>` as `{"vec": []}` or `{"vec": [value]}`,
+    /// `Object<T>` as an address, `String`, `FixedPoint32` and `FixedPoint64` as `{"value": raw}`,
+    /// and `vector<u8>` as a hex string.
+    #[clap(long, parse(from_os_str), conflicts_with_all = &["function_id", "args", "type_args"])]
+    pub(crate) json_file: Option<PathBuf>,
 }
 
 impl EntryFunctionArguments {
-    /// Construct and return an entry function payload from function_id, args, and type_args.
-    pub fn create_entry_function_payload(self) -> CliTypedResult<EntryFunction> {
+    /// Construct and return an entry function payload from function_id, args, and type_args,
+    /// or from the JSON file.
+    pub async fn create_entry_function_payload(
+        self,
+        client: &Client,
+    ) -> CliTypedResult<EntryFunction> {
+        if let Some(json_file) = &self.json_file {
+            let payload = read_entry_function_payload(json_file)?;
+            let function = get_function_abi(client, &payload.function).await?;
+            let args = parse_json_args(&function, &payload.type_arguments, payload.arguments)?;
+            let mut type_args = Vec::new();
+            for type_arg in payload.type_arguments {
+                type_args.push(
+                    TypeTag::try_from(type_arg).map_err(|err| {
+                        CliError::UnableToParse("type_arguments", err.to_string())
+                    })?,
+                );
+            }
+            return Ok(EntryFunction::new(
+                payload.function.module.into(),
+                payload.function.name.into(),
+                type_args,
+                args,
+            ));
+        }
+
+        let function_id = self.function_id()?;
         let args: Vec<Vec<u8>> = self
             .arg_vec
             .args
@@ -1727,12 +1873,433 @@ impl EntryFunctionArguments {
         }
 
         Ok(EntryFunction::new(
-            self.function_id.module_id,
-            self.function_id.member_id,
+            function_id.module_id,
+            function_id.member_id,
             parsed_type_args,
             args,
         ))
     }
+
+    /// Construct and return a view request, along with the ABI of the viewed function if it was
+    /// needed to parse the arguments.
+    ///
+    /// The ABI is only fetched for a JSON file, or for vector arguments, which may be `Option<T>`
+    /// or contain `Object<T>` parameters.
+    pub async fn create_view_request(
+        self,
+        client: &Client,
+    ) -> CliTypedResult<(ViewRequest, Option<MoveFunction>)> {
+        if let Some(json_file) = &self.json_file {
+            let payload = read_entry_function_payload(json_file)?;
+            let function = get_function_abi(client, &payload.function).await?;
+            // The fullnode parses the arguments itself, this reports bad arguments the same way
+            // as for entry functions
+            parse_json_args(
+                &function,
+                &payload.type_arguments,
+                payload.arguments.clone(),
+            )?;
+            return Ok((
+                ViewRequest {
+                    function: payload.function,
+                    type_arguments: payload.type_arguments,
+                    arguments: payload.arguments,
+                },
+                Some(function),
+            ));
+        }
+
+        let function_id = self.function_id()?;
+        let function_id = EntryFunctionId {
+            module: function_id.module_id.into(),
+            name: function_id.member_id.into(),
+        };
+        let needs_abi = self.arg_vec.args.iter().any(|arg| arg._vector_depth > 0);
+        let mut args: Vec<serde_json::Value> = vec![];
+        for arg in self.arg_vec.args {
+            args.push(arg.to_json()?);
+        }
+
+        let function = if needs_abi {
+            let function = get_function_abi(client, &function_id).await?;
+            args = typed_view_args(&function, &self.type_args, args)?;
+            Some(function)
+        } else {
+            None
+        };
+        Ok((
+            ViewRequest {
+                function: function_id,
+                type_arguments: self.type_args,
+                arguments: args,
+            },
+            function,
+        ))
+    }
+
+    fn function_id(&self) -> CliTypedResult<MemberId> {
+        self.function_id.clone().ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "Must provide either --function-id or --json-file".to_string(),
+            )
+        })
+    }
+}
+
+fn read_entry_function_payload(path: &Path) -> CliTypedResult<EntryFunctionPayload> {
+    let bytes = read_from_file(path)?;
+    serde_json::from_slice(&bytes)
+        .map_err(|err| CliError::UnableToReadFile(format!("{}", path.display()), err.to_string()))
+}
+
+/// Fetches the ABI of a function from the module published on-chain
+pub(crate) async fn get_function_abi(
+    client: &Client,
+    function: &EntryFunctionId,
+) -> CliTypedResult<MoveFunction> {
+    let module_id = ModuleId::from(function.module.clone());
+    let bytes = client
+        .get_account_module_bcs(*module_id.address(), module_id.name().as_str())
+        .await?
+        .into_inner();
+    MoveModuleBytecode::new(bytes.to_vec())
+        .try_parse_abi()?
+        .abi
+        .and_then(|abi| {
+            abi.exposed_functions
+                .into_iter()
+                .find(|func| func.name == function.name)
+        })
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Function {} not found on-chain", function))
+        })
+}
+
+/// Parses JSON arguments into BCS, using the types of the function's parameters
+fn parse_json_args(
+    function: &MoveFunction,
+    type_args: &[MoveType],
+    args: Vec<serde_json::Value>,
+) -> CliTypedResult<Vec<Vec<u8>>> {
+    let param_types: Vec<&MoveType> = function
+        .params
+        .iter()
+        .filter(|param| !param.is_signer())
+        .collect();
+    if param_types.len() != args.len() {
+        return Err(CliError::CommandArgumentError(format!(
+            "Function {} expects {} arguments, got {}",
+            function.name,
+            param_types.len(),
+            args.len()
+        )));
+    }
+    if function.generic_type_params.len() != type_args.len() {
+        return Err(CliError::CommandArgumentError(format!(
+            "Function {} expects {} type arguments, got {}",
+            function.name,
+            function.generic_type_params.len(),
+            type_args.len()
+        )));
+    }
+
+    param_types
+        .into_iter()
+        .zip(args)
+        .enumerate()
+        .map(|(index, (param_type, arg))| {
+            parse_json_arg(param_type, type_args, arg)
+                .and_then(|value| {
+                    value.simple_serialize().ok_or_else(|| {
+                        CliError::UnexpectedError("Failed to serialize argument".to_string())
+                    })
+                })
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "Invalid argument {} of type {}: {}",
+                        index, param_type, err
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Converts arguments given with `--args` into the JSON format of the REST API, using the types
+/// of the function's parameters
+///
+/// Vectors of at most one value are accepted for `Option<T>`, and addresses for `Object<T>`.
+fn typed_view_args(
+    function: &MoveFunction,
+    type_args: &[MoveType],
+    args: Vec<serde_json::Value>,
+) -> CliTypedResult<Vec<serde_json::Value>> {
+    let param_types: Vec<&MoveType> = function
+        .params
+        .iter()
+        .filter(|param| !param.is_signer())
+        .collect();
+    let args = if param_types.len() == args.len() {
+        param_types
+            .into_iter()
+            .zip(args)
+            .map(|(param_type, arg)| typed_view_arg(param_type, type_args, arg, false))
+            .collect::<CliTypedResult<_>>()?
+    } else {
+        args
+    };
+    // Reports bad arguments the same way as for a JSON file
+    parse_json_args(function, type_args, args.clone())?;
+    Ok(args)
+}
+
+fn typed_view_arg(
+    ty: &MoveType,
+    type_args: &[MoveType],
+    arg: serde_json::Value,
+    nested: bool,
+) -> CliTypedResult<serde_json::Value> {
+    Ok(match (ty, arg) {
+        (MoveType::Vector { items }, serde_json::Value::Array(values)) => serde_json::Value::Array(
+            values
+                .into_iter()
+                .map(|value| typed_view_arg(items, type_args, value, true))
+                .collect::<CliTypedResult<_>>()?,
+        ),
+        (MoveType::Struct(tag), arg) if tag.address == AccountAddress::ONE.into() => {
+            match (tag.module.as_str(), tag.name.as_str(), arg) {
+                ("option", "Option", serde_json::Value::Array(values)) => {
+                    let inner_type = tag.generic_type_params.first().ok_or_else(|| {
+                        CliError::UnexpectedError("Option without a type parameter".to_string())
+                    })?;
+                    serde_json::json!({
+                        "vec": values
+                            .into_iter()
+                            .map(|value| typed_view_arg(inner_type, type_args, value, true))
+                            .collect::<CliTypedResult<Vec<_>>>()?,
+                    })
+                },
+                // The REST API only parses objects as an address outside of vectors and structs
+                ("object", "Object", serde_json::Value::String(address)) if nested => {
+                    serde_json::json!({ "inner": address })
+                },
+                (_, _, arg) => arg,
+            }
+        },
+        (MoveType::GenericTypeParam { index }, arg) => match type_args.get(*index as usize) {
+            Some(ty) => typed_view_arg(ty, type_args, arg, nested)?,
+            None => arg,
+        },
+        (_, arg) => arg,
+    })
+}
+
+/// Parses a JSON argument, in the format of the REST API, into a Move value of the given type
+///
+/// Only the structs that can be passed to entry functions are supported.
+fn parse_json_arg(
+    ty: &MoveType,
+    type_args: &[MoveType],
+    arg: serde_json::Value,
+) -> CliTypedResult<MoveValue> {
+    Ok(match ty {
+        MoveType::Bool => MoveValue::Bool(
+            arg.as_bool()
+                .ok_or_else(|| CliError::UnableToParse("bool", arg.to_string()))?,
+        ),
+        MoveType::U8 => MoveValue::U8(parse_json_number(&arg, "u8")?),
+        MoveType::U16 => MoveValue::U16(parse_json_number(&arg, "u16")?),
+        MoveType::U32 => MoveValue::U32(parse_json_number(&arg, "u32")?),
+        MoveType::U64 => MoveValue::U64(parse_json_number(&arg, "u64")?),
+        MoveType::U128 => MoveValue::U128(parse_json_number(&arg, "u128")?),
+        MoveType::U256 => MoveValue::U256(parse_json_number(&arg, "u256")?),
+        MoveType::Address => MoveValue::Address(parse_json_address(&arg)?),
+        MoveType::Vector { items } => match (items.as_ref(), arg) {
+            (MoveType::U8, serde_json::Value::String(hex)) => MoveValue::vector_u8(
+                HexEncodedBytes::from_str(&hex)
+                    .map_err(|err| CliError::UnableToParse("vector<u8>", err.to_string()))?
+                    .0,
+            ),
+            (items, serde_json::Value::Array(values)) => MoveValue::Vector(
+                values
+                    .into_iter()
+                    .map(|value| parse_json_arg(items, type_args, value))
+                    .collect::<CliTypedResult<_>>()?,
+            ),
+            (_, arg) => return Err(CliError::UnableToParse("vector", arg.to_string())),
+        },
+        MoveType::Struct(tag) if tag.address == AccountAddress::ONE.into() => {
+            match (tag.module.as_str(), tag.name.as_str()) {
+                ("string", "String") => {
+                    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::vector_u8(
+                        arg.as_str()
+                            .ok_or_else(|| CliError::UnableToParse("String", arg.to_string()))?
+                            .as_bytes()
+                            .to_vec(),
+                    )]))
+                },
+                ("object", "Object") => {
+                    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Address(
+                        parse_json_address(&arg)?,
+                    )]))
+                },
+                ("option", "Option") => {
+                    let inner_type = tag.generic_type_params.first().ok_or_else(|| {
+                        CliError::UnexpectedError("Option without a type parameter".to_string())
+                    })?;
+                    let values = match arg.get("vec") {
+                        Some(serde_json::Value::Array(values)) if values.len() <= 1 => {
+                            values.clone()
+                        },
+                        _ => return Err(CliError::UnableToParse("Option", arg.to_string())),
+                    };
+                    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Vector(
+                        values
+                            .into_iter()
+                            .map(|value| parse_json_arg(inner_type, type_args, value))
+                            .collect::<CliTypedResult<_>>()?,
+                    )]))
+                },
+                ("fixed_point32", "FixedPoint32") => {
+                    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::U64(
+                        parse_json_number(json_field(&arg, "value")?, "FixedPoint32")?,
+                    )]))
+                },
+                ("fixed_point64", "FixedPoint64") => {
+                    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::U128(
+                        parse_json_number(json_field(&arg, "value")?, "FixedPoint64")?,
+                    )]))
+                },
+                _ => {
+                    return Err(CliError::CommandArgumentError(format!(
+                        "Unsupported struct argument type {}",
+                        ty
+                    )))
+                },
+            }
+        },
+        MoveType::GenericTypeParam { index } => {
+            let ty = type_args.get(*index as usize).ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Missing type argument T{}", index))
+            })?;
+            parse_json_arg(ty, type_args, arg)?
+        },
+        _ => {
+            return Err(CliError::CommandArgumentError(format!(
+                "Unsupported argument type {}",
+                ty
+            )))
+        },
+    })
+}
+
+/// Parses a JSON number, or a number in a JSON string as the REST API uses for large integers
+fn parse_json_number<T: FromStr>(arg: &serde_json::Value, name: &'static str) -> CliTypedResult<T>
+where
+    T::Err: Display,
+{
+    let number = match arg {
+        serde_json::Value::Number(number) => number.to_string(),
+        serde_json::Value::String(number) => number.clone(),
+        _ => return Err(CliError::UnableToParse(name, arg.to_string())),
+    };
+    T::from_str(&number).map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+fn parse_json_address(arg: &serde_json::Value) -> CliTypedResult<AccountAddress> {
+    let address = match arg {
+        serde_json::Value::String(address) => address,
+        // Objects are output by the REST API with their address in `inner`
+        serde_json::Value::Object(fields) => match fields.get("inner") {
+            Some(serde_json::Value::String(address)) => address,
+            _ => return Err(CliError::UnableToParse("address", arg.to_string())),
+        },
+        _ => return Err(CliError::UnableToParse("address", arg.to_string())),
+    };
+    load_account_arg(address)
+}
+
+fn json_field<'a>(
+    arg: &'a serde_json::Value,
+    field: &'static str,
+) -> CliTypedResult<&'a serde_json::Value> {
+    arg.get(field)
+        .ok_or_else(|| CliError::UnableToParse(field, arg.to_string()))
+}
+
+/// Decodes the values returned by a view function for display, using its return types
+///
+/// Options are shown as their value or `null`, and objects as their address, rather than
+/// as the underlying structs.
+pub fn decode_view_values(
+    function: &MoveFunction,
+    type_args: &[MoveType],
+    values: Vec<serde_json::Value>,
+) -> Vec<serde_json::Value> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| match function.return_.get(index) {
+            Some(ty) => decode_view_value(ty, type_args, value),
+            None => value,
+        })
+        .collect()
+}
+
+/// Whether values returned by a view function contain structs, which are only decoded with the
+/// function's ABI
+pub fn view_values_need_decoding(values: &[serde_json::Value]) -> bool {
+    values.iter().any(|value| match value {
+        serde_json::Value::Object(_) => true,
+        serde_json::Value::Array(values) => view_values_need_decoding(values),
+        _ => false,
+    })
+}
+
+fn decode_view_value(
+    ty: &MoveType,
+    type_args: &[MoveType],
+    value: serde_json::Value,
+) -> serde_json::Value {
+    match (ty, value) {
+        (MoveType::Vector { items }, serde_json::Value::Array(values)) => serde_json::Value::Array(
+            values
+                .into_iter()
+                .map(|value| decode_view_value(items, type_args, value))
+                .collect(),
+        ),
+        (MoveType::Struct(tag), value) if tag.address == AccountAddress::ONE.into() => {
+            match (tag.module.as_str(), tag.name.as_str(), value) {
+                ("option", "Option", serde_json::Value::Object(mut fields)) => {
+                    match (tag.generic_type_params.first(), fields.remove("vec")) {
+                        (Some(inner_type), Some(serde_json::Value::Array(mut values)))
+                            if values.len() <= 1 =>
+                        {
+                            values
+                                .pop()
+                                .map(|value| decode_view_value(inner_type, type_args, value))
+                                .unwrap_or(serde_json::Value::Null)
+                        },
+                        (_, Some(vec)) => {
+                            fields.insert("vec".to_string(), vec);
+                            serde_json::Value::Object(fields)
+                        },
+                        (_, None) => serde_json::Value::Object(fields),
+                    }
+                },
+                ("object", "Object", serde_json::Value::Object(mut fields))
+                    if fields.contains_key("inner") =>
+                {
+                    fields.remove("inner").unwrap()
+                },
+                (_, _, value) => value,
+            }
+        },
+        (MoveType::GenericTypeParam { index }, value) => match type_args.get(*index as usize) {
+            Some(ty) => decode_view_value(ty, type_args, value),
+            None => value,
+        },
+        (_, value) => value,
+    }
 }
 
 /// Common options for interactions with a multisig account.
@@ -1742,3 +2309,147 @@ pub struct MultisigAccount {
     #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
     pub(crate) multisig_address: AccountAddress,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_rest_client::aptos_api_types::{
+        IdentifierWrapper, MoveFunctionGenericTypeParam, MoveFunctionVisibility, MoveStructTag,
+    };
+
+    fn function(params: Vec<MoveType>, return_: Vec<MoveType>) -> MoveFunction {
+        MoveFunction {
+            name: IdentifierWrapper::from_str("test").unwrap(),
+            visibility: MoveFunctionVisibility::Public,
+            is_entry: true,
+            is_view: false,
+            generic_type_params: vec![MoveFunctionGenericTypeParam {
+                constraints: vec![],
+            }],
+            params,
+            return_,
+        }
+    }
+
+    fn object_type() -> MoveType {
+        MoveType::Struct(MoveStructTag::new(
+            AccountAddress::ONE.into(),
+            IdentifierWrapper::from_str("object").unwrap(),
+            IdentifierWrapper::from_str("Object").unwrap(),
+            vec![MoveType::GenericTypeParam { index: 0 }],
+        ))
+    }
+
+    #[test]
+    fn test_parse_json_args() {
+        let function = function(
+            vec![
+                MoveType::Reference {
+                    mutable: false,
+                    to: Box::new(MoveType::Signer),
+                },
+                MoveType::from_str("0x1::option::Option<u64>").unwrap(),
+                MoveType::from_str("0x1::string::String").unwrap(),
+                MoveType::from_str("vector<u8>").unwrap(),
+                object_type(),
+                MoveType::GenericTypeParam { index: 0 },
+            ],
+            vec![],
+        );
+        let args = parse_json_args(&function, &[MoveType::U8], vec![
+            serde_json::json!({"vec": ["5"]}),
+            serde_json::json!("hi"),
+            serde_json::json!("0x0102"),
+            serde_json::json!("0x1"),
+            serde_json::json!(7),
+        ])
+        .unwrap();
+        assert_eq!(args, vec![
+            bcs::to_bytes(&Some(5u64)).unwrap(),
+            bcs::to_bytes("hi").unwrap(),
+            bcs::to_bytes(&vec![1u8, 2]).unwrap(),
+            bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+            vec![7],
+        ]);
+
+        // Options hold at most one value, and arguments must match the parameters
+        assert!(parse_json_args(&function, &[MoveType::U8], vec![
+            serde_json::json!({"vec": ["5", "6"]}),
+            serde_json::json!("hi"),
+            serde_json::json!("0x0102"),
+            serde_json::json!("0x1"),
+            serde_json::json!(7),
+        ])
+        .is_err());
+        assert!(parse_json_args(&function, &[MoveType::U8], vec![]).is_err());
+    }
+
+    #[test]
+    fn test_typed_view_args() {
+        let function = function(
+            vec![
+                MoveType::from_str("0x1::option::Option<u64>").unwrap(),
+                MoveType::Vector {
+                    items: Box::new(object_type()),
+                },
+                object_type(),
+                MoveType::from_str("0x1::string::String").unwrap(),
+                MoveType::GenericTypeParam { index: 0 },
+            ],
+            vec![],
+        );
+        let type_args = [MoveType::from_str("0x1::option::Option<u8>").unwrap()];
+        let args = typed_view_args(&function, &type_args, vec![
+            serde_json::json!([5]),
+            serde_json::json!(["0x1", "0x2"]),
+            serde_json::json!("0x3"),
+            serde_json::json!("hi"),
+            serde_json::json!([]),
+        ])
+        .unwrap();
+        assert_eq!(args, vec![
+            serde_json::json!({"vec": [5]}),
+            serde_json::json!([{"inner": "0x1"}, {"inner": "0x2"}]),
+            serde_json::json!("0x3"),
+            serde_json::json!("hi"),
+            serde_json::json!({"vec": []}),
+        ]);
+
+        // Options hold at most one value
+        assert!(typed_view_args(&function, &type_args, vec![
+            serde_json::json!([5, 6]),
+            serde_json::json!([]),
+            serde_json::json!("0x3"),
+            serde_json::json!("hi"),
+            serde_json::json!([]),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_decode_view_values() {
+        let function = function(vec![], vec![
+            MoveType::from_str("vector<0x1::option::Option<u64>>").unwrap(),
+            object_type(),
+            MoveType::U64,
+        ]);
+        let values = vec![
+            serde_json::json!([{"vec": ["5"]}, {"vec": []}]),
+            serde_json::json!({"inner": "0x1"}),
+            serde_json::json!("10"),
+        ];
+        // Only structs need the ABI to be decoded
+        assert!(view_values_need_decoding(&values));
+        assert!(!view_values_need_decoding(&[
+            serde_json::json!(["5", "6"]),
+            serde_json::json!("10")
+        ]));
+
+        let values = decode_view_values(&function, &[MoveType::U8], values);
+        assert_eq!(values, vec![
+            serde_json::json!(["5", null]),
+            serde_json::json!("0x1"),
+            serde_json::json!("10"),
+        ]);
+    }
+}
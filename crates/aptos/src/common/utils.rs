@@ -4,21 +4,25 @@
 use crate::{
     common::types::{
         account_address_from_public_key, CliError, CliTypedResult, PromptOptions,
-        TransactionOptions, TransactionSummary,
+        TransactionOptions, TransactionOutcome, TransactionSummary,
     },
     config::GlobalConfig,
     CliResult,
 };
 use aptos_build_info::build_information;
 use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use aptos_gas_profiling::{FrameName, TransactionGasLog};
 use aptos_keygen::KeyGen;
 use aptos_logger::{debug, Level};
 use aptos_rest_client::{aptos_api_types::HashValue, Account, Client, State};
 use aptos_telemetry::service::telemetry_is_disabled;
 use aptos_types::{
+    access_path::Path as AccessPathKind,
     account_address::create_multisig_account_address,
     chain_id::ChainId,
+    state_store::state_key::{StateKey, StateKeyInner},
     transaction::{authenticator::AuthenticationKey, TransactionPayload},
+    write_set::WriteOp,
 };
 use itertools::Itertools;
 use move_core_types::account_address::AccountAddress;
@@ -461,15 +465,143 @@ pub fn start_logger() {
 pub async fn profile_or_submit(
     payload: TransactionPayload,
     txn_options_ref: &TransactionOptions,
-) -> CliTypedResult<TransactionSummary> {
-    // Profile gas if needed.
-    if txn_options_ref.profile_gas {
-        txn_options_ref.profile_gas(payload).await
+) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+    // Profile gas if needed, which needs the private key, unlike writing an unsigned transaction.
+    if txn_options_ref.profile_gas && txn_options_ref.output_unsigned.is_none() {
+        txn_options_ref
+            .profile_gas(payload)
+            .await
+            .map(TransactionOutcome::Submitted)
     } else {
         // Otherwise submit the transaction.
         txn_options_ref
             .submit_transaction(payload)
             .await
-            .map(TransactionSummary::from)
+            .map(|outcome| outcome.map(TransactionSummary::from))
+    }
+}
+
+/// Writes the execution & IO and storage fee flamegraphs of a gas profile to `gas-profiling/`
+pub fn save_gas_profile(
+    hash: aptos_crypto::HashValue,
+    gas_log: &TransactionGasLog,
+) -> CliTypedResult<()> {
+    // Generate the file name for the flamegraphs
+    let entry_point = gas_log.entry_point();
+
+    let human_readable_name = match entry_point {
+        FrameName::Script => "script".to_string(),
+        FrameName::Function {
+            module_id, name, ..
+        } => {
+            let addr_short = module_id.address().short_str_lossless();
+            let addr_truncated = if addr_short.len() > 4 {
+                &addr_short[..4]
+            } else {
+                addr_short.as_str()
+            };
+            format!("0x{}-{}-{}", addr_truncated, module_id.name(), name)
+        },
+    };
+    let raw_file_name = format!("txn-{}-{}", hash, human_readable_name);
+
+    // Create the directory if it does not exist yet.
+    let dir: &Path = Path::new("gas-profiling");
+
+    macro_rules! create_dir {
+        () => {
+            if let Err(err) = std::fs::create_dir(dir) {
+                if err.kind() != std::io::ErrorKind::AlreadyExists {
+                    return Err(CliError::UnexpectedError(format!(
+                        "failed to create directory {}",
+                        dir.display()
+                    )));
+                }
+            }
+        };
+    }
+
+    // Generate the execution & IO flamegraph.
+    println!();
+    match gas_log.to_flamegraph(format!("Transaction {} -- Execution & IO", hash))? {
+        Some(graph_bytes) => {
+            create_dir!();
+            let graph_file_path = Path::join(dir, format!("{}.exec_io.svg", raw_file_name));
+            std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to write flamegraph to file {} : {:?}",
+                    graph_file_path.display(),
+                    err
+                ))
+            })?;
+            println!(
+                "Execution & IO Gas flamegraph saved to {}",
+                graph_file_path.display()
+            );
+        },
+        None => {
+            println!("Skipped generating execution & IO flamegraph");
+        },
+    }
+
+    // Generate the storage fee flamegraph.
+    match gas_log
+        .storage
+        .to_flamegraph(format!("Transaction {} -- Storage Fee", hash))?
+    {
+        Some(graph_bytes) => {
+            create_dir!();
+            let graph_file_path = Path::join(dir, format!("{}.storage.svg", raw_file_name));
+            std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to write flamegraph to file {} : {:?}",
+                    graph_file_path.display(),
+                    err
+                ))
+            })?;
+            println!(
+                "Storage fee flamegraph saved to {}",
+                graph_file_path.display()
+            );
+        },
+        None => {
+            println!("Skipped generating storage fee flamegraph");
+        },
+    }
+
+    println!();
+
+    Ok(())
+}
+
+/// Describes a state key, e.g. the address and type of a resource
+pub fn format_key(key: &StateKey) -> String {
+    match key.inner() {
+        StateKeyInner::AccessPath(path) => match path.get_path() {
+            AccessPathKind::Code(module_id) => format!("code {}", module_id.short_str_lossless()),
+            AccessPathKind::Resource(tag) => {
+                format!("resource {} {}", path.address.to_hex_literal(), tag)
+            },
+            AccessPathKind::ResourceGroup(tag) => {
+                format!("resource group {} {}", path.address.to_hex_literal(), tag)
+            },
+        },
+        StateKeyInner::TableItem { handle, key } => format!(
+            "table item {} 0x{}",
+            handle.0.to_hex_literal(),
+            hex::encode(key)
+        ),
+        StateKeyInner::Raw(bytes) => format!("raw 0x{}", hex::encode(bytes)),
+    }
+}
+
+/// Describes a write op as `create`, `modify` or `delete`
+pub fn write_op_kind(op: &WriteOp) -> &'static str {
+    if op.is_creation() {
+        "create"
+    } else if op.is_deletion() {
+        "delete"
+    } else {
+        "modify"
     }
 }
@@ -1,13 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod simulate;
+
 #[cfg(feature = "no-upload-proposal")]
 use crate::common::utils::read_from_file;
 use crate::{
     common::{
         types::{
             CliError, CliTypedResult, MovePackageDir, PoolAddressArgs, ProfileOptions,
-            PromptOptions, RestOptions, TransactionOptions, TransactionSummary,
+            PromptOptions, RestOptions, TransactionOptions, TransactionOutcome, TransactionSummary,
         },
         utils::prompt_yes_with_override,
     },
@@ -56,6 +58,7 @@ pub enum GovernanceTool {
     ShowProposal(ViewProposal),
     ListProposals(ListProposals),
     VerifyProposal(VerifyProposal),
+    SimulateProposal(simulate::SimulateProposal),
     ExecuteProposal(ExecuteProposal),
     GenerateUpgradeProposal(GenerateUpgradeProposal),
     ApproveExecutionHash(ApproveExecutionHash),
@@ -72,6 +75,7 @@ impl GovernanceTool {
             ShowProposal(tool) => tool.execute_serialized().await,
             ListProposals(tool) => tool.execute_serialized().await,
             VerifyProposal(tool) => tool.execute_serialized().await,
+            SimulateProposal(tool) => tool.execute_serialized().await,
             ApproveExecutionHash(tool) => tool.execute_serialized().await,
         }
     }
@@ -324,7 +328,7 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
 
         let txn: Transaction = if self.is_multi_step {
             self.txn_options
-                .submit_transaction(aptos_stdlib::aptos_governance_create_proposal_v2(
+                .sign_and_submit_transaction(aptos_stdlib::aptos_governance_create_proposal_v2(
                     self.pool_address_args.pool_address,
                     script_hash.to_vec(),
                     self.metadata_url.to_string().as_bytes().to_vec(),
@@ -334,7 +338,7 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
                 .await?
         } else {
             self.txn_options
-                .submit_transaction(aptos_stdlib::aptos_governance_create_proposal(
+                .sign_and_submit_transaction(aptos_stdlib::aptos_governance_create_proposal(
                     self.pool_address_args.pool_address,
                     script_hash.to_vec(),
                     self.metadata_url.to_string().as_bytes().to_vec(),
@@ -542,7 +546,7 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
 
             summaries.push(
                 self.txn_options
-                    .submit_transaction(aptos_stdlib::aptos_governance_vote(
+                    .sign_and_submit_transaction(aptos_stdlib::aptos_governance_vote(
                         pool_address,
                         proposal_id,
                         vote,
@@ -568,19 +572,19 @@ pub struct ApproveExecutionHash {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for ApproveExecutionHash {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for ApproveExecutionHash {
     fn command_name(&self) -> &'static str {
         "ApproveExecutionHash"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         Ok(self
             .txn_options
             .submit_transaction(
                 aptos_stdlib::aptos_governance_add_approved_script_hash_script(self.proposal_id),
             )
             .await
-            .map(TransactionSummary::from)?)
+            .map(|outcome| outcome.map(TransactionSummary::from))?)
     }
 }
 
@@ -702,12 +706,12 @@ pub struct ExecuteProposal {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for ExecuteProposal {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for ExecuteProposal {
     fn command_name(&self) -> &'static str {
         "ExecuteProposal"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let (bytecode, _script_hash) = self
             .compile_proposal_args
             .compile("ExecuteProposal", self.txn_options.prompt_options)?;
@@ -719,7 +723,7 @@ impl CliCommand<TransactionSummary> for ExecuteProposal {
         self.txn_options
             .submit_transaction(txn)
             .await
-            .map(TransactionSummary::from)
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -0,0 +1,521 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{CliCommand, CliError, CliTypedResult, ProfileOptions, PromptOptions, RestOptions},
+        utils::{format_key, write_op_kind},
+    },
+    governance::{get_proposal, CompileScriptFunction, Proposal, VotingForum},
+};
+use aptos_debugger::AptosDebugger;
+use aptos_rest_client::{
+    aptos_api_types::{AptosError, AptosErrorCode},
+    error::{AptosErrorResponse, RestError},
+    Client,
+};
+use aptos_types::{
+    access_path::Path,
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::Features,
+    state_store::{state_key::StateKeyInner, table::TableHandle},
+    transaction::ChangeSet,
+};
+use aptos_vm::move_vm_ext::SessionId;
+use async_trait::async_trait;
+use clap::Parser;
+use move_binary_format::{compatibility::Compatibility, normalized, CompiledModule};
+use move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    value::{MoveStruct, MoveValue},
+};
+use move_vm_types::gas::UnmeteredGasMeter;
+use serde::Serialize;
+
+/// Resources at `0x1` left out of the reported config changes, as they are either modified by
+/// resolving any proposal or reported separately
+const RESOLUTION_RESOURCES: &[(&str, &str)] = &[
+    ("timestamp", "CurrentTimeMicroseconds"),
+    ("aptos_governance", "ApprovedExecutionHashes"),
+    ("features", "Features"),
+];
+
+/// Simulate the execution of a governance proposal against the current state of the chain
+///
+/// The proposal's script is compiled locally, checked against the on-chain execution hash,
+/// and executed as the resolution of the proposal on top of the latest state fetched from the
+/// fullnode. Nothing is submitted to the chain. If voting hasn't closed yet, the proposal is
+/// assumed to pass. The resulting on-chain config changes, feature flag toggles and module
+/// upgrades are reported.
+#[derive(Parser)]
+pub struct SimulateProposal {
+    /// The id of the onchain proposal
+    #[clap(long)]
+    pub(crate) proposal_id: u64,
+
+    #[clap(flatten)]
+    pub(crate) compile_proposal_args: CompileScriptFunction,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+/// Effects of executing a proposal
+#[derive(Debug, Serialize)]
+pub struct ProposalSimulation {
+    pub proposal_id: u64,
+    /// Version of the state the proposal was executed against
+    pub version: u64,
+    /// Whether voting was still open, and the proposal was assumed to pass
+    pub assumed_passed: bool,
+    /// Resources and table items changed by the proposal
+    pub config_changes: Vec<String>,
+    /// Feature flags enabled or disabled by the proposal
+    pub feature_changes: Vec<FeatureChange>,
+    /// Modules published by the proposal
+    pub module_changes: Vec<ModuleChange>,
+}
+
+/// A feature flag toggled by a proposal, numbered as in `std::features`
+#[derive(Debug, Serialize)]
+pub struct FeatureChange {
+    pub feature: u64,
+    pub enabled: bool,
+}
+
+/// A module published by a proposal
+#[derive(Debug, Serialize)]
+pub struct ModuleChange {
+    pub module: String,
+    /// One of `added`, `upgraded` or `unchanged`
+    pub change: String,
+    /// Differences with the published module, if it is upgraded
+    pub details: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProposalSimulation> for SimulateProposal {
+    fn command_name(&self) -> &'static str {
+        "SimulateProposal"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProposalSimulation> {
+        let (bytecode, script_hash) = self
+            .compile_proposal_args
+            .compile("SimulateProposal", self.prompt_options)?;
+
+        let client = self.rest_options.client(&self.profile)?;
+        let forum = client
+            .get_account_resource_bcs::<VotingForum>(
+                AccountAddress::ONE,
+                "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
+            )
+            .await?
+            .into_inner();
+        let voting_table = forum.table_handle.0;
+        let proposal: Proposal = get_proposal(&client, voting_table, self.proposal_id)
+            .await?
+            .into();
+
+        if proposal.is_resolved {
+            return Err(CliError::CommandArgumentError(format!(
+                "Proposal {} has already been resolved",
+                self.proposal_id
+            )));
+        }
+        if script_hash.to_hex() != proposal.execution_hash {
+            return Err(CliError::CommandArgumentError(format!(
+                "Script hash {} doesn't match the execution hash {} of proposal {}",
+                script_hash.to_hex(),
+                proposal.execution_hash,
+                self.proposal_id
+            )));
+        }
+
+        let state = client.get_ledger_information().await?.into_inner();
+        let version = state.version;
+        let now_micros = state.timestamp_usecs;
+
+        // Resolution requires voting to be closed with enough yes votes, so while voting is
+        // open, all remaining votes are cast in favor and time is moved past the expiration
+        let voting_closed =
+            now_micros / 1_000_000 >= proposal.expiration_secs || resolvable_early(&proposal, 0);
+        if voting_closed && !has_passed(&proposal) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Proposal {} has failed and cannot be resolved",
+                self.proposal_id
+            )));
+        }
+        let assumed_passed = !voting_closed;
+        let mut resolution_micros = now_micros + 1_000_000;
+        if assumed_passed && !resolvable_early(&proposal, u64::MAX) {
+            resolution_micros = resolution_micros.max(proposal.expiration_secs * 1_000_000);
+        }
+
+        let features_before = client
+            .get_account_resource_at_version_bcs::<Features>(
+                CORE_CODE_ADDRESS,
+                "0x1::features::Features",
+                version,
+            )
+            .await?
+            .into_inner();
+
+        let debugger = AptosDebugger::rest_client(client.clone())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        // The script hash is part of the session id, as resolving checks it against the
+        // execution hash of the proposal
+        let session_id = SessionId::Txn {
+            sender: CORE_CODE_ADDRESS,
+            sequence_number: 0,
+            script_hash: script_hash.to_vec(),
+        };
+        let proposal_id = self.proposal_id;
+        let mut publish_request = None;
+        let change_set = debugger
+            .run_session_at_version(version + 1, session_id, |session| {
+                let gas_meter = &mut UnmeteredGasMeter;
+                if assumed_passed {
+                    session.execute_function_bypass_visibility(
+                        &ModuleId::new(CORE_CODE_ADDRESS, ident_str!("voting").to_owned()),
+                        ident_str!("vote"),
+                        vec![TypeTag::Struct(Box::new(StructTag {
+                            address: CORE_CODE_ADDRESS,
+                            module: ident_str!("governance_proposal").to_owned(),
+                            name: ident_str!("GovernanceProposal").to_owned(),
+                            type_params: vec![],
+                        }))],
+                        serialize_args(vec![
+                            MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Bool(false)])),
+                            MoveValue::Address(CORE_CODE_ADDRESS),
+                            MoveValue::U64(proposal_id),
+                            MoveValue::U64(u64::MAX),
+                            MoveValue::Bool(true),
+                        ]),
+                        gas_meter,
+                    )?;
+                }
+                session.execute_function_bypass_visibility(
+                    &ModuleId::new(CORE_CODE_ADDRESS, ident_str!("timestamp").to_owned()),
+                    ident_str!("update_global_time"),
+                    vec![],
+                    serialize_args(vec![
+                        MoveValue::Signer(AccountAddress::ZERO),
+                        MoveValue::Address(CORE_CODE_ADDRESS),
+                        MoveValue::U64(resolution_micros),
+                    ]),
+                    gas_meter,
+                )?;
+                session.execute_script(
+                    bytecode.as_slice(),
+                    vec![],
+                    serialize_args(vec![MoveValue::U64(proposal_id)]),
+                    gas_meter,
+                )?;
+                publish_request = session.extract_publish_request();
+                Ok(())
+            })
+            .map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to simulate proposal: {}", err))
+            })?;
+
+        let mut module_changes = vec![];
+        if let Some(request) = publish_request {
+            for module in request.bundle.iter() {
+                module_changes.push(
+                    module_change(&client, version, request.destination, module.code()).await?,
+                );
+            }
+        }
+
+        Ok(ProposalSimulation {
+            proposal_id,
+            version,
+            assumed_passed,
+            config_changes: config_changes(&change_set, forum.table_handle),
+            feature_changes: feature_changes(&features_before, &change_set)?,
+            module_changes,
+        })
+    }
+}
+
+fn serialize_args(args: Vec<MoveValue>) -> Vec<Vec<u8>> {
+    args.into_iter()
+        .map(|arg| arg.simple_serialize().expect("Move values must serialize"))
+        .collect()
+}
+
+/// Whether the proposal reaches its early resolution threshold, with `extra_yes_votes` cast
+fn resolvable_early(proposal: &Proposal, extra_yes_votes: u64) -> bool {
+    proposal
+        .early_resolution_vote_threshold
+        .map_or(false, |threshold| {
+            proposal.yes_votes + extra_yes_votes as u128 >= threshold
+                || proposal.no_votes >= threshold
+        })
+}
+
+/// Whether the proposal succeeds once voting is closed, as in `voting::get_proposal_state`
+fn has_passed(proposal: &Proposal) -> bool {
+    proposal.yes_votes > proposal.no_votes
+        && proposal.yes_votes + proposal.no_votes >= proposal.min_vote_threshold
+}
+
+/// Lists the state changed by the proposal, other than by resolving it
+fn config_changes(change_set: &ChangeSet, voting_table: TableHandle) -> Vec<String> {
+    change_set
+        .write_set()
+        .iter()
+        .filter(|(key, _)| match key.inner() {
+            StateKeyInner::AccessPath(path) => match path.get_path() {
+                Path::Resource(tag) => {
+                    tag.address != CORE_CODE_ADDRESS
+                        || !RESOLUTION_RESOURCES.iter().any(|(module, name)| {
+                            tag.module.as_str() == *module && tag.name.as_str() == *name
+                        })
+                },
+                _ => true,
+            },
+            StateKeyInner::TableItem { handle, .. } => *handle != voting_table,
+            StateKeyInner::Raw(_) => true,
+        })
+        .map(|(key, op)| format!("{} {}", write_op_kind(op), format_key(key)))
+        .collect()
+}
+
+/// Lists the feature flags toggled by the proposal
+fn feature_changes(
+    before: &Features,
+    change_set: &ChangeSet,
+) -> CliTypedResult<Vec<FeatureChange>> {
+    let after = change_set
+        .write_set()
+        .iter()
+        .find_map(|(key, op)| match key.inner() {
+            StateKeyInner::AccessPath(path) => match path.get_path() {
+                Path::Resource(tag)
+                    if tag.address == CORE_CODE_ADDRESS
+                        && tag.module.as_str() == "features"
+                        && tag.name.as_str() == "Features" =>
+                {
+                    op.bytes()
+                },
+                _ => None,
+            },
+            _ => None,
+        });
+    let after: Features = match after {
+        Some(bytes) => bcs::from_bytes(bytes).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to deserialize feature flags: {}", err))
+        })?,
+        None => return Ok(vec![]),
+    };
+
+    let is_set = |features: &Features, index: usize| {
+        features
+            .features
+            .get(index / 8)
+            .map_or(false, |byte| byte & (1 << (index % 8)) != 0)
+    };
+    let num_bits = before.features.len().max(after.features.len()) * 8;
+    Ok((0..num_bits)
+        .filter(|index| is_set(before, *index) != is_set(&after, *index))
+        .map(|index| FeatureChange {
+            feature: index as u64,
+            enabled: is_set(&after, index),
+        })
+        .collect())
+}
+
+/// Compares a module published by the proposal with the one currently on-chain
+async fn module_change(
+    client: &Client,
+    version: u64,
+    destination: AccountAddress,
+    code: &[u8],
+) -> CliTypedResult<ModuleChange> {
+    let new_module = CompiledModule::deserialize(code).map_err(|err| {
+        CliError::UnexpectedError(format!("Failed to deserialize published module: {}", err))
+    })?;
+    let module_id = new_module.self_id();
+    let module = format!(
+        "{}::{}",
+        destination.to_hex_literal(),
+        module_id.name().as_str()
+    );
+
+    let old_code = match client
+        .get_account_module_bcs_at_version(destination, module_id.name().as_str(), version)
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(RestError::Api(AptosErrorResponse {
+            error:
+                AptosError {
+                    error_code: AptosErrorCode::ModuleNotFound | AptosErrorCode::AccountNotFound,
+                    ..
+                },
+            ..
+        })) => {
+            return Ok(ModuleChange {
+                module,
+                change: "added".to_string(),
+                details: vec![],
+            })
+        },
+        Err(err) => return Err(err.into()),
+    };
+    if old_code.as_ref() == code {
+        return Ok(ModuleChange {
+            module,
+            change: "unchanged".to_string(),
+            details: vec![],
+        });
+    }
+
+    let old_module = CompiledModule::deserialize(&old_code).map_err(|err| {
+        CliError::UnexpectedError(format!(
+            "Failed to deserialize published module `{}`: {}",
+            module, err
+        ))
+    })?;
+    let old_module = normalized::Module::new(&old_module);
+    let new_module = normalized::Module::new(&new_module);
+    let mut details: Vec<String> = Compatibility::incompatibilities(&old_module, &new_module)
+        .iter()
+        .map(|incompatibility| incompatibility.to_string())
+        .collect();
+    for name in new_module.structs.keys() {
+        if !old_module.structs.contains_key(name) {
+            details.push(format!("struct `{}` added", name));
+        }
+    }
+    for name in new_module.exposed_functions.keys() {
+        if !old_module.exposed_functions.contains_key(name) {
+            details.push(format!("function `{}` added", name));
+        }
+    }
+
+    Ok(ModuleChange {
+        module,
+        change: "upgraded".to_string(),
+        details,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        access_path::AccessPath,
+        state_store::state_key::StateKey,
+        transaction::CheckChangeSet,
+        write_set::{WriteOp, WriteSetMut},
+    };
+    use move_core_types::{identifier::Identifier, vm_status::VMStatus};
+    use std::collections::BTreeMap;
+
+    struct NoChecks;
+
+    impl CheckChangeSet for NoChecks {
+        fn check_change_set(&self, _change_set: &ChangeSet) -> Result<(), VMStatus> {
+            Ok(())
+        }
+    }
+
+    fn proposal(
+        yes_votes: u128,
+        no_votes: u128,
+        min_vote_threshold: u128,
+        early_resolution_vote_threshold: Option<u128>,
+    ) -> Proposal {
+        Proposal {
+            proposer: AccountAddress::ONE,
+            metadata: BTreeMap::new(),
+            creation_time_secs: 0,
+            execution_hash: String::new(),
+            min_vote_threshold,
+            expiration_secs: 0,
+            early_resolution_vote_threshold,
+            yes_votes,
+            no_votes,
+            is_resolved: false,
+            resolution_time_secs: 0,
+        }
+    }
+
+    fn resource_change_set(name: &str, value: Vec<u8>) -> ChangeSet {
+        let tag = StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: ident_str!("features").to_owned(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        };
+        let key = StateKey::access_path(
+            AccessPath::resource_access_path(CORE_CODE_ADDRESS, tag).unwrap(),
+        );
+        let write_set = WriteSetMut::new(vec![(key, WriteOp::Modification(value))])
+            .freeze()
+            .unwrap();
+        ChangeSet::new(write_set, vec![], &NoChecks).unwrap()
+    }
+
+    fn features(bytes: Vec<u8>) -> Features {
+        Features { features: bytes }
+    }
+
+    #[test]
+    fn test_resolvable_early() {
+        assert!(!resolvable_early(&proposal(100, 0, 50, None), 100));
+        assert!(!resolvable_early(&proposal(60, 0, 50, Some(100)), 39));
+        assert!(resolvable_early(&proposal(60, 0, 50, Some(100)), 40));
+        assert!(resolvable_early(&proposal(100, 0, 50, Some(100)), 0));
+        assert!(resolvable_early(&proposal(0, 100, 50, Some(100)), 0));
+    }
+
+    #[test]
+    fn test_has_passed() {
+        assert!(has_passed(&proposal(60, 40, 100, None)));
+        assert!(!has_passed(&proposal(50, 50, 100, None)));
+        assert!(!has_passed(&proposal(40, 60, 100, None)));
+        assert!(!has_passed(&proposal(60, 30, 100, None)));
+    }
+
+    #[test]
+    fn test_feature_changes() {
+        // Bits 0 and 2 are set before, bit 0 is cleared and bit 17, beyond the current length
+        // of the bitset, is set by the proposal
+        let before = features(vec![0b0000_0101]);
+        let after = features(vec![0b0000_0100, 0, 0b0000_0010]);
+        let change_set = resource_change_set("Features", bcs::to_bytes(&after).unwrap());
+        let changes: Vec<_> = feature_changes(&before, &change_set)
+            .unwrap()
+            .into_iter()
+            .map(|change| (change.feature, change.enabled))
+            .collect();
+        assert_eq!(changes, vec![(0, false), (17, true)]);
+
+        // Clearing bits beyond the length of the new bitset
+        let changes: Vec<_> = feature_changes(
+            &after,
+            &resource_change_set(
+                "Features",
+                bcs::to_bytes(&features(vec![0b0000_0100])).unwrap(),
+            ),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|change| (change.feature, change.enabled))
+        .collect();
+        assert_eq!(changes, vec![(17, false)]);
+
+        // The feature flags aren't written
+        let change_set = resource_change_set("OtherResource", vec![]);
+        assert!(feature_changes(&before, &change_set).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::{
+        CliCommand, CliError, CliTypedResult, MovePackageDir, ProfileOptions, RestOptions,
+    },
+    move_tool::{stored_package::CachedPackageRegistry, IncludedArtifacts},
+};
+use aptos_framework::{natives::code::UpgradePolicy, BuiltPackage};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{FeatureFlag, Features},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use move_binary_format::{compatibility::Compatibility, normalized, CompiledModule};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Check that a local package can be published as an upgrade of the one on-chain
+///
+/// Compiles the package locally, downloads the published package, and applies the same
+/// upgrade checks as publishing on-chain: the upgrade policy, the set of modules, and the
+/// backward compatibility of struct layouts and function signatures. Every breaking change
+/// is reported, rather than only the first one the VM would reject.
+#[derive(Parser)]
+pub struct CheckUpgrade {
+    /// Address of the account the package is published at
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// Summary of a compatible package upgrade
+#[derive(Debug, Serialize)]
+pub struct UpgradeCheckSummary {
+    pub package: String,
+    pub account: AccountAddress,
+    pub published_upgrade_number: u64,
+    pub published_upgrade_policy: String,
+    pub upgrade_policy: String,
+    pub modules_checked: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<UpgradeCheckSummary> for CheckUpgrade {
+    fn command_name(&self) -> &'static str {
+        "CheckUpgrade"
+    }
+
+    async fn execute(self) -> CliTypedResult<UpgradeCheckSummary> {
+        let account = if let Some(account) = self.account {
+            account
+        } else {
+            self.profile_options.account_address()?
+        };
+
+        let build_options = IncludedArtifacts::None.build_options(
+            self.move_options.skip_fetch_latest_git_deps,
+            self.move_options.named_addresses(),
+            self.move_options.bytecode_version,
+        );
+        let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let new_metadata = pack.extract_metadata()?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let url = self.rest_options.url(&self.profile_options)?;
+        let registry = CachedPackageRegistry::create(url, account).await?;
+        let published = registry
+            .get_package(pack.name())
+            .await
+            .map_err(|s| CliError::CommandArgumentError(s.to_string()))?;
+
+        // Publishing checks the friend declarations unless friends are treated as private
+        let features = client
+            .get_account_resource_bcs::<Features>(CORE_CODE_ADDRESS, "0x1::features::Features")
+            .await?
+            .into_inner();
+        let compatibility = Compatibility::new(
+            true,
+            true,
+            !features.is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
+        );
+
+        let old_policy = published.upgrade_policy();
+        let new_policy = new_metadata.upgrade_policy;
+        let new_module_names: Vec<String> = pack
+            .modules()
+            .map(|module| module.self_id().name().to_string())
+            .collect();
+        let mut other_packages = BTreeMap::new();
+        for package_name in registry.package_names() {
+            if package_name == published.name() {
+                continue;
+            }
+            let other = registry.get_package(package_name).await?;
+            let module_names = other
+                .module_names()
+                .into_iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            other_packages.insert(package_name.to_string(), module_names);
+        }
+        let mut breaking_changes = package_breaking_changes(
+            old_policy,
+            new_policy,
+            &published.module_names(),
+            &new_module_names,
+            &other_packages,
+        );
+
+        // Module level checks, as done by the VM when publishing the bundle
+        let mut modules_checked = vec![];
+        for new_module in pack.modules() {
+            let name = new_module.self_id().name().to_string();
+            if !published.module_names().contains(&name.as_str()) {
+                continue;
+            }
+            let bytes = registry.get_bytecode(&name).await?;
+            let old_module = CompiledModule::deserialize(&bytes).map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to deserialize published module `{}`: {}",
+                    name, err
+                ))
+            })?;
+            let old_module = normalized::Module::new(&old_module);
+            let new_module = normalized::Module::new(new_module);
+            for incompatibility in Compatibility::incompatibilities(&old_module, &new_module) {
+                if compatibility.is_breaking(&incompatibility) {
+                    breaking_changes.push(format!("module `{}`: {}", name, incompatibility));
+                }
+            }
+            modules_checked.push(name);
+        }
+
+        if !breaking_changes.is_empty() {
+            return Err(CliError::IncompatibleUpgradeError(format!(
+                "\n- {}",
+                breaking_changes.join("\n- ")
+            )));
+        }
+
+        Ok(UpgradeCheckSummary {
+            package: pack.name().to_string(),
+            account,
+            published_upgrade_number: published.upgrade_number(),
+            published_upgrade_policy: old_policy.to_string(),
+            upgrade_policy: new_policy.to_string(),
+            modules_checked,
+        })
+    }
+}
+
+/// Package level checks, as done by `code::publish_package`
+fn package_breaking_changes(
+    old_policy: UpgradePolicy,
+    new_policy: UpgradePolicy,
+    old_module_names: &[&str],
+    new_module_names: &[String],
+    other_packages: &BTreeMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut breaking_changes = vec![];
+    if new_policy.policy <= UpgradePolicy::arbitrary().policy {
+        breaking_changes.push(format!(
+            "upgrade policy `{}` is not allowed for publishing",
+            new_policy
+        ));
+    }
+    if old_policy.policy >= UpgradePolicy::immutable().policy {
+        breaking_changes.push("published package is immutable".to_string());
+    }
+    if new_policy.policy < old_policy.policy {
+        breaking_changes.push(format!(
+            "upgrade policy is weakened from `{}` to `{}`",
+            old_policy, new_policy
+        ));
+    }
+    for old_module_name in old_module_names {
+        if !new_module_names.iter().any(|name| name == old_module_name) {
+            breaking_changes.push(format!("module `{}` was removed", old_module_name));
+        }
+    }
+    for (package_name, module_names) in other_packages {
+        for module_name in module_names {
+            if new_module_names.iter().any(|name| name == module_name) {
+                breaking_changes.push(format!(
+                    "module `{}` is already published in package `{}`",
+                    module_name, package_name
+                ));
+            }
+        }
+    }
+    breaking_changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_compatible_upgrade() {
+        let breaking_changes = package_breaking_changes(
+            UpgradePolicy::compat(),
+            UpgradePolicy::compat(),
+            &["a", "b"],
+            &module_names(&["a", "b", "c"]),
+            &BTreeMap::from([("other".to_string(), module_names(&["d"]))]),
+        );
+        assert!(breaking_changes.is_empty(), "{:?}", breaking_changes);
+
+        // Making the package immutable is allowed
+        let breaking_changes = package_breaking_changes(
+            UpgradePolicy::compat(),
+            UpgradePolicy::immutable(),
+            &["a"],
+            &module_names(&["a"]),
+            &BTreeMap::new(),
+        );
+        assert!(breaking_changes.is_empty(), "{:?}", breaking_changes);
+    }
+
+    #[test]
+    fn test_upgrade_policies() {
+        let breaking_changes = package_breaking_changes(
+            UpgradePolicy::compat(),
+            UpgradePolicy::arbitrary(),
+            &["a"],
+            &module_names(&["a"]),
+            &BTreeMap::new(),
+        );
+        assert_eq!(breaking_changes, vec![
+            "upgrade policy `arbitrary` is not allowed for publishing",
+            "upgrade policy is weakened from `compatible` to `arbitrary`",
+        ]);
+
+        let breaking_changes = package_breaking_changes(
+            UpgradePolicy::immutable(),
+            UpgradePolicy::compat(),
+            &["a"],
+            &module_names(&["a"]),
+            &BTreeMap::new(),
+        );
+        assert_eq!(breaking_changes, vec![
+            "published package is immutable",
+            "upgrade policy is weakened from `immutable` to `compatible`",
+        ]);
+    }
+
+    #[test]
+    fn test_package_modules() {
+        let breaking_changes = package_breaking_changes(
+            UpgradePolicy::compat(),
+            UpgradePolicy::compat(),
+            &["a", "b"],
+            &module_names(&["a", "c"]),
+            &BTreeMap::from([
+                ("other".to_string(), module_names(&["c", "d"])),
+                ("unrelated".to_string(), module_names(&["e"])),
+            ]),
+        );
+        assert_eq!(breaking_changes, vec![
+            "module `b` was removed",
+            "module `c` is already published in package `other`",
+        ]);
+    }
+}
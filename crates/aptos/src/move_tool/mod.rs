@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aptos_debug_natives;
+mod check_upgrade;
 pub mod coverage;
 mod disassembler;
 mod manifest;
 pub mod package_hooks;
+mod replay;
 mod show;
 pub mod stored_package;
 mod transactional_tests_runner;
@@ -14,9 +16,10 @@ use crate::{
     account::derive_resource_account::ResourceAccountSeed,
     common::{
         types::{
-            load_account_arg, ArgWithTypeVec, CliConfig, CliError, CliTypedResult,
-            ConfigSearchMode, EntryFunctionArguments, MoveManifestAccountWrapper, MovePackageDir,
-            ProfileOptions, PromptOptions, RestOptions, TransactionOptions, TransactionSummary,
+            decode_view_values, get_function_abi, load_account_arg, view_values_need_decoding,
+            ArgWithTypeVec, CliConfig, CliError, CliTypedResult, ConfigSearchMode,
+            EntryFunctionArguments, MoveManifestAccountWrapper, MovePackageDir, ProfileOptions,
+            PromptOptions, RestOptions, TransactionOptions, TransactionOutcome, TransactionSummary,
         },
         utils::{
             check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
@@ -25,9 +28,11 @@ use crate::{
     },
     governance::CompileScriptFunction,
     move_tool::{
+        check_upgrade::CheckUpgrade,
         coverage::SummaryCoverage,
         disassembler::Disassemble,
         manifest::{Dependency, ManifestNamedAddress, MovePackageManifest, PackageInfo},
+        replay::Replay,
     },
     CliCommand, CliResult,
 };
@@ -37,7 +42,7 @@ use aptos_framework::{
     prover::ProverOptions, BuildOptions, BuiltPackage,
 };
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
-use aptos_rest_client::aptos_api_types::{EntryFunctionId, MoveType, ViewRequest};
+use aptos_rest_client::aptos_api_types::MoveType;
 use aptos_transactional_test_harness::run_aptos_test;
 use aptos_types::{
     account_address::{create_resource_address, AccountAddress},
@@ -79,6 +84,7 @@ use transactional_tests_runner::TransactionalTestOpts;
 /// about this code.
 #[derive(Subcommand)]
 pub enum MoveTool {
+    CheckUpgrade(CheckUpgrade),
     Clean(CleanPackage),
     Compile(CompilePackage),
     CompileScript(CompileScript),
@@ -92,6 +98,7 @@ pub enum MoveTool {
     List(ListPackage),
     Prove(ProvePackage),
     Publish(PublishPackage),
+    Replay(Replay),
     Run(RunFunction),
     RunScript(RunScript),
     #[clap(subcommand, hide = true)]
@@ -105,6 +112,7 @@ pub enum MoveTool {
 impl MoveTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            MoveTool::CheckUpgrade(tool) => tool.execute_serialized().await,
             MoveTool::Clean(tool) => tool.execute_serialized().await,
             MoveTool::Compile(tool) => tool.execute_serialized().await,
             MoveTool::CompileScript(tool) => tool.execute_serialized().await,
@@ -119,6 +127,7 @@ impl MoveTool {
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
             MoveTool::Publish(tool) => tool.execute_serialized().await,
+            MoveTool::Replay(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
             MoveTool::Show(tool) => tool.execute_serialized().await,
@@ -711,12 +720,12 @@ impl IncludedArtifacts {
 pub const MAX_PUBLISH_PACKAGE_SIZE: usize = 60_000;
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for PublishPackage {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for PublishPackage {
     fn command_name(&self) -> &'static str {
         "PublishPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let PublishPackage {
             move_options,
             txn_options,
@@ -779,12 +788,12 @@ pub struct CreateResourceAccountAndPublishPackage {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for CreateResourceAccountAndPublishPackage {
     fn command_name(&self) -> &'static str {
         "ResourceAccountPublishPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let CreateResourceAccountAndPublishPackage {
             address_name,
             mut move_options,
@@ -847,7 +856,7 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
         txn_options
             .submit_transaction(payload)
             .await
-            .map(TransactionSummary::from)
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -1096,14 +1105,17 @@ pub struct RunFunction {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RunFunction {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for RunFunction {
     fn command_name(&self) -> &'static str {
         "RunFunction"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        let client = self.txn_options.rest_client()?;
         let payload = TransactionPayload::EntryFunction(
-            self.entry_function_args.create_entry_function_payload()?,
+            self.entry_function_args
+                .create_entry_function_payload(&client)
+                .await?,
         );
         profile_or_submit(payload, &self.txn_options).await
     }
@@ -1125,21 +1137,23 @@ impl CliCommand<Vec<serde_json::Value>> for ViewFunction {
     }
 
     async fn execute(self) -> CliTypedResult<Vec<serde_json::Value>> {
-        let mut args: Vec<serde_json::Value> = vec![];
-        for arg in self.entry_function_args.arg_vec.args {
-            args.push(arg.to_json()?);
+        let client = self.txn_options.rest_client()?;
+        let (view_request, function) = self
+            .entry_function_args
+            .create_view_request(&client)
+            .await?;
+        let function_id = view_request.function.clone();
+        let type_args = view_request.type_arguments.clone();
+        let values = self.txn_options.view(view_request).await?;
+        if !view_values_need_decoding(&values) {
+            return Ok(values);
         }
 
-        let view_request = ViewRequest {
-            function: EntryFunctionId {
-                module: self.entry_function_args.function_id.module_id.into(),
-                name: self.entry_function_args.function_id.member_id.into(),
-            },
-            type_arguments: self.entry_function_args.type_args,
-            arguments: args,
+        let function = match function {
+            Some(function) => function,
+            None => get_function_abi(&client, &function_id).await?,
         };
-
-        self.txn_options.view(view_request).await
+        Ok(decode_view_values(&function, &type_args, values))
     }
 }
 
@@ -1160,12 +1174,12 @@ pub struct RunScript {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RunScript {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for RunScript {
     fn command_name(&self) -> &'static str {
         "RunScript"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let (bytecode, _script_hash) = self
             .compile_proposal_args
             .compile("RunScript", self.txn_options.prompt_options)?;
@@ -0,0 +1,474 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions},
+    utils::{format_key, save_gas_profile, write_op_kind},
+};
+use aptos_debugger::AptosDebugger;
+use aptos_gas_profiling::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog};
+use aptos_rest_client::aptos_api_types::HashValue;
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::StateKey,
+    transaction::{Transaction, TransactionOutput, TransactionPayload, TransactionStatus},
+    write_set::WriteSet,
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Replay a committed transaction locally and compare the result with the chain
+///
+/// The transaction is re-executed against the state of the chain right before it was
+/// committed, fetched from the fullnode. The status, gas used, write set and events of the
+/// replay are compared with what was committed on-chain.
+#[derive(Parser)]
+pub struct Replay {
+    /// Version of the transaction to replay
+    #[clap(long)]
+    pub(crate) txn_version: u64,
+
+    /// Include a Move-level execution trace in the output
+    ///
+    /// The trace contains the call stack, the resources read, the state written and the
+    /// events emitted by the transaction. Only user transactions can be traced.
+    #[clap(long)]
+    pub(crate) trace: bool,
+
+    /// Profile the gas usage of the transaction
+    ///
+    /// Flamegraphs of the execution & IO gas and of the storage fees are saved to
+    /// `gas-profiling/`. Only user transactions can be profiled.
+    #[clap(long)]
+    pub(crate) profile_gas: bool,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// Result of replaying a committed transaction
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub version: u64,
+    pub transaction_hash: HashValue,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub success: Option<bool>,
+    /// Whether the replayed output is identical to the committed one
+    pub matches_on_chain: bool,
+    /// Every difference found between the replayed and the committed output
+    pub mismatches: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ReplayTrace>,
+}
+
+/// Move-level execution trace of a replayed transaction
+#[derive(Debug, Serialize)]
+pub struct ReplayTrace {
+    /// Functions called, indented by call depth
+    pub call_stack: Vec<String>,
+    /// Resources loaded from global storage, with the function that loaded them
+    pub resource_reads: Vec<String>,
+    /// State written by the transaction
+    pub writes: Vec<String>,
+    /// Events emitted by the transaction, with their decoded data
+    pub events: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<ReplaySummary> for Replay {
+    fn command_name(&self) -> &'static str {
+        "Replay"
+    }
+
+    async fn execute(self) -> CliTypedResult<ReplaySummary> {
+        if self.txn_version == 0 {
+            return Err(CliError::CommandArgumentError(
+                "The genesis transaction cannot be replayed".to_string(),
+            ));
+        }
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let committed = client
+            .get_transactions_bcs(Some(self.txn_version), Some(1))
+            .await?
+            .into_inner()
+            .pop()
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Transaction at version {} not found",
+                    self.txn_version
+                ))
+            })?;
+        let debugger = AptosDebugger::rest_client(client)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        // Profiling is needed for the trace as well, since it records the calls made
+        let (vm_status, output, gas_log) = if self.trace || self.profile_gas {
+            let txn = match committed.transaction.clone() {
+                Transaction::UserTransaction(txn) => txn,
+                _ => {
+                    return Err(CliError::CommandArgumentError(
+                        "Only user transactions can be traced or gas profiled".to_string(),
+                    ))
+                },
+            };
+            if !matches!(
+                txn.payload(),
+                TransactionPayload::EntryFunction(_) | TransactionPayload::Script(_)
+            ) {
+                return Err(CliError::CommandArgumentError(
+                    "Only entry function and script transactions can be traced or gas profiled"
+                        .to_string(),
+                ));
+            }
+            let (vm_status, output, gas_log) = debugger
+                .execute_transaction_at_version_with_gas_profiler(self.txn_version, txn)
+                .map_err(|err| {
+                    CliError::UnexpectedError(format!("Failed to replay transaction: {}", err))
+                })?;
+            (Some(vm_status.to_string()), output, Some(gas_log))
+        } else {
+            let output = debugger
+                .execute_transactions_at_version(self.txn_version, vec![committed
+                    .transaction
+                    .clone()])
+                .map_err(|err| {
+                    CliError::UnexpectedError(format!("Failed to replay transaction: {}", err))
+                })?
+                .pop()
+                .ok_or_else(|| {
+                    CliError::UnexpectedError("Replay produced no output".to_string())
+                })?;
+            (None, output, None)
+        };
+
+        let hash = committed.info.transaction_hash();
+        if self.profile_gas {
+            if let Some(ref gas_log) = gas_log {
+                save_gas_profile(hash, gas_log)?;
+            }
+        }
+
+        let mismatches = compare_outputs(
+            &output,
+            &committed.info.status().clone().into(),
+            committed.info.gas_used(),
+            &committed.changes,
+            &committed.events,
+        );
+
+        let trace = match (self.trace, gas_log) {
+            (true, Some(gas_log)) => {
+                let decoded_events = debugger
+                    .annotate_events_at_version(self.txn_version, output.events())
+                    .map_err(|err| {
+                        CliError::UnexpectedError(format!("Failed to decode events: {}", err))
+                    })?;
+                Some(ReplayTrace {
+                    call_stack: call_stack(&gas_log),
+                    resource_reads: resource_reads(&gas_log),
+                    writes: output
+                        .write_set()
+                        .iter()
+                        .map(|(key, op)| format!("{} {}", write_op_kind(op), format_key(key)))
+                        .collect(),
+                    events: output
+                        .events()
+                        .iter()
+                        .zip(decoded_events)
+                        .map(|(event, data)| format!("{} {}", event.type_tag(), data))
+                        .collect(),
+                })
+            },
+            _ => None,
+        };
+
+        let success = match output.status() {
+            TransactionStatus::Keep(exec_status) => Some(exec_status.is_success()),
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => None,
+        };
+        Ok(ReplaySummary {
+            version: self.txn_version,
+            transaction_hash: hash.into(),
+            vm_status: vm_status.unwrap_or_else(|| format!("{:?}", output.status())),
+            gas_used: output.gas_used(),
+            success,
+            matches_on_chain: mismatches.is_empty(),
+            mismatches,
+            trace,
+        })
+    }
+}
+
+/// Lists the differences between a replayed output and the committed one
+fn compare_outputs(
+    output: &TransactionOutput,
+    expected_status: &TransactionStatus,
+    expected_gas_used: u64,
+    expected_write_set: &WriteSet,
+    expected_events: &[ContractEvent],
+) -> Vec<String> {
+    let mut mismatches = vec![];
+
+    if output.status() != expected_status {
+        mismatches.push(format!(
+            "status: replayed {:?}, on-chain {:?}",
+            output.status(),
+            expected_status
+        ));
+    }
+    if output.gas_used() != expected_gas_used {
+        mismatches.push(format!(
+            "gas used: replayed {}, on-chain {}",
+            output.gas_used(),
+            expected_gas_used
+        ));
+    }
+
+    let keys: BTreeSet<&StateKey> = output
+        .write_set()
+        .iter()
+        .chain(expected_write_set.iter())
+        .map(|(key, _)| key)
+        .collect();
+    for key in keys {
+        match (output.write_set().get(key), expected_write_set.get(key)) {
+            (Some(op), None) => mismatches.push(format!(
+                "write set: {} {} only in replay",
+                write_op_kind(op),
+                format_key(key)
+            )),
+            (None, Some(op)) => mismatches.push(format!(
+                "write set: {} {} only on-chain",
+                write_op_kind(op),
+                format_key(key)
+            )),
+            (Some(op), Some(expected_op)) if op != expected_op => mismatches.push(format!(
+                "write set: {} differs, replayed {}, on-chain {}",
+                format_key(key),
+                write_op_kind(op),
+                write_op_kind(expected_op)
+            )),
+            _ => {},
+        }
+    }
+
+    if output.events().len() != expected_events.len() {
+        mismatches.push(format!(
+            "events: replayed {} events, on-chain {}",
+            output.events().len(),
+            expected_events.len()
+        ));
+    }
+    for (index, (event, expected_event)) in output.events().iter().zip(expected_events).enumerate()
+    {
+        if event != expected_event {
+            mismatches.push(format!(
+                "events: event {} differs, replayed {}, on-chain {}",
+                index,
+                event.type_tag(),
+                expected_event.type_tag()
+            ));
+        }
+    }
+
+    mismatches
+}
+
+fn call_stack(gas_log: &TransactionGasLog) -> Vec<String> {
+    fn visit(frame: &CallFrame, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!(
+            "{}{}",
+            "  ".repeat(depth),
+            format_frame(&frame.name)
+        ));
+        for event in &frame.events {
+            match event {
+                ExecutionGasEvent::Call(callee) => visit(callee, depth + 1, lines),
+                ExecutionGasEvent::CallNative {
+                    module_id, fn_name, ..
+                } => lines.push(format!(
+                    "{}{}::{} (native)",
+                    "  ".repeat(depth + 1),
+                    module_id.short_str_lossless(),
+                    fn_name
+                )),
+                _ => {},
+            }
+        }
+    }
+
+    let mut lines = vec![];
+    visit(&gas_log.call_graph, 0, &mut lines);
+    lines
+}
+
+fn resource_reads(gas_log: &TransactionGasLog) -> Vec<String> {
+    fn visit(frame: &CallFrame, reads: &mut Vec<String>) {
+        for event in &frame.events {
+            match event {
+                ExecutionGasEvent::Call(callee) => visit(callee, reads),
+                ExecutionGasEvent::LoadResource { addr, ty, .. } => reads.push(format!(
+                    "{} {} in {}",
+                    addr.to_hex_literal(),
+                    ty,
+                    format_frame(&frame.name)
+                )),
+                _ => {},
+            }
+        }
+    }
+
+    let mut reads = vec![];
+    visit(&gas_log.call_graph, &mut reads);
+    reads
+}
+
+fn format_frame(name: &FrameName) -> String {
+    match name {
+        FrameName::Script => "<script>".to_string(),
+        FrameName::Function {
+            module_id,
+            name,
+            ty_args,
+        } if ty_args.is_empty() => format!("{}::{}", module_id.short_str_lossless(), name),
+        FrameName::Function {
+            module_id,
+            name,
+            ty_args,
+        } => format!(
+            "{}::{}<{}>",
+            module_id.short_str_lossless(),
+            name,
+            ty_args
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        event::EventKey,
+        transaction::ExecutionStatus,
+        write_set::{WriteOp, WriteSetMut},
+    };
+    use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+
+    fn write_set(ops: Vec<(u8, WriteOp)>) -> WriteSet {
+        WriteSetMut::new(
+            ops.into_iter()
+                .map(|(key, op)| (StateKey::raw(vec![key]), op)),
+        )
+        .freeze()
+        .unwrap()
+    }
+
+    fn event(sequence_number: u64, data: u8) -> ContractEvent {
+        ContractEvent::new(
+            EventKey::new(0, AccountAddress::ONE),
+            sequence_number,
+            TypeTag::U64,
+            vec![data],
+        )
+    }
+
+    fn output(
+        status: ExecutionStatus,
+        gas_used: u64,
+        write_set: WriteSet,
+        events: Vec<ContractEvent>,
+    ) -> TransactionOutput {
+        TransactionOutput::new(write_set, events, gas_used, TransactionStatus::Keep(status))
+    }
+
+    #[test]
+    fn test_compare_identical_outputs() {
+        let ops = vec![(1, WriteOp::Modification(vec![1]))];
+        let replayed = output(ExecutionStatus::Success, 10, write_set(ops.clone()), vec![
+            event(0, 1),
+        ]);
+        let mismatches = compare_outputs(
+            &replayed,
+            &TransactionStatus::Keep(ExecutionStatus::Success),
+            10,
+            &write_set(ops),
+            &[event(0, 1)],
+        );
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+    }
+
+    #[test]
+    fn test_compare_status_and_gas() {
+        let replayed = output(ExecutionStatus::OutOfGas, 10, write_set(vec![]), vec![]);
+        let mismatches = compare_outputs(
+            &replayed,
+            &TransactionStatus::Keep(ExecutionStatus::Success),
+            12,
+            &write_set(vec![]),
+            &[],
+        );
+        assert_eq!(mismatches, vec![
+            "status: replayed Keep(OutOfGas), on-chain Keep(Success)".to_string(),
+            "gas used: replayed 10, on-chain 12".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_compare_write_sets() {
+        let replayed = output(
+            ExecutionStatus::Success,
+            10,
+            write_set(vec![
+                (1, WriteOp::Modification(vec![1])),
+                (2, WriteOp::Creation(vec![2])),
+                (4, WriteOp::Modification(vec![4])),
+            ]),
+            vec![],
+        );
+        let mismatches = compare_outputs(
+            &replayed,
+            &TransactionStatus::Keep(ExecutionStatus::Success),
+            10,
+            &write_set(vec![
+                (1, WriteOp::Modification(vec![1])),
+                (2, WriteOp::Modification(vec![2])),
+                (3, WriteOp::Deletion),
+            ]),
+            &[],
+        );
+        assert_eq!(mismatches, vec![
+            "write set: raw 0x02 differs, replayed create, on-chain modify".to_string(),
+            "write set: delete raw 0x03 only on-chain".to_string(),
+            "write set: modify raw 0x04 only in replay".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_compare_events() {
+        let replayed = output(ExecutionStatus::Success, 10, write_set(vec![]), vec![
+            event(0, 1),
+            event(1, 2),
+        ]);
+        let mismatches = compare_outputs(
+            &replayed,
+            &TransactionStatus::Keep(ExecutionStatus::Success),
+            10,
+            &write_set(vec![]),
+            &[event(0, 1), event(1, 3), event(2, 4)],
+        );
+        assert_eq!(mismatches, vec![
+            "events: replayed 2 events, on-chain 3".to_string(),
+            "events: event 1 differs, replayed u64, on-chain u64".to_string(),
+        ]);
+    }
+}
@@ -18,6 +18,8 @@ use std::{fs, path::Path};
 /// Represents the package registry at a given account.
 pub struct CachedPackageRegistry {
     inner: PackageRegistry,
+    client: Client,
+    addr: AccountAddress,
 }
 
 /// Represents the package metadata found in an registry.
@@ -39,7 +41,11 @@ impl CachedPackageRegistry {
             .get_account_resource_bcs::<PackageRegistry>(addr, "0x1::code::PackageRegistry")
             .await?
             .into_inner();
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            client,
+            addr,
+        })
     }
 
     /// Returns the list of packages in this registry by name.
@@ -80,6 +86,16 @@ impl CachedPackageRegistry {
         }
         bail!("package `{}` not found", name)
     }
+
+    /// Fetches the published bytecode of the given module at the address of this registry.
+    pub async fn get_bytecode(&self, module_name: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
+        let bytes = self
+            .client
+            .get_account_module_bcs(self.addr, module_name.as_ref())
+            .await?
+            .into_inner();
+        Ok(bytes.to_vec())
+    }
 }
 
 impl<'a> CachedPackageMetadata<'a> {
@@ -8,7 +8,7 @@ use crate::{
         types::{
             CliCommand, CliError, CliResult, CliTypedResult, ConfigSearchMode,
             OptionalPoolAddressArgs, PoolAddressArgs, ProfileOptions, PromptOptions, RestOptions,
-            TransactionOptions, TransactionSummary,
+            TransactionOptions, TransactionOutcome, TransactionSummary,
         },
         utils::{prompt_yes_with_override, read_from_file},
     },
@@ -606,12 +606,12 @@ pub struct InitializeValidator {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for InitializeValidator {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for InitializeValidator {
     fn command_name(&self) -> &'static str {
         "InitializeValidator"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let operator_config = self.operator_config_file_args.load()?;
         let consensus_public_key = self
             .validator_consensus_key_args
@@ -650,7 +650,7 @@ impl CliCommand<TransactionSummary> for InitializeValidator {
                 bcs::to_bytes(&full_node_network_addresses)?,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -698,12 +698,12 @@ pub struct JoinValidatorSet {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for JoinValidatorSet {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for JoinValidatorSet {
     fn command_name(&self) -> &'static str {
         "JoinValidatorSet"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -711,7 +711,7 @@ impl CliCommand<TransactionSummary> for JoinValidatorSet {
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_join_validator_set(address))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -728,12 +728,12 @@ pub struct LeaveValidatorSet {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for LeaveValidatorSet {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for LeaveValidatorSet {
     fn command_name(&self) -> &'static str {
         "LeaveValidatorSet"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -741,7 +741,7 @@ impl CliCommand<TransactionSummary> for LeaveValidatorSet {
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_leave_validator_set(address))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -1247,12 +1247,12 @@ pub struct UpdateConsensusKey {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UpdateConsensusKey {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for UpdateConsensusKey {
     fn command_name(&self) -> &'static str {
         "UpdateConsensusKey"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -1271,7 +1271,7 @@ impl CliCommand<TransactionSummary> for UpdateConsensusKey {
                 consensus_proof_of_possession.to_bytes().to_vec(),
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -1291,12 +1291,12 @@ pub struct UpdateValidatorNetworkAddresses {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for UpdateValidatorNetworkAddresses {
     fn command_name(&self) -> &'static str {
         "UpdateValidatorNetworkAddresses"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let address = self
             .operator_args
             .address_fallback_to_txn(&self.txn_options)?;
@@ -1332,7 +1332,7 @@ impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
                 bcs::to_bytes(&full_node_network_addresses)?,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -1519,6 +1519,7 @@ impl CliCommand<()> for BootstrapDbFromBackup {
         let global_opt = GlobalRestoreOpt {
             dry_run: false,
             db_dir: Some(self.db_dir),
+            db_path_overrides: None,
             target_version: None,
             trusted_waypoints: Default::default(),
             rocksdb_opt: RocksdbOpt::default(),
@@ -0,0 +1,398 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::derive_resource_account::ResourceAccountSeed,
+    common::types::{
+        CliCommand, CliError, CliResult, CliTypedResult, ProfileOptions, RestOptions,
+        TransactionOptions, TransactionOutcome, TransactionSummary,
+    },
+};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_rest_client::aptos_api_types::{EntryFunctionId, ViewRequest, U64};
+use aptos_types::{
+    account_address::{create_delegation_pool_address, AccountAddress},
+    transaction::TransactionPayload,
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Tool for interacting with delegation pools
+///
+/// Delegation pools allow many delegators to stake to a single validator, with the
+/// operator receiving a fixed commission on rewards.
+#[derive(Subcommand)]
+pub enum DelegationTool {
+    AddStake(AddDelegatedStake),
+    InitializePool(InitializeDelegationPool),
+    ReactivateStake(ReactivateDelegatedStake),
+    ShowBalance(ShowDelegatedBalance),
+    Unlock(UnlockDelegatedStake),
+    Withdraw(WithdrawDelegatedStake),
+}
+
+impl DelegationTool {
+    pub async fn execute(self) -> CliResult {
+        use DelegationTool::*;
+        match self {
+            AddStake(tool) => tool.execute_serialized().await,
+            InitializePool(tool) => tool.execute_serialized().await,
+            ReactivateStake(tool) => tool.execute_serialized().await,
+            ShowBalance(tool) => tool.execute_serialized().await,
+            Unlock(tool) => tool.execute_serialized().await,
+            Withdraw(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Arguments shared by commands that move a delegator's stake within a delegation pool
+#[derive(Parser)]
+pub struct DelegatedStakeArgs {
+    /// Address of the delegation pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) pool_address: AccountAddress,
+
+    /// Amount of Octas (10^-8 APT)
+    #[clap(long)]
+    pub(crate) amount: u64,
+}
+
+/// Initialize a delegation pool
+///
+/// The pool is hosted at a resource account derived from the owner and `--seed`. The owner
+/// initially acts as both the operator and the voter of the pool.
+#[derive(Parser)]
+pub struct InitializeDelegationPool {
+    /// Percentage of rewards taken by the operator, in hundredths of a percent (e.g. 500 is 5%)
+    #[clap(long)]
+    pub(crate) operator_commission_percentage: u64,
+
+    #[clap(flatten)]
+    pub(crate) seed_args: ResourceAccountSeed,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+/// The outcome of initializing a delegation pool, with the address the pool is hosted at
+#[derive(Debug, Serialize)]
+pub struct InitializeDelegationPoolSummary {
+    pub pool_address: AccountAddress,
+    #[serde(flatten)]
+    pub transaction: TransactionOutcome<TransactionSummary>,
+}
+
+impl InitializeDelegationPool {
+    /// Returns the payload initializing the pool, and the address the pool will be hosted at
+    /// for the given owner
+    fn payload(
+        &self,
+        owner: AccountAddress,
+    ) -> CliTypedResult<(TransactionPayload, AccountAddress)> {
+        let seed = self.seed_args.seed()?;
+        let pool_address = create_delegation_pool_address(owner, &seed);
+        let payload = aptos_stdlib::delegation_pool_initialize_delegation_pool(
+            self.operator_commission_percentage,
+            seed,
+        );
+        Ok((payload, pool_address))
+    }
+}
+
+#[async_trait]
+impl CliCommand<InitializeDelegationPoolSummary> for InitializeDelegationPool {
+    fn command_name(&self) -> &'static str {
+        "InitializeDelegationPool"
+    }
+
+    async fn execute(self) -> CliTypedResult<InitializeDelegationPoolSummary> {
+        let owner = self.txn_options.sender_address()?;
+        let (payload, pool_address) = self.payload(owner)?;
+        let transaction = self
+            .txn_options
+            .submit_transaction(payload)
+            .await?
+            .map(TransactionSummary::from);
+        Ok(InitializeDelegationPoolSummary {
+            pool_address,
+            transaction,
+        })
+    }
+}
+
+/// Add APT to a delegation pool
+///
+/// A fee is charged on the added stake if it is added while the pool's stake is pending
+/// activation, which is refunded at the end of the current epoch.
+#[derive(Parser)]
+pub struct AddDelegatedStake {
+    #[clap(flatten)]
+    pub(crate) stake_args: DelegatedStakeArgs,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionOutcome<TransactionSummary>> for AddDelegatedStake {
+    fn command_name(&self) -> &'static str {
+        "AddDelegatedStake"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::delegation_pool_add_stake(
+                self.stake_args.pool_address,
+                self.stake_args.amount,
+            ))
+            .await
+            .map(|outcome| outcome.map(TransactionSummary::from))
+    }
+}
+
+/// Unlock active APT in a delegation pool
+///
+/// Unlocked stake becomes pending inactive and can be withdrawn once the lockup cycle ends.
+#[derive(Parser)]
+pub struct UnlockDelegatedStake {
+    #[clap(flatten)]
+    pub(crate) stake_args: DelegatedStakeArgs,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionOutcome<TransactionSummary>> for UnlockDelegatedStake {
+    fn command_name(&self) -> &'static str {
+        "UnlockDelegatedStake"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::delegation_pool_unlock(
+                self.stake_args.pool_address,
+                self.stake_args.amount,
+            ))
+            .await
+            .map(|outcome| outcome.map(TransactionSummary::from))
+    }
+}
+
+/// Reactivate pending inactive APT in a delegation pool
+///
+/// This moves stake that was unlocked in the current lockup cycle back to active.
+#[derive(Parser)]
+pub struct ReactivateDelegatedStake {
+    #[clap(flatten)]
+    pub(crate) stake_args: DelegatedStakeArgs,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionOutcome<TransactionSummary>> for ReactivateDelegatedStake {
+    fn command_name(&self) -> &'static str {
+        "ReactivateDelegatedStake"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::delegation_pool_reactivate_stake(
+                self.stake_args.pool_address,
+                self.stake_args.amount,
+            ))
+            .await
+            .map(|outcome| outcome.map(TransactionSummary::from))
+    }
+}
+
+/// Withdraw inactive APT from a delegation pool
+///
+/// Only stake whose lockup cycle has ended can be withdrawn. `Unlock` must be called first.
+#[derive(Parser)]
+pub struct WithdrawDelegatedStake {
+    #[clap(flatten)]
+    pub(crate) stake_args: DelegatedStakeArgs,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionOutcome<TransactionSummary>> for WithdrawDelegatedStake {
+    fn command_name(&self) -> &'static str {
+        "WithdrawDelegatedStake"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
+        self.txn_options
+            .submit_transaction(aptos_stdlib::delegation_pool_withdraw(
+                self.stake_args.pool_address,
+                self.stake_args.amount,
+            ))
+            .await
+            .map(|outcome| outcome.map(TransactionSummary::from))
+    }
+}
+
+/// Show a delegator's stake in a delegation pool
+///
+/// Balances are in Octas (10^-8 APT), split by the state of the stake.
+#[derive(Parser)]
+pub struct ShowDelegatedBalance {
+    /// Address of the delegation pool
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) pool_address: AccountAddress,
+
+    /// Address of the delegator
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) delegator_address: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A delegator's stake in a delegation pool
+#[derive(Debug, Serialize)]
+pub struct DelegatedBalance {
+    pub pool_address: AccountAddress,
+    pub delegator_address: AccountAddress,
+    pub active: u64,
+    pub inactive: u64,
+    pub pending_inactive: u64,
+}
+
+#[async_trait]
+impl CliCommand<DelegatedBalance> for ShowDelegatedBalance {
+    fn command_name(&self) -> &'static str {
+        "ShowDelegatedBalance"
+    }
+
+    async fn execute(self) -> CliTypedResult<DelegatedBalance> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let delegator_address = if let Some(address) = self.delegator_address {
+            address
+        } else {
+            self.profile_options.account_address()?
+        };
+
+        let request = ViewRequest {
+            function: EntryFunctionId::from_str("0x1::delegation_pool::get_stake")
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            type_arguments: vec![],
+            arguments: vec![
+                serde_json::Value::String(self.pool_address.to_hex_literal()),
+                serde_json::Value::String(delegator_address.to_hex_literal()),
+            ],
+        };
+        let response = client.view(&request, None).await?.into_inner();
+        parse_delegated_balance(self.pool_address, delegator_address, response)
+    }
+}
+
+/// Parses the `[active, inactive, pending_inactive]` amounts returned by
+/// `0x1::delegation_pool::get_stake`
+fn parse_delegated_balance(
+    pool_address: AccountAddress,
+    delegator_address: AccountAddress,
+    response: Vec<serde_json::Value>,
+) -> CliTypedResult<DelegatedBalance> {
+    let balances = response
+        .into_iter()
+        .map(|value| serde_json::from_value::<U64>(value).map(u64::from))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to parse delegated stake: {}", err))
+        })?;
+    if let [active, inactive, pending_inactive] = balances[..] {
+        Ok(DelegatedBalance {
+            pool_address,
+            delegator_address,
+            active,
+            inactive,
+            pending_inactive,
+        })
+    } else {
+        Err(CliError::UnexpectedError(format!(
+            "Expected 3 stake amounts from delegation pool, got {}",
+            balances.len()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::account_address::create_resource_address;
+    use serde_json::json;
+
+    #[test]
+    fn test_initialize_delegation_pool_payload() {
+        let owner = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let command = InitializeDelegationPool::parse_from([
+            "initialize-pool",
+            "--operator-commission-percentage",
+            "500",
+            "--seed",
+            "pool",
+            "--seed-encoding",
+            "utf8",
+        ]);
+        let (payload, pool_address) = command.payload(owner).unwrap();
+
+        assert_eq!(
+            payload,
+            aptos_stdlib::delegation_pool_initialize_delegation_pool(500, b"pool".to_vec())
+        );
+        // The framework prefixes the seed with the module salt before creating the resource
+        // account hosting the pool.
+        assert_eq!(
+            pool_address,
+            create_resource_address(owner, b"aptos_framework::delegation_poolpool")
+        );
+    }
+
+    #[test]
+    fn test_parse_delegated_balance() {
+        let pool_address = AccountAddress::from_hex_literal("0x1234").unwrap();
+        let delegator_address = AccountAddress::from_hex_literal("0x5678").unwrap();
+
+        let balance = parse_delegated_balance(pool_address, delegator_address, vec![
+            json!("100"),
+            json!("20"),
+            json!("3"),
+        ])
+        .unwrap();
+        assert_eq!(balance.pool_address, pool_address);
+        assert_eq!(balance.delegator_address, delegator_address);
+        assert_eq!(balance.active, 100);
+        assert_eq!(balance.inactive, 20);
+        assert_eq!(balance.pending_inactive, 3);
+
+        // The view function returns exactly three amounts.
+        assert!(
+            parse_delegated_balance(pool_address, delegator_address, vec![
+                json!("100"),
+                json!("20"),
+            ])
+            .is_err()
+        );
+        // Amounts are u64s encoded as strings.
+        assert!(
+            parse_delegated_balance(pool_address, delegator_address, vec![
+                json!("100"),
+                json!(-20),
+                json!("3"),
+            ])
+            .is_err()
+        );
+    }
+}
@@ -4,7 +4,8 @@
 use crate::{
     common::{
         types::{
-            CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
+            CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions,
+            TransactionOutcome, TransactionSummary,
         },
         utils::prompt_yes_with_override,
     },
@@ -20,12 +21,16 @@ use aptos_types::{
 use async_trait::async_trait;
 use clap::Parser;
 
+pub mod delegation;
+
 /// Tool for manipulating stake and stake pools
 ///
 #[derive(Parser)]
 pub enum StakeTool {
     AddStake(AddStake),
     CreateStakingContract(CreateStakingContract),
+    #[clap(subcommand)]
+    Delegation(delegation::DelegationTool),
     DistributeVestedCoins(DistributeVestedCoins),
     IncreaseLockup(IncreaseLockup),
     InitializeStakeOwner(InitializeStakeOwner),
@@ -43,6 +48,7 @@ impl StakeTool {
         match self {
             AddStake(tool) => tool.execute_serialized().await,
             CreateStakingContract(tool) => tool.execute_serialized().await,
+            Delegation(tool) => tool.execute().await,
             DistributeVestedCoins(tool) => tool.execute_serialized().await,
             IncreaseLockup(tool) => tool.execute_serialized().await,
             InitializeStakeOwner(tool) => tool.execute_serialized().await,
@@ -90,7 +96,7 @@ impl CliCommand<Vec<TransactionSummary>> for AddStake {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_add_stake(amount))
+                            .sign_and_submit_transaction(aptos_stdlib::stake_add_stake(amount))
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -98,7 +104,7 @@ impl CliCommand<Vec<TransactionSummary>> for AddStake {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_add_stake(
+                            .sign_and_submit_transaction(aptos_stdlib::staking_contract_add_stake(
                                 stake_pool.operator_address,
                                 amount,
                             ))
@@ -151,7 +157,7 @@ impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_unlock(amount))
+                            .sign_and_submit_transaction(aptos_stdlib::stake_unlock(amount))
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -159,10 +165,12 @@ impl CliCommand<Vec<TransactionSummary>> for UnlockStake {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_unlock_stake(
-                                stake_pool.operator_address,
-                                amount,
-                            ))
+                            .sign_and_submit_transaction(
+                                aptos_stdlib::staking_contract_unlock_stake(
+                                    stake_pool.operator_address,
+                                    amount,
+                                ),
+                            )
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -215,7 +223,7 @@ impl CliCommand<Vec<TransactionSummary>> for WithdrawStake {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.node_op_options
-                            .submit_transaction(aptos_stdlib::stake_withdraw(amount))
+                            .sign_and_submit_transaction(aptos_stdlib::stake_withdraw(amount))
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -223,7 +231,7 @@ impl CliCommand<Vec<TransactionSummary>> for WithdrawStake {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.node_op_options
-                            .submit_transaction(aptos_stdlib::staking_contract_distribute(
+                            .sign_and_submit_transaction(aptos_stdlib::staking_contract_distribute(
                                 owner_address,
                                 stake_pool.operator_address,
                             ))
@@ -272,7 +280,7 @@ impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_increase_lockup())
+                            .sign_and_submit_transaction(aptos_stdlib::stake_increase_lockup())
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -280,9 +288,11 @@ impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_reset_lockup(
-                                stake_pool.operator_address,
-                            ))
+                            .sign_and_submit_transaction(
+                                aptos_stdlib::staking_contract_reset_lockup(
+                                    stake_pool.operator_address,
+                                ),
+                            )
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -290,7 +300,7 @@ impl CliCommand<Vec<TransactionSummary>> for IncreaseLockup {
                 StakePoolType::Vesting => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_reset_lockup(
+                            .sign_and_submit_transaction(aptos_stdlib::vesting_reset_lockup(
                                 stake_pool.vesting_contract.unwrap(),
                             ))
                             .await
@@ -326,12 +336,12 @@ pub struct InitializeStakeOwner {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for InitializeStakeOwner {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for InitializeStakeOwner {
     fn command_name(&self) -> &'static str {
         "InitializeStakeOwner"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let owner_address = self.txn_options.sender_address()?;
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_initialize_stake_owner(
@@ -340,7 +350,7 @@ impl CliCommand<TransactionSummary> for InitializeStakeOwner {
                 self.voter_address.unwrap_or(owner_address),
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -381,7 +391,7 @@ impl CliCommand<Vec<TransactionSummary>> for SetOperator {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_operator(
+                            .sign_and_submit_transaction(aptos_stdlib::stake_set_operator(
                                 new_operator_address,
                             ))
                             .await
@@ -391,7 +401,7 @@ impl CliCommand<Vec<TransactionSummary>> for SetOperator {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(
+                            .sign_and_submit_transaction(
                                 aptos_stdlib::staking_contract_switch_operator_with_same_commission(
                                     stake_pool.operator_address,
                                     new_operator_address,
@@ -404,7 +414,7 @@ impl CliCommand<Vec<TransactionSummary>> for SetOperator {
                 StakePoolType::Vesting => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(
+                            .sign_and_submit_transaction(
                                 aptos_stdlib::vesting_update_operator_with_same_commission(
                                     stake_pool.vesting_contract.unwrap(),
                                     new_operator_address,
@@ -457,7 +467,7 @@ impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
                 StakePoolType::Direct => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::stake_set_delegated_voter(
+                            .sign_and_submit_transaction(aptos_stdlib::stake_set_delegated_voter(
                                 new_voter_address,
                             ))
                             .await
@@ -467,10 +477,12 @@ impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
                 StakePoolType::StakingContract => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::staking_contract_update_voter(
-                                stake_pool.operator_address,
-                                new_voter_address,
-                            ))
+                            .sign_and_submit_transaction(
+                                aptos_stdlib::staking_contract_update_voter(
+                                    stake_pool.operator_address,
+                                    new_voter_address,
+                                ),
+                            )
                             .await
                             .map(|inner| inner.into())?,
                     );
@@ -478,7 +490,7 @@ impl CliCommand<Vec<TransactionSummary>> for SetDelegatedVoter {
                 StakePoolType::Vesting => {
                     transaction_summaries.push(
                         self.txn_options
-                            .submit_transaction(aptos_stdlib::vesting_update_voter(
+                            .sign_and_submit_transaction(aptos_stdlib::vesting_update_voter(
                                 stake_pool.vesting_contract.unwrap(),
                                 new_voter_address,
                             ))
@@ -518,12 +530,12 @@ pub struct CreateStakingContract {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for CreateStakingContract {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for CreateStakingContract {
     fn command_name(&self) -> &'static str {
         "CreateStakingContract"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let pool_address = default_stake_pool_address(
             self.txn_options.profile_options.account_address()?,
             self.operator,
@@ -545,7 +557,7 @@ impl CliCommand<TransactionSummary> for CreateStakingContract {
                 vec![],
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -564,17 +576,17 @@ pub struct DistributeVestedCoins {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for DistributeVestedCoins {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for DistributeVestedCoins {
     fn command_name(&self) -> &'static str {
         "DistributeVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
         self.txn_options
             .submit_transaction(aptos_stdlib::vesting_distribute(vesting_contract_address))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -597,17 +609,17 @@ pub struct UnlockVestedCoins {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for UnlockVestedCoins {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for UnlockVestedCoins {
     fn command_name(&self) -> &'static str {
         "UnlockVestedCoins"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let vesting_contract_address = create_vesting_contract_address(self.admin_address, 0, &[]);
         self.txn_options
             .submit_transaction(aptos_stdlib::vesting_vest(vesting_contract_address))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
 
@@ -631,12 +643,12 @@ pub struct RequestCommission {
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RequestCommission {
+impl CliCommand<TransactionOutcome<TransactionSummary>> for RequestCommission {
     fn command_name(&self) -> &'static str {
         "RequestCommission"
     }
 
-    async fn execute(mut self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(mut self) -> CliTypedResult<TransactionOutcome<TransactionSummary>> {
         let client = self
             .txn_options
             .rest_options
@@ -663,6 +675,6 @@ impl CliCommand<TransactionSummary> for RequestCommission {
                 self.operator_address,
             ))
             .await
-            .map(|inner| inner.into())
+            .map(|outcome| outcome.map(TransactionSummary::from))
     }
 }
@@ -16,7 +16,8 @@ use crate::{
             CliTypedResult, EncodingOptions, EntryFunctionArguments, FaucetOptions, GasOptions,
             KeyType, MoveManifestAccountWrapper, MovePackageDir, OptionalPoolAddressArgs,
             PoolAddressArgs, PrivateKeyInputOptions, PromptOptions, PublicKeyInputOptions,
-            RestOptions, RngArgs, SaveFile, TransactionOptions, TransactionSummary,
+            RestOptions, RngArgs, SaveFile, TransactionOptions, TransactionOutcome,
+            TransactionSummary,
         },
         utils::write_to_file,
     },
@@ -301,6 +302,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn transfer_invalid_addr(
@@ -311,13 +313,13 @@ impl CliTestFramework {
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
             entry_function_args: EntryFunctionArguments {
-                function_id: MemberId {
+                function_id: Some(MemberId {
                     module_id: ModuleId::new(
                         AccountAddress::ONE,
                         Identifier::from_str("coin").unwrap(),
                     ),
                     member_id: Identifier::from_str("transfer").unwrap(),
-                },
+                }),
                 arg_vec: ArgWithTypeVec {
                     args: vec![
                         ArgWithType::from_str("address:0xdeadbeefcafebabe").unwrap(),
@@ -330,11 +332,13 @@ impl CliTestFramework {
                     IdentifierWrapper::from_str("AptosCoin").unwrap(),
                     vec![],
                 ))],
+                json_file: None,
             },
             txn_options: self.transaction_options(sender_index, gas_options),
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn show_validator_config(
@@ -397,6 +401,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn add_stake(
@@ -457,6 +462,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn leave_validator_set(
@@ -470,6 +476,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn update_validator_network_addresses(
@@ -494,6 +501,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn analyze_validator_performance(
@@ -533,6 +541,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
@@ -579,6 +588,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn create_stake_pool(
@@ -591,8 +601,9 @@ impl CliTestFramework {
     ) -> CliTypedResult<TransactionSummary> {
         RunFunction {
             entry_function_args: EntryFunctionArguments {
-                function_id: MemberId::from_str("0x1::staking_contract::create_staking_contract")
-                    .unwrap(),
+                function_id: Some(
+                    MemberId::from_str("0x1::staking_contract::create_staking_contract").unwrap(),
+                ),
                 arg_vec: ArgWithTypeVec {
                     args: vec![
                         ArgWithType::address(self.account_id(operator_index)),
@@ -603,11 +614,13 @@ impl CliTestFramework {
                     ],
                 },
                 type_args: vec![],
+                json_file: None,
             },
             txn_options: self.transaction_options(owner_index, None),
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn set_operator(
@@ -869,6 +882,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn download_package(
@@ -915,13 +929,15 @@ impl CliTestFramework {
         RunFunction {
             txn_options: self.transaction_options(index, gas_options),
             entry_function_args: EntryFunctionArguments {
-                function_id,
+                function_id: Some(function_id),
                 arg_vec: ArgWithTypeVec { args: parsed_args },
                 type_args: parsed_type_args,
+                json_file: None,
             },
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     /// Runs the given script contents using the local aptos_framework directory.
@@ -985,6 +1001,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     pub async fn run_script_with_script_path(
@@ -1011,6 +1028,7 @@ impl CliTestFramework {
         }
         .execute()
         .await
+        .and_then(TransactionOutcome::into_submitted)
     }
 
     fn aptos_framework_dir() -> PathBuf {
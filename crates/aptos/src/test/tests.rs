@@ -42,6 +42,7 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "governance", "execute-proposal", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "generate-upgrade-proposal", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "propose", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "governance", "simulate-proposal", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "vote", "--help"]).await;
 
     assert_cmd_not_panic(&["aptos", "info"]).await;
@@ -53,6 +54,7 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "key", "extract-peer", "--help"]).await;
 
     assert_cmd_not_panic(&["aptos", "move"]).await;
+    assert_cmd_not_panic(&["aptos", "move", "check-upgrade", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "clean", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "compile", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "compile-script", "--help"]).await;
@@ -61,6 +63,7 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "move", "list", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "prove", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "publish", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "move", "replay", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "run", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "run-script", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "test", "--help"]).await;
@@ -90,12 +93,23 @@ async fn ensure_every_command_args_work() {
 
     assert_cmd_not_panic(&["aptos", "stake"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "add-stake", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "add-stake", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "initialize-pool", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "reactivate-stake", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "show-balance", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "unlock", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "delegation", "withdraw", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "increase-lockup", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "initialize-stake-owner", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "set-delegated-voter", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "set-operator", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "unlock-stake", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "withdraw-stake", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "transaction"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "sign", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "submit", "--help"]).await;
 }
 
 /// Ensure we can parse URLs for args
@@ -0,0 +1,553 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions, PrivateKeyInputOptions,
+        ProfileOptions, PromptOptions, RestOptions, TransactionSummary,
+    },
+    utils::{prompt_yes_with_override, read_from_file, write_to_file},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    PrivateKey, SigningKey,
+};
+use aptos_rest_client::aptos_api_types::HashValue;
+use aptos_types::transaction::{
+    authenticator::{AccountAuthenticator, TransactionAuthenticator},
+    RawTransaction, RawTransactionWithData, SignedTransaction,
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Tool for signing and submitting transactions offline
+///
+/// Any transaction command can write its transaction to a file with `--output-unsigned` instead
+/// of signing and submitting it.  The file can then be signed on another machine, possibly by
+/// several parties, and submitted once all signatures are collected.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// A transaction waiting for signatures, as stored in the file written by `--output-unsigned`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OfflineTransaction {
+    pub raw_txn: RawTransaction,
+    /// Additional signers of a multi-agent transaction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary_signer_addresses: Vec<AccountAddress>,
+    /// Account paying for the gas of the transaction instead of the sender
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_payer_address: Option<AccountAddress>,
+    /// Public keys of the signers which are K-of-N multisig accounts
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub multisig_public_keys: BTreeMap<AccountAddress, MultiEd25519PublicKey>,
+    /// Signatures collected so far
+    #[serde(default)]
+    pub signatures: Vec<OfflineSignature>,
+}
+
+/// A signature of an `OfflineTransaction` on behalf of one of its signers
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OfflineSignature {
+    pub signer: AccountAddress,
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+impl OfflineTransaction {
+    pub fn new(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: Option<AccountAddress>,
+    ) -> Self {
+        OfflineTransaction {
+            raw_txn,
+            secondary_signer_addresses,
+            fee_payer_address,
+            multisig_public_keys: BTreeMap::new(),
+            signatures: vec![],
+        }
+    }
+
+    pub fn load(path: &Path) -> CliTypedResult<Self> {
+        serde_json::from_slice(&read_from_file(path)?)
+            .map_err(|err| CliError::UnableToParse("transaction file", err.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> CliTypedResult<()> {
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(path, "transaction file", &bytes)
+    }
+
+    /// Accounts which have to sign the transaction: the sender, the secondary signers and then
+    /// the fee payer
+    pub fn signers(&self) -> Vec<AccountAddress> {
+        std::iter::once(self.raw_txn.sender())
+            .chain(self.secondary_signer_addresses.iter().copied())
+            .chain(self.fee_payer_address)
+            .collect()
+    }
+
+    /// Accounts which don't have enough signatures yet
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        self.signers()
+            .into_iter()
+            .filter(|signer| !matches!(self.account_authenticator(*signer), Ok(Some(_))))
+            .collect()
+    }
+
+    /// Records that `signer` is a multisig account with the given public key
+    pub fn set_multisig_public_key(
+        &mut self,
+        signer: AccountAddress,
+        public_key: MultiEd25519PublicKey,
+    ) -> CliTypedResult<()> {
+        match self.multisig_public_keys.get(&signer) {
+            Some(existing) if existing != &public_key => {
+                Err(CliError::CommandArgumentError(format!(
+                    "A different multisig public key was already recorded for {}",
+                    signer
+                )))
+            },
+            _ => {
+                self.multisig_public_keys.insert(signer, public_key);
+                Ok(())
+            },
+        }
+    }
+
+    /// Signs the transaction on behalf of `signer`, replacing any previous signature by the same key
+    pub fn sign(
+        &mut self,
+        signer: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<()> {
+        if !self.signers().contains(&signer) {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} is not a signer of this transaction, expected one of {:?}",
+                signer,
+                self.signers()
+            )));
+        }
+
+        let public_key = private_key.public_key();
+        if let Some(multisig_public_key) = self.multisig_public_keys.get(&signer) {
+            if !multisig_public_key.public_keys().contains(&public_key) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "The private key is not one of the keys of multisig account {}",
+                    signer
+                )));
+            }
+        }
+
+        let signature = self.sign_message(private_key)?;
+        self.signatures.retain(|existing| {
+            existing.signer != signer
+                || (self.multisig_public_keys.contains_key(&signer)
+                    && existing.public_key != public_key)
+        });
+        self.signatures.push(OfflineSignature {
+            signer,
+            public_key,
+            signature,
+        });
+        Ok(())
+    }
+
+    /// Combines the collected signatures into a `SignedTransaction`
+    pub fn into_signed_transaction(self) -> CliTypedResult<SignedTransaction> {
+        let missing_signers = self.missing_signers();
+        if !missing_signers.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "The transaction is missing signatures from {:?}",
+                missing_signers
+            )));
+        }
+
+        let mut authenticators = Vec::new();
+        for signer in self.signers() {
+            authenticators.push(
+                self.account_authenticator(signer)?
+                    .expect("Missing signers are checked above"),
+            );
+        }
+        let mut authenticators = authenticators.into_iter();
+        let sender = authenticators
+            .next()
+            .expect("The sender is always a signer");
+        let secondary_signers: Vec<_> = authenticators
+            .by_ref()
+            .take(self.secondary_signer_addresses.len())
+            .collect();
+
+        let signed_txn = if let Some(fee_payer_address) = self.fee_payer_address {
+            SignedTransaction::new_fee_payer(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                authenticators.next().expect("The fee payer is a signer"),
+            )
+        } else if !self.secondary_signer_addresses.is_empty() {
+            SignedTransaction::new_multi_agent(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+            )
+        } else {
+            let authenticator = match sender {
+                AccountAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => TransactionAuthenticator::ed25519(public_key, signature),
+                AccountAuthenticator::MultiEd25519 {
+                    public_key,
+                    signature,
+                } => TransactionAuthenticator::multi_ed25519(public_key, signature),
+                authenticator => {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Unsupported authenticator scheme {}",
+                        authenticator.scheme()
+                    )))
+                },
+            };
+            SignedTransaction::new_with_authenticator(self.raw_txn, authenticator)
+        };
+
+        signed_txn
+            .clone()
+            .check_signature()
+            .map_err(|err| CliError::UnexpectedError(format!("Invalid signature: {}", err)))?;
+        Ok(signed_txn)
+    }
+
+    pub fn summary(&self, transaction_file: &Path) -> OfflineTransactionSummary {
+        let missing_signers = self.missing_signers();
+        let transaction_hash = if missing_signers.is_empty() {
+            self.clone()
+                .into_signed_transaction()
+                .ok()
+                .map(|signed_txn| signed_txn.committed_hash().into())
+        } else {
+            None
+        };
+
+        OfflineTransactionSummary {
+            transaction_file: transaction_file.display().to_string(),
+            sender: self.raw_txn.sender(),
+            signers: self.signers(),
+            missing_signers,
+            transaction_hash,
+        }
+    }
+
+    /// Signs the message that all signers of the transaction have to sign
+    fn sign_message(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<Ed25519Signature> {
+        let signature = match self.fee_payer_address {
+            Some(fee_payer_address) => {
+                private_key.sign(&RawTransactionWithData::new_multi_agent_with_fee_payer(
+                    self.raw_txn.clone(),
+                    self.secondary_signer_addresses.clone(),
+                    fee_payer_address,
+                ))?
+            },
+            None if !self.secondary_signer_addresses.is_empty() => {
+                private_key.sign(&RawTransactionWithData::new_multi_agent(
+                    self.raw_txn.clone(),
+                    self.secondary_signer_addresses.clone(),
+                ))?
+            },
+            None => private_key.sign(&self.raw_txn)?,
+        };
+        Ok(signature)
+    }
+
+    /// Builds the authenticator of `signer`, or `None` if there aren't enough signatures yet
+    fn account_authenticator(
+        &self,
+        signer: AccountAddress,
+    ) -> CliTypedResult<Option<AccountAuthenticator>> {
+        let mut signatures = self
+            .signatures
+            .iter()
+            .filter(|signature| signature.signer == signer);
+
+        if let Some(multisig_public_key) = self.multisig_public_keys.get(&signer) {
+            let indexed_signatures: Vec<_> = signatures
+                .filter_map(|signature| {
+                    multisig_public_key
+                        .public_keys()
+                        .iter()
+                        .position(|public_key| public_key == &signature.public_key)
+                        .map(|index| (signature.signature.clone(), index as u8))
+                })
+                .take(*multisig_public_key.threshold() as usize)
+                .collect();
+            if indexed_signatures.len() < *multisig_public_key.threshold() as usize {
+                return Ok(None);
+            }
+            Ok(Some(AccountAuthenticator::multi_ed25519(
+                multisig_public_key.clone(),
+                MultiEd25519Signature::new(indexed_signatures)?,
+            )))
+        } else {
+            Ok(signatures.next().map(|signature| {
+                AccountAuthenticator::ed25519(
+                    signature.public_key.clone(),
+                    signature.signature.clone(),
+                )
+            }))
+        }
+    }
+}
+
+/// Signature status of a transaction file
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OfflineTransactionSummary {
+    pub transaction_file: String,
+    pub sender: AccountAddress,
+    pub signers: Vec<AccountAddress>,
+    pub missing_signers: Vec<AccountAddress>,
+    /// Hash of the transaction, once all signatures are collected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HashValue>,
+}
+
+/// Sign a transaction file written by `--output-unsigned`
+///
+/// The signature is added to the file, so signatures from several parties can be collected
+/// one after the other for multi-agent transactions, fee payer transactions and transactions
+/// from K-of-N multisig accounts.  No network access is needed.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// File containing the transaction to sign
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+
+    /// File to write the signed transaction to
+    ///
+    /// Defaults to updating `--transaction-file` in place
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: Option<PathBuf>,
+
+    /// Account to sign for
+    ///
+    /// Defaults to the account of the profile, or the address derived from the private key.
+    /// This has to be set when signing for a multisig account.
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) signer_address: Option<AccountAddress>,
+
+    /// File containing the MultiEd25519 public key of `--signer-address`
+    ///
+    /// Only needed when signing for a K-of-N multisig account, and only by the first signer of
+    /// that account.  Encoded with type from `--encoding`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_public_key_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<OfflineTransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<OfflineTransactionSummary> {
+        let mut txn = OfflineTransaction::load(&self.transaction_file)?;
+        let (private_key, signer) = self.private_key_options.extract_private_key_and_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.signer_address,
+        )?;
+        if let Some(ref file) = self.multisig_public_key_file {
+            let public_key = self
+                .encoding_options
+                .encoding
+                .load_key("--multisig-public-key-file", file.as_path())?;
+            txn.set_multisig_public_key(signer, public_key)?;
+        }
+
+        eprintln!(
+            "{}",
+            txn.raw_txn.format_for_client(|_| "script".to_string())
+        );
+        prompt_yes_with_override(
+            &format!("Do you want to sign the transaction above as {}?", signer),
+            self.prompt_options,
+        )?;
+        txn.sign(signer, &private_key)?;
+
+        let output_file = self.output_file.unwrap_or(self.transaction_file);
+        txn.save(&output_file)?;
+        Ok(txn.summary(&output_file))
+    }
+}
+
+/// Submit a transaction file once all of its signatures are collected
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// File containing the signed transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let signed_txn =
+            OfflineTransaction::load(&self.transaction_file)?.into_signed_transaction()?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        client
+            .submit_and_wait(&signed_txn)
+            .await
+            .map(|response| TransactionSummary::from(response.into_inner()))
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_keygen::KeyGen;
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{EntryFunction, TransactionPayload},
+    };
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+    fn raw_txn(sender: AccountAddress) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap()),
+                Identifier::new("transfer").unwrap(),
+                vec![],
+                vec![],
+            )),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    fn random_key() -> Ed25519PrivateKey {
+        KeyGen::from_os_rng().generate_ed25519_private_key()
+    }
+
+    #[test]
+    fn test_multi_agent_signatures() {
+        let sender = AccountAddress::random();
+        let secondary = AccountAddress::random();
+        let mut txn = OfflineTransaction::new(raw_txn(sender), vec![secondary], None);
+        assert_eq!(txn.missing_signers(), vec![sender, secondary]);
+        assert!(txn.sign(AccountAddress::random(), &random_key()).is_err());
+
+        txn.sign(sender, &random_key()).unwrap();
+        assert_eq!(txn.missing_signers(), vec![secondary]);
+        assert!(txn.clone().into_signed_transaction().is_err());
+
+        // The file format round trips
+        let txn: OfflineTransaction =
+            serde_json::from_slice(&serde_json::to_vec(&txn).unwrap()).unwrap();
+        let mut txn = txn;
+        txn.sign(secondary, &random_key()).unwrap();
+        assert!(txn.missing_signers().is_empty());
+        assert!(matches!(
+            txn.into_signed_transaction().unwrap().authenticator(),
+            TransactionAuthenticator::MultiAgent { .. }
+        ));
+    }
+
+    #[test]
+    fn test_fee_payer_signatures() {
+        let sender = AccountAddress::random();
+        let secondary = AccountAddress::random();
+        let fee_payer = AccountAddress::random();
+        let mut txn = OfflineTransaction::new(raw_txn(sender), vec![secondary], Some(fee_payer));
+        assert_eq!(txn.signers(), vec![sender, secondary, fee_payer]);
+
+        txn.sign(fee_payer, &random_key()).unwrap();
+        txn.sign(sender, &random_key()).unwrap();
+        assert_eq!(txn.missing_signers(), vec![secondary]);
+        txn.sign(secondary, &random_key()).unwrap();
+
+        // All signers sign the transaction along with the fee payer address, which is checked
+        // when combining the signatures
+        let signed_txn = txn.into_signed_transaction().unwrap();
+        match signed_txn.authenticator() {
+            TransactionAuthenticator::FeePayer {
+                secondary_signer_addresses,
+                fee_payer_address,
+                ..
+            } => {
+                assert_eq!(secondary_signer_addresses, vec![secondary]);
+                assert_eq!(fee_payer_address, fee_payer);
+            },
+            authenticator => panic!("Unexpected authenticator {:?}", authenticator),
+        }
+    }
+
+    #[test]
+    fn test_multisig_signatures() {
+        let keys: Vec<_> = (0..3).map(|_| random_key()).collect();
+        let multisig_public_key =
+            MultiEd25519PublicKey::new(keys.iter().map(|key| key.public_key()).collect(), 2)
+                .unwrap();
+        let sender = AccountAddress::random();
+        let mut txn = OfflineTransaction::new(raw_txn(sender), vec![], None);
+        txn.set_multisig_public_key(sender, multisig_public_key)
+            .unwrap();
+        assert!(txn.sign(sender, &random_key()).is_err());
+
+        txn.sign(sender, &keys[2]).unwrap();
+        txn.sign(sender, &keys[2]).unwrap();
+        assert_eq!(txn.missing_signers(), vec![sender]);
+        txn.sign(sender, &keys[0]).unwrap();
+        assert!(txn.missing_signers().is_empty());
+        assert!(matches!(
+            txn.into_signed_transaction().unwrap().authenticator(),
+            TransactionAuthenticator::MultiEd25519 { .. }
+        ));
+    }
+}
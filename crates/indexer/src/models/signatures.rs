@@ -6,9 +6,10 @@ use crate::{models::transactions::Transaction, schema::signatures, util::standar
 use anyhow::{Context, Result};
 use aptos_api_types::{
     AccountSignature as APIAccountSignature, Ed25519Signature as APIEd25519Signature,
-    MultiAgentSignature as APIMultiAgentSignature,
+    FeePayerSignature as APIFeePayerSignature, MultiAgentSignature as APIMultiAgentSignature,
     MultiEd25519Signature as APIMultiEd25519Signature,
-    TransactionSignature as APITransactionSignature,
+    Secp256k1EcdsaSignature as APISecp256k1EcdsaSignature,
+    TransactionSignature as APITransactionSignature, WebAuthnSignature as APIWebAuthnSignature,
 };
 use aptos_bitvec::BitVec;
 use field_count::FieldCount;
@@ -74,6 +75,34 @@ impl Signature {
                 transaction_version,
                 transaction_block_height,
             ),
+            APITransactionSignature::FeePayerSignature(sig) => Self::parse_fee_payer_signature(
+                sig,
+                sender,
+                transaction_version,
+                transaction_block_height,
+            ),
+            APITransactionSignature::Secp256k1EcdsaSignature(sig) => {
+                Ok(vec![Self::parse_secp256k1_ecdsa_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    true,
+                    0,
+                    None,
+                )])
+            },
+            APITransactionSignature::WebAuthnSignature(sig) => {
+                Ok(vec![Self::parse_webauthn_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    true,
+                    0,
+                    None,
+                )])
+            },
         }
     }
 
@@ -86,6 +115,11 @@ impl Signature {
             APITransactionSignature::MultiAgentSignature(_) => {
                 String::from("multi_agent_signature")
             },
+            APITransactionSignature::FeePayerSignature(_) => String::from("fee_payer_signature"),
+            APITransactionSignature::Secp256k1EcdsaSignature(_) => {
+                String::from("secp256k1_ecdsa_signature")
+            },
+            APITransactionSignature::WebAuthnSignature(_) => String::from("webauthn_signature"),
         }
     }
 
@@ -114,6 +148,57 @@ impl Signature {
         }
     }
 
+    fn parse_secp256k1_ecdsa_signature(
+        s: &APISecp256k1EcdsaSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+        is_sender_primary: bool,
+        multi_agent_index: i64,
+        override_address: Option<&String>,
+    ) -> Self {
+        let signer = standardize_address(override_address.unwrap_or(sender));
+        Self {
+            transaction_version,
+            transaction_block_height,
+            signer,
+            is_sender_primary,
+            type_: String::from("secp256k1_ecdsa_signature"),
+            public_key: s.public_key.to_string(),
+            threshold: 1,
+            public_key_indices: serde_json::Value::Array(vec![]),
+            signature: s.signature.to_string(),
+            multi_agent_index,
+            multi_sig_index: 0,
+        }
+    }
+
+    /// Only the signature of the assertion is kept, not the authenticator and client data.
+    fn parse_webauthn_signature(
+        s: &APIWebAuthnSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+        is_sender_primary: bool,
+        multi_agent_index: i64,
+        override_address: Option<&String>,
+    ) -> Self {
+        let signer = standardize_address(override_address.unwrap_or(sender));
+        Self {
+            transaction_version,
+            transaction_block_height,
+            signer,
+            is_sender_primary,
+            type_: String::from("webauthn_signature"),
+            public_key: s.public_key.to_string(),
+            threshold: 1,
+            public_key_indices: serde_json::Value::Array(vec![]),
+            signature: s.signature.to_string(),
+            multi_agent_index,
+            multi_sig_index: 0,
+        }
+    }
+
     fn parse_multi_signature(
         s: &APIMultiEd25519Signature,
         sender: &String,
@@ -192,6 +277,35 @@ impl Signature {
         Ok(signatures)
     }
 
+    /// The fee payer signature follows the secondary signatures, with the next multi agent index.
+    fn parse_fee_payer_signature(
+        s: &APIFeePayerSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+    ) -> Result<Vec<Self>> {
+        let mut signatures = Self::parse_multi_agent_signature(
+            &APIMultiAgentSignature {
+                sender: s.sender.clone(),
+                secondary_signer_addresses: s.secondary_signer_addresses.clone(),
+                secondary_signers: s.secondary_signers.clone(),
+            },
+            sender,
+            transaction_version,
+            transaction_block_height,
+        )?;
+        signatures.append(&mut Self::parse_multi_agent_signature_helper(
+            &s.fee_payer_signer,
+            sender,
+            transaction_version,
+            transaction_block_height,
+            false,
+            s.secondary_signer_addresses.len() as i64,
+            Some(&s.fee_payer_address.to_string()),
+        ));
+        Ok(signatures)
+    }
+
     fn parse_multi_agent_signature_helper(
         s: &APIAccountSignature,
         sender: &String,
@@ -220,6 +334,26 @@ impl Signature {
                 multi_agent_index,
                 override_address,
             ),
+            APIAccountSignature::Secp256k1EcdsaSignature(sig) => {
+                vec![Self::parse_secp256k1_ecdsa_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    is_sender_primary,
+                    multi_agent_index,
+                    override_address,
+                )]
+            },
+            APIAccountSignature::WebAuthnSignature(sig) => vec![Self::parse_webauthn_signature(
+                sig,
+                sender,
+                transaction_version,
+                transaction_block_height,
+                is_sender_primary,
+                multi_agent_index,
+                override_address,
+            )],
         }
     }
 }
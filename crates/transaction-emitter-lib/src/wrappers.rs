@@ -10,7 +10,7 @@ use crate::{
 use anyhow::{bail, Context, Result};
 use aptos_logger::{error, info};
 use aptos_sdk::transaction_builder::TransactionFactory;
-use aptos_transaction_generator_lib::TransactionType;
+use aptos_transaction_generator_lib::args::transaction_mix_per_phase;
 use rand::{rngs::StdRng, SeedableRng};
 use std::time::{Duration, Instant};
 
@@ -84,52 +84,11 @@ pub async fn emit_transactions_with_cluster(
         StdRng::from_entropy(),
     );
 
-    let arg_transaction_types = args
-        .transaction_type
-        .iter()
-        .map(|t| t.materialize())
-        .collect::<Vec<_>>();
-
-    let arg_transaction_weights = if args.transaction_weights.is_empty() {
-        vec![1; arg_transaction_types.len()]
-    } else {
-        assert_eq!(
-            args.transaction_weights.len(),
-            arg_transaction_types.len(),
-            "Transaction types and weights need to be the same length"
-        );
-        args.transaction_weights.clone()
-    };
-    let arg_transaction_phases = if args.transaction_phases.is_empty() {
-        vec![0; arg_transaction_types.len()]
-    } else {
-        assert_eq!(
-            args.transaction_phases.len(),
-            arg_transaction_types.len(),
-            "Transaction types and phases need to be the same length"
-        );
-        args.transaction_phases.clone()
-    };
-
-    let mut transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>> = Vec::new();
-    for (transaction_type, (weight, phase)) in arg_transaction_types.into_iter().zip(
-        arg_transaction_weights
-            .into_iter()
-            .zip(arg_transaction_phases.into_iter()),
-    ) {
-        assert!(
-            phase <= transaction_mix_per_phase.len(),
-            "cannot skip phases ({})",
-            transaction_mix_per_phase.len()
-        );
-        if phase == transaction_mix_per_phase.len() {
-            transaction_mix_per_phase.push(Vec::new());
-        }
-        transaction_mix_per_phase
-            .get_mut(phase)
-            .unwrap()
-            .push((transaction_type, weight));
-    }
+    let transaction_mix_per_phase = transaction_mix_per_phase(
+        &args.transaction_type,
+        &args.transaction_weights,
+        &args.transaction_phases,
+    );
 
     let mut emit_job_request =
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
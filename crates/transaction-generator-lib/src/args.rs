@@ -142,3 +142,50 @@ impl TransactionTypeArg {
         }
     }
 }
+
+/// Groups the transaction types given on the command line into the transaction mix of each
+/// phase. The weights default to 1 and the phases to 0, i.e. a single phase in which all types
+/// are equally likely.
+pub fn transaction_mix_per_phase(
+    transaction_types: &[TransactionTypeArg],
+    transaction_weights: &[usize],
+    transaction_phases: &[usize],
+) -> Vec<Vec<(TransactionType, usize)>> {
+    let transaction_weights = if transaction_weights.is_empty() {
+        vec![1; transaction_types.len()]
+    } else {
+        assert_eq!(
+            transaction_weights.len(),
+            transaction_types.len(),
+            "Transaction types and weights need to be the same length"
+        );
+        transaction_weights.to_vec()
+    };
+    let transaction_phases = if transaction_phases.is_empty() {
+        vec![0; transaction_types.len()]
+    } else {
+        assert_eq!(
+            transaction_phases.len(),
+            transaction_types.len(),
+            "Transaction types and phases need to be the same length"
+        );
+        transaction_phases.to_vec()
+    };
+
+    let mut transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>> = Vec::new();
+    for (transaction_type, (weight, phase)) in transaction_types
+        .iter()
+        .zip(transaction_weights.into_iter().zip(transaction_phases))
+    {
+        assert!(
+            phase <= transaction_mix_per_phase.len(),
+            "cannot skip phases ({})",
+            transaction_mix_per_phase.len()
+        );
+        if phase == transaction_mix_per_phase.len() {
+            transaction_mix_per_phase.push(Vec::new());
+        }
+        transaction_mix_per_phase[phase].push((transaction_type.materialize(), weight));
+    }
+    transaction_mix_per_phase
+}
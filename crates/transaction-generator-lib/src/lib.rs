@@ -12,6 +12,7 @@ use aptos_sdk::{
     types::{transaction::SignedTransaction, LocalAccount},
 };
 use async_trait::async_trait;
+use serde::Serialize;
 use std::{
     collections::HashMap,
     sync::{
@@ -46,7 +47,7 @@ pub use publishing::module_simple::EntryPoints;
 
 pub const SEND_AMOUNT: u64 = 1;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize)]
 pub enum TransactionType {
     CoinTransfer {
         invalid_transaction_ratio: usize,
@@ -18,6 +18,7 @@ use move_binary_format::{
 };
 use rand::{distributions::Alphanumeric, prelude::StdRng, seq::SliceRandom, Rng};
 use rand_core::RngCore;
+use serde::Serialize;
 
 //
 // Contains all the code to work on the Simple package
@@ -101,7 +102,7 @@ pub fn scramble(module: &mut CompiledModule, fn_count: usize, rng: &mut StdRng)
 // List of entry points to expose
 //
 // More info in the Simple.move
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize)]
 pub enum EntryPoints {
     // 0 args
     /// Empty (NoOp) function
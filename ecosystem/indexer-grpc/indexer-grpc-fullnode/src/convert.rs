@@ -6,8 +6,9 @@ use aptos_api_types::{
     EntryFunctionPayload, Event, GenesisPayload, MoveAbility, MoveFunction,
     MoveFunctionGenericTypeParam, MoveFunctionVisibility, MoveModule, MoveModuleBytecode,
     MoveModuleId, MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType,
-    MultiEd25519Signature, MultisigPayload, MultisigTransactionPayload, ScriptPayload, Transaction,
-    TransactionInfo, TransactionPayload, TransactionSignature, WriteSet, WriteSetChange,
+    MultiEd25519Signature, MultisigPayload, MultisigTransactionPayload, ScriptPayload,
+    Secp256k1EcdsaSignature, Transaction, TransactionInfo, TransactionPayload,
+    TransactionSignature, WebAuthnSignature, WriteSet, WriteSetChange,
 };
 use aptos_bitvec::BitVec;
 use aptos_logger::warn;
@@ -541,6 +542,25 @@ pub fn convert_ed25519_signature(sig: &Ed25519Signature) -> transaction::Ed25519
     }
 }
 
+// The protos don't have a secp256k1 ECDSA signature yet, so it's represented as an Ed25519
+// signature of the same shape, with an unspecified signature type.
+pub fn convert_secp256k1_ecdsa_signature(
+    sig: &Secp256k1EcdsaSignature,
+) -> transaction::Ed25519Signature {
+    transaction::Ed25519Signature {
+        public_key: sig.public_key.0.clone(),
+        signature: sig.signature.0.clone(),
+    }
+}
+
+// Likewise for WebAuthn signatures, of which only the public key and signature are kept.
+pub fn convert_webauthn_signature(sig: &WebAuthnSignature) -> transaction::Ed25519Signature {
+    transaction::Ed25519Signature {
+        public_key: sig.public_key.0.clone(),
+        signature: sig.signature.0.clone(),
+    }
+}
+
 pub fn convert_multi_ed25519_signature(
     sig: &MultiEd25519Signature,
 ) -> transaction::MultiEd25519Signature {
@@ -564,6 +584,9 @@ pub fn convert_account_signature(
         AccountSignature::MultiEd25519Signature(_) => {
             transaction::account_signature::Type::MultiEd25519
         },
+        AccountSignature::Secp256k1EcdsaSignature(_) | AccountSignature::WebAuthnSignature(_) => {
+            transaction::account_signature::Type::Unspecified
+        },
     };
     let signature = match account_signature {
         AccountSignature::Ed25519Signature(s) => {
@@ -574,6 +597,12 @@ pub fn convert_account_signature(
                 convert_multi_ed25519_signature(s),
             )
         },
+        AccountSignature::Secp256k1EcdsaSignature(s) => {
+            transaction::account_signature::Signature::Ed25519(convert_secp256k1_ecdsa_signature(s))
+        },
+        AccountSignature::WebAuthnSignature(s) => {
+            transaction::account_signature::Signature::Ed25519(convert_webauthn_signature(s))
+        },
     };
     transaction::AccountSignature {
         r#type: r#type as i32,
@@ -593,7 +622,10 @@ pub fn convert_transaction_signature(
         TransactionSignature::MultiEd25519Signature(_) => {
             transaction::signature::Type::MultiEd25519
         },
-        TransactionSignature::MultiAgentSignature(_) => transaction::signature::Type::MultiAgent,
+        TransactionSignature::MultiAgentSignature(_)
+        | TransactionSignature::FeePayerSignature(_) => transaction::signature::Type::MultiAgent,
+        TransactionSignature::Secp256k1EcdsaSignature(_)
+        | TransactionSignature::WebAuthnSignature(_) => transaction::signature::Type::Unspecified,
     };
 
     let signature = match signature {
@@ -618,6 +650,31 @@ pub fn convert_transaction_signature(
                     .collect(),
             })
         },
+        // The protos don't have a fee payer signature yet, so the fee payer is represented as the
+        // last secondary signer of a multi agent signature.
+        TransactionSignature::FeePayerSignature(s) => {
+            transaction::signature::Signature::MultiAgent(transaction::MultiAgentSignature {
+                sender: Some(convert_account_signature(&s.sender)),
+                secondary_signer_addresses: s
+                    .secondary_signer_addresses
+                    .iter()
+                    .chain(std::iter::once(&s.fee_payer_address))
+                    .map(|s| s.to_string())
+                    .collect(),
+                secondary_signers: s
+                    .secondary_signers
+                    .iter()
+                    .chain(std::iter::once(&s.fee_payer_signer))
+                    .map(convert_account_signature)
+                    .collect(),
+            })
+        },
+        TransactionSignature::Secp256k1EcdsaSignature(s) => {
+            transaction::signature::Signature::Ed25519(convert_secp256k1_ecdsa_signature(s))
+        },
+        TransactionSignature::WebAuthnSignature(s) => {
+            transaction::signature::Signature::Ed25519(convert_webauthn_signature(s))
+        },
     };
 
     Some(transaction::Signature {
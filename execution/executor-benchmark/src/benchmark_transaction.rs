@@ -82,4 +82,18 @@ impl TransactionBlockExecutor<BenchmarkTransaction> for AptosVM {
             state_view,
         )
     }
+
+    fn execute_transaction_block_sequential(
+        transactions: Vec<Transaction>,
+        state_view: CachedStateView,
+    ) -> Result<ChunkOutput> {
+        <AptosVM as TransactionBlockExecutor<Transaction>>::execute_transaction_block_sequential(
+            transactions,
+            state_view,
+        )
+    }
+
+    fn invalidate_cross_block_cache() {
+        <AptosVM as TransactionBlockExecutor<Transaction>>::invalidate_cross_block_cache()
+    }
 }
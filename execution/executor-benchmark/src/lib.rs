@@ -19,9 +19,15 @@ use crate::{
     transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
 };
-use aptos_config::config::{NodeConfig, PrunerConfig};
+use aptos_config::config::{NodeConfig, PrunerConfig, StorageDirPaths};
 use aptos_db::AptosDB;
-use aptos_executor::block_executor::{BlockExecutor, TransactionBlockExecutor};
+use aptos_executor::{
+    block_executor::{BlockExecutor, TransactionBlockExecutor},
+    metrics::{
+        APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
+        APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
+    },
+};
 use aptos_jellyfish_merkle::metrics::{
     APTOS_JELLYFISH_INTERNAL_ENCODED_BYTES, APTOS_JELLYFISH_LEAF_ENCODED_BYTES,
 };
@@ -33,6 +39,7 @@ use aptos_transaction_generator_lib::{
 use aptos_vm::counters::TXN_GAS_USAGE;
 use gen_executor::DbGenInitTransactionExecutor;
 use pipeline::PipelineConfig;
+use serde::Serialize;
 use std::{
     fs,
     path::Path,
@@ -76,15 +83,51 @@ fn create_checkpoint(
     }
     std::fs::create_dir_all(checkpoint_dir.as_ref()).unwrap();
 
-    AptosDB::create_checkpoint(source_dir, checkpoint_dir, use_sharded_state_merkle_db)
-        .expect("db checkpoint creation fails.");
+    AptosDB::create_checkpoint(
+        &StorageDirPaths::from_path(source_dir),
+        &StorageDirPaths::from_path(checkpoint_dir),
+        use_sharded_state_merkle_db,
+    )
+    .expect("db checkpoint creation fails.");
+}
+
+/// The workload of a benchmark run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Workload {
+    /// Raw coin transfers, generated without the transaction generator.
+    RawTransfer,
+    /// Transactions generated by the transaction generator, from the list of transaction types
+    /// with their weights of each phase.
+    TransactionMix(Vec<Vec<(TransactionType, usize)>>),
+}
+
+/// The result of a benchmark run.
+#[derive(Debug, Serialize)]
+pub struct BenchmarkResult {
+    pub workload: Workload,
+    pub block_size: usize,
+    pub num_blocks: usize,
+    pub num_txns: u64,
+    pub elapsed_secs: f64,
+    /// Overall throughput, in transactions and gas per second.
+    pub tps: f64,
+    pub gps: f64,
+    /// Throughput over the time spent in the VM, in the executor (including the VM), and in
+    /// committing the blocks respectively. Not set if no time was spent in the stage.
+    pub vm_tps: Option<f64>,
+    pub execution_tps: Option<f64>,
+    pub commit_tps: Option<f64>,
 }
 
-/// Runs the benchmark with given parameters.
+/// Runs the benchmark with given parameters. The transactions are generated by the
+/// transaction generator according to `transaction_mix_per_phase` (a list of transaction types
+/// with their weights for each phase, the blocks being split evenly between the phases), or are
+/// raw coin transfers if not set.
 pub fn run_benchmark<V>(
     block_size: usize,
     num_blocks: usize,
-    transaction_type: Option<TransactionType>,
+    transaction_mix_per_phase: Option<Vec<Vec<(TransactionType, usize)>>>,
     transactions_per_sender: usize,
     num_main_signer_accounts: usize,
     num_additional_dst_pool_accounts: usize,
@@ -95,7 +138,8 @@ pub fn run_benchmark<V>(
     use_state_kv_db: bool,
     use_sharded_state_merkle_db: bool,
     pipeline_config: PipelineConfig,
-) where
+) -> BenchmarkResult
+where
     V: TransactionBlockExecutor<BenchmarkTransaction> + 'static,
 {
     create_checkpoint(
@@ -112,23 +156,26 @@ pub fn run_benchmark<V>(
 
     let (db, executor) = init_db_and_executor::<V>(&config);
 
-    let transaction_generator_creator = transaction_type.map(|transaction_type| {
-        init_workload::<V, _>(
-            transaction_type,
-            num_main_signer_accounts,
-            num_additional_dst_pool_accounts,
-            db.clone(),
-            &source_dir,
-            // Initialization pipeline is temporary, so needs to be fully committed.
-            PipelineConfig {
-                delay_execution_start: false,
-                split_stages: false,
-                skip_commit: false,
-                allow_discards: false,
-                allow_aborts: false,
-            },
-        )
-    });
+    let transaction_generator_creator =
+        transaction_mix_per_phase
+            .as_ref()
+            .map(|transaction_mix_per_phase| {
+                init_workload::<V, _>(
+                    transaction_mix_per_phase,
+                    num_main_signer_accounts,
+                    num_additional_dst_pool_accounts,
+                    db.clone(),
+                    &source_dir,
+                    // Initialization pipeline is temporary, so needs to be fully committed.
+                    PipelineConfig {
+                        delay_execution_start: false,
+                        split_stages: false,
+                        skip_commit: false,
+                        allow_discards: false,
+                        allow_aborts: false,
+                    },
+                )
+            });
 
     let version = db.reader.get_latest_version().unwrap();
 
@@ -145,11 +192,16 @@ pub fn run_benchmark<V>(
 
     let mut start_time = Instant::now();
     let start_gas = TXN_GAS_USAGE.get_sample_sum();
-    if let Some(transaction_generator_creator) = transaction_generator_creator {
+    let start_vm_secs = APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum();
+    let start_execution_secs = APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.get_sample_sum();
+    let start_commit_secs = APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS.get_sample_sum();
+    if let Some((transaction_generator_creator, cur_phase)) = transaction_generator_creator {
         generator.run_workload(
             block_size,
             num_blocks,
             transaction_generator_creator,
+            cur_phase,
+            transaction_mix_per_phase.as_ref().map_or(1, Vec::len),
             transactions_per_sender,
         );
     } else {
@@ -162,33 +214,50 @@ pub fn run_benchmark<V>(
     generator.drop_sender();
     pipeline.join();
 
-    let elapsed = start_time.elapsed().as_secs_f32();
+    let elapsed = start_time.elapsed().as_secs_f64();
     let delta_v = db.reader.get_latest_version().unwrap() - version;
     let delta_gas = TXN_GAS_USAGE.get_sample_sum() - start_gas;
-    info!(
-        "Executed workload {}",
-        if let Some(ttype) = transaction_type {
-            format!("{:?} via txn generator", ttype)
-        } else {
-            "raw transfer".to_string()
-        }
-    );
-    info!("Overall TPS: {} txn/s", delta_v as f32 / elapsed);
-    info!("Overall GPS: {} gas/s", delta_gas as f32 / elapsed);
+    let workload = match transaction_mix_per_phase {
+        Some(transaction_mix_per_phase) => Workload::TransactionMix(transaction_mix_per_phase),
+        None => Workload::RawTransfer,
+    };
+    info!("Executed workload {:?}", workload);
+    info!("Overall TPS: {} txn/s", delta_v as f64 / elapsed);
+    info!("Overall GPS: {} gas/s", delta_gas / elapsed);
 
     if verify_sequence_numbers {
         generator.verify_sequence_numbers(db.reader);
     }
+
+    let stage_tps = |delta_secs: f64| (delta_secs > 0.0).then(|| delta_v as f64 / delta_secs);
+    BenchmarkResult {
+        workload,
+        block_size,
+        num_blocks,
+        num_txns: delta_v,
+        elapsed_secs: elapsed,
+        tps: delta_v as f64 / elapsed,
+        gps: delta_gas / elapsed,
+        vm_tps: stage_tps(APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum() - start_vm_secs),
+        execution_tps: stage_tps(
+            APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.get_sample_sum() - start_execution_secs,
+        ),
+        commit_tps: stage_tps(
+            APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS.get_sample_sum() - start_commit_secs,
+        ),
+    }
 }
 
+/// Creates the transaction generator of the workload, along with the phase it generates
+/// transactions of.
 fn init_workload<V, P: AsRef<Path>>(
-    transaction_type: TransactionType,
+    transaction_mix_per_phase: &[Vec<(TransactionType, usize)>],
     num_main_signer_accounts: usize,
     num_additional_dst_pool_accounts: usize,
     db: DbReaderWriter,
     db_dir: &P,
     pipeline_config: PipelineConfig,
-) -> Box<dyn TransactionGeneratorCreator>
+) -> (Box<dyn TransactionGeneratorCreator>, Arc<AtomicUsize>)
 where
     V: TransactionBlockExecutor<BenchmarkTransaction> + 'static,
 {
@@ -214,30 +283,29 @@ where
         accounts_cache.split(num_main_signer_accounts);
     let transaction_factory = TransactionGenerator::create_transaction_factory();
 
+    let phase = Arc::new(AtomicUsize::new(0));
     let (txn_generator_creator, _address_pool, _account_pool) = runtime.block_on(async {
-        let phase = Arc::new(AtomicUsize::new(0));
-
         let db_gen_init_transaction_executor = DbGenInitTransactionExecutor {
             db: db.clone(),
             block_sender,
         };
 
         create_txn_generator_creator(
-            &[vec![(transaction_type, 1)]],
+            transaction_mix_per_phase,
             1,
             &mut main_signer_accounts,
             burner_accounts,
             &db_gen_init_transaction_executor,
             &transaction_factory,
             &transaction_factory,
-            phase,
+            phase.clone(),
         )
         .await
     });
 
     pipeline.join();
 
-    txn_generator_creator
+    (txn_generator_creator, phase)
 }
 
 pub fn add_accounts<V>(
@@ -362,16 +430,17 @@ fn add_accounts_impl<V>(
 mod tests {
     use crate::{
         benchmark_transaction::BenchmarkTransaction, native_executor::NativeExecutor,
-        pipeline::PipelineConfig,
+        pipeline::PipelineConfig, BenchmarkResult, Workload,
     };
     use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use aptos_executor::block_executor::TransactionBlockExecutor;
     use aptos_temppath::TempPath;
     use aptos_transaction_generator_lib::args::TransactionTypeArg;
     use aptos_vm::AptosVM;
+    use serde_json::json;
 
     fn test_generic_benchmark<E>(
-        transaction_type: Option<TransactionTypeArg>,
+        transaction_mix_per_phase: Option<Vec<Vec<(TransactionTypeArg, usize)>>>,
         verify_sequence_numbers: bool,
     ) where
         E: TransactionBlockExecutor<BenchmarkTransaction> + 'static,
@@ -407,7 +476,19 @@ mod tests {
         super::run_benchmark::<E>(
             6, /* block_size */
             5, /* num_blocks */
-            transaction_type.map(|t| t.materialize()),
+            transaction_mix_per_phase.map(|transaction_mix_per_phase| {
+                transaction_mix_per_phase
+                    .into_iter()
+                    .map(|transaction_mix| {
+                        transaction_mix
+                            .into_iter()
+                            .map(|(transaction_type, weight)| {
+                                (transaction_type.materialize(), weight)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }),
             2,  /* transactions per sender */
             25, /* num_main_signer_accounts */
             30, /* num_dst_pool_accounts */
@@ -434,7 +515,86 @@ mod tests {
 
     #[test]
     fn test_benchmark_transaction() {
-        test_generic_benchmark::<AptosVM>(Some(TransactionTypeArg::CreateNewAccountResource), true);
+        test_generic_benchmark::<AptosVM>(
+            Some(vec![vec![(
+                TransactionTypeArg::CreateNewAccountResource,
+                1,
+            )]]),
+            true,
+        );
+    }
+
+    #[test]
+    fn test_benchmark_transaction_mix() {
+        test_generic_benchmark::<AptosVM>(
+            Some(vec![vec![
+                (TransactionTypeArg::CoinTransfer, 2),
+                (TransactionTypeArg::CreateNewAccountResource, 1),
+            ]]),
+            true,
+        );
+    }
+
+    #[test]
+    fn test_benchmark_transaction_phases() {
+        test_generic_benchmark::<AptosVM>(
+            Some(vec![vec![(TransactionTypeArg::CoinTransfer, 1)], vec![(
+                TransactionTypeArg::CreateNewAccountResource,
+                1,
+            )]]),
+            true,
+        );
+    }
+
+    #[test]
+    fn test_benchmark_result_json() {
+        let result = BenchmarkResult {
+            workload: Workload::TransactionMix(vec![
+                vec![(TransactionTypeArg::CoinTransfer.materialize(), 2)],
+                vec![(TransactionTypeArg::Batch100Transfer.materialize(), 1)],
+            ]),
+            block_size: 6,
+            num_blocks: 5,
+            num_txns: 35,
+            elapsed_secs: 2.0,
+            tps: 17.5,
+            gps: 100.0,
+            vm_tps: Some(70.0),
+            execution_tps: None,
+            commit_tps: Some(35.0),
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "workload": {
+                    "transaction_mix": [
+                        [[{"CoinTransfer": {
+                            "invalid_transaction_ratio": 0,
+                            "sender_use_account_pool": false,
+                        }}, 2]],
+                        [[{"BatchTransfer": {"batch_size": 100}}, 1]],
+                    ],
+                },
+                "block_size": 6,
+                "num_blocks": 5,
+                "num_txns": 35,
+                "elapsed_secs": 2.0,
+                "tps": 17.5,
+                "gps": 100.0,
+                "vm_tps": 70.0,
+                "execution_tps": null,
+                "commit_tps": 35.0,
+            }),
+        );
+
+        let result = BenchmarkResult {
+            workload: Workload::RawTransfer,
+            ..result
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["workload"],
+            json!("raw_transfer"),
+        );
     }
 
     #[test]
@@ -12,7 +12,7 @@ use aptos_executor_benchmark::{
 };
 use aptos_metrics_core::{register_int_gauge, IntGauge};
 use aptos_push_metrics::MetricsPusher;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use aptos_transaction_generator_lib::args::{transaction_mix_per_phase, TransactionTypeArg};
 use aptos_vm::AptosVM;
 use clap::{Parser, Subcommand};
 use once_cell::sync::Lazy;
@@ -120,6 +120,11 @@ struct Opt {
     #[clap(long)]
     concurrency_level: Option<usize>,
 
+    /// Number of shards the blocks are partitioned into, each executed by its own parallel
+    /// execution with concurrency_level / num_executor_shards threads.
+    #[clap(long, default_value = "1")]
+    num_executor_shards: usize,
+
     #[clap(flatten)]
     pruner_opt: PrunerOpt,
 
@@ -182,10 +187,23 @@ enum Command {
         #[clap(long, default_value = "0")]
         additional_dst_pool_accounts: usize,
 
-        /// Workload (transaction type). Uses raw coin transfer if not set,
-        /// and if set uses transaction-generator-lib to generate it
-        #[clap(long, arg_enum, ignore_case = true)]
-        transaction_type: Option<TransactionTypeArg>,
+        /// Workload (transaction types). Uses raw coin transfer if not set,
+        /// and if set uses transaction-generator-lib to generate a mix of them
+        #[clap(long, arg_enum, min_values = 1, ignore_case = true)]
+        transaction_type: Vec<TransactionTypeArg>,
+
+        /// Weights of the transaction types in the mix, all equal if not set
+        #[clap(long, min_values = 0)]
+        transaction_weights: Vec<usize>,
+
+        /// Phases of the transaction types, all in a single phase if not set. The blocks are
+        /// split evenly between the phases, which run in order.
+        #[clap(long, min_values = 0)]
+        transaction_phases: Vec<usize>,
+
+        /// Writes the benchmark result as JSON to the given file
+        #[clap(long, parse(from_os_str))]
+        output_json: Option<PathBuf>,
 
         #[clap(long, parse(from_os_str))]
         data_dir: PathBuf,
@@ -235,13 +253,23 @@ where
             main_signer_accounts,
             additional_dst_pool_accounts,
             transaction_type,
+            transaction_weights,
+            transaction_phases,
+            output_json,
             data_dir,
             checkpoint_dir,
         } => {
-            aptos_executor_benchmark::run_benchmark::<E>(
+            let transaction_mix_per_phase = (!transaction_type.is_empty()).then(|| {
+                transaction_mix_per_phase(
+                    &transaction_type,
+                    &transaction_weights,
+                    &transaction_phases,
+                )
+            });
+            let result = aptos_executor_benchmark::run_benchmark::<E>(
                 opt.block_size,
                 blocks,
-                transaction_type.map(|t| t.materialize()),
+                transaction_mix_per_phase,
                 opt.transactions_per_sender,
                 main_signer_accounts,
                 additional_dst_pool_accounts,
@@ -253,6 +281,13 @@ where
                 opt.use_sharded_state_merkle_db,
                 opt.pipeline_opt.pipeline_config(),
             );
+            if let Some(output_json) = output_json {
+                std::fs::write(
+                    &output_json,
+                    serde_json::to_string_pretty(&result).expect("Failed to serialize the result"),
+                )
+                .expect("Failed to write the result");
+            }
         },
         Command::AddAccounts {
             data_dir,
@@ -292,6 +327,7 @@ fn main() {
         .build_global()
         .expect("Failed to build rayon global thread pool.");
     AptosVM::set_concurrency_level_once(opt.concurrency_level());
+    AptosVM::set_num_shards_once(opt.num_executor_shards);
     NativeExecutor::set_concurrency_level_once(opt.concurrency_level());
 
     if opt.use_native_executor {
@@ -28,7 +28,10 @@ use std::{
     io::{Read, Write},
     iter::once,
     path::Path,
-    sync::{mpsc, Arc},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
 };
 
 const META_FILENAME: &str = "metadata.toml";
@@ -270,18 +273,24 @@ impl TransactionGenerator {
         self.gen_transfer_transactions(block_size, num_transfer_blocks, transactions_per_sender);
     }
 
+    /// Generates `num_blocks` blocks with the transaction generator. The blocks are split evenly
+    /// between the `num_phases` phases of the workload, which start in order by setting
+    /// `cur_phase`.
     pub fn run_workload(
         &mut self,
         block_size: usize,
         num_blocks: usize,
         mut transaction_generator_creator: Box<dyn TransactionGeneratorCreator>,
+        cur_phase: Arc<AtomicUsize>,
+        num_phases: usize,
         transactions_per_sender: usize,
     ) {
         assert!(self.block_sender.is_some());
         let mut transaction_generator =
             transaction_generator_creator.create_transaction_generator();
 
-        for _ in 0..num_blocks {
+        for block_idx in 0..num_blocks {
+            cur_phase.store(block_idx * num_phases / num_blocks, Ordering::Relaxed);
             // TODO: handle when block_size isn't divisible by transactions_per_sender
             let transactions: Vec<_> = (0..(block_size / transactions_per_sender))
                 .into_iter()
@@ -6,6 +6,7 @@
 
 use crate::{
     components::{block_tree::BlockTree, chunk_output::ChunkOutput},
+    differential_execution::{self, ExecutionResult},
     logging::{LogEntry, LogSchema},
     metrics::{
         APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
@@ -13,29 +14,45 @@ use crate::{
         APTOS_EXECUTOR_TRANSACTIONS_SAVED, APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
     },
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use aptos_crypto::HashValue;
 use aptos_executor_types::{BlockExecutorTrait, Error, StateComputeResult};
-use aptos_infallible::RwLock;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_state_view::StateViewId;
 use aptos_storage_interface::{
-    async_proof_fetcher::AsyncProofFetcher, cached_state_view::CachedStateView, DbReaderWriter,
+    async_proof_fetcher::AsyncProofFetcher, cached_state_view::CachedStateView,
+    state_value_cache::StateValueCache, DbReaderWriter,
 };
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, state_store::state_value::StateValue,
     transaction::Transaction,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::{AptosVM, VMExecutor};
 use fail::fail_point;
-use std::{marker::PhantomData, sync::Arc};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+/// The memory budget of the state values cached across blocks.
+const STATE_VALUE_CACHE_MAX_BYTES: usize = 256 << 20;
 
 pub trait TransactionBlockExecutor<T>: Send + Sync {
     fn execute_transaction_block(
         transactions: Vec<T>,
         state_view: CachedStateView,
     ) -> Result<ChunkOutput>;
+
+    /// Re-executes the (already converted) transactions of a block sequentially, used by the
+    /// differential execution mode to cross-check the result of 'execute_transaction_block'.
+    fn execute_transaction_block_sequential(
+        _transactions: Vec<Transaction>,
+        _state_view: CachedStateView,
+    ) -> Result<ChunkOutput> {
+        Err(anyhow!("Sequential execution is not supported"))
+    }
+
+    /// See 'VMExecutor::invalidate_cross_block_cache'.
+    fn invalidate_cross_block_cache() {}
 }
 
 impl TransactionBlockExecutor<Transaction> for AptosVM {
@@ -45,6 +62,17 @@ impl TransactionBlockExecutor<Transaction> for AptosVM {
     ) -> Result<ChunkOutput> {
         ChunkOutput::by_transaction_execution::<AptosVM>(transactions, state_view)
     }
+
+    fn execute_transaction_block_sequential(
+        transactions: Vec<Transaction>,
+        state_view: CachedStateView,
+    ) -> Result<ChunkOutput> {
+        ChunkOutput::by_sequential_transaction_execution::<AptosVM>(transactions, state_view)
+    }
+
+    fn invalidate_cross_block_cache() {
+        <AptosVM as VMExecutor>::invalidate_cross_block_cache()
+    }
 }
 
 pub struct BlockExecutor<V, T> {
@@ -95,6 +123,8 @@ where
     }
 
     fn reset(&self) -> Result<()> {
+        // The state may have been synced past the blocks executed so far.
+        V::invalidate_cross_block_cache();
         *self.inner.write() = Some(BlockExecutorInner::new(self.db.clone())?);
         Ok(())
     }
@@ -133,6 +163,11 @@ where
 struct BlockExecutorInner<V, T> {
     db: DbReaderWriter,
     block_tree: BlockTree,
+    state_value_cache: Arc<StateValueCache>,
+    /// The uncommitted blocks that either publish modules, or descend from such a block. The VM
+    /// caches can't be reused across the executions of these blocks and the blocks on other
+    /// forks.
+    blocks_with_pending_module_writes: Mutex<HashSet<HashValue>>,
     phantom: PhantomData<(V, T)>,
 }
 
@@ -143,9 +178,21 @@ where
 {
     pub fn new(db: DbReaderWriter) -> Result<Self> {
         let block_tree = BlockTree::new(&db.reader)?;
+        let committed_version = block_tree
+            .root_block()
+            .output
+            .result_view
+            .txn_accumulator()
+            .num_leaves()
+            .checked_sub(1);
         Ok(Self {
             db,
             block_tree,
+            state_value_cache: Arc::new(StateValueCache::new(
+                STATE_VALUE_CACHE_MAX_BYTES,
+                committed_version,
+            )),
+            blocks_with_pending_module_writes: Mutex::new(HashSet::new()),
             phantom: PhantomData,
         })
     }
@@ -166,6 +213,29 @@ where
     V: TransactionBlockExecutor<T>,
     T: Send + Sync,
 {
+    /// Forgets the committed and the discarded blocks with pending module writes.
+    fn prune_blocks_with_pending_module_writes(
+        &self,
+        committed_block_ids: &[HashValue],
+    ) -> Result<()> {
+        let mut blocks = self.blocks_with_pending_module_writes.lock();
+        if committed_block_ids.iter().any(|id| blocks.contains(id)) {
+            // The VMs may have been used for the blocks on the discarded forks.
+            V::invalidate_cross_block_cache();
+        }
+        let block_ids: Vec<_> = blocks
+            .iter()
+            .filter(|id| !committed_block_ids.contains(id))
+            .copied()
+            .collect();
+        *blocks = block_ids
+            .iter()
+            .zip(self.block_tree.get_blocks_opt(&block_ids)?)
+            .filter_map(|(id, block)| block.map(|_| *id))
+            .collect();
+        Ok(())
+    }
+
     fn committed_block_id(&self) -> HashValue {
         self.block_tree.root_block().id
     }
@@ -195,6 +265,10 @@ where
             return Ok(b.output.as_state_compute_result(parent_accumulator));
         }
 
+        let mut has_pending_module_writes = self
+            .blocks_with_pending_module_writes
+            .lock()
+            .contains(&parent_block_id);
         let output = if parent_block_id != committed_block.id && parent_output.has_reconfiguration()
         {
             info!(
@@ -214,7 +288,10 @@ where
                 parent_view.verified_state_view(
                     StateViewId::BlockExecution { block_id },
                     Arc::clone(&self.db.reader),
-                    Arc::new(AsyncProofFetcher::new(self.db.reader.clone())),
+                    Arc::new(
+                        AsyncProofFetcher::new(self.db.reader.clone())
+                            .with_state_value_cache(Arc::clone(&self.state_value_cache)),
+                    ),
                 )?
             };
 
@@ -225,17 +302,52 @@ where
                         "Injected error in vm_execute_block"
                     )))
                 });
-                V::execute_transaction_block(transactions, state_view)?
+                if has_pending_module_writes {
+                    V::invalidate_cross_block_cache();
+                }
+                let chunk_output = V::execute_transaction_block(transactions, state_view)?;
+                if has_pending_module_writes {
+                    V::invalidate_cross_block_cache();
+                }
+                chunk_output
             };
             chunk_output.trace_log_transaction_status();
+            has_pending_module_writes |= chunk_output.has_module_writes();
+            let maybe_executed_block = differential_execution::should_check().map(|permit| {
+                (
+                    permit,
+                    chunk_output.transactions.clone(),
+                    chunk_output.transaction_outputs.clone(),
+                )
+            });
 
             let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
                 .with_label_values(&["apply_to_ledger"])
                 .start_timer();
             let (output, _, _) = chunk_output.apply_to_ledger(parent_view)?;
+
+            if let Some((permit, transactions, transaction_outputs)) = maybe_executed_block {
+                differential_execution::spawn_check(
+                    permit,
+                    V::execute_transaction_block_sequential,
+                    parent_view.clone(),
+                    Arc::clone(&self.db.reader),
+                    ExecutionResult {
+                        state_view_id: StateViewId::BlockExecution { block_id },
+                        transactions,
+                        transaction_outputs,
+                        state_root: output.result_view.state().current.root_hash(),
+                    },
+                );
+            }
             output
         };
         output.ensure_ends_with_state_checkpoint()?;
+        if has_pending_module_writes {
+            self.blocks_with_pending_module_writes
+                .lock()
+                .insert(block_id);
+        }
 
         let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
             .with_label_values(&["as_state_compute_result"])
@@ -331,9 +443,14 @@ where
             sync_commit,
             result_in_memory_state,
         )?;
+        self.state_value_cache.apply_committed_write_sets(
+            first_version,
+            txns_to_commit.iter().map(|txn| txn.write_set()),
+        );
         self.block_tree
             .prune(ledger_info_with_sigs.ledger_info())
             .expect("Failure pruning block tree.");
+        self.prune_blocks_with_pending_module_writes(&block_ids)?;
 
         Ok(())
     }
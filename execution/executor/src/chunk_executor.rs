@@ -10,6 +10,7 @@ use crate::{
         chunk_commit_queue::ChunkCommitQueue,
         chunk_output::ChunkOutput,
     },
+    differential_execution::{self, ExecutionResult},
     logging::{LogEntry, LogSchema},
     metrics::{
         APTOS_EXECUTOR_APPLY_CHUNK_SECONDS, APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS,
@@ -205,6 +206,13 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
             ChunkOutput::by_transaction_execution::<V>(transactions, state_view)?
         };
+        let maybe_executed_chunk = differential_execution::should_check().map(|permit| {
+            (
+                permit,
+                chunk_output.transactions.clone(),
+                chunk_output.transaction_outputs.clone(),
+            )
+        });
         let executed_chunk = Self::apply_chunk_output_for_state_sync(
             verified_target_li,
             epoch_change_li,
@@ -212,6 +220,22 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             chunk_output,
             &txn_info_list_with_proof.transaction_infos[txns_to_skip..],
         )?;
+        if let Some((permit, transactions, transaction_outputs)) = maybe_executed_chunk {
+            differential_execution::spawn_check(
+                permit,
+                ChunkOutput::by_sequential_transaction_execution::<V>,
+                latest_view.clone(),
+                Arc::clone(&self.db.reader),
+                ExecutionResult {
+                    state_view_id: StateViewId::ChunkExecution {
+                        first_version: latest_view.txn_accumulator().num_leaves(),
+                    },
+                    transactions,
+                    transaction_outputs,
+                    state_root: executed_chunk.result_view.state().current.root_hash(),
+                },
+            );
+        }
 
         // Add result to commit queue.
         self.commit_queue.lock().enqueue(executed_chunk);
@@ -255,6 +279,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         // Apply transaction outputs.
         let state_view = self.state_view(&latest_view)?;
         let chunk_output = ChunkOutput::by_transaction_output(txns_and_outputs, state_view)?;
+        if chunk_output.has_module_writes() {
+            V::invalidate_cross_block_cache();
+        }
         let executed_chunk = Self::apply_chunk_output_for_state_sync(
             verified_target_li,
             epoch_change_li,
@@ -587,6 +614,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
 
         let state_view = self.state_view(latest_view)?;
         let chunk_output = ChunkOutput::by_transaction_output(txns_and_outputs, state_view)?;
+        if chunk_output.has_module_writes() {
+            V::invalidate_cross_block_cache();
+        }
         let (executed_batch, to_discard, to_retry) = chunk_output.apply_to_ledger(latest_view)?;
         ensure_no_discard(to_discard)?;
         ensure_no_retry(to_retry)?;
@@ -100,7 +100,7 @@ impl ApplyChunkOutput {
             .map(|idx| idx + 1);
 
         // Transactions after the epoch ending are all to be retried.
-        let to_retry = if let Some(pos) = new_epoch_marker {
+        let epoch_ending_retry: Vec<Transaction> = if let Some(pos) = new_epoch_marker {
             transaction_outputs.drain(pos..);
             transactions.drain(pos..).collect()
         } else {
@@ -117,12 +117,25 @@ impl ApplyChunkOutput {
             .collect();
 
         // Separate transactions with the Keep status out.
-        let (to_keep, to_discard) =
+        let (to_keep, not_kept) =
             itertools::zip_eq(transactions.into_iter(), transaction_outputs.into_iter())
                 .partition::<Vec<(Transaction, ParsedTransactionOutput)>, _>(|(_, o)| {
                     matches!(o.status(), TransactionStatus::Keep(_))
                 });
 
+        // Transactions skipped because the block gas limit was reached are to be retried as
+        // well (and precede the ones after the epoch ending, if any).
+        let (retry, to_discard) = not_kept
+            .into_iter()
+            .partition::<Vec<(Transaction, ParsedTransactionOutput)>, _>(|(_, o)| {
+                matches!(o.status(), TransactionStatus::Retry)
+            });
+        let to_retry = retry
+            .into_iter()
+            .map(|(t, _)| t)
+            .chain(epoch_ending_retry)
+            .collect();
+
         // Sanity check transactions with the Discard status:
         let to_discard = to_discard
             .into_iter()
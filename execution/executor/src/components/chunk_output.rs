@@ -14,6 +14,7 @@ use aptos_storage_interface::{
 };
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS,
+    executable::ModulePath,
     transaction::{ExecutionStatus, Transaction, TransactionOutput, TransactionStatus},
 };
 use aptos_vm::{AptosVM, VMExecutor};
@@ -51,6 +52,22 @@ impl ChunkOutput {
         })
     }
 
+    /// Executes the transactions sequentially, regardless of the configured concurrency level.
+    /// Doesn't update the processed transaction counters, since the result is only used to
+    /// cross-check a (parallel) execution of the same transactions.
+    pub fn by_sequential_transaction_execution<V: VMExecutor>(
+        transactions: Vec<Transaction>,
+        state_view: CachedStateView,
+    ) -> Result<Self> {
+        let transaction_outputs = V::execute_block_sequential(transactions.clone(), &state_view)?;
+
+        Ok(Self {
+            transactions,
+            transaction_outputs,
+            state_cache: state_view.into_state_cache(),
+        })
+    }
+
     pub fn by_transaction_output(
         transactions_and_outputs: Vec<(Transaction, TransactionOutput)>,
         state_view: CachedStateView,
@@ -86,6 +103,16 @@ impl ChunkOutput {
         ApplyChunkOutput::apply(self, base_view)
     }
 
+    /// Whether any of the outputs publishes or upgrades a module.
+    pub fn has_module_writes(&self) -> bool {
+        self.transaction_outputs.iter().any(|output| {
+            output
+                .write_set()
+                .iter()
+                .any(|(state_key, _)| state_key.module_path().is_some())
+        })
+    }
+
     pub fn trace_log_transaction_status(&self) {
         let status: Vec<_> = self
             .transaction_outputs
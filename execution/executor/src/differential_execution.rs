@@ -0,0 +1,239 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Paranoid mode that re-executes a sampled fraction of the blocks and chunks sequentially in a
+//! background thread and compares the outputs and the resulting state root against the (parallel)
+//! execution result. Mismatches are reported via `APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT`,
+//! logged, and optionally dumped to a file for offline debugging.
+
+use crate::{
+    components::chunk_output::ChunkOutput, metrics::APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT,
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_state_view::StateViewId;
+use aptos_storage_interface::{
+    async_proof_fetcher::AsyncProofFetcher, cached_state_view::CachedStateView, DbReader,
+    ExecutedTrees,
+};
+use aptos_types::transaction::{Transaction, TransactionOutput};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+static DIFFERENTIAL_EXECUTION: OnceCell<DifferentialExecution> = OnceCell::new();
+
+struct DifferentialExecution {
+    sampler: Sampler,
+    /// Where to write the details of the executions with mismatches, if set.
+    dump_dir: Option<PathBuf>,
+}
+
+/// Picks the executions to cross-check.
+pub(crate) struct Sampler {
+    /// Every `sample_interval`-th execution is re-executed, 0 disables the mode.
+    sample_interval: u64,
+    num_executions: AtomicU64,
+    check_in_progress: AtomicBool,
+}
+
+impl Sampler {
+    pub(crate) fn new(sample_interval: u64) -> Self {
+        Self {
+            sample_interval,
+            num_executions: AtomicU64::new(0),
+            check_in_progress: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a permit to cross-check the next execution if it is sampled. At most one check
+    /// runs at a time, samples taken while the permit of the previous check is held are skipped.
+    pub(crate) fn should_check(&self) -> Option<CheckPermit<'_>> {
+        if self.sample_interval == 0 {
+            return None;
+        }
+        if self.num_executions.fetch_add(1, Ordering::Relaxed) % self.sample_interval != 0 {
+            return None;
+        }
+        if self.check_in_progress.swap(true, Ordering::AcqRel) {
+            APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT
+                .with_label_values(&["skipped"])
+                .inc();
+            return None;
+        }
+        Some(CheckPermit { sampler: self })
+    }
+}
+
+/// Held while a sampled execution is cross-checked, so that the check is finished even if the
+/// execution fails before it is spawned.
+pub(crate) struct CheckPermit<'a> {
+    sampler: &'a Sampler,
+}
+
+impl Drop for CheckPermit<'_> {
+    fn drop(&mut self) {
+        self.sampler
+            .check_in_progress
+            .store(false, Ordering::Release);
+    }
+}
+
+/// Sets the differential execution config, can only be called once. The mode is disabled if the
+/// config is never set.
+pub fn set_differential_execution_config_once(sample_interval: u64, dump_dir: Option<PathBuf>) {
+    DIFFERENTIAL_EXECUTION
+        .set(DifferentialExecution {
+            sampler: Sampler::new(sample_interval),
+            dump_dir,
+        })
+        .ok();
+}
+
+/// Returns a permit to cross-check the next executed block or chunk if it is sampled.
+pub(crate) fn should_check() -> Option<CheckPermit<'static>> {
+    DIFFERENTIAL_EXECUTION
+        .get()
+        .and_then(|differential_execution| differential_execution.sampler.should_check())
+}
+
+/// The result of the execution of a block or chunk, to be cross-checked.
+pub(crate) struct ExecutionResult {
+    /// Identifies the execution, must be either `BlockExecution` or `ChunkExecution`.
+    pub state_view_id: StateViewId,
+    pub transactions: Vec<Transaction>,
+    pub transaction_outputs: Vec<TransactionOutput>,
+    pub state_root: HashValue,
+}
+
+/// The details of an execution with mismatches, as dumped to
+/// `differential_execution_<name>.bcs` in the dump dir.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MismatchDump {
+    /// `block_<block id>` or `chunk_<first version>`.
+    pub name: String,
+    pub transactions: Vec<Transaction>,
+    pub parallel_outputs: Vec<TransactionOutput>,
+    pub sequential_outputs: Vec<TransactionOutput>,
+    pub parallel_state_root: HashValue,
+    pub sequential_state_root: HashValue,
+}
+
+fn execution_name(state_view_id: StateViewId) -> String {
+    match state_view_id {
+        StateViewId::BlockExecution { block_id } => format!("block_{}", block_id),
+        StateViewId::ChunkExecution { first_version } => format!("chunk_{}", first_version),
+        state_view_id => format!("{:?}", state_view_id),
+    }
+}
+
+/// Re-executes the transactions on top of `parent_view` in a background thread using
+/// `execute_sequential`, and compares the result.
+pub(crate) fn spawn_check(
+    permit: CheckPermit<'static>,
+    execute_sequential: fn(Vec<Transaction>, CachedStateView) -> Result<ChunkOutput>,
+    parent_view: ExecutedTrees,
+    reader: Arc<dyn DbReader>,
+    result: ExecutionResult,
+) {
+    let dump_dir = DIFFERENTIAL_EXECUTION
+        .get()
+        .and_then(|differential_execution| differential_execution.dump_dir.as_deref());
+    let spawned = std::thread::Builder::new()
+        .name("diff_execution".to_string())
+        .spawn(move || {
+            let _permit = permit;
+            let name = execution_name(result.state_view_id);
+            let label = match check(execute_sequential, &parent_view, reader, &result, dump_dir) {
+                Ok(label) => label,
+                Err(err) => {
+                    warn!("Failed to re-execute {} sequentially: {:?}", name, err);
+                    "error"
+                },
+            };
+            APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT
+                .with_label_values(&[label])
+                .inc();
+        });
+    if let Err(err) = spawned {
+        warn!(
+            "Failed to spawn the differential execution thread: {:?}",
+            err
+        );
+        APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT
+            .with_label_values(&["error"])
+            .inc();
+    }
+}
+
+/// Re-executes the transactions of `result` and returns the label of the outcome, one of
+/// `match`, `output_mismatch` and `state_root_mismatch`. Mismatches are dumped to `dump_dir`.
+pub(crate) fn check(
+    execute_sequential: fn(Vec<Transaction>, CachedStateView) -> Result<ChunkOutput>,
+    parent_view: &ExecutedTrees,
+    reader: Arc<dyn DbReader>,
+    result: &ExecutionResult,
+    dump_dir: Option<&Path>,
+) -> Result<&'static str> {
+    let name = execution_name(result.state_view_id);
+    let state_view = parent_view.verified_state_view(
+        result.state_view_id,
+        Arc::clone(&reader),
+        Arc::new(AsyncProofFetcher::new(reader)),
+    )?;
+    let chunk_output = execute_sequential(result.transactions.clone(), state_view)?;
+    let sequential_outputs = chunk_output.transaction_outputs.clone();
+    let (executed_chunk, _, _) = chunk_output.apply_to_ledger(parent_view)?;
+    let sequential_state_root = executed_chunk.result_view.state().current.root_hash();
+
+    let label = if sequential_outputs != result.transaction_outputs {
+        let first_mismatch = sequential_outputs
+            .iter()
+            .zip(result.transaction_outputs.iter())
+            .position(|(sequential, parallel)| sequential != parallel);
+        error!(
+            "Sequential and parallel execution outputs of {} differ, first mismatch at index \
+             {:?}, {} sequential vs {} parallel outputs",
+            name,
+            first_mismatch,
+            sequential_outputs.len(),
+            result.transaction_outputs.len(),
+        );
+        "output_mismatch"
+    } else if sequential_state_root != result.state_root {
+        error!(
+            "Sequential and parallel execution state roots of {} differ: {} vs {}",
+            name, sequential_state_root, result.state_root,
+        );
+        "state_root_mismatch"
+    } else {
+        return Ok("match");
+    };
+
+    if let Some(dump_dir) = dump_dir {
+        let path = dump_dir.join(format!("differential_execution_{}.bcs", name));
+        let dump = MismatchDump {
+            name,
+            transactions: result.transactions.clone(),
+            parallel_outputs: result.transaction_outputs.clone(),
+            sequential_outputs,
+            parallel_state_root: result.state_root,
+            sequential_state_root,
+        };
+        match bcs::to_bytes(&dump)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(std::fs::write(&path, bytes)?))
+        {
+            Ok(()) => info!("Dumped the mismatching execution to {}.", path.display()),
+            Err(err) => warn!("Failed to dump the mismatching execution: {:?}", err),
+        }
+    }
+    Ok(label)
+}
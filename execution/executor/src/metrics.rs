@@ -187,3 +187,13 @@ pub static APTOS_PROCESSED_USER_TRANSACTIONS_CORE_EVENTS: Lazy<IntCounterVec> =
     )
     .unwrap()
 });
+
+/// Count of the blocks re-executed sequentially by the differential execution mode, by result.
+pub static APTOS_EXECUTOR_DIFFERENTIAL_EXECUTION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_executor_differential_execution_count",
+        "Count of the blocks sampled for differential execution. result is match, output_mismatch, state_root_mismatch, error or skipped",
+        &["result"]
+    )
+    .unwrap()
+});
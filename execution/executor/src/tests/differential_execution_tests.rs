@@ -0,0 +1,163 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    components::chunk_output::ChunkOutput,
+    differential_execution::{check, ExecutionResult, MismatchDump, Sampler},
+    mock_vm::{encode_mint_transaction, MockVM},
+    tests::{gen_address, gen_block_id, TestExecutor},
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_state_view::StateViewId;
+use aptos_storage_interface::{
+    cached_state_view::CachedStateView, sync_proof_fetcher::SyncProofFetcher, DbReaderWriter,
+    ExecutedTrees,
+};
+use aptos_types::{
+    test_helpers::transaction_test_helpers::block,
+    transaction::{Transaction, TransactionOutput},
+};
+use std::sync::Arc;
+
+fn execute_block(db: &DbReaderWriter) -> (ExecutedTrees, ExecutionResult) {
+    let parent_view = db.reader.get_latest_executed_trees().unwrap();
+    let state_view_id = StateViewId::BlockExecution {
+        block_id: gen_block_id(1),
+    };
+    let state_view = parent_view
+        .verified_state_view(
+            state_view_id,
+            Arc::clone(&db.reader),
+            Arc::new(SyncProofFetcher::new(db.reader.clone())),
+        )
+        .unwrap();
+    let txns = block(vec![
+        encode_mint_transaction(gen_address(0), 100),
+        encode_mint_transaction(gen_address(1), 100),
+    ]);
+    let chunk_output = ChunkOutput::by_transaction_execution::<MockVM>(txns, state_view).unwrap();
+    let transactions = chunk_output.transactions.clone();
+    let transaction_outputs = chunk_output.transaction_outputs.clone();
+    let (executed_chunk, _, _) = chunk_output.apply_to_ledger(&parent_view).unwrap();
+
+    (parent_view, ExecutionResult {
+        state_view_id,
+        transactions,
+        transaction_outputs,
+        state_root: executed_chunk.result_view.state().current.root_hash(),
+    })
+}
+
+/// Executes sequentially, but charges one more gas unit for the first transaction.
+fn execute_sequential_with_extra_gas(
+    transactions: Vec<Transaction>,
+    state_view: CachedStateView,
+) -> Result<ChunkOutput> {
+    let mut chunk_output =
+        ChunkOutput::by_sequential_transaction_execution::<MockVM>(transactions, state_view)?;
+    let output = &chunk_output.transaction_outputs[0];
+    chunk_output.transaction_outputs[0] = TransactionOutput::new(
+        output.write_set().clone(),
+        output.events().to_vec(),
+        output.gas_used() + 1,
+        output.status().clone(),
+    );
+    Ok(chunk_output)
+}
+
+#[test]
+fn test_sampler() {
+    let sampler = Sampler::new(3);
+    let permit = sampler.should_check();
+    assert!(permit.is_some());
+    assert!(sampler.should_check().is_none());
+    assert!(sampler.should_check().is_none());
+    // Sampled, but skipped as the previous check is still in progress.
+    assert!(sampler.should_check().is_none());
+
+    drop(permit);
+    assert!(sampler.should_check().is_none());
+    assert!(sampler.should_check().is_none());
+    assert!(sampler.should_check().is_some());
+
+    let disabled = Sampler::new(0);
+    assert!((0..10).all(|_| disabled.should_check().is_none()));
+}
+
+#[test]
+fn test_check_match() {
+    let executor = TestExecutor::new();
+    let (parent_view, result) = execute_block(&executor.db);
+    let dump_dir = aptos_temppath::TempPath::new();
+    dump_dir.create_as_dir().unwrap();
+
+    let label = check(
+        ChunkOutput::by_sequential_transaction_execution::<MockVM>,
+        &parent_view,
+        Arc::clone(&executor.db.reader),
+        &result,
+        Some(dump_dir.path()),
+    )
+    .unwrap();
+    assert_eq!(label, "match");
+    assert_eq!(std::fs::read_dir(dump_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_check_output_mismatch_is_dumped() {
+    let executor = TestExecutor::new();
+    let (parent_view, result) = execute_block(&executor.db);
+    let dump_dir = aptos_temppath::TempPath::new();
+    dump_dir.create_as_dir().unwrap();
+
+    let label = check(
+        execute_sequential_with_extra_gas,
+        &parent_view,
+        Arc::clone(&executor.db.reader),
+        &result,
+        Some(dump_dir.path()),
+    )
+    .unwrap();
+    assert_eq!(label, "output_mismatch");
+
+    let name = format!("block_{}", gen_block_id(1));
+    let dump: MismatchDump = bcs::from_bytes(
+        &std::fs::read(
+            dump_dir
+                .path()
+                .join(format!("differential_execution_{}.bcs", name)),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(dump.name, name);
+    assert_eq!(dump.transactions, result.transactions);
+    assert_eq!(dump.parallel_outputs, result.transaction_outputs);
+    assert_eq!(
+        dump.sequential_outputs[0].gas_used(),
+        result.transaction_outputs[0].gas_used() + 1
+    );
+    assert_eq!(
+        dump.sequential_outputs[1..],
+        result.transaction_outputs[1..]
+    );
+    assert_eq!(dump.parallel_state_root, result.state_root);
+}
+
+#[test]
+fn test_check_state_root_mismatch() {
+    let executor = TestExecutor::new();
+    let (parent_view, mut result) = execute_block(&executor.db);
+    result.state_root = HashValue::zero();
+
+    let label = check(
+        ChunkOutput::by_sequential_transaction_execution::<MockVM>,
+        &parent_view,
+        Arc::clone(&executor.db.reader),
+        &result,
+        None,
+    )
+    .unwrap();
+    assert_eq!(label, "state_root_mismatch");
+}
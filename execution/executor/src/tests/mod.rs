@@ -42,6 +42,7 @@ use proptest::prelude::*;
 use std::{iter::once, sync::Arc};
 
 mod chunk_executor_tests;
+mod differential_execution_tests;
 
 fn execute_and_commit_block(
     executor: &TestExecutor,
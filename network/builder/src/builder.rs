@@ -12,10 +12,10 @@
 //! long as the latter is in its trusted peers set.
 use aptos_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RoleType, CONNECTION_BACKOFF_BASE,
-        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
-        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
-        NETWORK_CHANNEL_SIZE,
+        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, ProtocolRateLimitConfig, RoleType,
+        CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS,
+        MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS,
+        MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
@@ -39,11 +39,17 @@ use aptos_network::{
             NewNetworkSender,
         },
     },
+    ProtocolId,
 };
 use aptos_network_discovery::DiscoveryChangeListener;
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
-use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    clone::Clone,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::runtime::Handle;
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -88,6 +94,7 @@ impl NetworkBuilder {
         max_concurrent_network_reqs: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_rate_limits: HashMap<ProtocolId, ProtocolRateLimitConfig>,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -105,6 +112,7 @@ impl NetworkBuilder {
             enable_proxy_protocol,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            outbound_rate_limits,
         );
 
         NetworkBuilder {
@@ -145,6 +153,7 @@ impl NetworkBuilder {
             MAX_CONCURRENT_NETWORK_REQS,
             MAX_INBOUND_CONNECTIONS,
             TCPBufferCfg::default(),
+            HashMap::new(),
         );
 
         builder.add_connectivity_manager(
@@ -201,6 +210,7 @@ impl NetworkBuilder {
                 config.outbound_rx_buffer_size_bytes,
                 config.outbound_tx_buffer_size_bytes,
             ),
+            outbound_protocol_rate_limits(config),
         );
 
         network_builder.add_connection_monitoring(
@@ -466,6 +476,31 @@ impl NetworkBuilder {
     }
 }
 
+/// Resolve the configured per-protocol outbound rate limits by protocol name.
+/// Unknown protocol names are a configuration error.
+fn outbound_protocol_rate_limits(
+    config: &NetworkConfig,
+) -> HashMap<ProtocolId, ProtocolRateLimitConfig> {
+    config
+        .outbound_protocol_rate_limits
+        .iter()
+        .map(|(protocol_name, rate_limit_config)| {
+            let protocol_id = ProtocolId::from_name(protocol_name).unwrap_or_else(|| {
+                panic!(
+                    "Unknown protocol in outbound_protocol_rate_limits: {}! Known protocols: {}",
+                    protocol_name,
+                    ProtocolId::all()
+                        .iter()
+                        .map(|protocol_id| protocol_id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            });
+            (protocol_id, *rate_limit_config)
+        })
+        .collect()
+}
+
 /// Retrieve and merge seeds so that they have all keys associated
 fn merge_seeds(config: &NetworkConfig) -> PeerSet {
     config.verify_seeds().expect("Seeds must be well formed");
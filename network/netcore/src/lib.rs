@@ -9,4 +9,5 @@
 //! socket.
 
 pub mod framing;
+pub mod substreams;
 pub mod transport;
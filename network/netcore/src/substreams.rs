@@ -0,0 +1,263 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Independent substreams on top of a single connection
+//!
+//! Some transports (e.g., QUIC) can carry many independent, ordered byte streams
+//! over a single connection, so that data lost or delayed on one stream doesn't
+//! block the others. Such connections expose a [`StreamMultiplexer`] through the
+//! [`MultiplexedSocket`] trait, in addition to their primary byte stream. Sockets
+//! that don't support substreams (e.g., TCP) simply return `None`.
+
+use futures::{
+    future::BoxFuture,
+    io::{AsyncRead, AsyncWrite},
+};
+use std::{fmt::Debug, io, pin::Pin, sync::Arc};
+
+/// The length (in bytes) of a connection's channel binding value
+pub const CHANNEL_BINDING_LENGTH: usize = 32;
+
+/// The sending half of a unidirectional substream
+pub type OutboundSubstream = Pin<Box<dyn AsyncWrite + Send>>;
+
+/// The receiving half of a unidirectional substream
+pub type InboundSubstream = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Opens and accepts unidirectional substreams on a single connection.
+pub trait StreamMultiplexer: Debug + Send + Sync {
+    /// Opens a new substream to the remote peer.
+    fn open_stream(&self) -> BoxFuture<'_, io::Result<OutboundSubstream>>;
+
+    /// Accepts the next substream opened by the remote peer. Returns `None` once
+    /// the connection has been closed.
+    fn accept_stream(&self) -> BoxFuture<'_, io::Result<Option<InboundSubstream>>>;
+
+    /// Returns a value that is unique to this connection and known to both
+    /// endpoints (e.g., exported from the TLS session). Authenticating it with
+    /// the noise session ensures that the substreams belong to the same
+    /// connection as the authenticated primary stream.
+    fn channel_binding(&self) -> io::Result<[u8; CHANNEL_BINDING_LENGTH]>;
+}
+
+/// A socket that may carry additional substreams.
+pub trait MultiplexedSocket {
+    /// Returns the substream multiplexer of this connection, if supported.
+    fn multiplexer(&self) -> Option<Arc<dyn StreamMultiplexer>> {
+        None
+    }
+}
+
+impl MultiplexedSocket for aptos_memsocket::MemorySocket {}
+
+impl MultiplexedSocket for crate::transport::tcp::TcpSocket {}
+
+#[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+pub use memory::{MemoryMultiplexedSocket, MemoryMultiplexer};
+
+#[cfg(any(test, feature = "testing", feature = "fuzzing"))]
+mod memory {
+    use super::{
+        InboundSubstream, MultiplexedSocket, OutboundSubstream, StreamMultiplexer,
+        CHANNEL_BINDING_LENGTH,
+    };
+    use aptos_memsocket::MemorySocket;
+    use futures::{
+        channel::mpsc,
+        future::{BoxFuture, FutureExt},
+        io::{AsyncRead, AsyncWrite},
+        lock::Mutex,
+        stream::StreamExt,
+    };
+    use std::{
+        io,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+
+    /// Used to give every in-memory connection a distinct channel binding
+    static NEXT_CHANNEL_BINDING: AtomicU64 = AtomicU64::new(0);
+
+    /// An in-memory [`StreamMultiplexer`], where every substream is a [`MemorySocket`].
+    #[derive(Debug)]
+    pub struct MemoryMultiplexer {
+        outbound_streams: mpsc::UnboundedSender<MemorySocket>,
+        inbound_streams: Mutex<mpsc::UnboundedReceiver<MemorySocket>>,
+        channel_binding: [u8; CHANNEL_BINDING_LENGTH],
+    }
+
+    impl MemoryMultiplexer {
+        /// Construct both sides of an in-memory multiplexer.
+        pub fn new_pair() -> (Self, Self) {
+            let mut channel_binding = [0u8; CHANNEL_BINDING_LENGTH];
+            let id = NEXT_CHANNEL_BINDING.fetch_add(1, Ordering::Relaxed);
+            channel_binding[..8].copy_from_slice(&id.to_le_bytes());
+            Self::new_pair_with_bindings(channel_binding, channel_binding)
+        }
+
+        /// Construct both sides of an in-memory multiplexer with the given
+        /// channel bindings. Mismatching bindings emulate an attacker relaying
+        /// the primary stream between two distinct connections.
+        pub fn new_pair_with_bindings(
+            binding_a: [u8; CHANNEL_BINDING_LENGTH],
+            binding_b: [u8; CHANNEL_BINDING_LENGTH],
+        ) -> (Self, Self) {
+            let (a_tx, a_rx) = mpsc::unbounded();
+            let (b_tx, b_rx) = mpsc::unbounded();
+            let a = Self {
+                outbound_streams: b_tx,
+                inbound_streams: Mutex::new(a_rx),
+                channel_binding: binding_a,
+            };
+            let b = Self {
+                outbound_streams: a_tx,
+                inbound_streams: Mutex::new(b_rx),
+                channel_binding: binding_b,
+            };
+            (a, b)
+        }
+    }
+
+    impl StreamMultiplexer for MemoryMultiplexer {
+        fn open_stream(&self) -> BoxFuture<'_, io::Result<OutboundSubstream>> {
+            let (local, remote) = MemorySocket::new_pair();
+            let result = self
+                .outbound_streams
+                .unbounded_send(remote)
+                .map(|()| Box::pin(local) as OutboundSubstream)
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe));
+            futures::future::ready(result).boxed()
+        }
+
+        fn accept_stream(&self) -> BoxFuture<'_, io::Result<Option<InboundSubstream>>> {
+            async move {
+                let stream = self.inbound_streams.lock().await.next().await;
+                Ok(stream.map(|stream| Box::pin(stream) as InboundSubstream))
+            }
+            .boxed()
+        }
+
+        fn channel_binding(&self) -> io::Result<[u8; CHANNEL_BINDING_LENGTH]> {
+            Ok(self.channel_binding)
+        }
+    }
+
+    /// A [`MemorySocket`] that also carries in-memory substreams, emulating a
+    /// QUIC connection in tests.
+    #[derive(Debug)]
+    pub struct MemoryMultiplexedSocket {
+        socket: MemorySocket,
+        multiplexer: Arc<MemoryMultiplexer>,
+    }
+
+    impl MemoryMultiplexedSocket {
+        /// Construct both sides of an in-memory multiplexed socket.
+        pub fn new_pair() -> (Self, Self) {
+            let (multiplexer_a, multiplexer_b) = MemoryMultiplexer::new_pair();
+            Self::from_multiplexers(multiplexer_a, multiplexer_b)
+        }
+
+        /// Construct both sides of an in-memory multiplexed socket on top of
+        /// the given multiplexers.
+        pub fn from_multiplexers(
+            multiplexer_a: MemoryMultiplexer,
+            multiplexer_b: MemoryMultiplexer,
+        ) -> (Self, Self) {
+            let (socket_a, socket_b) = MemorySocket::new_pair();
+            let a = Self {
+                socket: socket_a,
+                multiplexer: Arc::new(multiplexer_a),
+            };
+            let b = Self {
+                socket: socket_b,
+                multiplexer: Arc::new(multiplexer_b),
+            };
+            (a, b)
+        }
+    }
+
+    impl MultiplexedSocket for MemoryMultiplexedSocket {
+        fn multiplexer(&self) -> Option<Arc<dyn StreamMultiplexer>> {
+            Some(self.multiplexer.clone())
+        }
+    }
+
+    impl AsyncRead for MemoryMultiplexedSocket {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            context: &mut Context,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.socket).poll_read(context, buf)
+        }
+    }
+
+    impl AsyncWrite for MemoryMultiplexedSocket {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            context: &mut Context,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.socket).poll_write(context, buf)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.socket).poll_flush(context)
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.socket).poll_close(context)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{
+        executor::block_on,
+        io::{AsyncReadExt, AsyncWriteExt},
+    };
+
+    #[test]
+    fn memory_substreams() {
+        let (socket_a, socket_b) = MemoryMultiplexedSocket::new_pair();
+        let multiplexer_a = socket_a.multiplexer().unwrap();
+        let multiplexer_b = socket_b.multiplexer().unwrap();
+        assert_eq!(
+            multiplexer_a.channel_binding().unwrap(),
+            multiplexer_b.channel_binding().unwrap()
+        );
+
+        block_on(async move {
+            let mut first = multiplexer_a.open_stream().await.unwrap();
+            let mut second = multiplexer_a.open_stream().await.unwrap();
+            second.write_all(b"second").await.unwrap();
+            second.close().await.unwrap();
+            first.write_all(b"first").await.unwrap();
+            first.close().await.unwrap();
+
+            // Substreams are accepted in the order they were opened
+            for expected in [&b"first"[..], &b"second"[..]] {
+                let mut stream = multiplexer_b.accept_stream().await.unwrap().unwrap();
+                let mut buf = Vec::new();
+                stream.read_to_end(&mut buf).await.unwrap();
+                assert_eq!(buf, expected);
+            }
+        });
+    }
+
+    #[test]
+    fn memory_channel_bindings_are_unique() {
+        let (socket_a, _) = MemoryMultiplexedSocket::new_pair();
+        let (socket_b, _) = MemoryMultiplexedSocket::new_pair();
+        assert_ne!(
+            socket_a.multiplexer().unwrap().channel_binding().unwrap(),
+            socket_b.multiplexer().unwrap().channel_binding().unwrap()
+        );
+    }
+}
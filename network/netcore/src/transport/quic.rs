@@ -0,0 +1,425 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! QUIC Transport
+//!
+//! Every connection has a primary bidirectional stream, which is exposed as the
+//! [`QuicSocket`] byte stream, and any number of unidirectional substreams that
+//! are exposed through its [`StreamMultiplexer`].
+//!
+//! QUIC requires TLS 1.3, but peers are not authenticated by TLS: every endpoint
+//! uses an ephemeral self-signed certificate, which isn't verified by the dialer.
+//! Peers are instead authenticated by the noise handshake on the primary stream,
+//! which must then authenticate the connection's
+//! [`channel_binding`](StreamMultiplexer::channel_binding) to ensure the
+//! substreams belong to the same (end-to-end) connection.
+
+use crate::{
+    substreams::{
+        InboundSubstream, MultiplexedSocket, OutboundSubstream, StreamMultiplexer,
+        CHANNEL_BINDING_LENGTH,
+    },
+    transport::{
+        tcp::{invalid_addr_error, resolve_with_filter},
+        Transport,
+    },
+};
+use aptos_types::{
+    network_address::{parse_dns_quic, parse_ip_quic, NetworkAddress},
+    PeerId,
+};
+use futures::{
+    future::{BoxFuture, FutureExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    stream::{self, BoxStream, StreamExt},
+};
+use quinn::{ConnectionError, Endpoint, RecvStream, SendStream};
+use std::{
+    fmt, io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+/// The ALPN protocol identifier for AptosNet over QUIC
+const ALPN_PROTOCOL: &[u8] = b"aptosnet";
+
+/// The server name of the (unverified) self-signed certificates
+const SERVER_NAME: &str = "aptosnet";
+
+/// The label used to export the channel binding from the TLS session
+const CHANNEL_BINDING_LABEL: &[u8] = b"EXPORTER-aptosnet-channel-binding";
+
+/// The dialer writes this byte when opening the primary stream, as QUIC streams
+/// only become visible to the remote peer once data has been sent on them.
+const PRIMARY_STREAM_PREAMBLE: u8 = 0;
+
+/// The interval at which keep-alive packets are sent on idle connections
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Transport to build QUIC connections
+#[derive(Debug, Clone, Default)]
+pub struct QuicTransport;
+
+impl Transport for QuicTransport {
+    type Error = io::Error;
+    type Inbound = BoxFuture<'static, io::Result<QuicSocket>>;
+    type Listener = BoxStream<'static, io::Result<(Self::Inbound, NetworkAddress)>>;
+    type Outbound = BoxFuture<'static, io::Result<QuicSocket>>;
+    type Output = QuicSocket;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        let ((ipaddr, port), addr_suffix) =
+            parse_ip_quic(addr.as_slice()).ok_or_else(|| invalid_addr_error(&addr))?;
+        if !addr_suffix.is_empty() {
+            return Err(invalid_addr_error(&addr));
+        }
+
+        let endpoint = Endpoint::server(server_config()?, SocketAddr::new(ipaddr, port))?;
+        let listen_addr = quic_address(endpoint.local_addr()?);
+
+        let listener = stream::unfold(endpoint, |endpoint| async move {
+            let connecting = endpoint.accept().await?;
+            let dialer_addr = quic_address(connecting.remote_address());
+            let inbound = async move {
+                let connection = connecting.await.map_err(connection_error)?;
+                let (send, mut recv) = connection.accept_bi().await.map_err(connection_error)?;
+                let mut preamble = [0u8; 1];
+                AsyncReadExt::read_exact(&mut recv, &mut preamble).await?;
+                if preamble[0] != PRIMARY_STREAM_PREAMBLE {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Unexpected QUIC primary stream preamble",
+                    ));
+                }
+                Ok(QuicSocket::new(connection, send, recv))
+            };
+            Some((Ok((inbound.boxed(), dialer_addr)), endpoint))
+        });
+
+        Ok((listener.boxed(), listen_addr))
+    }
+
+    fn dial(&self, _peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        let protos = addr.as_slice();
+
+        // ensure addr is well formed to save some work before potentially
+        // spawning a dial task that will fail anyway.
+        parse_ip_quic(protos)
+            .map(|_| ())
+            .or_else(|| parse_dns_quic(protos).map(|_| ()))
+            .ok_or_else(|| invalid_addr_error(&addr))?;
+
+        Ok(resolve_and_connect(addr).boxed())
+    }
+}
+
+/// Resolves the given `/../udp/<port>/quic` address and connects to the first
+/// socket address that accepts the connection.
+async fn resolve_and_connect(addr: NetworkAddress) -> io::Result<QuicSocket> {
+    let protos = addr.as_slice();
+
+    if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_quic(protos) {
+        connect(SocketAddr::new(ipaddr, port)).await
+    } else if let Some(((ip_filter, dns_name, port), _addr_suffix)) = parse_dns_quic(protos) {
+        let socketaddr_iter = resolve_with_filter(ip_filter, dns_name.as_ref(), port).await?;
+        let mut last_err = None;
+
+        // try to connect until the first succeeds
+        for socketaddr in socketaddr_iter {
+            match connect(socketaddr).await {
+                Ok(socket) => return Ok(socket),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "could not resolve dns name to any address: name: {}, ip filter: {:?}",
+                    dns_name.as_ref(),
+                    ip_filter,
+                ),
+            )
+        }))
+    } else {
+        Err(invalid_addr_error(&addr))
+    }
+}
+
+async fn connect(remote_addr: SocketAddr) -> io::Result<QuicSocket> {
+    let bind_addr = if remote_addr.is_ipv4() {
+        SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)
+    } else {
+        SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+    };
+    let mut endpoint = Endpoint::client(bind_addr)?;
+    endpoint.set_default_client_config(client_config()?);
+
+    let connection = endpoint
+        .connect(remote_addr, SERVER_NAME)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+        .await
+        .map_err(connection_error)?;
+    let (mut send, recv) = connection.open_bi().await.map_err(connection_error)?;
+    AsyncWriteExt::write_all(&mut send, &[PRIMARY_STREAM_PREAMBLE]).await?;
+    Ok(QuicSocket::new(connection, send, recv))
+}
+
+fn quic_address(addr: SocketAddr) -> NetworkAddress {
+    use aptos_types::network_address::Protocol;
+
+    NetworkAddress::from_protocols(vec![
+        Protocol::from(addr.ip()),
+        Protocol::Udp(addr.port()),
+        Protocol::Quic,
+    ])
+    .expect("ip/udp/quic is a valid network address")
+}
+
+fn connection_error(err: ConnectionError) -> io::Error {
+    let kind = match err {
+        ConnectionError::TimedOut => io::ErrorKind::TimedOut,
+        ConnectionError::Reset => io::ErrorKind::ConnectionReset,
+        ConnectionError::ApplicationClosed(_)
+        | ConnectionError::ConnectionClosed(_)
+        | ConnectionError::LocallyClosed => io::ErrorKind::ConnectionAborted,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
+
+fn other_error<E: fmt::Display>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.to_string())
+}
+
+fn transport_config() -> Arc<quinn::TransportConfig> {
+    let mut transport = quinn::TransportConfig::default();
+    transport.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    Arc::new(transport)
+}
+
+fn server_config() -> io::Result<quinn::ServerConfig> {
+    let cert =
+        rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()]).map_err(other_error)?;
+    let cert_der = cert.serialize_der().map_err(other_error)?;
+    let key_der = cert.serialize_private_key_der();
+
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(other_error)?
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![rustls::Certificate(cert_der)],
+            rustls::PrivateKey(key_der),
+        )
+        .map_err(other_error)?;
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+    let mut config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    config.transport = transport_config();
+    Ok(config)
+}
+
+fn client_config() -> io::Result<quinn::ClientConfig> {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(other_error)?
+        .with_custom_certificate_verifier(Arc::new(UnverifiedServerCertificate))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+    let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+    config.transport_config(transport_config());
+    Ok(config)
+}
+
+/// Accepts any server certificate. The remote peer is authenticated by the
+/// noise handshake instead (see the module documentation).
+struct UnverifiedServerCertificate;
+
+impl rustls::client::ServerCertVerifier for UnverifiedServerCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// The [`StreamMultiplexer`] of a QUIC connection
+#[derive(Debug)]
+pub struct QuicMultiplexer {
+    connection: quinn::Connection,
+}
+
+impl StreamMultiplexer for QuicMultiplexer {
+    fn open_stream(&self) -> BoxFuture<'_, io::Result<OutboundSubstream>> {
+        async move {
+            let stream = self.connection.open_uni().await.map_err(connection_error)?;
+            Ok(Box::pin(stream) as OutboundSubstream)
+        }
+        .boxed()
+    }
+
+    fn accept_stream(&self) -> BoxFuture<'_, io::Result<Option<InboundSubstream>>> {
+        async move {
+            match self.connection.accept_uni().await {
+                Ok(stream) => Ok(Some(Box::pin(stream) as InboundSubstream)),
+                Err(ConnectionError::ApplicationClosed(_))
+                | Err(ConnectionError::LocallyClosed) => Ok(None),
+                Err(err) => Err(connection_error(err)),
+            }
+        }
+        .boxed()
+    }
+
+    fn channel_binding(&self) -> io::Result<[u8; CHANNEL_BINDING_LENGTH]> {
+        let mut channel_binding = [0u8; CHANNEL_BINDING_LENGTH];
+        self.connection
+            .export_keying_material(&mut channel_binding, CHANNEL_BINDING_LABEL, &[])
+            .map_err(|err| other_error(format!("{:?}", err)))?;
+        Ok(channel_binding)
+    }
+}
+
+/// The primary stream of a QUIC connection
+pub struct QuicSocket {
+    send: SendStream,
+    recv: RecvStream,
+    multiplexer: Arc<QuicMultiplexer>,
+}
+
+impl QuicSocket {
+    fn new(connection: quinn::Connection, send: SendStream, recv: RecvStream) -> Self {
+        Self {
+            send,
+            recv,
+            multiplexer: Arc::new(QuicMultiplexer { connection }),
+        }
+    }
+}
+
+impl fmt::Debug for QuicSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicSocket")
+            .field(
+                "remote_address",
+                &self.multiplexer.connection.remote_address(),
+            )
+            .finish()
+    }
+}
+
+impl MultiplexedSocket for QuicSocket {
+    fn multiplexer(&self) -> Option<Arc<dyn StreamMultiplexer>> {
+        Some(self.multiplexer.clone())
+    }
+}
+
+impl AsyncRead for QuicSocket {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.recv).poll_read(context, buf)
+    }
+}
+
+impl AsyncWrite for QuicSocket {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(context, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(context)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_close(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::future::join;
+
+    #[tokio::test]
+    async fn simple_listen_and_dial() -> Result<(), io::Error> {
+        let t = QuicTransport::default();
+
+        let (mut listener, addr) = t.listen_on("/ip4/127.0.0.1/udp/0/quic".parse().unwrap())?;
+        let dial = t.dial(PeerId::random(), addr)?;
+        let listener = async move {
+            let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+            inbound.await
+        };
+        let (outbound, inbound) = join(dial, listener).await;
+        let (mut outbound, mut inbound) = (outbound?, inbound?);
+
+        // The primary stream works in both directions
+        outbound.write_all(b"Earth").await?;
+        outbound.flush().await?;
+        let mut buf = [0; 5];
+        inbound.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Earth");
+        inbound.write_all(b"Air").await?;
+        inbound.flush().await?;
+        let mut buf = [0; 3];
+        outbound.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Air");
+
+        // Substreams are independent of the primary stream
+        let outbound_multiplexer = outbound.multiplexer().unwrap();
+        let inbound_multiplexer = inbound.multiplexer().unwrap();
+        let mut substream = outbound_multiplexer.open_stream().await?;
+        substream.write_all(b"Fire").await?;
+        substream.close().await?;
+        let mut substream = inbound_multiplexer.accept_stream().await?.unwrap();
+        let mut buf = Vec::new();
+        substream.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"Fire");
+
+        // Both endpoints agree on the channel binding
+        assert_eq!(
+            outbound_multiplexer.channel_binding()?,
+            inbound_multiplexer.channel_binding()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_multiaddrs() {
+        let t = QuicTransport::default();
+
+        let result = t.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap());
+        assert!(result.is_err());
+
+        let peer_id = PeerId::random();
+        let result = t.dial(peer_id, "/ip4/127.0.0.1/tcp/22".parse().unwrap());
+        assert!(result.is_err());
+        let result = t.dial(peer_id, "/memory/22".parse().unwrap());
+        assert!(result.is_err());
+    }
+}
@@ -264,6 +264,48 @@ pub fn inbound_rpc_handler_latency(
     ])
 }
 
+pub static APTOS_NETWORK_OUTBOUND_QUEUEING_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_outbound_queueing_delay_seconds",
+        "Time outbound messages spend queued in the peer actor before being written",
+        &["role_type", "network_id", "peer_id", "protocol_id"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_queueing_delay(
+    network_context: &NetworkContext,
+    protocol_id: ProtocolId,
+) -> Histogram {
+    APTOS_NETWORK_OUTBOUND_QUEUEING_DELAY.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_id.as_str(),
+    ])
+}
+
+pub static APTOS_NETWORK_PENDING_OUTBOUND_MESSAGES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_network_pending_outbound_messages",
+        "Number of outbound messages queued in the peer actors, by protocol",
+        &["role_type", "network_id", "peer_id", "protocol_id"]
+    )
+    .unwrap()
+});
+
+pub fn pending_outbound_messages(
+    network_context: &NetworkContext,
+    protocol_id: ProtocolId,
+) -> IntGauge {
+    APTOS_NETWORK_PENDING_OUTBOUND_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_id.as_str(),
+    ])
+}
+
 pub static APTOS_NETWORK_DIRECT_SEND_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_network_direct_send_messages",
@@ -415,6 +457,15 @@ pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of messages read from substreams, pending to be handled by the peer actor
+pub static PENDING_SUBSTREAM_MESSAGES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_network_pending_substream_messages",
+        "Number of pending inbound substream messages"
+    )
+    .unwrap()
+});
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
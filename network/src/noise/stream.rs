@@ -11,6 +11,7 @@
 
 use aptos_crypto::{noise, x25519};
 use aptos_logger::prelude::*;
+use aptos_netcore::substreams::{MultiplexedSocket, StreamMultiplexer};
 use futures::{
     io::{AsyncRead, AsyncWrite},
     ready,
@@ -19,6 +20,7 @@ use std::{
     convert::TryInto,
     io,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -396,6 +398,17 @@ where
     }
 }
 
+/// Substreams are not encrypted by noise. They are only secured by the underlying
+/// transport (see [`StreamMultiplexer::channel_binding`]).
+impl<TSocket> MultiplexedSocket for NoiseStream<TSocket>
+where
+    TSocket: MultiplexedSocket,
+{
+    fn multiplexer(&self) -> Option<Arc<dyn StreamMultiplexer>> {
+        self.socket.multiplexer()
+    }
+}
+
 //
 // NoiseBuffers
 // ------------
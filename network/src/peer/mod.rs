@@ -13,6 +13,10 @@
 //! [`Peer`] owns the actual underlying connection socket and is reponsible for
 //! the socket's shutdown, graceful or otherwise.
 //!
+//! Outbound messages are written in priority order (see [`MessagePriority`]),
+//! so that, e.g., consensus messages are not delayed by large state sync
+//! responses on the same connection.
+//!
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
@@ -35,8 +39,9 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::aptos_channel;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{config::ProtocolRateLimitConfig, network_id::NetworkContext};
 use aptos_logger::prelude::*;
+use aptos_netcore::substreams::{MultiplexedSocket, StreamMultiplexer};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::PeerId;
@@ -46,22 +51,36 @@ use futures::{
     channel::oneshot,
     io::{AsyncRead, AsyncWrite},
     stream::StreamExt,
-    SinkExt,
+    FutureExt, SinkExt,
 };
 use futures_util::stream::select;
+pub use outbound_queue::{MessagePriority, OutboundMessage};
+use outbound_queue::{NextMessage, OutboundQueue};
 use serde::Serialize;
-use std::{fmt, panic, time::Duration};
+use std::{collections::HashMap, fmt, panic, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod outbound_queue;
+mod substreams;
 #[cfg(test)]
 mod test;
 
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
 
+/// The maximum number of (non-streamed) messages buffered between the outbound
+/// queue and the socket writer. Kept small so that messages are prioritized
+/// while they wait for the socket, rather than after.
+const MAX_PENDING_WRITER_MESSAGES: usize = 16;
+
+/// The maximum number of (non-streamed) messages buffered for the writer of a
+/// single substream. Substreams are independent, so this is large enough that a
+/// slow substream doesn't hold up the messages of the other protocols.
+const MAX_PENDING_SUBSTREAM_WRITER_MESSAGES: usize = 1024;
+
 /// Requests [`Peer`] receives from the [`PeerManager`](crate::peer_manager::PeerManager).
 #[derive(Debug)]
 pub enum PeerRequest {
@@ -138,11 +157,13 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// Outbound byte rate limits for individual protocols
+    outbound_rate_limits: HashMap<ProtocolId, ProtocolRateLimitConfig>,
 }
 
 impl<TSocket> Peer<TSocket>
 where
-    TSocket: AsyncRead + AsyncWrite + Send + 'static,
+    TSocket: AsyncRead + AsyncWrite + MultiplexedSocket + Send + 'static,
 {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -158,6 +179,7 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_rate_limits: HashMap<ProtocolId, ProtocolRateLimitConfig>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -191,6 +213,7 @@ where
             max_frame_size,
             max_message_size,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            outbound_rate_limits,
         }
     }
 
@@ -209,8 +232,9 @@ where
         );
 
         // Split the connection into a ReadHalf and a WriteHalf.
-        let (read_socket, write_socket) =
-            tokio::io::split(self.connection.take().unwrap().compat());
+        let socket = self.connection.take().unwrap();
+        let multiplexer = socket.multiplexer();
+        let (read_socket, write_socket) = tokio::io::split(socket.compat());
 
         let mut reader =
             MultiplexMessageStream::new(read_socket.compat(), self.max_frame_size).fuse();
@@ -218,7 +242,7 @@ where
 
         // Start writer "process" as a separate task. We receive two handles to
         // communicate with the task:
-        //   1. `write_reqs_tx`: Queue of pending NetworkMessages to write.
+        //   1. `write_reqs_tx`: Queue of pending OutboundMessages to write.
         //   2. `close_tx`: Handle to close the task and underlying connection.
         let (mut write_reqs_tx, writer_close_tx) = Self::start_writer_task(
             &self.executor,
@@ -226,10 +250,33 @@ where
             self.connection_metadata.clone(),
             self.network_context,
             writer,
+            multiplexer.clone(),
             self.max_frame_size,
             self.max_message_size,
+            &self.outbound_rate_limits,
         );
 
+        // If the connection supports substreams, start reading the messages the
+        // remote peer writes to them. Otherwise, there are no substream messages.
+        let (substream_messages, substream_reader_handle) = match multiplexer {
+            Some(multiplexer) => {
+                let (messages, handle) = substreams::start_substream_reader_task(
+                    &self.executor,
+                    self.network_context,
+                    self.connection_metadata.clone(),
+                    multiplexer,
+                    self.max_frame_size,
+                    self.max_message_size / self.max_frame_size,
+                );
+                (messages, Some(handle))
+            },
+            None => {
+                let (_, messages) = aptos_channels::new(1, &counters::PENDING_SUBSTREAM_MESSAGES);
+                (messages, None)
+            },
+        };
+        let mut substream_messages = substream_messages.fuse();
+
         // Start main Peer event loop.
         let reason = loop {
             if let State::ShuttingDown(reason) = self.state {
@@ -267,6 +314,24 @@ where
                         None => self.shutdown(DisconnectReason::ConnectionLost),
                     }
                 },
+                // Handle a new inbound MultiplexMessage that we've read off a
+                // substream of the connection. The connection itself is only
+                // considered closed once the primary stream is closed.
+                maybe_message = substream_messages.next() => {
+                    if let Some(message) = maybe_message {
+                        if let Err(err) = self.handle_inbound_message(message, &mut write_reqs_tx).await {
+                            warn!(
+                                NetworkSchema::new(&self.network_context)
+                                    .connection_metadata(&self.connection_metadata),
+                                error = %err,
+                                "{} Error in handling inbound substream message from peer: {}, error: {}",
+                                self.network_context,
+                                remote_peer_id.short_str(),
+                                err
+                            );
+                        }
+                    }
+                },
                 // Drive the queue of pending inbound rpcs. When one is fulfilled
                 // by an upstream protocol, send the response to the remote peer.
                 maybe_response = self.inbound_rpcs.next_completed_response() => {
@@ -286,6 +351,11 @@ where
             }
         };
 
+        // Stop reading substreams, which drops them along with the connection.
+        if let Some(handle) = substream_reader_handle {
+            handle.abort();
+        }
+
         // Finish shutting down the connection. Close the writer task and notify
         // PeerManager that this connection has shutdown.
         self.do_shutdown(writer_close_tx, reason).await;
@@ -294,100 +364,107 @@ where
     // Start a new task on the given executor which is responsible for writing outbound messages on
     // the wire. The function returns two channels which can be used to send instructions to the
     // task:
-    // 1. The first channel is used to send outbound NetworkMessages to the task
+    // 1. The first channel is used to send outbound messages to the task
     // 2. The second channel is used to instruct the task to close the connection and terminate.
-    // If outbound messages are queued when the task receives a close instruction, it discards
-    // them and immediately closes the connection.
+    // Outbound messages are buffered in an `OutboundQueue` and written in priority order, subject
+    // to the per-protocol rate limits. If outbound messages are queued when the task receives a
+    // close instruction, it discards them and immediately closes the connection.
+    // If the connection supports substreams, the messages of every protocol are written to a
+    // dedicated substream (opened on the first message), so that the protocols don't block each
+    // other on the wire. Control messages are always written to the primary stream.
+    #[allow(clippy::too_many_arguments)]
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: NetworkContext,
-        mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        multiplexer: Option<Arc<dyn StreamMultiplexer>>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (aptos_channels::Sender<NetworkMessage>, oneshot::Sender<()>) {
+        outbound_rate_limits: &HashMap<ProtocolId, ProtocolRateLimitConfig>,
+    ) -> (aptos_channels::Sender<OutboundMessage>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<NetworkMessage>, _) =
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<OutboundMessage>, _) =
             aptos_channels::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, mut close_rx) = oneshot::channel();
 
-        let (mut msg_tx, msg_rx) = aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
-        let (stream_msg_tx, stream_msg_rx) =
-            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
-
-        // this task ends when the multiplex task ends (by dropping the senders)
-        let writer_task = async move {
-            let mut stream = select(msg_rx, stream_msg_rx);
-            let log_context =
-                NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
-            while let Some(message) = stream.next().await {
-                if let Err(err) = writer.send(&message).await {
-                    warn!(
-                        log_context,
-                        error = %err,
-                        "{} Error in sending message to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str(),
-                    );
-                }
-            }
-            info!(
-                log_context,
-                "{} Closing connection to peer: {}",
-                network_context,
-                remote_peer_id.short_str()
-            );
-            let flush_and_close = async {
-                writer.flush().await?;
-                writer.close().await?;
-                Ok(()) as Result<(), WriteError>
-            };
-            match time_service
-                .timeout(transport::TRANSPORT_TIMEOUT, flush_and_close)
-                .await
-            {
-                Err(_) => {
-                    info!(
-                        log_context,
-                        "{} Timeout in flush/close of connection to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str()
-                    );
-                },
-                Ok(Err(err)) => {
-                    info!(
-                        log_context,
-                        error = %err,
-                        "{} Failure in flush/close of connection to peer: {}, error: {}",
-                        network_context,
-                        remote_peer_id.short_str(),
-                        err
-                    );
-                },
-                Ok(Ok(())) => {
-                    info!(
-                        log_context,
-                        "{} Closed connection to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str()
-                    );
-                },
-            }
-        };
+        // The multiplex task only hands a few messages at a time to the writer task, so
+        // that messages remain in the (prioritized) outbound queue while the socket is busy.
+        let mut main_writer = MessageWriter::spawn(
+            executor,
+            time_service.clone(),
+            connection_metadata.clone(),
+            network_context,
+            writer,
+            max_frame_size,
+            max_message_size,
+            MAX_PENDING_WRITER_MESSAGES,
+        );
+        let mut outbound_queue = OutboundQueue::new(
+            network_context,
+            remote_peer_id,
+            outbound_rate_limits,
+            time_service.clone(),
+        );
+        let executor_clone = executor.clone();
         let multiplex_task = async move {
-            let mut outbound_stream =
-                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
+            // The substream writer of every protocol. `None` if the substream
+            // couldn't be opened, in which case the protocol uses the primary
+            // stream (for the rest of the connection, to preserve message order).
+            let mut substream_writers: HashMap<ProtocolId, Option<MessageWriter>> = HashMap::new();
             loop {
-                futures::select! {
-                    message = write_reqs_rx.select_next_some() => {
-                        // either channel full would block the other one
-                        let result = if outbound_stream.should_stream(&message) {
-                            outbound_stream.stream_message(message).await
-                        } else {
-                            msg_tx.send(MultiplexMessage::Message(message)).await.map_err(|_| anyhow::anyhow!("Writer task ended"))
+                // Move all messages that are already waiting into the outbound queue,
+                // so that the next write picks the most important one.
+                while let Some(Some(message)) = write_reqs_rx.next().now_or_never() {
+                    outbound_queue.push(message);
+                }
+
+                let throttle_delay = match outbound_queue.pop() {
+                    NextMessage::Ready(protocol_id, message) => {
+                        // Stop writing as soon as we're asked to close the connection
+                        if !matches!(close_rx.try_recv(), Ok(None)) {
+                            break;
+                        }
+
+                        let writer = match (protocol_id, &multiplexer) {
+                            (Some(protocol_id), Some(multiplexer)) => {
+                                if !substream_writers.contains_key(&protocol_id) {
+                                    let substream_writer = match multiplexer.open_stream().await {
+                                        Ok(substream) => Some(MessageWriter::spawn(
+                                            &executor_clone,
+                                            time_service.clone(),
+                                            connection_metadata.clone(),
+                                            network_context,
+                                            MultiplexMessageSink::new(substream, max_frame_size),
+                                            max_frame_size,
+                                            max_message_size,
+                                            MAX_PENDING_SUBSTREAM_WRITER_MESSAGES,
+                                        )),
+                                        Err(err) => {
+                                            warn!(
+                                                NetworkSchema::new(&network_context)
+                                                    .connection_metadata(&connection_metadata),
+                                                error = %err,
+                                                "{} Failed to open substream for protocol {} to peer: {}, error: {}",
+                                                network_context,
+                                                protocol_id,
+                                                remote_peer_id.short_str(),
+                                                err
+                                            );
+                                            None
+                                        },
+                                    };
+                                    substream_writers.insert(protocol_id, substream_writer);
+                                }
+                                substream_writers
+                                    .get_mut(&protocol_id)
+                                    .and_then(Option::as_mut)
+                                    .unwrap_or(&mut main_writer)
+                            },
+                            _ => &mut main_writer,
                         };
-                        if let Err(err) = result {
+                        if let Err(err) = writer.write(message).await {
                             warn!(
                                 error = %err,
                                 "{} Error in sending message to peer: {}",
@@ -395,14 +472,30 @@ where
                                 remote_peer_id.short_str(),
                             );
                         }
+                        continue;
+                    },
+                    NextMessage::Throttled(delay) => Some(delay),
+                    NextMessage::Empty => None,
+                };
+
+                // Nothing can be written right now. Wait for a new message, the end of
+                // the rate limiting delay (if any), or the close instruction.
+                let throttle_timeout = match throttle_delay {
+                    Some(delay) => time_service.sleep(delay).left_future(),
+                    None => futures::future::pending::<()>().right_future(),
+                };
+                futures::select! {
+                    message = write_reqs_rx.select_next_some() => {
+                        outbound_queue.push(message);
                     },
+                    _ = throttle_timeout.fuse() => {},
                     _ = close_rx => {
                         break;
                     }
                 }
             }
+            outbound_queue.clear();
         };
-        executor.spawn(writer_task);
         executor.spawn(multiplex_task);
         (write_reqs_tx, close_tx)
     }
@@ -466,7 +559,7 @@ where
     async fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -486,7 +579,7 @@ where
                     let message_type = frame_prefix.as_ref().first().unwrap_or(&0);
                     let protocol_id = frame_prefix.as_ref().get(1).unwrap_or(&0);
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
-                    let message = NetworkMessage::Error(error_code);
+                    let message = OutboundMessage::error(error_code);
 
                     write_reqs_tx.send(message).await?;
                     return Err(err.into());
@@ -547,7 +640,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -565,11 +658,14 @@ where
                     protocol_id,
                     message_len as u64,
                 );
-                let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+                let message = OutboundMessage::new(
                     protocol_id,
-                    priority: Priority::default(),
-                    raw_msg: Vec::from(message.mdata.as_ref()),
-                });
+                    NetworkMessage::DirectSendMsg(DirectSendMsg {
+                        protocol_id,
+                        priority: Priority::default(),
+                        raw_msg: Vec::from(message.mdata.as_ref()),
+                    }),
+                );
 
                 match write_reqs_tx.send(message).await {
                     Ok(_) => {
@@ -668,3 +764,114 @@ where
         );
     }
 }
+
+/// Writes messages to a single stream of the connection (the primary stream or
+/// a substream), through a separate writer task. Dropping the `MessageWriter`
+/// flushes and closes the stream.
+struct MessageWriter {
+    /// Channel to the writer task for messages that fit into a single frame
+    msg_tx: aptos_channels::Sender<MultiplexMessage>,
+    /// Fragments large messages into multiple frames
+    outbound_stream: OutboundStream,
+}
+
+impl MessageWriter {
+    /// Spawns the writer task for the given stream. At most `max_pending_messages`
+    /// (non-streamed) messages are buffered for the task.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn(
+        executor: &Handle,
+        time_service: TimeService,
+        connection_metadata: ConnectionMetadata,
+        network_context: NetworkContext,
+        mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        max_frame_size: usize,
+        max_message_size: usize,
+        max_pending_messages: usize,
+    ) -> Self {
+        let remote_peer_id = connection_metadata.remote_peer_id;
+        let (msg_tx, msg_rx) =
+            aptos_channels::new(max_pending_messages, &counters::PENDING_MULTIPLEX_MESSAGE);
+        let (stream_msg_tx, stream_msg_rx) =
+            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
+
+        // this task ends when the message writer is dropped (by dropping the senders)
+        let writer_task = async move {
+            let mut stream = select(msg_rx, stream_msg_rx);
+            let log_context =
+                NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
+            while let Some(message) = stream.next().await {
+                if let Err(err) = writer.send(&message).await {
+                    warn!(
+                        log_context,
+                        error = %err,
+                        "{} Error in sending message to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str(),
+                    );
+                }
+            }
+            info!(
+                log_context,
+                "{} Closing connection to peer: {}",
+                network_context,
+                remote_peer_id.short_str()
+            );
+            let flush_and_close = async {
+                writer.flush().await?;
+                writer.close().await?;
+                Ok(()) as Result<(), WriteError>
+            };
+            match time_service
+                .timeout(transport::TRANSPORT_TIMEOUT, flush_and_close)
+                .await
+            {
+                Err(_) => {
+                    info!(
+                        log_context,
+                        "{} Timeout in flush/close of connection to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str()
+                    );
+                },
+                Ok(Err(err)) => {
+                    info!(
+                        log_context,
+                        error = %err,
+                        "{} Failure in flush/close of connection to peer: {}, error: {}",
+                        network_context,
+                        remote_peer_id.short_str(),
+                        err
+                    );
+                },
+                Ok(Ok(())) => {
+                    info!(
+                        log_context,
+                        "{} Closed connection to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str()
+                    );
+                },
+            }
+        };
+        executor.spawn(writer_task);
+
+        Self {
+            msg_tx,
+            outbound_stream: OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx),
+        }
+    }
+
+    /// Hands the message to the writer task, fragmenting it if necessary
+    async fn write(&mut self, message: NetworkMessage) -> anyhow::Result<()> {
+        // either channel full would block the other one
+        if self.outbound_stream.should_stream(&message) {
+            self.outbound_stream.stream_message(message).await
+        } else {
+            self.msg_tx
+                .send(MultiplexMessage::Message(message))
+                .await
+                .map_err(|_| anyhow::anyhow!("Writer task ended"))
+        }
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The outbound queue of a [`Peer`](crate::peer::Peer) connection.
+//!
+//! All protocols share a single connection, so without any ordering a large
+//! state sync response could delay consensus messages written after it. To
+//! avoid this, pending messages are grouped into [`MessagePriority`] classes
+//! and the writer always picks the oldest message of the most important class
+//! that is ready to be sent. To keep sustained high priority traffic from
+//! starving the other classes (e.g., health checks, which would then time out
+//! and disconnect the peer), messages that have waited longer than
+//! [`MAX_QUEUEING_DELAY`] are written first, oldest first, regardless of their
+//! class. Protocols may additionally be limited to a maximum outbound byte rate
+//! (per connection) via [`ProtocolRateLimitConfig`].
+//!
+//! All times are read from the [`TimeService`], so that queueing and throttling
+//! can be tested with mock time.
+
+use crate::{
+    counters,
+    protocols::wire::messaging::v1::{ErrorCode, NetworkMessage},
+    ProtocolId,
+};
+use aptos_config::{config::ProtocolRateLimitConfig, network_id::NetworkContext};
+use aptos_rate_limiter::rate_limit::Bucket;
+use aptos_short_hex_str::AsShortHexStr;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::PeerId;
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// Label used for the outbound rate limiting metrics and logs
+const OUTBOUND_RATE_LIMIT_LABEL: &str = "outbound_protocol";
+
+/// Messages that have been queued for longer than this are written before all
+/// other protocol messages, regardless of their priority class
+pub const MAX_QUEUEING_DELAY: Duration = Duration::from_secs(1);
+
+/// The priority class of an outbound message. Classes are ordered from the
+/// most important (written first) to the least important.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MessagePriority {
+    Consensus,
+    Mempool,
+    StateSync,
+    Monitoring,
+}
+
+impl MessagePriority {
+    /// Returns the priority class of all messages for the given protocol
+    pub fn from_protocol_id(protocol_id: ProtocolId) -> Self {
+        match protocol_id {
+            ProtocolId::ConsensusRpcBcs
+            | ProtocolId::ConsensusDirectSendBcs
+            | ProtocolId::ConsensusDirectSendJson
+            | ProtocolId::ConsensusRpcJson
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::ConsensusDirectSendCompressed => MessagePriority::Consensus,
+            ProtocolId::MempoolDirectSend | ProtocolId::MempoolRpc => MessagePriority::Mempool,
+            ProtocolId::StateSyncDirectSend | ProtocolId::StorageServiceRpc => {
+                MessagePriority::StateSync
+            },
+            ProtocolId::DiscoveryDirectSend
+            | ProtocolId::HealthCheckerRpc
+            | ProtocolId::PeerMonitoringServiceRpc => MessagePriority::Monitoring,
+        }
+    }
+}
+
+/// A [`NetworkMessage`] waiting to be written to the wire, tagged with the
+/// protocol it belongs to (if any).
+#[derive(Debug)]
+pub struct OutboundMessage {
+    protocol_id: Option<ProtocolId>,
+    message: NetworkMessage,
+}
+
+impl OutboundMessage {
+    /// Creates a new outbound message for the given protocol
+    pub fn new(protocol_id: ProtocolId, message: NetworkMessage) -> Self {
+        Self {
+            protocol_id: Some(protocol_id),
+            message,
+        }
+    }
+
+    /// Creates a new outbound error message. Error messages don't belong to
+    /// any protocol and are always written before all other messages.
+    pub fn error(error_code: ErrorCode) -> Self {
+        Self {
+            protocol_id: None,
+            message: NetworkMessage::Error(error_code),
+        }
+    }
+
+    pub fn into_message(self) -> NetworkMessage {
+        self.message
+    }
+}
+
+/// The result of asking the [`OutboundQueue`] for the next message to write
+#[derive(Debug)]
+pub enum NextMessage {
+    /// The message should be written now. Carries the protocol of the message,
+    /// if any (control messages don't belong to a protocol).
+    Ready(Option<ProtocolId>, NetworkMessage),
+    /// All pending messages are rate limited. The queue should be polled again
+    /// after the given delay (or once a new message arrives).
+    Throttled(Duration),
+    /// There are no pending messages
+    Empty,
+}
+
+/// Pending outbound messages of a single connection, ordered by priority class
+pub struct OutboundQueue {
+    network_context: NetworkContext,
+    time_service: TimeService,
+    /// Messages that don't belong to a protocol (e.g., error codes)
+    control_messages: VecDeque<OutboundMessage>,
+    /// Protocol messages (with the time they were enqueued), grouped by
+    /// priority class and then by protocol. Each protocol queue is FIFO, so the
+    /// relative order of messages of a single protocol is always preserved.
+    protocol_messages:
+        BTreeMap<MessagePriority, HashMap<ProtocolId, VecDeque<(Instant, OutboundMessage)>>>,
+    /// Byte rate limiters for the protocols that have been configured with one
+    rate_limiters: HashMap<ProtocolId, Bucket>,
+}
+
+impl OutboundQueue {
+    pub fn new(
+        network_context: NetworkContext,
+        remote_peer_id: PeerId,
+        rate_limits: &HashMap<ProtocolId, ProtocolRateLimitConfig>,
+        time_service: TimeService,
+    ) -> Self {
+        let rate_limiters = rate_limits
+            .iter()
+            .map(|(protocol_id, config)| {
+                let bucket = Bucket::new(
+                    OUTBOUND_RATE_LIMIT_LABEL.to_string(),
+                    format!("{}:{}", network_context, remote_peer_id.short_str()),
+                    protocol_id.as_str().to_string(),
+                    config
+                        .byte_bucket_size
+                        .saturating_mul(config.initial_bucket_fill_percentage as usize)
+                        / 100,
+                    config.byte_bucket_size,
+                    config.byte_rate,
+                    Some(counters::NETWORK_RATE_LIMIT_METRICS.clone()),
+                )
+                .starting_at(time_service.now());
+                (*protocol_id, bucket)
+            })
+            .collect();
+
+        Self {
+            network_context,
+            time_service,
+            control_messages: VecDeque::new(),
+            protocol_messages: BTreeMap::new(),
+            rate_limiters,
+        }
+    }
+
+    /// Adds a new message to the back of its protocol queue
+    pub fn push(&mut self, message: OutboundMessage) {
+        match message.protocol_id {
+            Some(protocol_id) => {
+                self.protocol_messages
+                    .entry(MessagePriority::from_protocol_id(protocol_id))
+                    .or_default()
+                    .entry(protocol_id)
+                    .or_default()
+                    .push_back((self.time_service.now(), message));
+                counters::pending_outbound_messages(&self.network_context, protocol_id).inc();
+            },
+            None => self.control_messages.push_back(message),
+        }
+    }
+
+    /// Removes and returns the next message to write. Control messages are
+    /// always returned first. Otherwise, the oldest message that has waited for
+    /// longer than [`MAX_QUEUEING_DELAY`] is returned, or else the oldest
+    /// message of the highest priority class. Rate limited messages are skipped.
+    pub fn pop(&mut self) -> NextMessage {
+        if let Some(message) = self.control_messages.pop_front() {
+            return NextMessage::Ready(None, message.into_message());
+        }
+
+        // Order the head message of every protocol queue. Messages that have
+        // waited for too long form a class of their own (`None`), which is
+        // written before all others.
+        let now = self.time_service.now();
+        let mut candidates: Vec<(
+            Option<MessagePriority>,
+            Instant,
+            MessagePriority,
+            ProtocolId,
+        )> = self
+            .protocol_messages
+            .iter()
+            .flat_map(|(priority, queues)| {
+                queues.iter().filter_map(move |(protocol_id, queue)| {
+                    queue.front().map(|(enqueue_time, _)| {
+                        let aged =
+                            now.saturating_duration_since(*enqueue_time) >= MAX_QUEUEING_DELAY;
+                        let class = if aged { None } else { Some(*priority) };
+                        (class, *enqueue_time, *priority, *protocol_id)
+                    })
+                })
+            })
+            .collect();
+        candidates.sort_by_key(|(class, enqueue_time, _, _)| (*class, *enqueue_time));
+
+        let mut min_throttle_delay: Option<Duration> = None;
+        for (_, _, priority, protocol_id) in candidates {
+            let queue = self
+                .protocol_messages
+                .get_mut(&priority)
+                .and_then(|queues| queues.get_mut(&protocol_id))
+                .expect("Candidate protocol queue must exist!");
+            let message_len = queue
+                .front()
+                .expect("Candidate protocol queue must not be empty!")
+                .1
+                .message
+                .data_len();
+
+            // Check if the protocol is allowed to send the message now
+            if let Some(bucket) = self.rate_limiters.get_mut(&protocol_id) {
+                if let Err(ready_time) = acquire_tokens_for_message(bucket, message_len, now) {
+                    let delay = ready_time.saturating_duration_since(now);
+                    min_throttle_delay =
+                        Some(min_throttle_delay.map_or(delay, |min_delay| min(min_delay, delay)));
+                    continue;
+                }
+            }
+
+            let (enqueue_time, message) = queue
+                .pop_front()
+                .expect("Candidate protocol queue must not be empty!");
+            counters::pending_outbound_messages(&self.network_context, protocol_id).dec();
+            counters::outbound_queueing_delay(&self.network_context, protocol_id)
+                .observe(now.saturating_duration_since(enqueue_time).as_secs_f64());
+            return NextMessage::Ready(Some(protocol_id), message.into_message());
+        }
+
+        match min_throttle_delay {
+            Some(delay) => NextMessage::Throttled(delay),
+            None => NextMessage::Empty,
+        }
+    }
+
+    /// Drops all pending messages (e.g., because the connection is closing)
+    pub fn clear(&mut self) {
+        for queues in self.protocol_messages.values() {
+            for (protocol_id, queue) in queues {
+                counters::pending_outbound_messages(&self.network_context, *protocol_id)
+                    .sub(queue.len() as i64);
+            }
+        }
+        self.control_messages.clear();
+        self.protocol_messages.clear();
+    }
+}
+
+/// Acquires the tokens required to send a message of the given length. If the
+/// message is larger than the bucket itself, it is allowed through as soon as
+/// the bucket holds any tokens at all (which then drains the bucket), otherwise
+/// it would never be sent. On failure, returns the time at which to retry.
+fn acquire_tokens_for_message(
+    bucket: &mut Bucket,
+    message_len: usize,
+    now: Instant,
+) -> Result<(), Instant> {
+    match bucket.acquire_all_tokens_at(message_len, now) {
+        Ok(()) => Ok(()),
+        Err(Some(ready_time)) => Err(ready_time),
+        Err(None) => bucket
+            .acquire_tokens_at(message_len, now)
+            .map(|_| ())
+            .map_err(|_| bucket.time_of_next_refill()),
+    }
+}
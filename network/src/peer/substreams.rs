@@ -0,0 +1,135 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reading messages from the substreams of a connection.
+//!
+//! If the connection supports substreams (e.g., QUIC), the remote peer writes
+//! the messages of every protocol to a dedicated substream, so that a message
+//! delayed on one substream doesn't block the messages of other protocols. All
+//! substreams are read by a single task, which reassembles streamed messages
+//! (separately for every substream) and forwards them to the [`Peer`] actor.
+//!
+//! [`Peer`]: crate::peer::Peer
+
+use crate::{
+    counters,
+    logging::NetworkSchema,
+    protocols::{
+        stream::{InboundStreamBuffer, StreamMessage},
+        wire::messaging::v1::{MultiplexMessage, MultiplexMessageStream, ReadError},
+    },
+    transport::ConnectionMetadata,
+};
+use aptos_config::network_id::NetworkContext;
+use aptos_logger::prelude::*;
+use aptos_netcore::substreams::{InboundSubstream, StreamMultiplexer};
+use aptos_short_hex_str::AsShortHexStr;
+use futures::{
+    future::{self, AbortHandle, Abortable, FutureExt},
+    stream::{SelectAll, Stream, StreamExt},
+    SinkExt,
+};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// The maximum number of messages read from substreams that are waiting to be
+/// handled by the peer actor.
+const MAX_PENDING_SUBSTREAM_MESSAGES: usize = 1024;
+
+/// Spawns the task that accepts and reads all inbound substreams of a connection.
+/// Returns the stream of inbound messages and a handle to stop the task (which
+/// also drops all inbound substreams).
+pub fn start_substream_reader_task(
+    executor: &Handle,
+    network_context: NetworkContext,
+    connection_metadata: ConnectionMetadata,
+    multiplexer: Arc<dyn StreamMultiplexer>,
+    max_frame_size: usize,
+    max_fragments: usize,
+) -> (
+    aptos_channels::Receiver<Result<MultiplexMessage, ReadError>>,
+    AbortHandle,
+) {
+    let (mut messages_tx, messages_rx) = aptos_channels::new(
+        MAX_PENDING_SUBSTREAM_MESSAGES,
+        &counters::PENDING_SUBSTREAM_MESSAGES,
+    );
+
+    let reader_task = async move {
+        let mut substreams = SelectAll::new();
+        loop {
+            futures::select! {
+                maybe_substream = multiplexer.accept_stream().fuse() => {
+                    match maybe_substream {
+                        Ok(Some(substream)) => substreams.push(read_substream(
+                            network_context,
+                            connection_metadata.clone(),
+                            substream,
+                            max_frame_size,
+                            max_fragments,
+                        ).boxed()),
+                        // The connection was closed
+                        Ok(None) => break,
+                        Err(err) => {
+                            info!(
+                                NetworkSchema::new(&network_context)
+                                    .connection_metadata(&connection_metadata),
+                                error = %err,
+                                "{} Failed to accept substream from peer: {}, error: {}",
+                                network_context,
+                                connection_metadata.remote_peer_id.short_str(),
+                                err
+                            );
+                            break;
+                        },
+                    }
+                },
+                message = substreams.select_next_some() => {
+                    // The peer actor has terminated
+                    if messages_tx.send(message).await.is_err() {
+                        break;
+                    }
+                },
+            }
+        }
+    };
+
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    executor.spawn(Abortable::new(reader_task, abort_registration));
+    (messages_rx, abort_handle)
+}
+
+/// Returns the stream of messages read from a single substream, with all
+/// streamed messages reassembled.
+fn read_substream(
+    network_context: NetworkContext,
+    connection_metadata: ConnectionMetadata,
+    substream: InboundSubstream,
+    max_frame_size: usize,
+    max_fragments: usize,
+) -> impl Stream<Item = Result<MultiplexMessage, ReadError>> {
+    let mut inbound_stream = InboundStreamBuffer::new(max_fragments);
+    MultiplexMessageStream::new(substream, max_frame_size).filter_map(move |message| {
+        let result = match message {
+            Ok(MultiplexMessage::Stream(StreamMessage::Header(header))) => {
+                inbound_stream.new_stream(header).map(|()| None)
+            },
+            Ok(MultiplexMessage::Stream(StreamMessage::Fragment(fragment))) => inbound_stream
+                .append_fragment(fragment)
+                .map(|message| message.map(|message| Ok(MultiplexMessage::Message(message)))),
+            message => Ok(Some(message)),
+        };
+        let message = result.unwrap_or_else(|err| {
+            warn!(
+                NetworkSchema::new(&network_context).connection_metadata(&connection_metadata),
+                error = %err,
+                "{} Error in handling inbound stream message from peer: {}, error: {}",
+                network_context,
+                connection_metadata.remote_peer_id.short_str(),
+                err
+            );
+            None
+        });
+        future::ready(message)
+    })
+}
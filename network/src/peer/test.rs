@@ -7,7 +7,10 @@ use crate::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
-    peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
+    peer::{
+        outbound_queue::{NextMessage, OutboundQueue, MAX_QUEUEING_DELAY},
+        DisconnectReason, OutboundMessage, Peer, PeerNotification, PeerRequest,
+    },
     peer_manager::TransportNotification,
     protocols::{
         direct_send::Message,
@@ -15,8 +18,8 @@ use crate::{
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
-                DirectSendMsg, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
-                NetworkMessage, RpcRequest, RpcResponse,
+                DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, Priority, RpcRequest, RpcResponse,
             },
         },
     },
@@ -24,9 +27,15 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{PeerRole, ProtocolRateLimitConfig},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
-use aptos_netcore::transport::ConnectionOrigin;
+use aptos_netcore::{
+    substreams::{MemoryMultiplexedSocket, MultiplexedSocket},
+    transport::ConnectionOrigin,
+};
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
@@ -37,7 +46,12 @@ use futures::{
     stream::{StreamExt, TryStreamExt},
     SinkExt,
 };
-use std::{collections::HashSet, str::FromStr, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
 use tokio::runtime::{Handle, Runtime};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
@@ -57,6 +71,25 @@ fn build_test_peer(
     aptos_channel::Receiver<ProtocolId, PeerNotification>,
 ) {
     let (a, b) = MemorySocket::new_pair();
+    let (peer, peer_handle, connection_notifs_rx, peer_notifs_rx) =
+        build_test_peer_with_socket(executor, time_service, origin, a);
+    (peer, peer_handle, b, connection_notifs_rx, peer_notifs_rx)
+}
+
+fn build_test_peer_with_socket<TSocket>(
+    executor: Handle,
+    time_service: TimeService,
+    origin: ConnectionOrigin,
+    socket: TSocket,
+) -> (
+    Peer<TSocket>,
+    PeerHandle,
+    aptos_channels::Receiver<TransportNotification<TSocket>>,
+    aptos_channel::Receiver<ProtocolId, PeerNotification>,
+)
+where
+    TSocket: AsyncRead + AsyncWrite + MultiplexedSocket + Send + 'static,
+{
     let peer_id = PeerId::random();
     let connection = Connection {
         metadata: ConnectionMetadata::new(
@@ -68,7 +101,7 @@ fn build_test_peer(
             ProtocolIdSet::empty(),
             PeerRole::Unknown,
         ),
-        socket: a,
+        socket,
     };
 
     let (connection_notifs_tx, connection_notifs_rx) = aptos_channels::new_test(1);
@@ -90,10 +123,11 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        HashMap::new(),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
-    (peer, peer_handle, b, connection_notifs_rx, peer_notifs_rx)
+    (peer, peer_handle, connection_notifs_rx, peer_notifs_rx)
 }
 
 fn build_test_connected_peers(
@@ -152,10 +186,10 @@ fn build_network_sink_stream(
     (sink, stream)
 }
 
-async fn assert_disconnected_event(
+async fn assert_disconnected_event<TSocket: fmt::Debug>(
     peer_id: PeerId,
     reason: DisconnectReason,
-    connection_notifs_rx: &mut aptos_channels::Receiver<TransportNotification<MemorySocket>>,
+    connection_notifs_rx: &mut aptos_channels::Receiver<TransportNotification<TSocket>>,
 ) {
     match connection_notifs_rx.next().await {
         Some(TransportNotification::Disconnected(metadata, actual_reason)) => {
@@ -947,3 +981,295 @@ fn peers_send_multiplex() {
 
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
+
+// If the connection supports substreams, outbound protocol messages should be
+// written to a substream rather than the primary stream.
+#[test]
+fn peer_send_message_substream() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (socket, mut remote_socket) = MemoryMultiplexedSocket::new_pair();
+    let (peer, mut peer_handle, _connection_notifs_rx, _peer_notifs_rx) =
+        build_test_peer_with_socket(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+            socket,
+        );
+    let multiplexer = remote_socket.multiplexer().unwrap();
+
+    let send_msg = Message {
+        protocol_id: PROTOCOL,
+        mdata: Bytes::from("hello world"),
+    };
+    let recv_msg = MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: Vec::from("hello world"),
+    }));
+
+    let client = async move {
+        // All messages of the protocol should arrive on a single substream
+        let substream = multiplexer.accept_stream().await.unwrap().unwrap();
+        let mut substream = MultiplexMessageStream::new(substream, MAX_FRAME_SIZE);
+        for _ in 0..30 {
+            let msg = substream.next().await.unwrap().unwrap();
+            assert_eq!(msg, recv_msg);
+        }
+        // Client then closes the connection.
+        remote_socket.close().await.unwrap();
+    };
+
+    let server = async move {
+        for _ in 0..30 {
+            peer_handle.send_direct_send(send_msg.clone());
+        }
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
+// Two connected Peer actors should be able to exchange (normal and streamed)
+// messages over substreams and then shutdown gracefully.
+#[test]
+fn peers_send_multiplex_substreams() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (socket_a, socket_b) = MemoryMultiplexedSocket::new_pair();
+    let (peer_a, mut peer_handle_a, mut connection_notifs_rx_a, mut peer_notifs_rx_a) =
+        build_test_peer_with_socket(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+            socket_a,
+        );
+    let (peer_b, mut peer_handle_b, mut connection_notifs_rx_b, mut peer_notifs_rx_b) =
+        build_test_peer_with_socket(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Outbound,
+            socket_b,
+        );
+
+    let remote_peer_id_a = peer_a.remote_peer_id();
+    let remote_peer_id_b = peer_b.remote_peer_id();
+
+    let test = async move {
+        let msg_a = Message {
+            protocol_id: PROTOCOL,
+            mdata: Bytes::from(vec![0; MAX_MESSAGE_SIZE]), // stream message
+        };
+        let msg_b = Message {
+            protocol_id: ProtocolId::ConsensusDirectSendBcs,
+            mdata: Bytes::from(vec![1; 1024]), // normal message
+        };
+
+        // Peer A -> msg_a -> Peer B
+        peer_handle_a.send_direct_send(msg_a.clone());
+        // Peer A <- msg_b <- Peer B
+        peer_handle_b.send_direct_send(msg_b.clone());
+
+        // Check that each peer received the other's message
+        let notif_a = peer_notifs_rx_a.next().await;
+        let notif_b = peer_notifs_rx_b.next().await;
+        assert_eq!(notif_a, Some(PeerNotification::RecvMessage(msg_b)));
+        assert_eq!(notif_b, Some(PeerNotification::RecvMessage(msg_a)));
+
+        // Shut one peers and the other should shutdown due to ConnectionLost
+        drop(peer_handle_a);
+
+        // Check that we received both shutdown events
+        assert_disconnected_event(
+            remote_peer_id_a,
+            DisconnectReason::Requested,
+            &mut connection_notifs_rx_a,
+        )
+        .await;
+        assert_disconnected_event(
+            remote_peer_id_b,
+            DisconnectReason::ConnectionLost,
+            &mut connection_notifs_rx_b,
+        )
+        .await;
+    };
+
+    rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
+}
+
+fn create_direct_send(protocol_id: ProtocolId, data: Vec<u8>) -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id,
+        priority: Priority::default(),
+        raw_msg: data,
+    })
+}
+
+fn push_direct_send(outbound_queue: &mut OutboundQueue, message: &NetworkMessage) {
+    let protocol_id = match message {
+        NetworkMessage::DirectSendMsg(message) => message.protocol_id,
+        message => panic!("Expected a direct send message, but got: {:?}", message),
+    };
+    outbound_queue.push(OutboundMessage::new(protocol_id, message.clone()));
+}
+
+fn assert_next_message(outbound_queue: &mut OutboundQueue, expected_message: &NetworkMessage) {
+    match outbound_queue.pop() {
+        NextMessage::Ready(_, message) => assert_eq!(&message, expected_message),
+        next_message => panic!("Expected a ready message, but got: {:?}", next_message),
+    }
+}
+
+#[test]
+fn outbound_queue_priority_order() {
+    let mut outbound_queue = OutboundQueue::new(
+        NetworkContext::mock(),
+        PeerId::random(),
+        &HashMap::new(),
+        TimeService::mock(),
+    );
+
+    // Enqueue messages from the least to the most important
+    let monitoring_message = create_direct_send(ProtocolId::HealthCheckerRpc, vec![0]);
+    let state_sync_message = create_direct_send(ProtocolId::StorageServiceRpc, vec![1]);
+    let mempool_message = create_direct_send(ProtocolId::MempoolDirectSend, vec![2]);
+    let consensus_message = create_direct_send(ProtocolId::ConsensusDirectSendBcs, vec![3]);
+    for message in [
+        &monitoring_message,
+        &state_sync_message,
+        &mempool_message,
+        &consensus_message,
+    ] {
+        push_direct_send(&mut outbound_queue, message);
+    }
+
+    // Error messages are always written first
+    let error_code = ErrorCode::parsing_error(0, 0);
+    outbound_queue.push(OutboundMessage::error(error_code.clone()));
+    assert_next_message(&mut outbound_queue, &NetworkMessage::Error(error_code));
+
+    // Verify the messages are written from the most to the least important
+    for expected_message in [
+        &consensus_message,
+        &mempool_message,
+        &state_sync_message,
+        &monitoring_message,
+    ] {
+        assert_next_message(&mut outbound_queue, expected_message);
+    }
+    assert!(matches!(outbound_queue.pop(), NextMessage::Empty));
+}
+
+#[test]
+fn outbound_queue_protocol_fifo() {
+    let mut outbound_queue = OutboundQueue::new(
+        NetworkContext::mock(),
+        PeerId::random(),
+        &HashMap::new(),
+        TimeService::mock(),
+    );
+
+    // Enqueue several messages for protocols in the same priority class
+    let messages = vec![
+        create_direct_send(ProtocolId::ConsensusRpcBcs, vec![0]),
+        create_direct_send(ProtocolId::ConsensusDirectSendBcs, vec![1]),
+        create_direct_send(ProtocolId::ConsensusRpcBcs, vec![2]),
+        create_direct_send(ProtocolId::ConsensusDirectSendBcs, vec![3]),
+    ];
+    for message in &messages {
+        push_direct_send(&mut outbound_queue, message);
+    }
+
+    // Verify the messages are written in the order they were enqueued
+    for expected_message in &messages {
+        assert_next_message(&mut outbound_queue, expected_message);
+    }
+}
+
+#[test]
+fn outbound_queue_rate_limits() {
+    let rate_limits = [(ProtocolId::StorageServiceRpc, ProtocolRateLimitConfig {
+        byte_rate: 100,
+        byte_bucket_size: 100,
+        initial_bucket_fill_percentage: 100,
+    })]
+    .into_iter()
+    .collect();
+    let time_service = TimeService::mock();
+    let mut outbound_queue = OutboundQueue::new(
+        NetworkContext::mock(),
+        PeerId::random(),
+        &rate_limits,
+        time_service.clone(),
+    );
+
+    // The first state sync message fits into the bucket
+    let state_sync_message = create_direct_send(ProtocolId::StorageServiceRpc, vec![0; 80]);
+    push_direct_send(&mut outbound_queue, &state_sync_message);
+    assert_next_message(&mut outbound_queue, &state_sync_message);
+
+    // The second state sync message is throttled
+    let state_sync_message = create_direct_send(ProtocolId::StorageServiceRpc, vec![1; 80]);
+    push_direct_send(&mut outbound_queue, &state_sync_message);
+    assert!(matches!(outbound_queue.pop(), NextMessage::Throttled(_)));
+
+    // Lower priority messages are still written while state sync is throttled
+    let monitoring_message = create_direct_send(ProtocolId::HealthCheckerRpc, vec![2; 80]);
+    push_direct_send(&mut outbound_queue, &monitoring_message);
+    assert_next_message(&mut outbound_queue, &monitoring_message);
+    let delay = match outbound_queue.pop() {
+        NextMessage::Throttled(delay) => delay,
+        next_message => panic!("Expected a throttled message, but got: {:?}", next_message),
+    };
+
+    // The state sync message is still throttled just before the delay ends, and written after it
+    let time_service = time_service.into_mock();
+    time_service.advance(delay - Duration::from_millis(1));
+    assert!(matches!(outbound_queue.pop(), NextMessage::Throttled(_)));
+    time_service.advance(Duration::from_millis(1));
+    assert_next_message(&mut outbound_queue, &state_sync_message);
+}
+
+#[test]
+fn outbound_queue_no_starvation() {
+    let time_service = TimeService::mock();
+    let mut outbound_queue = OutboundQueue::new(
+        NetworkContext::mock(),
+        PeerId::random(),
+        &HashMap::new(),
+        time_service.clone(),
+    );
+    let time_service = time_service.into_mock();
+
+    // Enqueue a health check behind a backlog of consensus messages
+    let health_check_message = create_direct_send(ProtocolId::HealthCheckerRpc, vec![0]);
+    push_direct_send(&mut outbound_queue, &health_check_message);
+    for _ in 0..5 {
+        push_direct_send(
+            &mut outbound_queue,
+            &create_direct_send(ProtocolId::ConsensusDirectSendBcs, vec![1]),
+        );
+    }
+
+    // Keep the consensus queue full, and verify that the health check is written
+    // once it has waited for longer than the maximum queueing delay
+    let step = Duration::from_millis(100);
+    let mut elapsed = Duration::ZERO;
+    loop {
+        push_direct_send(
+            &mut outbound_queue,
+            &create_direct_send(ProtocolId::ConsensusDirectSendBcs, vec![1]),
+        );
+        match outbound_queue.pop() {
+            NextMessage::Ready(Some(ProtocolId::HealthCheckerRpc), message) => {
+                assert_eq!(message, health_check_message);
+                assert!(elapsed >= MAX_QUEUEING_DELAY);
+                break;
+            },
+            NextMessage::Ready(Some(ProtocolId::ConsensusDirectSendBcs), _) => {
+                assert!(elapsed < MAX_QUEUEING_DELAY);
+            },
+            next_message => panic!("Unexpected next message: {:?}", next_message),
+        }
+        time_service.advance(step);
+        elapsed += step;
+    }
+}
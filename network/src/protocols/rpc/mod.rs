@@ -27,7 +27,7 @@
 //! [`NetworkMessage`] arrivals and polls for completed rpc requests. The queues
 //! also do not write to the wire directly; instead, they're given a reference to
 //! the [`Peer`] actor's write queue, which they can enqueue a new outbound
-//! [`NetworkMessage`] onto.
+//! [`NetworkMessage`] onto (tagged with its protocol, for prioritization).
 //!
 //! ## Timeouts:
 //!
@@ -51,7 +51,7 @@ use crate::{
         RESPONSE_LABEL, SENT_LABEL,
     },
     logging::NetworkSchema,
-    peer::PeerNotification,
+    peer::{OutboundMessage, PeerNotification},
     protocols::{
         network::SerializedRequest,
         wire::messaging::v1::{NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse},
@@ -175,7 +175,9 @@ pub struct InboundRpcs {
     remote_peer_id: PeerId,
     /// The core async queue of pending inbound rpc tasks. The tasks are driven
     /// to completion by the `InboundRpcs::next_completed_response()` method.
-    inbound_rpc_tasks: FuturesUnordered<BoxFuture<'static, Result<RpcResponse, RpcError>>>,
+    /// Each response is tagged with the protocol of the corresponding request.
+    inbound_rpc_tasks:
+        FuturesUnordered<BoxFuture<'static, Result<(ProtocolId, RpcResponse), RpcError>>>,
     /// A blanket timeout on all inbound rpc requests. If the application handler
     /// doesn't respond to the request before this timeout, the request will be
     /// dropped.
@@ -258,11 +260,11 @@ impl InboundRpcs {
             .map(move |result| {
                 // Flatten the errors
                 let maybe_response = match result {
-                    Ok(Ok(Ok(response_bytes))) => Ok(RpcResponse {
+                    Ok(Ok(Ok(response_bytes))) => Ok((protocol_id, RpcResponse {
                         request_id,
                         priority,
                         raw_response: Vec::from(response_bytes.as_ref()),
-                    }),
+                    })),
                     Ok(Ok(Err(err))) => Err(err),
                     Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
                     Err(timeout::Elapsed) => Err(RpcError::TimedOut),
@@ -288,7 +290,7 @@ impl InboundRpcs {
     /// `futures::select!`.
     pub fn next_completed_response(
         &mut self,
-    ) -> impl Future<Output = Result<RpcResponse, RpcError>> + FusedFuture + '_ {
+    ) -> impl Future<Output = Result<(ProtocolId, RpcResponse), RpcError>> + FusedFuture + '_ {
         self.inbound_rpc_tasks.select_next_some()
     }
 
@@ -297,11 +299,11 @@ impl InboundRpcs {
     /// the outbound write queue.
     pub async fn send_outbound_response(
         &mut self,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
-        maybe_response: Result<RpcResponse, RpcError>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
+        maybe_response: Result<(ProtocolId, RpcResponse), RpcError>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
-        let response = match maybe_response {
+        let (protocol_id, response) = match maybe_response {
             Ok(response) => response,
             Err(err) => {
                 counters::rpc_messages(network_context, RESPONSE_LABEL, FAILED_LABEL).inc();
@@ -318,7 +320,7 @@ impl InboundRpcs {
             self.remote_peer_id.short_str(),
             response.request_id,
         );
-        let message = NetworkMessage::RpcResponse(response);
+        let message = OutboundMessage::new(protocol_id, NetworkMessage::RpcResponse(response));
         write_reqs_tx.send(message).await?;
 
         // Collect counters for sent response.
@@ -380,7 +382,7 @@ impl OutboundRpcs {
     pub async fn handle_outbound_request(
         &mut self,
         request: OutboundRpcRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
         let peer_id = &self.remote_peer_id;
@@ -425,12 +427,15 @@ impl OutboundRpcs {
             counters::outbound_rpc_request_latency(network_context, protocol_id).start_timer();
 
         // Enqueue rpc request message onto outbound write queue.
-        let message = NetworkMessage::RpcRequest(RpcRequest {
+        let message = OutboundMessage::new(
             protocol_id,
-            request_id,
-            priority: Priority::default(),
-            raw_request: Vec::from(request_data.as_ref()),
-        });
+            NetworkMessage::RpcRequest(RpcRequest {
+                protocol_id,
+                request_id,
+                priority: Priority::default(),
+                raw_request: Vec::from(request_data.as_ref()),
+            }),
+        );
         write_reqs_tx.send(message).await?;
 
         // Collect counters for requests sent.
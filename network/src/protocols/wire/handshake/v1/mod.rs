@@ -85,6 +85,14 @@ impl ProtocolId {
         }
     }
 
+    /// Returns the protocol with the given name (see [`ProtocolId::as_str`]), if any
+    pub fn from_name(name: &str) -> Option<ProtocolId> {
+        ProtocolId::all()
+            .iter()
+            .find(|protocol_id| protocol_id.as_str() == name)
+            .copied()
+    }
+
     pub fn all() -> &'static [ProtocolId] {
         &[
             ProtocolId::ConsensusRpcBcs,
@@ -36,6 +36,14 @@ fn test_as_u8_serde_equiv() {
     }
 }
 
+#[test]
+fn test_protocol_from_name() {
+    for protocol in ProtocolId::all() {
+        assert_eq!(ProtocolId::from_name(protocol.as_str()), Some(*protocol));
+    }
+    assert_eq!(ProtocolId::from_name("UnknownProtocolRpc"), None);
+}
+
 #[test]
 fn represents_same_network() {
     let mut handshake_msg = HandshakeMsg::new_for_testing();
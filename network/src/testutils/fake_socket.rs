@@ -9,6 +9,7 @@
 //!
 
 use aptos_memsocket::MemorySocket;
+use aptos_netcore::substreams::MultiplexedSocket;
 use futures::{
     io::{AsyncRead, AsyncWrite},
     ready,
@@ -204,6 +205,8 @@ impl AsyncRead for ReadOnlyTestSocketVec {
     }
 }
 
+impl MultiplexedSocket for ReadOnlyTestSocketVec {}
+
 //
 // ReadWriteTestSocket
 // ==================
@@ -304,6 +307,8 @@ impl<'a> AsyncRead for ReadWriteTestSocket<'a> {
     }
 }
 
+impl<'a> MultiplexedSocket for ReadWriteTestSocket<'a> {}
+
 //
 // Tests
 // =====
@@ -19,17 +19,22 @@ use aptos_id_generator::{IdGenerator, U32IdGenerator};
 use aptos_logger::prelude::*;
 // Re-exposed for aptos-network-checker
 pub use aptos_netcore::transport::tcp::{resolve_and_connect, TCPBufferCfg, TcpSocket};
-use aptos_netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use aptos_netcore::{
+    substreams::{MultiplexedSocket, CHANNEL_BINDING_LENGTH},
+    transport::{proxy_protocol, tcp, ConnectionOrigin, Transport},
+};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{timeout, TimeService, TimeServiceTrait};
 use aptos_types::{
     chain_id::ChainId,
-    network_address::{parse_dns_tcp, parse_ip_tcp, parse_memory, NetworkAddress},
+    network_address::{
+        parse_dns_quic, parse_dns_tcp, parse_ip_quic, parse_ip_tcp, parse_memory, NetworkAddress,
+    },
     PeerId,
 };
 use futures::{
     future::{Future, FutureExt},
-    io::{AsyncRead, AsyncWrite},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     stream::{Stream, StreamExt, TryStreamExt},
 };
 use serde::{Deserialize, Serialize};
@@ -59,9 +64,15 @@ pub const APTOS_TCP_TRANSPORT: tcp::TcpTransport = tcp::TcpTransport {
 };
 
 /// A trait alias for "socket-like" things.
-pub trait TSocket: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
+pub trait TSocket:
+    AsyncRead + AsyncWrite + MultiplexedSocket + Send + fmt::Debug + Unpin + 'static
+{
+}
 
-impl<T> TSocket for T where T: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
+impl<T> TSocket for T where
+    T: AsyncRead + AsyncWrite + MultiplexedSocket + Send + fmt::Debug + Unpin + 'static
+{
+}
 
 /// Unique local identifier for a connection.
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -224,6 +235,31 @@ impl UpgradeContext {
     }
 }
 
+/// If the connection carries substreams (e.g., QUIC), exchange the connection's
+/// channel binding over the authenticated noise stream and ensure both peers
+/// observe the same value. Otherwise, the substreams could belong to a different
+/// (e.g., relayed) connection than the one the remote peer authenticated on.
+async fn verify_channel_binding<T: TSocket>(socket: &mut NoiseStream<T>) -> io::Result<()> {
+    let multiplexer = match socket.multiplexer() {
+        Some(multiplexer) => multiplexer,
+        None => return Ok(()),
+    };
+
+    let channel_binding = multiplexer.channel_binding()?;
+    socket.write_all(&channel_binding).await?;
+    socket.flush().await?;
+
+    let mut remote_channel_binding = [0u8; CHANNEL_BINDING_LENGTH];
+    socket.read_exact(&mut remote_channel_binding).await?;
+    if channel_binding != remote_channel_binding {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Channel binding mismatch: substreams don't belong to the authenticated connection",
+        ));
+    }
+    Ok(())
+}
+
 /// If we have proxy protocol enabled, then prepend the un-proxied address to the error.
 fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAddress) -> io::Error {
     if proxy_protocol_enabled {
@@ -289,6 +325,11 @@ async fn upgrade_inbound<T: TSocket>(
     let remote_pubkey = socket.get_remote_static();
     let addr = addr.append_prod_protos(remote_pubkey, HANDSHAKE_VERSION);
 
+    // bind the substreams (if any) to the authenticated connection
+    verify_channel_binding(&mut socket)
+        .await
+        .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -366,6 +407,9 @@ pub async fn upgrade_outbound<T: TSocket>(
     // sanity check: Noise IK should always guarantee this is true
     debug_assert_eq!(remote_pubkey, socket.get_remote_static());
 
+    // bind the substreams (if any) to the authenticated connection
+    verify_channel_binding(&mut socket).await?;
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -404,7 +448,9 @@ pub async fn upgrade_outbound<T: TSocket>(
 ///
 /// The base transport layer is pluggable, so long as it provides a reliable,
 /// ordered, connection-oriented, byte-stream abstraction (e.g., TCP). We currently
-/// use either `MemoryTransport` or `TcpTransport` as this base layer.
+/// use either `MemoryTransport`, `TcpTransport` or `QuicTransport` as this base
+/// layer. Base transports may also provide additional substreams (e.g., QUIC),
+/// which are bound to the authenticated connection after the noise handshake.
 ///
 /// Inbound and outbound connections are first established with the `base_transport`
 /// and then negotiate a secure, authenticated transport layer (currently Noise
@@ -476,13 +522,15 @@ where
         let (base_transport_protos, base_transport_suffix) = parse_ip_tcp(protos)
             .map(|x| (&protos[..2], x.1))
             .or_else(|| parse_dns_tcp(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_ip_quic(protos).map(|x| (&protos[..3], x.1)))
+            .or_else(|| parse_dns_quic(protos).map(|x| (&protos[..3], x.1)))
             .or_else(|| parse_memory(protos).map(|x| (&protos[..1], x.1)))
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
                         "Unexpected dialing network address: '{}', expected: \
-                         memory, ip+tcp, or dns+tcp",
+                         memory, ip+tcp, dns+tcp, ip+udp+quic or dns+udp+quic",
                         addr
                     ),
                 )
@@ -528,6 +576,12 @@ where
     /// `/dns/<ipaddr>/tcp/<port>` or
     /// `/dns4/<ipaddr>/tcp/<port>` or
     /// `/dns6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then `/<base_transport>` is:
+    ///
+    /// `/ip4/<ipaddr>/udp/<port>/quic` or
+    /// `/ip6/<ipaddr>/udp/<port>/quic` or
+    /// `/dns/<ipaddr>/udp/<port>/quic` (or `dns4`/`dns6`)
     pub fn dial(
         &self,
         peer_id: PeerId,
@@ -574,6 +628,11 @@ where
     ///
     /// `/ip4/<ipaddr>/tcp/<port>` or
     /// `/ip6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then we expect:
+    ///
+    /// `/ip4/<ipaddr>/udp/<port>/quic` or
+    /// `/ip6/<ipaddr>/udp/<port>/quic`
     pub fn listen_on(
         &self,
         addr: NetworkAddress,
@@ -12,7 +12,8 @@ use aptos_crypto::{test_utils::TEST_SEED, traits::Uniform, x25519};
 use aptos_infallible::RwLock;
 use aptos_netcore::{
     framing::{read_u16frame, write_u16frame},
-    transport::{memory, ConnectionOrigin, Transport},
+    substreams::{MemoryMultiplexedSocket, MemoryMultiplexer, CHANNEL_BINDING_LENGTH},
+    transport::{memory, quic::QuicTransport, ConnectionOrigin, Transport},
 };
 use aptos_time_service::MockTimeService;
 use aptos_types::{
@@ -225,6 +226,22 @@ fn expect_ip4_tcp_noise_addr(addr: &NetworkAddress) {
     );
 }
 
+/// Check that the network address matches the format
+/// `"/ip4/<ipaddr>/udp/<port>/quic/noise-ik/<pubkey>/handshake/<version>"`
+fn expect_ip4_quic_noise_addr(addr: &NetworkAddress) {
+    assert!(
+        matches!(addr.as_slice(), [
+            Ip4(_),
+            Udp(_),
+            Quic,
+            NoiseIK(_),
+            Handshake(_)
+        ]),
+        "addr: '{}'",
+        addr
+    );
+}
+
 fn test_transport_success<TTransport>(
     base_transport: TTransport,
     auth: Auth,
@@ -579,3 +596,75 @@ fn test_tcp_transport_rejects_unauthed_dialer() {
         expect_ip4_tcp_noise_addr,
     );
 }
+
+//////////////////////////////////////
+// AptosNetTransport<QuicTransport> //
+//////////////////////////////////////
+
+#[test]
+fn test_quic_transport_mutual_auth() {
+    test_transport_success(
+        QuicTransport,
+        Auth::Mutual,
+        "/ip4/127.0.0.1/udp/0/quic",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_server_only_auth() {
+    test_transport_success(
+        QuicTransport,
+        Auth::ServerOnly,
+        "/ip4/127.0.0.1/udp/0/quic",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_rejects_unauthed_dialer() {
+    test_transport_rejects_unauthed_dialer(
+        QuicTransport,
+        "/ip4/127.0.0.1/udp/0/quic",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_transport_rejects_mismatched_channel_binding() {
+    let (
+        rt,
+        _mock_time,
+        (listener_peer_id, listener_transport),
+        (_dialer_peer_id, dialer_transport),
+        _trusted_peers,
+        _supported_protocols,
+    ) = setup(memory::MemoryTransport, Auth::Mutual);
+
+    // emulate an attacker relaying the noise stream between two distinct connections
+    let (dialer_multiplexer, listener_multiplexer) = MemoryMultiplexer::new_pair_with_bindings(
+        [1u8; CHANNEL_BINDING_LENGTH],
+        [2u8; CHANNEL_BINDING_LENGTH],
+    );
+    let (dialer_socket, listener_socket) =
+        MemoryMultiplexedSocket::from_multiplexers(dialer_multiplexer, listener_multiplexer);
+    let addr = NetworkAddress::from(Memory(0));
+
+    let listener_task = upgrade_inbound(
+        listener_transport.ctxt.clone(),
+        future::ready(Ok(listener_socket)),
+        addr.clone(),
+        false, /* Disable proxy protocol */
+    );
+    let dialer_task = upgrade_outbound(
+        dialer_transport.ctxt.clone(),
+        future::ready(Ok(dialer_socket)),
+        addr,
+        listener_peer_id,
+        listener_transport.identity_pubkey,
+    );
+
+    let (listener_result, dialer_result) = rt.block_on(future::join(listener_task, dialer_task));
+    listener_result.expect_err("should fail because the channel bindings don't match");
+    dialer_result.expect_err("should fail because the channel bindings don't match");
+}
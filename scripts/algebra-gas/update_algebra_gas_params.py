@@ -91,6 +91,77 @@ def get_algebra_lines(gas_per_ns):
     _,_,nanoseconds['ark_bls12_381_multi_pairing_per_pair'],nanoseconds['ark_bls12_381_multi_pairing_base'] = get_bench_ns_linear('target/criterion/ark_bls12_381/pairing_product')
     _,_,nanoseconds['ark_h2c_bls12381g1_xmd_sha256_sswu_per_msg_byte'],nanoseconds['ark_h2c_bls12381g1_xmd_sha256_sswu_base'] = get_bench_ns_linear('target/criterion/ark_bls12_381/hash_to_g1_proj')
     _,_,nanoseconds['ark_h2c_bls12381g2_xmd_sha256_sswu_per_msg_byte'],nanoseconds['ark_h2c_bls12381g2_xmd_sha256_sswu_base'] = get_bench_ns_linear('target/criterion/ark_bls12_381/hash_to_g2_proj')
+    nanoseconds['ark_bn254_fr_add'] = load_bench_ns.main('target/criterion/ark_bn254/fr_add')
+    nanoseconds['ark_bn254_fr_deser'] = load_bench_ns.main('target/criterion/ark_bn254/fr_deser')
+    nanoseconds['ark_bn254_fr_div'] = load_bench_ns.main('target/criterion/ark_bn254/fr_div')
+    nanoseconds['ark_bn254_fr_eq'] = load_bench_ns.main('target/criterion/ark_bn254/fr_eq')
+    nanoseconds['ark_bn254_fr_from_u64'] = load_bench_ns.main('target/criterion/ark_bn254/fr_from_u64')
+    nanoseconds['ark_bn254_fr_inv'] = load_bench_ns.main('target/criterion/ark_bn254/fr_inv')
+    nanoseconds['ark_bn254_fr_mul'] = load_bench_ns.main('target/criterion/ark_bn254/fr_mul')
+    nanoseconds['ark_bn254_fr_neg'] = load_bench_ns.main('target/criterion/ark_bn254/fr_neg')
+    nanoseconds['ark_bn254_fr_one'] = load_bench_ns.main('target/criterion/ark_bn254/fr_one')
+    nanoseconds['ark_bn254_fr_serialize'] = load_bench_ns.main('target/criterion/ark_bn254/fr_serialize')
+    nanoseconds['ark_bn254_fr_square'] = load_bench_ns.main('target/criterion/ark_bn254/fr_square')
+    nanoseconds['ark_bn254_fr_sub'] = load_bench_ns.main('target/criterion/ark_bn254/fr_sub')
+    nanoseconds['ark_bn254_fr_zero'] = load_bench_ns.main('target/criterion/ark_bn254/fr_zero')
+    nanoseconds['ark_bn254_fq_add'] = load_bench_ns.main('target/criterion/ark_bn254/fq_add')
+    nanoseconds['ark_bn254_fq_clone'] = load_bench_ns.main('target/criterion/ark_bn254/fq_clone')
+    nanoseconds['ark_bn254_fq_deser'] = load_bench_ns.main('target/criterion/ark_bn254/fq_deser')
+    nanoseconds['ark_bn254_fq_div'] = load_bench_ns.main('target/criterion/ark_bn254/fq_div')
+    nanoseconds['ark_bn254_fq_eq'] = load_bench_ns.main('target/criterion/ark_bn254/fq_eq')
+    nanoseconds['ark_bn254_fq_from_u64'] = load_bench_ns.main('target/criterion/ark_bn254/fq_from_u64')
+    nanoseconds['ark_bn254_fq_inv'] = load_bench_ns.main('target/criterion/ark_bn254/fq_inv')
+    nanoseconds['ark_bn254_fq_mul'] = load_bench_ns.main('target/criterion/ark_bn254/fq_mul')
+    nanoseconds['ark_bn254_fq_neg'] = load_bench_ns.main('target/criterion/ark_bn254/fq_neg')
+    nanoseconds['ark_bn254_fq_one'] = load_bench_ns.main('target/criterion/ark_bn254/fq_one')
+    nanoseconds['ark_bn254_fq_pow_u256'] = load_bench_ns.main('target/criterion/ark_bn254/fq_pow_u256')
+    nanoseconds['ark_bn254_fq_serialize'] = load_bench_ns.main('target/criterion/ark_bn254/fq_serialize')
+    nanoseconds['ark_bn254_fq_square'] = load_bench_ns.main('target/criterion/ark_bn254/fq_square')
+    nanoseconds['ark_bn254_fq_sub'] = load_bench_ns.main('target/criterion/ark_bn254/fq_sub')
+    nanoseconds['ark_bn254_fq_zero'] = load_bench_ns.main('target/criterion/ark_bn254/fq_zero')
+    nanoseconds['ark_bn254_fq12_add'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_add')
+    nanoseconds['ark_bn254_fq12_clone'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_clone')
+    nanoseconds['ark_bn254_fq12_deser'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_deser')
+    nanoseconds['ark_bn254_fq12_div'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_div')
+    nanoseconds['ark_bn254_fq12_eq'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_eq')
+    nanoseconds['ark_bn254_fq12_from_u64'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_from_u64')
+    nanoseconds['ark_bn254_fq12_inv'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_inv')
+    nanoseconds['ark_bn254_fq12_mul'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_mul')
+    nanoseconds['ark_bn254_fq12_neg'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_neg')
+    nanoseconds['ark_bn254_fq12_one'] = 1
+    nanoseconds['ark_bn254_fq12_pow_u256'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_pow_u256')
+    nanoseconds['ark_bn254_fq12_serialize'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_serialize')
+    nanoseconds['ark_bn254_fq12_square'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_square')
+    nanoseconds['ark_bn254_fq12_sub'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_sub')
+    nanoseconds['ark_bn254_fq12_zero'] = load_bench_ns.main('target/criterion/ark_bn254/fq12_zero')
+    nanoseconds['ark_bn254_g1_affine_deser_comp'] = load_bench_ns.main('target/criterion/ark_bn254/g1_affine_deser_comp')
+    nanoseconds['ark_bn254_g1_affine_deser_uncomp'] = load_bench_ns.main('target/criterion/ark_bn254/g1_affine_deser_uncomp')
+    nanoseconds['ark_bn254_g1_affine_serialize_comp'] = load_bench_ns.main('target/criterion/ark_bn254/g1_affine_serialize_comp')
+    nanoseconds['ark_bn254_g1_affine_serialize_uncomp'] = load_bench_ns.main('target/criterion/ark_bn254/g1_affine_serialize_uncomp')
+    nanoseconds['ark_bn254_g1_proj_add'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_add')
+    nanoseconds['ark_bn254_g1_proj_double'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_double')
+    nanoseconds['ark_bn254_g1_proj_eq'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_eq')
+    nanoseconds['ark_bn254_g1_proj_generator'] = 1
+    nanoseconds['ark_bn254_g1_proj_infinity'] = 1
+    nanoseconds['ark_bn254_g1_proj_neg'] = 1
+    nanoseconds['ark_bn254_g1_proj_scalar_mul'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_scalar_mul')
+    nanoseconds['ark_bn254_g1_proj_sub'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_sub')
+    nanoseconds['ark_bn254_g1_proj_to_affine'] = load_bench_ns.main('target/criterion/ark_bn254/g1_proj_to_affine')
+    nanoseconds['ark_bn254_g2_affine_deser_comp'] = load_bench_ns.main('target/criterion/ark_bn254/g2_affine_deser_comp')
+    nanoseconds['ark_bn254_g2_affine_deser_uncomp'] = load_bench_ns.main('target/criterion/ark_bn254/g2_affine_deser_uncomp')
+    nanoseconds['ark_bn254_g2_affine_serialize_comp'] = load_bench_ns.main('target/criterion/ark_bn254/g2_affine_serialize_comp')
+    nanoseconds['ark_bn254_g2_affine_serialize_uncomp'] = load_bench_ns.main('target/criterion/ark_bn254/g2_affine_serialize_uncomp')
+    nanoseconds['ark_bn254_g2_proj_add'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_add')
+    nanoseconds['ark_bn254_g2_proj_double'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_double')
+    nanoseconds['ark_bn254_g2_proj_eq'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_eq')
+    nanoseconds['ark_bn254_g2_proj_generator'] = 1
+    nanoseconds['ark_bn254_g2_proj_infinity'] = 1
+    nanoseconds['ark_bn254_g2_proj_neg'] = 1
+    nanoseconds['ark_bn254_g2_proj_scalar_mul'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_scalar_mul')
+    nanoseconds['ark_bn254_g2_proj_sub'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_sub')
+    nanoseconds['ark_bn254_g2_proj_to_affine'] = load_bench_ns.main('target/criterion/ark_bn254/g2_proj_to_affine')
+    nanoseconds['ark_bn254_pairing'] = load_bench_ns.main('target/criterion/ark_bn254/pairing')
+    _,_,nanoseconds['ark_bn254_multi_pairing_per_pair'],nanoseconds['ark_bn254_multi_pairing_base'] = get_bench_ns_linear('target/criterion/ark_bn254/pairing_product')
     gas_units = {k:gas_per_ns*v for k,v in nanoseconds.items()}
     lines = [f'    [.algebra.{k}, {{ {TARGET_GAS_VERSION}.. => "algebra.{k}" }}, {prettify_number(v)} * MUL],' for k,v in sorted(gas_units.items())]
     return lines
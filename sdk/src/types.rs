@@ -5,12 +5,17 @@
 use crate::{
     crypto::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        hash::CryptoHash,
+        secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+        traits::{SigningKey, Uniform},
     },
     transaction_builder::TransactionBuilder,
     types::{
         account_address::AccountAddress,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+        transaction::{
+            authenticator::{AccountAuthenticator, AuthenticationKey},
+            RawTransaction, RawTransactionWithData, SignedTransaction,
+        },
     },
 };
 use anyhow::Result;
@@ -18,6 +23,7 @@ use aptos_types::event::EventKey;
 pub use aptos_types::*;
 use bip39::{Language, Mnemonic, Seed};
 use ed25519_dalek_bip32::{DerivationPath, ExtendedSecretKey};
+use serde::Serialize;
 use std::str::FromStr;
 
 /// LocalAccount represents an account on the Aptos blockchain. Internally it
@@ -82,10 +88,21 @@ impl LocalAccount {
         Self::new(address, key, 0)
     }
 
+    /// Generate a new account locally, with a secp256k1 ECDSA key. Note: This function does not
+    /// actually create an account on the Aptos blockchain, it just generates a new account
+    /// locally.
+    pub fn generate_secp256k1_ecdsa<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let key = AccountKey::generate_secp256k1_ecdsa(rng);
+        let address = key.authentication_key().derived_address();
+
+        Self::new(address, key, 0)
+    }
+
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
-        txn.sign(self.private_key(), self.public_key().clone())
-            .expect("Signing a txn can't fail")
-            .into_inner()
+        self.key.sign_transaction(txn)
     }
 
     pub fn sign_with_transaction_builder(
@@ -105,37 +122,75 @@ impl LocalAccount {
         secondary_signers: Vec<&Self>,
         builder: TransactionBuilder,
     ) -> SignedTransaction {
-        let secondary_signer_addresses = secondary_signers
+        let secondary_signer_addresses: Vec<_> = secondary_signers
             .iter()
             .map(|signer| signer.address())
             .collect();
-        let secondary_signer_privkeys = secondary_signers
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        *self.sequence_number_mut() += 1;
+        let message = RawTransactionWithData::new_multi_agent(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+        );
+        SignedTransaction::new_multi_agent(
+            raw_txn,
+            self.key.sign_message(&message),
+            secondary_signer_addresses,
+            secondary_signers
+                .iter()
+                .map(|signer| signer.key.sign_message(&message))
+                .collect(),
+        )
+    }
+
+    /// Signs the transaction built by `builder` as the sender, with the gas paid by `fee_payer`
+    /// rather than this account.
+    pub fn sign_fee_payer_with_transaction_builder(
+        &mut self,
+        secondary_signers: Vec<&Self>,
+        fee_payer_signer: &Self,
+        builder: TransactionBuilder,
+    ) -> SignedTransaction {
+        let secondary_signer_addresses: Vec<_> = secondary_signers
             .iter()
-            .map(|signer| signer.private_key())
+            .map(|signer| signer.address())
             .collect();
         let raw_txn = builder
             .sender(self.address())
             .sequence_number(self.sequence_number())
             .build();
         *self.sequence_number_mut() += 1;
-        raw_txn
-            .sign_multi_agent(
-                self.private_key(),
-                secondary_signer_addresses,
-                secondary_signer_privkeys,
-            )
-            .expect("Signing multi agent txn failed")
-            .into_inner()
+        let message = RawTransactionWithData::new_multi_agent_with_fee_payer(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+            fee_payer_signer.address(),
+        );
+        SignedTransaction::new_fee_payer(
+            raw_txn,
+            self.key.sign_message(&message),
+            secondary_signer_addresses,
+            secondary_signers
+                .iter()
+                .map(|signer| signer.key.sign_message(&message))
+                .collect(),
+            fee_payer_signer.address(),
+            fee_payer_signer.key.sign_message(&message),
+        )
     }
 
     pub fn address(&self) -> AccountAddress {
         self.address
     }
 
+    /// The Ed25519 private key of the account, panics if the account uses another scheme.
     pub fn private_key(&self) -> &Ed25519PrivateKey {
         self.key.private_key()
     }
 
+    /// The Ed25519 public key of the account, panics if the account uses another scheme.
     pub fn public_key(&self) -> &Ed25519PublicKey {
         self.key.public_key()
     }
@@ -167,11 +222,23 @@ impl LocalAccount {
 
 #[derive(Debug)]
 pub struct AccountKey {
-    private_key: Ed25519PrivateKey,
-    public_key: Ed25519PublicKey,
+    private_key: AccountPrivateKey,
     authentication_key: AuthenticationKey,
 }
 
+/// The key pair of an account, in one of the supported signature schemes.
+#[derive(Debug)]
+enum AccountPrivateKey {
+    Ed25519 {
+        private_key: Ed25519PrivateKey,
+        public_key: Ed25519PublicKey,
+    },
+    Secp256k1Ecdsa {
+        private_key: Secp256k1EcdsaPrivateKey,
+        public_key: Secp256k1EcdsaPublicKey,
+    },
+}
+
 impl AccountKey {
     pub fn generate<R>(rng: &mut R) -> Self
     where
@@ -181,28 +248,104 @@ impl AccountKey {
         Self::from_private_key(private_key)
     }
 
+    pub fn generate_secp256k1_ecdsa<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let private_key = Secp256k1EcdsaPrivateKey::generate(rng);
+        Self::from_secp256k1_ecdsa_private_key(private_key)
+    }
+
     pub fn from_private_key(private_key: Ed25519PrivateKey) -> Self {
         let public_key = Ed25519PublicKey::from(&private_key);
         let authentication_key = AuthenticationKey::ed25519(&public_key);
 
         Self {
-            private_key,
-            public_key,
+            private_key: AccountPrivateKey::Ed25519 {
+                private_key,
+                public_key,
+            },
             authentication_key,
         }
     }
 
+    pub fn from_secp256k1_ecdsa_private_key(private_key: Secp256k1EcdsaPrivateKey) -> Self {
+        let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+        let authentication_key = AuthenticationKey::secp256k1_ecdsa(&public_key);
+
+        Self {
+            private_key: AccountPrivateKey::Secp256k1Ecdsa {
+                private_key,
+                public_key,
+            },
+            authentication_key,
+        }
+    }
+
+    /// The Ed25519 private key, panics if the key uses another scheme.
     pub fn private_key(&self) -> &Ed25519PrivateKey {
-        &self.private_key
+        match &self.private_key {
+            AccountPrivateKey::Ed25519 { private_key, .. } => private_key,
+            AccountPrivateKey::Secp256k1Ecdsa { .. } => {
+                panic!("Not an Ed25519 key, use sign_transaction or sign_message instead")
+            },
+        }
     }
 
+    /// The Ed25519 public key, panics if the key uses another scheme.
     pub fn public_key(&self) -> &Ed25519PublicKey {
-        &self.public_key
+        match &self.private_key {
+            AccountPrivateKey::Ed25519 { public_key, .. } => public_key,
+            AccountPrivateKey::Secp256k1Ecdsa { .. } => {
+                panic!("Not an Ed25519 key, use authentication_key instead")
+            },
+        }
     }
 
     pub fn authentication_key(&self) -> AuthenticationKey {
         self.authentication_key
     }
+
+    /// Signs the transaction with this key as the only signer.
+    pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
+        match &self.private_key {
+            AccountPrivateKey::Ed25519 {
+                private_key,
+                public_key,
+            } => txn.sign(private_key, public_key.clone()),
+            AccountPrivateKey::Secp256k1Ecdsa {
+                private_key,
+                public_key,
+            } => txn.sign_secp256k1_ecdsa(private_key, public_key.clone()),
+        }
+        .expect("Signing a txn can't fail")
+        .into_inner()
+    }
+
+    /// Signs `message`, e.g. a multi-agent `RawTransactionWithData`, returning the authenticator
+    /// of this key.
+    pub fn sign_message<T: CryptoHash + Serialize>(&self, message: &T) -> AccountAuthenticator {
+        match &self.private_key {
+            AccountPrivateKey::Ed25519 {
+                private_key,
+                public_key,
+            } => AccountAuthenticator::ed25519(
+                public_key.clone(),
+                private_key
+                    .sign(message)
+                    .expect("Signing a message can't fail"),
+            ),
+            AccountPrivateKey::Secp256k1Ecdsa {
+                private_key,
+                public_key,
+            } => AccountAuthenticator::secp256k1_ecdsa(
+                public_key.clone(),
+                private_key
+                    .sign(message)
+                    .expect("Signing a message can't fail"),
+            ),
+        }
+    }
 }
 
 impl From<Ed25519PrivateKey> for AccountKey {
@@ -211,6 +354,12 @@ impl From<Ed25519PrivateKey> for AccountKey {
     }
 }
 
+impl From<Secp256k1EcdsaPrivateKey> for AccountKey {
+    fn from(private_key: Secp256k1EcdsaPrivateKey) -> Self {
+        Self::from_secp256k1_ecdsa_private_key(private_key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,4 +383,33 @@ mod tests {
         // Return an error for empty mnemonic phrase.
         assert!(LocalAccount::from_derive_path(derive_path, "", 0).is_err());
     }
+
+    #[test]
+    fn test_secp256k1_ecdsa_account_signs_transactions() {
+        use crate::{transaction_builder::TransactionFactory, types::chain_id::ChainId};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let mut account = LocalAccount::generate_secp256k1_ecdsa(&mut rng);
+        let other = LocalAccount::generate(&mut rng);
+        assert_eq!(
+            account.address(),
+            account.authentication_key().derived_address()
+        );
+
+        let factory = TransactionFactory::new(ChainId::test());
+        let txn = account.sign_with_transaction_builder(factory.transfer(other.address(), 1));
+        assert!(txn.signature_is_valid());
+        assert_eq!(
+            txn.authenticator().sender().authentication_key(),
+            account.authentication_key()
+        );
+
+        let txn = account.sign_multi_agent_with_transaction_builder(
+            vec![&other],
+            factory.transfer(other.address(), 1),
+        );
+        assert!(txn.signature_is_valid());
+        assert_eq!(account.sequence_number(), 2);
+    }
 }
@@ -9,12 +9,12 @@ use crate::{
     },
     test_helper,
     test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_info},
-    AptosDB, PrunerManager, StaleNodeIndexSchema,
+    AptosDB, PrunerManager, StaleNodeIndexSchema, LEDGER_DB_NAME,
 };
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
-    StateMerklePrunerConfig, BUFFERED_STATE_TARGET_ITEMS,
-    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    DbPathConfig, EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
+    StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_storage_interface::{DbReader, DbWriter, ExecutedTrees, Order};
@@ -103,13 +103,16 @@ fn test_pruner_config() {
         assert_eq!(state_merkle_pruner.is_pruner_enabled(), enable);
         assert_eq!(state_merkle_pruner.get_prune_window(), 20);
 
-        let ledger_pruner =
-            LedgerPrunerManager::new(Arc::clone(&aptos_db.ledger_db), LedgerPrunerConfig {
+        let ledger_pruner = LedgerPrunerManager::new(
+            Arc::clone(&aptos_db.ledger_db),
+            LedgerPrunerConfig {
                 enable,
                 prune_window: 100,
                 batch_size: 1,
                 user_pruning_window_offset: 0,
-            });
+            },
+            /*ledger_archive=*/ None,
+        );
         assert_eq!(ledger_pruner.is_pruner_enabled(), enable);
         assert_eq!(ledger_pruner.get_prune_window(), 100);
     }
@@ -175,6 +178,62 @@ fn test_get_latest_executed_trees() {
     );
 }
 
+#[test]
+fn test_create_checkpoint_with_db_path_overrides() {
+    let db_dir = TempPath::new();
+    let ledger_db_dir = TempPath::new();
+    let cp_dir = TempPath::new();
+    let cp_ledger_db_dir = TempPath::new();
+    let dir_paths = |default_dir: &TempPath, ledger_db_dir: &TempPath| {
+        StorageDirPaths::new(
+            default_dir.path(),
+            Some(&DbPathConfig {
+                ledger_db_path: Some(ledger_db_dir.path().to_path_buf()),
+                ..Default::default()
+            }),
+        )
+    };
+    let open = |dir_paths: &StorageDirPaths| {
+        AptosDB::open_with_dir_paths(
+            dir_paths,
+            false, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs::default(),
+            false, /* enable_indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None, /* ledger_archive */
+        )
+        .unwrap()
+    };
+
+    let txn_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        None,
+        0,
+        ExecutionStatus::MiscellaneousError(None),
+    );
+    put_transaction_info(&open(&dir_paths(&db_dir, &ledger_db_dir)), 0, &txn_info);
+
+    AptosDB::create_checkpoint(
+        &dir_paths(&db_dir, &ledger_db_dir),
+        &dir_paths(&cp_dir, &cp_ledger_db_dir),
+        false, /* use_sharded_state_merkle_db */
+    )
+    .unwrap();
+
+    // The ledger DB checkpoint is created under the overridden directory, not the default one.
+    assert!(cp_ledger_db_dir.path().join(LEDGER_DB_NAME).exists());
+    assert!(!cp_dir.path().join(LEDGER_DB_NAME).exists());
+    let cp_db = open(&dir_paths(&cp_dir, &cp_ledger_db_dir));
+    assert_eq!(
+        cp_db.ledger_store.get_transaction_info(0).unwrap(),
+        txn_info
+    );
+}
+
 pub fn test_state_merkle_pruning_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
 ) {
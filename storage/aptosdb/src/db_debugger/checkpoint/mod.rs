@@ -3,6 +3,7 @@
 
 use crate::{db_debugger::common::DbDir, AptosDB};
 use anyhow::{ensure, Result};
+use aptos_config::config::StorageDirPaths;
 use clap::Parser;
 use std::{fs, path::PathBuf};
 
@@ -22,6 +23,10 @@ impl Cmd {
         fs::create_dir_all(&self.output_dir)?;
 
         // TODO(grao): Support sharded state merkle db here.
-        AptosDB::create_checkpoint(self.db_dir, self.output_dir, false)
+        AptosDB::create_checkpoint(
+            &self.db_dir.dir_paths()?,
+            &StorageDirPaths::from_path(&self.output_dir),
+            false,
+        )
     }
 }
@@ -6,6 +6,7 @@ use crate::{
     LEDGER_DB_NAME, STATE_MERKLE_DB_NAME,
 };
 use anyhow::Result;
+use aptos_config::config::StorageDirPaths;
 use aptos_types::nibble::{nibble_path::NibblePath, Nibble};
 use clap::Parser;
 use std::path::{Path, PathBuf};
@@ -16,13 +17,37 @@ pub const PAGE_SIZE: usize = 10;
 pub struct DbDir {
     #[clap(long, parse(from_os_str))]
     db_dir: PathBuf,
+
+    /// YAML file with the root directories of the DBs (or DB shards) that are not stored under
+    /// `--db-dir`, in the format of `db_path_overrides` in the node's storage config.
+    #[clap(long, parse(from_os_str))]
+    db_path_overrides: Option<PathBuf>,
 }
 
 impl DbDir {
+    #[cfg(test)]
+    pub fn new<P: AsRef<Path>>(db_dir: P, db_path_overrides: Option<PathBuf>) -> Self {
+        Self {
+            db_dir: db_dir.as_ref().to_path_buf(),
+            db_path_overrides,
+        }
+    }
+
+    /// Returns the root directories of all DBs, applying the overrides (if any).
+    pub fn dir_paths(&self) -> Result<StorageDirPaths> {
+        Ok(StorageDirPaths::from_path_and_overrides_file(
+            &self.db_dir,
+            self.db_path_overrides.as_deref(),
+        )?)
+    }
+
     pub fn open_state_merkle_db(&self) -> Result<aptos_schemadb::DB> {
         aptos_schemadb::DB::open_cf_readonly(
             &aptos_schemadb::Options::default(),
-            self.db_dir.join(STATE_MERKLE_DB_NAME).as_path(),
+            self.dir_paths()?
+                .state_merkle_db_metadata_root_path()
+                .join(STATE_MERKLE_DB_NAME)
+                .as_path(),
             STATE_MERKLE_DB_NAME,
             state_merkle_db_column_families(),
         )
@@ -31,19 +56,16 @@ impl DbDir {
     pub fn open_ledger_db(&self) -> Result<aptos_schemadb::DB> {
         aptos_schemadb::DB::open_cf_readonly(
             &aptos_schemadb::Options::default(),
-            self.db_dir.join(LEDGER_DB_NAME).as_path(),
+            self.dir_paths()?
+                .ledger_db_root_path()
+                .join(LEDGER_DB_NAME)
+                .as_path(),
             LEDGER_DB_NAME,
             ledger_db_column_families(),
         )
     }
 }
 
-impl AsRef<Path> for DbDir {
-    fn as_ref(&self) -> &Path {
-        self.db_dir.as_path()
-    }
-}
-
 pub fn parse_nibble_path(src: &str) -> Result<NibblePath> {
     src.chars()
         .map(|c| Ok(Nibble::from(u8::from_str_radix(&c.to_string(), 16)?)))
@@ -4,7 +4,9 @@
 pub mod checkpoint;
 mod common;
 pub mod ledger;
+pub mod move_shard;
 pub mod state_tree;
+pub mod state_usage;
 pub mod truncate;
 
 use anyhow::Result;
@@ -21,6 +23,10 @@ pub enum Cmd {
     Ledger(ledger::Cmd),
 
     Truncate(truncate::Cmd),
+
+    MoveShard(move_shard::Cmd),
+
+    StateUsage(state_usage::Cmd),
 }
 
 impl Cmd {
@@ -30,6 +36,8 @@ impl Cmd {
             Cmd::Checkpoint(cmd) => cmd.run(),
             Cmd::Ledger(cmd) => cmd.run(),
             Cmd::Truncate(cmd) => cmd.run(),
+            Cmd::MoveShard(cmd) => cmd.run(),
+            Cmd::StateUsage(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,159 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{state_kv_db::StateKvDb, state_merkle_db::StateMerkleDb, NUM_STATE_SHARDS};
+use anyhow::{ensure, Result};
+use aptos_config::config::RocksdbConfig;
+use clap::{ArgEnum, Parser};
+use std::{fs, path::PathBuf};
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum ShardedDb {
+    StateKvDb,
+    StateMerkleDb,
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Move a shard of a state DB to a different root directory (e.g., on another disk). \
+             The node must be stopped, and its storage config updated with the new shard path \
+             afterwards."
+)]
+pub struct Cmd {
+    #[clap(long, arg_enum)]
+    db: ShardedDb,
+
+    #[clap(long)]
+    shard_id: u8,
+
+    /// The root directory the shard is currently stored under.
+    #[clap(long, parse(from_os_str))]
+    source_dir: PathBuf,
+
+    /// The root directory to move the shard to.
+    #[clap(long, parse(from_os_str))]
+    target_dir: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        ensure!(
+            (self.shard_id as usize) < NUM_STATE_SHARDS,
+            "Invalid shard id {}, there are only {} shards.",
+            self.shard_id,
+            NUM_STATE_SHARDS
+        );
+
+        let (source_path, target_path) = match self.db {
+            ShardedDb::StateKvDb => (
+                StateKvDb::db_shard_path(&self.source_dir, self.shard_id),
+                StateKvDb::db_shard_path(&self.target_dir, self.shard_id),
+            ),
+            ShardedDb::StateMerkleDb => (
+                StateMerkleDb::db_shard_path(&self.source_dir, self.shard_id),
+                StateMerkleDb::db_shard_path(&self.target_dir, self.shard_id),
+            ),
+        };
+        ensure!(
+            source_path.exists(),
+            "Shard doesn't exist at {:?}.",
+            source_path
+        );
+        ensure!(!target_path.exists(), "{:?} already exists.", target_path);
+
+        // The checkpoint hardlinks the files if possible, and copies them otherwise (e.g., when
+        // the target is on a different disk).
+        println!(
+            "Copying shard from {:?} to {:?}...",
+            source_path, target_path
+        );
+        {
+            let rocksdb_config = RocksdbConfig::default();
+            let db = match self.db {
+                ShardedDb::StateKvDb => StateKvDb::open_shard(
+                    &self.source_dir,
+                    self.shard_id,
+                    &rocksdb_config,
+                    /*readonly=*/ false,
+                )?,
+                ShardedDb::StateMerkleDb => StateMerkleDb::open_shard(
+                    &self.source_dir,
+                    self.shard_id,
+                    &rocksdb_config,
+                    /*readonly=*/ false,
+                )?,
+            };
+            fs::create_dir_all(
+                target_path
+                    .parent()
+                    .expect("Shard path must have a parent."),
+            )?;
+            db.create_checkpoint(&target_path)?;
+        }
+
+        println!("Removing shard at {:?}...", source_path);
+        fs::remove_dir_all(&source_path)?;
+
+        println!(
+            "Done! Set the path of shard {} of the {:?} to {:?} in the storage config \
+             (`db_path_overrides`) before starting the node.",
+            self.shard_id, self.db, self.target_dir
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue};
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_move_shard() {
+        let source_dir = TempPath::new();
+        let target_dir = TempPath::new();
+        let shard_id = 3;
+        let rocksdb_config = RocksdbConfig::default();
+
+        {
+            let db = StateKvDb::open_shard(
+                source_dir.path(),
+                shard_id,
+                &rocksdb_config,
+                /*readonly=*/ false,
+            )
+            .unwrap();
+            db.put::<DbMetadataSchema>(
+                &DbMetadataKey::StateKvShardCommitProgress(shard_id as usize),
+                &DbMetadataValue::Version(100),
+            )
+            .unwrap();
+        }
+
+        Cmd {
+            db: ShardedDb::StateKvDb,
+            shard_id,
+            source_dir: source_dir.path().to_path_buf(),
+            target_dir: target_dir.path().to_path_buf(),
+        }
+        .run()
+        .unwrap();
+
+        assert!(!StateKvDb::db_shard_path(source_dir.path(), shard_id).exists());
+        let db = StateKvDb::open_shard(
+            target_dir.path(),
+            shard_id,
+            &rocksdb_config,
+            /*readonly=*/ true,
+        )
+        .unwrap();
+        assert_eq!(
+            db.get::<DbMetadataSchema>(&DbMetadataKey::StateKvShardCommitProgress(
+                shard_id as usize
+            ))
+            .unwrap(),
+            Some(DbMetadataValue::Version(100))
+        );
+    }
+}
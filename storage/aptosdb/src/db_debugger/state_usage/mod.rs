@@ -0,0 +1,346 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, AptosDB};
+use anyhow::{format_err, Result};
+use aptos_config::config::{
+    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::HashValue;
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    access_path::Path,
+    state_store::{state_key::StateKeyInner, state_value::StateValue},
+    transaction::Version,
+};
+use clap::{ArgEnum, Parser};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{stdout, Write},
+    path::PathBuf,
+};
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Walk the state at a version and report the bytes and number of items, grouped by \
+             account, by resource type (with generic type parameters collapsed) and by table \
+             handle."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// The version to analyze, defaults to the latest state snapshot. There must be a state
+    /// snapshot at this version.
+    #[clap(long)]
+    version: Option<Version>,
+
+    /// Number of entries to report in each group.
+    #[clap(long, default_value = "100")]
+    top_n: usize,
+
+    #[clap(long, arg_enum, default_value = "json")]
+    format: OutputFormat,
+
+    /// Writes the report to this file instead of stdout.
+    #[clap(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    #[clap(long)]
+    use_state_kv_db: bool,
+}
+
+#[derive(Clone, Copy, Default, Serialize)]
+struct Usage {
+    items: u64,
+    bytes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, bytes: usize) {
+        self.items += 1;
+        self.bytes += bytes as u64;
+    }
+}
+
+#[derive(Serialize)]
+struct Entry {
+    key: String,
+    #[serde(flatten)]
+    usage: Usage,
+}
+
+#[derive(Serialize)]
+struct Report {
+    version: Version,
+    total: Usage,
+    by_account: Vec<Entry>,
+    by_resource_type: Vec<Entry>,
+    by_table_handle: Vec<Entry>,
+}
+
+#[derive(Default)]
+struct Analyzer {
+    total: Usage,
+    by_account: HashMap<String, Usage>,
+    by_resource_type: HashMap<String, Usage>,
+    by_table_handle: HashMap<String, Usage>,
+}
+
+impl Analyzer {
+    fn add(&mut self, state_key_inner: &StateKeyInner, bytes: usize) {
+        self.total.add(bytes);
+        match state_key_inner {
+            StateKeyInner::AccessPath(access_path) => {
+                self.by_account
+                    .entry(access_path.address.to_hex_literal())
+                    .or_default()
+                    .add(bytes);
+                let resource_type = match access_path.get_path() {
+                    Path::Resource(struct_tag) | Path::ResourceGroup(struct_tag) => format!(
+                        "{}::{}::{}{}",
+                        struct_tag.address.to_hex_literal(),
+                        struct_tag.module,
+                        struct_tag.name,
+                        if struct_tag.type_params.is_empty() {
+                            ""
+                        } else {
+                            "<..>"
+                        },
+                    ),
+                    Path::Code(_) => "<module>".to_string(),
+                };
+                self.by_resource_type
+                    .entry(resource_type)
+                    .or_default()
+                    .add(bytes);
+            },
+            StateKeyInner::TableItem { handle, .. } => {
+                self.by_table_handle
+                    .entry(handle.0.to_hex_literal())
+                    .or_default()
+                    .add(bytes);
+            },
+            StateKeyInner::Raw(_) => {},
+        }
+    }
+
+    fn into_report(self, version: Version, top_n: usize) -> Report {
+        let top = |group: HashMap<String, Usage>| {
+            let mut entries: Vec<_> = group
+                .into_iter()
+                .map(|(key, usage)| Entry { key, usage })
+                .collect();
+            entries.sort_by(|a, b| {
+                b.usage
+                    .bytes
+                    .cmp(&a.usage.bytes)
+                    .then_with(|| a.key.cmp(&b.key))
+            });
+            entries.truncate(top_n);
+            entries
+        };
+
+        Report {
+            version,
+            total: self.total,
+            by_account: top(self.by_account),
+            by_resource_type: top(self.by_resource_type),
+            by_table_handle: top(self.by_table_handle),
+        }
+    }
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let db = AptosDB::open_with_dir_paths(
+            &self.db_dir.dir_paths()?,
+            /*readonly=*/ true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs {
+                use_state_kv_db: self.use_state_kv_db,
+                ..Default::default()
+            },
+            /*enable_indexer=*/ false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            /*ledger_archive=*/ None,
+        )?;
+        let version = match self.version {
+            Some(version) => version,
+            None => {
+                db.get_state_snapshot_before(Version::MAX)?
+                    .ok_or_else(|| format_err!("No state snapshot found."))?
+                    .0
+            },
+        };
+        eprintln!("* Analyzing state usage at version {}.", version);
+
+        let mut analyzer = Analyzer::default();
+        for (n, res) in db
+            .state_store
+            .get_state_key_and_value_iter(version, HashValue::zero())?
+            .enumerate()
+        {
+            let (state_key, state_value): (_, StateValue) = res?;
+            analyzer.add(state_key.inner(), state_key.size() + state_value.size());
+            if (n + 1) % 1_000_000 == 0 {
+                eprintln!("  {} items scanned.", n + 1);
+            }
+        }
+        let report = analyzer.into_report(version, self.top_n);
+
+        let mut out: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(stdout()),
+        };
+        match self.format {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &report)?;
+                writeln!(out)?;
+            },
+            OutputFormat::Csv => {
+                writeln!(out, "group,key,items,bytes")?;
+                writeln!(out, "total,,{},{}", report.total.items, report.total.bytes)?;
+                for (group, entries) in [
+                    ("account", &report.by_account),
+                    ("resource_type", &report.by_resource_type),
+                    ("table_handle", &report.by_table_handle),
+                ] {
+                    for entry in entries {
+                        writeln!(
+                            out,
+                            "{},{},{},{}",
+                            group, entry.key, entry.usage.items, entry.usage.bytes
+                        )?;
+                    }
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_types::{
+        access_path::AccessPath,
+        account_address::AccountAddress,
+        state_store::{state_key::StateKey, table::TableHandle},
+    };
+    use move_core_types::{
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag},
+    };
+    use std::str::FromStr;
+
+    fn resource(address: AccountAddress, struct_tag: &str) -> StateKey {
+        StateKey::access_path(
+            AccessPath::resource_access_path(address, StructTag::from_str(struct_tag).unwrap())
+                .unwrap(),
+        )
+    }
+
+    fn summarize(entries: &[Entry]) -> Vec<(&str, u64, u64)> {
+        entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.usage.items, entry.usage.bytes))
+            .collect()
+    }
+
+    #[test]
+    fn test_analyzer() {
+        let a = AccountAddress::from_hex_literal("0xa").unwrap();
+        let b = AccountAddress::from_hex_literal("0xb").unwrap();
+        let handle1 = TableHandle(AccountAddress::from_hex_literal("0x100").unwrap());
+        let handle2 = TableHandle(AccountAddress::from_hex_literal("0x200").unwrap());
+
+        let state = vec![
+            (
+                resource(a, "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"),
+                100,
+            ),
+            (resource(b, "0x1::coin::CoinStore<0xb::foo::Foo>"), 50),
+            (resource(a, "0x1::account::Account"), 30),
+            (
+                StateKey::access_path(AccessPath::code_access_path(ModuleId::new(
+                    a,
+                    Identifier::new("bar").unwrap(),
+                ))),
+                150,
+            ),
+            (
+                StateKey::access_path(AccessPath::resource_group_access_path(
+                    b,
+                    StructTag::from_str("0x1::object::ObjectGroup").unwrap(),
+                )),
+                10,
+            ),
+            (StateKey::table_item(handle1, vec![1]), 40),
+            (StateKey::table_item(handle1, vec![2]), 5),
+            (StateKey::table_item(handle2, vec![1]), 20),
+            (StateKey::raw(b"raw".to_vec()), 7),
+        ];
+        let mut analyzer = Analyzer::default();
+        for (state_key, bytes) in &state {
+            analyzer.add(state_key.inner(), *bytes);
+        }
+
+        let report = analyzer.into_report(42, 100);
+        assert_eq!(report.version, 42);
+        // Raw keys only count towards the total.
+        assert_eq!(report.total.items, 9);
+        assert_eq!(report.total.bytes, 412);
+        assert_eq!(summarize(&report.by_account), vec![
+            ("0xa", 3, 280),
+            ("0xb", 2, 60),
+        ]);
+        // Generic type parameters are collapsed and modules are grouped together. Ties are
+        // broken by key.
+        assert_eq!(summarize(&report.by_resource_type), vec![
+            ("0x1::coin::CoinStore<..>", 2, 150),
+            ("<module>", 1, 150),
+            ("0x1::account::Account", 1, 30),
+            ("0x1::object::ObjectGroup", 1, 10),
+        ]);
+        assert_eq!(summarize(&report.by_table_handle), vec![
+            ("0x100", 2, 45),
+            ("0x200", 1, 20),
+        ]);
+    }
+
+    #[test]
+    fn test_analyzer_top_n() {
+        let mut analyzer = Analyzer::default();
+        for i in 1..=10u8 {
+            let handle = TableHandle(AccountAddress::new([i; AccountAddress::LENGTH]));
+            analyzer.add(StateKey::table_item(handle, vec![]).inner(), i as usize);
+        }
+
+        let report = analyzer.into_report(0, 3);
+        // The total covers the entries that are cut off.
+        assert_eq!(report.total.items, 10);
+        assert_eq!(report.total.bytes, 55);
+        assert_eq!(
+            report
+                .by_table_handle
+                .iter()
+                .map(|entry| entry.usage.bytes)
+                .collect::<Vec<_>>(),
+            vec![10, 9, 8]
+        );
+        assert!(report.by_account.is_empty());
+        assert!(report.by_resource_type.is_empty());
+    }
+}
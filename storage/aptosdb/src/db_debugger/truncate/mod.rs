@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    db_debugger::common::DbDir,
     jellyfish_merkle_node::JellyfishMerkleNodeSchema,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -16,7 +17,7 @@ use crate::{
     AptosDB, StateStore,
 };
 use anyhow::{ensure, Result};
-use aptos_config::config::RocksdbConfigs;
+use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
 use aptos_jellyfish_merkle::node_type::NodeKey;
 use aptos_schemadb::{ReadOptions, DB};
 use aptos_types::transaction::Version;
@@ -31,8 +32,8 @@ use std::{fs, path::PathBuf, sync::Arc};
         .args(&["backup-checkpoint-dir", "opt-out-backup-checkpoint"]),
 ))]
 pub struct Cmd {
-    #[clap(long, parse(from_os_str))]
-    db_dir: PathBuf,
+    #[clap(flatten)]
+    db_dir: DbDir,
 
     #[clap(long)]
     target_version: u64,
@@ -52,6 +53,7 @@ pub struct Cmd {
 
 impl Cmd {
     pub fn run(self) -> Result<()> {
+        let db_paths = self.db_dir.dir_paths()?;
         if !self.opt_out_backup_checkpoint {
             let backup_checkpoint_dir = self.backup_checkpoint_dir.unwrap();
             ensure!(
@@ -61,7 +63,11 @@ impl Cmd {
             println!("Creating backup at: {:?}", &backup_checkpoint_dir);
             fs::create_dir_all(&backup_checkpoint_dir)?;
             // TODO(grao): Support sharded state merkle db here.
-            AptosDB::create_checkpoint(&self.db_dir, backup_checkpoint_dir, false)?;
+            AptosDB::create_checkpoint(
+                &db_paths,
+                &StorageDirPaths::from_path(backup_checkpoint_dir),
+                false,
+            )?;
             println!("Done!");
         } else {
             println!("Opted out backup creation!.");
@@ -72,7 +78,7 @@ impl Cmd {
             ..Default::default()
         };
         let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
-            &self.db_dir,
+            &db_paths,
             rocksdb_config,
             /*readonly=*/ false,
             /*max_num_nodes_per_lru_cache_shard=*/ 0,
@@ -235,7 +241,7 @@ mod test {
             let target_version = db_version - 70;
 
             let cmd = Cmd {
-                db_dir: tmp_dir.path().to_path_buf(),
+                db_dir: DbDir::new(tmp_dir.path(), None),
                 target_version,
                 ledger_db_batch_size: 15,
                 opt_out_backup_checkpoint: true,
@@ -265,7 +271,7 @@ mod test {
             drop(db);
 
             let (ledger_db, state_merkle_db, _) = AptosDB::open_dbs(
-                tmp_dir.path().to_path_buf(),
+                &StorageDirPaths::from_path(tmp_dir.path()),
                 RocksdbConfigs::default(),
                 /*readonly=*/ false,
                 /*max_num_nodes_per_lru_cache_shard=*/ 0,
@@ -17,6 +17,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         EPOCH_BY_VERSION_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
+        EVENT_BY_TYPE_CF_NAME,
         EVENT_BY_VERSION_CF_NAME,
         EVENT_CF_NAME,
         LEDGER_INFO_CF_NAME,
@@ -25,6 +26,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         TRANSACTION_CF_NAME,
         TRANSACTION_ACCUMULATOR_CF_NAME,
         TRANSACTION_BY_ACCOUNT_CF_NAME,
+        TRANSACTION_BY_ENTRY_FUNCTION_CF_NAME,
         TRANSACTION_BY_HASH_CF_NAME,
         TRANSACTION_INFO_CF_NAME,
         VERSION_DATA_CF_NAME,
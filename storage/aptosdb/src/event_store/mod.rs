@@ -10,8 +10,11 @@ use super::AptosDB;
 use crate::{
     errors::AptosDbError,
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::{event_type_hash, EventByTypeSchema},
+        event_by_version::EventByVersionSchema,
     },
     utils::iterators::EventsByVersionIter,
 };
@@ -30,6 +33,7 @@ use aptos_types::{
     proof::position::Position,
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::{TryFrom, TryInto},
@@ -40,11 +44,16 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     db: Arc<DB>,
+    /// Whether to maintain `EventByTypeSchema` when saving events.
+    enable_secondary_indexes: bool,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, enable_secondary_indexes: bool) -> Self {
+        Self {
+            db,
+            enable_secondary_indexes,
+        }
     }
 
     /// Get all of the events given a transaction version.
@@ -188,7 +197,7 @@ impl EventStore {
         Ok(result)
     }
 
-    fn lookup_event_by_key(
+    pub fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
         seq_num: u64,
@@ -291,6 +300,33 @@ impl EventStore {
         }
     }
 
+    /// Given an event type, returns the `(version, index)` of up to `limit` events of that type,
+    /// starting at event `start_index` of `start_version` and ending at or before
+    /// `ledger_version`, in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64)>> {
+        let type_hash = event_type_hash(type_tag)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_hash, start_version, start_index))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((hash, version, index), ()) = res?;
+            if hash != type_hash || version > ledger_version {
+                break;
+            }
+            result.push((version, index));
+        }
+
+        Ok(result)
+    }
+
     pub fn get_block_metadata(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
         let (first_version, event_index, seq_num) = self
             .lookup_event_before_or_at_version(&new_block_event_key(), version)?
@@ -322,7 +358,14 @@ impl EventStore {
                 batch.put::<EventByVersionSchema>(
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
-                )
+                )?;
+                if self.enable_secondary_indexes {
+                    batch.put::<EventByTypeSchema>(
+                        &(event_type_hash(event.type_tag())?, version, idx as u64),
+                        &(),
+                    )?;
+                }
+                Ok(())
             })?;
 
         // EventAccumulatorSchema updates
@@ -454,21 +497,34 @@ impl EventStore {
     }
 
     /// Prune a set of candidate events in the range of version in [begin, end) and all related indices
+    ///
+    /// With `keep_indices`, the event by key and event by version indices are kept, so events
+    /// moved to the ledger archive can still be located.
     pub fn prune_events(
         &self,
         start: Version,
         end: Version,
+        keep_indices: bool,
         db_batch: &SchemaBatch,
     ) -> anyhow::Result<()> {
         let mut current_version = start;
         for events in self.get_events_by_version_iter(start, (end - start) as usize)? {
             for (current_index, event) in (events?).into_iter().enumerate() {
-                db_batch.delete::<EventByVersionSchema>(&(
-                    *event.key(),
+                if !keep_indices {
+                    db_batch.delete::<EventByVersionSchema>(&(
+                        *event.key(),
+                        current_version,
+                        event.sequence_number(),
+                    ))?;
+                    db_batch
+                        .delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+                }
+                // The secondary index might have been enabled at some point, always clean it up.
+                db_batch.delete::<EventByTypeSchema>(&(
+                    event_type_hash(event.type_tag())?,
                     current_version,
-                    event.sequence_number(),
+                    current_index as u64,
                 ))?;
-                db_batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
                 db_batch.delete::<EventSchema>(&(current_version, current_index as u64))?;
             }
             current_version += 1;
@@ -360,3 +360,115 @@ proptest! {
         test_get_last_version_before_timestamp_impl(new_block_events)
     }
 }
+
+fn test_lookup_events_by_type_impl(event_batches: Vec<Vec<ContractEvent>>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_secondary_indexes(&tmp_dir);
+    let store = &db.event_store;
+
+    let mut expected: HashMap<TypeTag, Vec<(Version, u64)>> = HashMap::new();
+    let batch = SchemaBatch::new();
+    for (ver, events) in event_batches.iter().enumerate() {
+        store.put_events(ver as Version, events, &batch).unwrap();
+        for (idx, event) in events.iter().enumerate() {
+            expected
+                .entry(event.type_tag().clone())
+                .or_default()
+                .push((ver as Version, idx as u64));
+        }
+    }
+    store.db.write_schemas(batch).unwrap();
+    let ledger_version = event_batches.len() as Version - 1;
+
+    for (type_tag, indices) in &expected {
+        assert_eq!(
+            &store
+                .lookup_events_by_type(type_tag, 0, 0, u64::MAX, ledger_version)
+                .unwrap(),
+            indices,
+        );
+        // Paging from the version of the last event.
+        let (last_version, last_index) = *indices.last().unwrap();
+        assert_eq!(
+            store
+                .lookup_events_by_type(type_tag, last_version, 0, 1, ledger_version)
+                .unwrap(),
+            indices
+                .iter()
+                .filter(|(ver, _)| *ver == last_version)
+                .take(1)
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        // Paging from the index of the last event skips the earlier events of its version.
+        assert_eq!(
+            store
+                .lookup_events_by_type(type_tag, last_version, last_index, u64::MAX, ledger_version)
+                .unwrap(),
+            vec![(last_version, last_index)],
+        );
+        assert!(store
+            .lookup_events_by_type(
+                type_tag,
+                last_version,
+                last_index + 1,
+                u64::MAX,
+                ledger_version
+            )
+            .unwrap()
+            .is_empty());
+        // Events after the ledger version are ignored.
+        if last_version > 0 {
+            assert!(store
+                .lookup_events_by_type(type_tag, last_version, 0, u64::MAX, last_version - 1)
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    // Pruning the events cleans up the index as well.
+    let batch = SchemaBatch::new();
+    store
+        .prune_events(
+            0,
+            event_batches.len() as Version,
+            false, /* keep_indices */
+            &batch,
+        )
+        .unwrap();
+    store.db.write_schemas(batch).unwrap();
+    for type_tag in expected.keys() {
+        assert!(store
+            .lookup_events_by_type(type_tag, 0, 0, u64::MAX, ledger_version)
+            .unwrap()
+            .is_empty());
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_lookup_events_by_type(
+        event_batches in vec(vec(any::<ContractEvent>().no_shrink(), 0..10), 1..10),
+    ) {
+        test_lookup_events_by_type_impl(event_batches);
+    }
+
+    #[test]
+    fn test_events_by_type_not_indexed_when_disabled(
+        events in vec(any::<ContractEvent>().no_shrink(), 1..10),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.event_store;
+        save(store, 0, &events);
+
+        for event in &events {
+            prop_assert!(store
+                .lookup_events_by_type(event.type_tag(), 0, 0, u64::MAX, 0)
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
@@ -27,9 +27,9 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         accumulator::InMemoryAccumulator, position::Position, AccumulatorConsistencyProof,
-        AccumulatorRangeProof, SparseMerkleProofExt, TransactionAccumulatorProof,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        AccumulatorRangeProof, SparseMerkleMultiProof, SparseMerkleProofExt,
+        TransactionAccumulatorProof, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_proof::StateProof,
     state_store::{
@@ -579,6 +579,18 @@ impl DbReader for FakeAptosDB {
             .get_events(event_key, start, order, limit, ledger_version)
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &move_core_types::language_storage::TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        self.inner
+            .get_events_by_type(type_tag, start_version, start_index, limit, ledger_version)
+    }
+
     fn get_block_timestamp(&self, version: Version) -> Result<u64> {
         self.inner.get_block_timestamp(version)
     }
@@ -665,6 +677,25 @@ impl DbReader for FakeAptosDB {
             .get_account_transactions(address, seq_num, limit, include_events, ledger_version)
     }
 
+    fn get_transactions_by_entry_function(
+        &self,
+        module: &move_core_types::language_storage::ModuleId,
+        function: &move_core_types::identifier::IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>> {
+        self.inner.get_transactions_by_entry_function(
+            module,
+            function,
+            start_version,
+            limit,
+            include_events,
+            ledger_version,
+        )
+    }
+
     fn get_state_proof_with_ledger_info(
         &self,
         known_version: u64,
@@ -735,6 +766,15 @@ impl DbReader for FakeAptosDB {
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
 
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        self.inner
+            .get_state_values_with_multi_proof_by_version(state_keys, version)
+    }
+
     fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
         // If the genesis is not executed yet, we need to get the executed trees from the inner AptosDB
         // This is because when we call save_transactions for the genesis block, we call [AptosDB::save_transactions]
@@ -835,6 +875,10 @@ impl DbReader for FakeAptosDB {
         self.inner.indexer_enabled()
     }
 
+    fn secondary_indexes_enabled(&self) -> bool {
+        self.inner.secondary_indexes_enabled()
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         self.inner.get_state_storage_usage(version)
     }
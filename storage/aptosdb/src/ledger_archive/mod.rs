@@ -0,0 +1,343 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the cold tier of the ledger db. When enabled, the ledger pruner first
+//! writes every range of versions it is about to prune into an immutable, compressed segment.
+//! Reads of transactions, transaction infos, write sets and events below the min readable version
+//! then fall back to these segments. The pruner keeps the transaction accumulator and the event
+//! indices in that case, so archived data is still served with proofs and by event key.
+//!
+//! A segment covers the half-open version range `[first_version, end_version)` and is named after
+//! it, so the segment index can be rebuilt by listing the archive storage. A pruned range larger
+//! than `TARGET_SEGMENT_BYTES` is split into several segments. Segments are kept small so that a
+//! random read only decompresses a few MB, and the most recently read ones are cached.
+
+use crate::{
+    schema::{
+        event::EventSchema, transaction::TransactionSchema,
+        transaction_info::TransactionInfoSchema, write_set::WriteSetSchema,
+    },
+    utils::iterators::{EventsByVersionIter, ExpectContinuousVersions},
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_compression::metrics::CompressionClient;
+use aptos_infallible::{Mutex, RwLock};
+use aptos_logger::info;
+use aptos_schemadb::{ReadOptions, DB};
+use aptos_types::{
+    contract_event::ContractEvent,
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
+};
+use itertools::izip;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[cfg(test)]
+mod test;
+
+const SEGMENT_FILE_EXTENSION: &str = "segment";
+/// Upper bound of the uncompressed size of a segment.
+const MAX_SEGMENT_BYTES: usize = 1 << 30;
+/// A segment is closed once its uncompressed size would exceed this, which leaves plenty of room
+/// under `MAX_SEGMENT_BYTES` for the single transaction that can always go into a new segment.
+const TARGET_SEGMENT_BYTES: usize = 1 << 22;
+/// Number of decompressed segments kept in memory.
+const SEGMENT_CACHE_SIZE: usize = 16;
+
+/// Where the segments are kept. Segments are written once and never modified afterwards.
+pub trait LedgerArchiveStorage: Send + Sync {
+    /// Persists the segment `name`. The segment must be durable once this returns.
+    fn put_segment(&self, name: &str, data: &[u8]) -> Result<()>;
+
+    /// Reads back the segment `name`.
+    fn get_segment(&self, name: &str) -> Result<Vec<u8>>;
+
+    /// Lists the names of all the segments in the storage.
+    fn list_segments(&self) -> Result<Vec<String>>;
+}
+
+/// Keeps the segments as files in a local directory.
+pub struct LocalFsArchiveStorage {
+    dir: PathBuf,
+}
+
+impl LocalFsArchiveStorage {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+}
+
+impl LedgerArchiveStorage for LocalFsArchiveStorage {
+    fn put_segment(&self, name: &str, data: &[u8]) -> Result<()> {
+        // Write to a temporary file first so a crash never leaves a partial segment behind.
+        let tmp_path = self.dir.join(format!("{}.tmp", name));
+        fs::write(&tmp_path, data)?;
+        fs::File::open(&tmp_path)?.sync_all()?;
+        fs::rename(&tmp_path, self.dir.join(name))?;
+        Ok(())
+    }
+
+    fn get_segment(&self, name: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(self.dir.join(name))?)
+    }
+
+    fn list_segments(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some(SEGMENT_FILE_EXTENSION) {
+                if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Everything the ledger pruner deletes for a single version.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ArchivedTransaction {
+    pub transaction: Transaction,
+    pub transaction_info: TransactionInfo,
+    pub write_set: WriteSet,
+    pub events: Vec<ContractEvent>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Segment {
+    first_version: Version,
+    transactions: Vec<ArchivedTransaction>,
+}
+
+impl Segment {
+    fn new(first_version: Version) -> Self {
+        Self {
+            first_version,
+            transactions: Vec::new(),
+        }
+    }
+
+    fn end_version(&self) -> Version {
+        self.first_version + self.transactions.len() as Version
+    }
+}
+
+fn segment_name(first_version: Version, end_version: Version) -> String {
+    format!(
+        "{:020}-{:020}.{}",
+        first_version, end_version, SEGMENT_FILE_EXTENSION
+    )
+}
+
+fn parse_segment_name(name: &str) -> Option<(Version, Version)> {
+    let (first_version, end_version) = name
+        .strip_suffix(SEGMENT_FILE_EXTENSION)?
+        .strip_suffix('.')?
+        .split_once('-')?;
+    Some((first_version.parse().ok()?, end_version.parse().ok()?))
+}
+
+pub struct LedgerArchive {
+    storage: Box<dyn LedgerArchiveStorage>,
+    /// first_version -> end_version of each known segment.
+    segments: RwLock<BTreeMap<Version, Version>>,
+    /// The most recently read segments, keyed by first_version.
+    segment_cache: Mutex<LruCache<Version, Arc<Segment>>>,
+    target_segment_bytes: usize,
+}
+
+impl LedgerArchive {
+    pub fn open(storage: Box<dyn LedgerArchiveStorage>) -> Result<Self> {
+        let mut segments = BTreeMap::new();
+        for name in storage.list_segments()? {
+            let (first_version, end_version) = parse_segment_name(&name)
+                .ok_or_else(|| format_err!("Unexpected ledger archive segment {}.", name))?;
+            // A crash between archiving and pruning can leave a shorter segment starting at the
+            // same version behind, keep the longest one.
+            let entry = segments.entry(first_version).or_insert(end_version);
+            *entry = std::cmp::max(*entry, end_version);
+        }
+        info!(num_segments = segments.len(), "Opened ledger archive.");
+
+        Ok(Self {
+            storage,
+            segments: RwLock::new(segments),
+            segment_cache: Mutex::new(LruCache::new(SEGMENT_CACHE_SIZE)),
+            target_segment_bytes: TARGET_SEGMENT_BYTES,
+        })
+    }
+
+    pub fn open_local<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        Self::open(Box::new(LocalFsArchiveStorage::new(dir)?))
+    }
+
+    #[cfg(test)]
+    fn set_target_segment_bytes(&mut self, target_segment_bytes: usize) {
+        self.target_segment_bytes = target_segment_bytes;
+    }
+
+    /// Writes the ledger data in `[begin, end)` into new segments.
+    pub(crate) fn archive_range(&self, db: &DB, begin: Version, end: Version) -> Result<()> {
+        if begin >= end {
+            return Ok(());
+        }
+        let num_versions = (end - begin) as usize;
+
+        let mut txn_iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+        txn_iter.seek(&begin)?;
+        let mut txn_info_iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+        txn_info_iter.seek(&begin)?;
+        let mut write_set_iter = db.iter::<WriteSetSchema>(ReadOptions::default())?;
+        write_set_iter.seek(&begin)?;
+        let mut event_iter = db.iter::<EventSchema>(ReadOptions::default())?;
+        event_iter.seek(&begin)?;
+
+        let mut segment = Segment::new(begin);
+        let mut segment_bytes = 0;
+        for (transaction, transaction_info, write_set, events) in izip!(
+            txn_iter.expect_continuous_versions(begin, num_versions)?,
+            txn_info_iter.expect_continuous_versions(begin, num_versions)?,
+            write_set_iter.expect_continuous_versions(begin, num_versions)?,
+            EventsByVersionIter::new(event_iter, begin, end),
+        ) {
+            let txn = ArchivedTransaction {
+                transaction: transaction?,
+                transaction_info: transaction_info?,
+                write_set: write_set?,
+                events: events?,
+            };
+            let txn_bytes = bcs::serialized_size(&txn)?;
+            if !segment.transactions.is_empty()
+                && segment_bytes + txn_bytes > self.target_segment_bytes
+            {
+                let next_first_version = segment.end_version();
+                self.put_segment(&std::mem::replace(
+                    &mut segment,
+                    Segment::new(next_first_version),
+                ))?;
+                segment_bytes = 0;
+            }
+            segment.transactions.push(txn);
+            segment_bytes += txn_bytes;
+        }
+        ensure!(
+            segment.end_version() == end,
+            "Expecting {} versions to archive, got {}.",
+            num_versions,
+            segment.end_version() - begin,
+        );
+
+        self.put_segment(&segment)
+    }
+
+    fn put_segment(&self, segment: &Segment) -> Result<()> {
+        let (first_version, end_version) = (segment.first_version, segment.end_version());
+        let data = aptos_compression::compress(
+            bcs::to_bytes(segment)?,
+            CompressionClient::LedgerArchive,
+            MAX_SEGMENT_BYTES,
+        )?;
+        self.storage
+            .put_segment(&segment_name(first_version, end_version), &data)?;
+
+        let mut segments = self.segments.write();
+        let entry = segments.entry(first_version).or_insert(end_version);
+        *entry = std::cmp::max(*entry, end_version);
+        Ok(())
+    }
+
+    /// Returns whether `version` can be served from the archive.
+    pub fn contains(&self, version: Version) -> bool {
+        self.find_segment(version).is_some()
+    }
+
+    /// Returns the archived data for `[start_version, start_version + limit)`, which must be
+    /// fully covered by the archive.
+    pub fn get_transactions(
+        &self,
+        start_version: Version,
+        limit: usize,
+    ) -> Result<Vec<ArchivedTransaction>> {
+        let end_version = start_version
+            .checked_add(limit as Version)
+            .ok_or_else(|| format_err!("Too many items requested"))?;
+
+        let mut ret = Vec::with_capacity(limit);
+        let mut version = start_version;
+        while version < end_version {
+            let (first_version, segment_end_version) = self
+                .find_segment(version)
+                .ok_or_else(|| format_err!("Version {} is not in the ledger archive.", version))?;
+            let segment = self.read_segment(first_version, segment_end_version)?;
+            let stop = std::cmp::min(end_version, segment.end_version());
+            ret.extend_from_slice(
+                &segment.transactions
+                    [(version - first_version) as usize..(stop - first_version) as usize],
+            );
+            version = stop;
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the archived data at `version`, which must be covered by the archive.
+    pub fn get_transaction(&self, version: Version) -> Result<ArchivedTransaction> {
+        let (first_version, end_version) = self
+            .find_segment(version)
+            .ok_or_else(|| format_err!("Version {} is not in the ledger archive.", version))?;
+        let segment = self.read_segment(first_version, end_version)?;
+        Ok(segment.transactions[(version - first_version) as usize].clone())
+    }
+
+    fn find_segment(&self, version: Version) -> Option<(Version, Version)> {
+        self.segments
+            .read()
+            .range(..=version)
+            .next_back()
+            .filter(|(_first_version, end_version)| version < **end_version)
+            .map(|(first_version, end_version)| (*first_version, *end_version))
+    }
+
+    fn read_segment(&self, first_version: Version, end_version: Version) -> Result<Arc<Segment>> {
+        if let Some(segment) = self.segment_cache.lock().get(&first_version) {
+            // A longer segment starting at the same version may have replaced a shorter one.
+            if segment.end_version() == end_version {
+                return Ok(Arc::clone(segment));
+            }
+        }
+
+        let data = self
+            .storage
+            .get_segment(&segment_name(first_version, end_version))?;
+        let segment: Segment = bcs::from_bytes(&aptos_compression::decompress(
+            &data,
+            CompressionClient::LedgerArchive,
+            MAX_SEGMENT_BYTES,
+        )?)?;
+        ensure!(
+            segment.first_version == first_version && segment.end_version() == end_version,
+            "Ledger archive segment [{}, {}) has unexpected range [{}, {}).",
+            first_version,
+            end_version,
+            segment.first_version,
+            segment.end_version(),
+        );
+
+        let segment = Arc::new(segment);
+        self.segment_cache
+            .lock()
+            .put(first_version, Arc::clone(&segment));
+        Ok(segment)
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    test_helper::{
+        arb_blocks_to_commit_with_block_nums, group_events_by_event_key, update_in_memory_state,
+        verify_events_by_event_key,
+    },
+    AptosDB, LedgerPrunerManager, PrunerManager,
+};
+use aptos_config::config::LedgerPrunerConfig;
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::{DbReader, DbWriter};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    block_metadata::BlockMetadata,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{SignedTransaction, TransactionToCommit},
+};
+use proptest::{collection::vec, prelude::*};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn arb_archived_transaction() -> impl Strategy<Value = ArchivedTransaction> {
+    (
+        prop_oneof![
+            any::<BlockMetadata>().prop_map(Transaction::BlockMetadata),
+            any::<SignedTransaction>().prop_map(Transaction::UserTransaction),
+        ],
+        any::<TransactionInfo>(),
+        any::<WriteSet>(),
+        vec(any::<ContractEvent>(), 0..3),
+    )
+        .prop_map(
+            |(transaction, transaction_info, write_set, events)| ArchivedTransaction {
+                transaction,
+                transaction_info,
+                write_set,
+                events,
+            },
+        )
+}
+
+fn put_transactions(aptos_db: &AptosDB, txns: &[ArchivedTransaction]) {
+    let batch = SchemaBatch::new();
+    for (version, txn) in txns.iter().enumerate() {
+        let version = version as Version;
+        aptos_db
+            .transaction_store
+            .put_transaction(version, &txn.transaction, &batch)
+            .unwrap();
+        aptos_db
+            .transaction_store
+            .put_write_set(version, &txn.write_set, &batch)
+            .unwrap();
+        batch
+            .put::<TransactionInfoSchema>(&version, &txn.transaction_info)
+            .unwrap();
+        aptos_db
+            .event_store
+            .put_events(version, &txn.events, &batch)
+            .unwrap();
+    }
+    aptos_db.ledger_db.write_schemas(batch).unwrap();
+}
+
+fn verify_archive_pruned_ledger(
+    txns: Vec<ArchivedTransaction>,
+    batch_size: usize,
+    one_txn_per_segment: bool,
+) {
+    let tmp_dir = TempPath::new();
+    let archive_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    put_transactions(&aptos_db, &txns);
+
+    let mut ledger_archive = LedgerArchive::open_local(archive_dir.path()).unwrap();
+    if one_txn_per_segment {
+        ledger_archive.set_target_segment_bytes(1);
+    }
+    let ledger_archive = Arc::new(ledger_archive);
+    let pruner = LedgerPrunerManager::new(
+        Arc::clone(&aptos_db.ledger_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size,
+            user_pruning_window_offset: 0,
+        },
+        Some(Arc::clone(&ledger_archive)),
+    );
+    let latest_version = txns.len() as Version - 1;
+    pruner.wake_and_wait_pruner(latest_version).unwrap();
+    assert_eq!(pruner.get_min_readable_version(), latest_version);
+    assert!(aptos_db.transaction_store.get_transaction(0).is_err());
+    if one_txn_per_segment {
+        // Pruned ranges beyond the target size are split instead of failing the pruner.
+        assert_eq!(
+            ledger_archive.segments.read().len(),
+            latest_version as usize
+        );
+    }
+
+    // Everything pruned is served by the archive, also after reopening it.
+    let reopened = LedgerArchive::open_local(archive_dir.path()).unwrap();
+    for archive in [&*ledger_archive, &reopened] {
+        assert_eq!(
+            archive
+                .get_transactions(0, latest_version as usize)
+                .unwrap(),
+            txns[..latest_version as usize].to_vec(),
+        );
+        assert!(!archive.contains(latest_version));
+        assert!(archive
+            .get_transactions(0, latest_version as usize + 1)
+            .is_err());
+    }
+}
+
+/// Counts the segments read from the underlying storage.
+struct CountingStorage {
+    inner: LocalFsArchiveStorage,
+    num_reads: Arc<AtomicUsize>,
+}
+
+impl LedgerArchiveStorage for CountingStorage {
+    fn put_segment(&self, name: &str, data: &[u8]) -> Result<()> {
+        self.inner.put_segment(name, data)
+    }
+
+    fn get_segment(&self, name: &str) -> Result<Vec<u8>> {
+        self.num_reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_segment(name)
+    }
+
+    fn list_segments(&self) -> Result<Vec<String>> {
+        self.inner.list_segments()
+    }
+}
+
+fn verify_alternating_segment_reads(txns: Vec<ArchivedTransaction>) {
+    let tmp_dir = TempPath::new();
+    let archive_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    put_transactions(&aptos_db, &txns);
+
+    let num_reads = Arc::new(AtomicUsize::new(0));
+    let mut ledger_archive = LedgerArchive::open(Box::new(CountingStorage {
+        inner: LocalFsArchiveStorage::new(archive_dir.path()).unwrap(),
+        num_reads: Arc::clone(&num_reads),
+    }))
+    .unwrap();
+    ledger_archive.set_target_segment_bytes(1);
+    let end_version = txns.len() as Version;
+    ledger_archive
+        .archive_range(&aptos_db.ledger_db, 0, end_version)
+        .unwrap();
+    assert_eq!(ledger_archive.segments.read().len(), txns.len());
+
+    // Jumping back and forth between the first and the last segment decompresses each only once.
+    let last_version = end_version - 1;
+    for _ in 0..5 {
+        for version in [0, last_version] {
+            assert_eq!(
+                ledger_archive.get_transaction(version).unwrap(),
+                txns[version as usize],
+            );
+        }
+    }
+    assert_eq!(num_reads.load(Ordering::SeqCst), 2);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_alternating_segment_reads(txns in vec(arb_archived_transaction(), 2..10)) {
+        verify_alternating_segment_reads(txns);
+    }
+
+    #[test]
+    fn test_archive_pruned_ledger(
+        txns in vec(arb_archived_transaction(), 2..30),
+        batch_size in 1usize..10,
+        one_txn_per_segment in any::<bool>(),
+    ) {
+        verify_archive_pruned_ledger(txns, batch_size, one_txn_per_segment);
+    }
+
+    #[test]
+    fn test_reads_fall_back_to_archive(input in arb_blocks_to_commit_with_block_nums(2, 10)) {
+        verify_reads_fall_back_to_archive(input);
+    }
+}
+
+fn verify_reads_fall_back_to_archive(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let archive_dir = TempPath::new();
+    let mut aptos_db = AptosDB::new_for_test(&tmp_dir);
+
+    let mut in_memory_state = aptos_db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit);
+        aptos_db
+            .save_transactions(
+                txns_to_commit,
+                cur_ver,                /* first_version */
+                cur_ver.checked_sub(1), /* base_state_version */
+                Some(ledger_info_with_sigs),
+                false, /* sync_commit */
+                in_memory_state.clone(),
+            )
+            .unwrap();
+        cur_ver += txns_to_commit.len() as Version;
+    }
+
+    // Prune everything but the latest version into the archive.
+    let ledger_archive = Arc::new(LedgerArchive::open_local(archive_dir.path()).unwrap());
+    aptos_db.ledger_archive = Some(Arc::clone(&ledger_archive));
+    aptos_db.ledger_pruner = LedgerPrunerManager::new(
+        Arc::clone(&aptos_db.ledger_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 3,
+            user_pruning_window_offset: 0,
+        },
+        Some(ledger_archive),
+    );
+    let ledger_version = cur_ver - 1;
+    aptos_db
+        .ledger_pruner
+        .wake_and_wait_pruner(ledger_version)
+        .unwrap();
+    assert_eq!(
+        aptos_db.ledger_pruner.get_min_readable_version(),
+        ledger_version
+    );
+    assert!(aptos_db.transaction_store.get_transaction(0).is_err());
+
+    let ledger_info = input.last().unwrap().1.ledger_info();
+    let txns_to_commit: Vec<_> = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.clone())
+        .collect();
+    for (version, txn_to_commit) in txns_to_commit.iter().enumerate() {
+        let version = version as Version;
+        let txn_with_proof = aptos_db
+            .get_transaction_by_version(version, ledger_version, true /* fetch_events */)
+            .unwrap();
+        assert_eq!(&txn_with_proof.transaction, txn_to_commit.transaction());
+        assert_eq!(
+            txn_with_proof.events.as_deref(),
+            Some(txn_to_commit.events())
+        );
+        txn_with_proof.proof.verify(ledger_info, version).unwrap();
+    }
+
+    // Ranges spanning the archive and the DB.
+    let num_txns = txns_to_commit.len() as u64;
+    let txn_list_with_proof = aptos_db
+        .get_transactions(0, num_txns, ledger_version, true /* fetch_events */)
+        .unwrap();
+    txn_list_with_proof.verify(ledger_info, Some(0)).unwrap();
+    assert_eq!(txn_list_with_proof.transactions.len(), txns_to_commit.len());
+    let txn_output_list_with_proof = aptos_db
+        .get_transaction_outputs(0, num_txns, ledger_version)
+        .unwrap();
+    txn_output_list_with_proof
+        .verify(ledger_info, Some(0))
+        .unwrap();
+
+    verify_events_by_event_key(
+        &aptos_db,
+        group_events_by_event_key(0, &txns_to_commit),
+        ledger_info,
+        true, /* is_latest */
+    );
+}
+
+#[test]
+fn test_parse_segment_name() {
+    assert_eq!(parse_segment_name(&segment_name(5, 100)), Some((5, 100)));
+    assert_eq!(parse_segment_name("00000000000000000005.segment"), None);
+    assert_eq!(
+        parse_segment_name("00000000000000000005-00000000000000000100.segment.tmp"),
+        None
+    );
+}
@@ -18,6 +18,7 @@ pub mod test_helper;
 
 pub mod backup;
 pub mod errors;
+pub mod ledger_archive;
 pub mod metrics;
 pub mod schema;
 pub mod state_restore;
@@ -46,6 +47,7 @@ use crate::{
     db_options::{gen_ledger_cfds, ledger_db_column_families, state_merkle_db_column_families},
     errors::AptosDbError,
     event_store::EventStore,
+    ledger_archive::{ArchivedTransaction, LedgerArchive},
     ledger_store::LedgerStore,
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
@@ -65,10 +67,11 @@ use crate::{
     state_merkle_db::StateMerkleDb,
     state_store::{buffered_state::BufferedState, StateStore},
     transaction_store::TransactionStore,
+    utils::prepare_checkpoint_path,
 };
 use anyhow::{bail, ensure, Result};
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG,
+    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 #[cfg(any(test, feature = "fuzzing"))]
 use aptos_config::config::{
@@ -93,9 +96,9 @@ use aptos_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
-        TransactionInfoListWithProof,
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleMultiProof,
+        SparseMerkleProofExt, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_proof::StateProof,
     state_store::{
@@ -115,6 +118,10 @@ use aptos_types::{
 use aptos_vm::data_cache::AsMoveResolver;
 use arr_macro::arr;
 use itertools::zip_eq;
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
+};
 use move_resource_viewer::MoveValueAnnotator;
 use once_cell::sync::Lazy;
 use std::{
@@ -132,7 +139,7 @@ pub const LEDGER_DB_NAME: &str = "ledger_db";
 pub const STATE_MERKLE_DB_NAME: &str = "state_merkle_db";
 pub const STATE_KV_DB_NAME: &str = "state_kv_db";
 
-pub(crate) const NUM_STATE_SHARDS: usize = 16;
+pub(crate) const NUM_STATE_SHARDS: usize = aptos_config::config::NUM_STATE_SHARDS;
 
 static COMMIT_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
     rayon::ThreadPoolBuilder::new()
@@ -288,6 +295,8 @@ pub struct AptosDB {
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     ledger_commit_lock: std::sync::Mutex<()>,
     indexer: Option<Indexer>,
+    enable_secondary_indexes: bool,
+    ledger_archive: Option<Arc<LedgerArchive>>,
 }
 
 impl AptosDB {
@@ -298,6 +307,8 @@ impl AptosDB {
         pruner_config: PrunerConfig,
         buffered_state_target_items: usize,
         hack_for_tests: bool,
+        enable_secondary_indexes: bool,
+        ledger_archive: Option<Arc<LedgerArchive>>,
     ) -> Self {
         let state_merkle_db = Arc::new(state_merkle_db);
         let state_kv_db = Arc::new(state_kv_db);
@@ -325,16 +336,23 @@ impl AptosDB {
         let ledger_pruner = LedgerPrunerManager::new(
             Arc::clone(&ledger_rocksdb),
             pruner_config.ledger_pruner_config,
+            ledger_archive.clone(),
         );
 
         AptosDB {
             ledger_db: Arc::clone(&ledger_rocksdb),
             state_merkle_db: Arc::clone(&state_merkle_db),
             state_kv_db: Arc::clone(&state_kv_db),
-            event_store: Arc::new(EventStore::new(Arc::clone(&ledger_rocksdb))),
+            event_store: Arc::new(EventStore::new(
+                Arc::clone(&ledger_rocksdb),
+                enable_secondary_indexes,
+            )),
             ledger_store: Arc::new(LedgerStore::new(Arc::clone(&ledger_rocksdb))),
             state_store,
-            transaction_store: Arc::new(TransactionStore::new(Arc::clone(&ledger_rocksdb))),
+            transaction_store: Arc::new(TransactionStore::new(
+                Arc::clone(&ledger_rocksdb),
+                enable_secondary_indexes,
+            )),
             ledger_pruner,
             // TODO(grao): Include other DBs.
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(
@@ -343,6 +361,8 @@ impl AptosDB {
             ),
             ledger_commit_lock: std::sync::Mutex::new(()),
             indexer: None,
+            enable_secondary_indexes,
+            ledger_archive,
         }
     }
 
@@ -354,6 +374,31 @@ impl AptosDB {
         enable_indexer: bool,
         buffered_state_target_items: usize,
         max_num_nodes_per_lru_cache_shard: usize,
+    ) -> Result<Self> {
+        Self::open_with_dir_paths(
+            &StorageDirPaths::from_path(db_root_path),
+            readonly,
+            pruner_config,
+            rocksdb_configs,
+            enable_indexer,
+            buffered_state_target_items,
+            max_num_nodes_per_lru_cache_shard,
+            /*ledger_archive=*/ None,
+        )
+    }
+
+    /// Opens the DB, with the individual DBs (and DB shards) at the given root directories.
+    /// If `ledger_archive` is set, the ledger pruner archives data into it before pruning, and
+    /// historical reads fall back to it.
+    pub fn open_with_dir_paths(
+        db_paths: &StorageDirPaths,
+        readonly: bool,
+        pruner_config: PrunerConfig,
+        rocksdb_configs: RocksdbConfigs,
+        enable_indexer: bool,
+        buffered_state_target_items: usize,
+        max_num_nodes_per_lru_cache_shard: usize,
+        ledger_archive: Option<Arc<LedgerArchive>>,
     ) -> Result<Self> {
         ensure!(
             pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -361,7 +406,7 @@ impl AptosDB {
         );
 
         let (ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
-            db_root_path.as_ref(),
+            db_paths,
             rocksdb_configs,
             readonly,
             max_num_nodes_per_lru_cache_shard,
@@ -374,24 +419,29 @@ impl AptosDB {
             pruner_config,
             buffered_state_target_items,
             readonly,
+            rocksdb_configs.enable_secondary_indexes,
+            ledger_archive,
         );
 
         if !readonly && enable_indexer {
-            myself.open_indexer(db_root_path, rocksdb_configs.index_db_config)?;
+            myself.open_indexer(
+                db_paths.default_root_path(),
+                rocksdb_configs.index_db_config,
+            )?;
         }
 
         Ok(myself)
     }
 
-    pub fn open_dbs<P: AsRef<Path> + Clone>(
-        db_root_path: P,
+    pub fn open_dbs(
+        db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
         readonly: bool,
         max_num_nodes_per_lru_cache_shard: usize,
     ) -> Result<(Arc<DB>, StateMerkleDb, StateKvDb)> {
         let instant = Instant::now();
 
-        let ledger_db_path = db_root_path.as_ref().join(LEDGER_DB_NAME);
+        let ledger_db_path = db_paths.ledger_db_root_path().join(LEDGER_DB_NAME);
 
         let ledger_db = if readonly {
             DB::open_cf_readonly(
@@ -410,14 +460,10 @@ impl AptosDB {
         };
 
         let ledger_db = Arc::new(ledger_db);
-        let state_kv_db = StateKvDb::new(
-            db_root_path.as_ref(),
-            rocksdb_configs,
-            readonly,
-            Arc::clone(&ledger_db),
-        )?;
+        let state_kv_db =
+            StateKvDb::new(db_paths, rocksdb_configs, readonly, Arc::clone(&ledger_db))?;
         let state_merkle_db = StateMerkleDb::new(
-            db_root_path,
+            db_paths,
             rocksdb_configs,
             readonly,
             max_num_nodes_per_lru_cache_shard,
@@ -525,6 +571,24 @@ impl AptosDB {
         )
     }
 
+    /// This opens db in non-readonly mode, without the pruner, and with the secondary indexes
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_secondary_indexes<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        Self::open(
+            db_root_path,
+            false,
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfigs {
+                enable_secondary_indexes: true,
+                ..Default::default()
+            },
+            false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )
+        .expect("Unable to open AptosDB")
+    }
+
     /// This opens db in non-readonly mode, without the pruner.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test_with_buffered_state_target_items<P: AsRef<Path> + Clone>(
@@ -633,6 +697,19 @@ impl AptosDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
+        if let Some(ledger_archive) = self.archived(version) {
+            let txn = ledger_archive.get_transaction(version)?;
+            return Ok(TransactionWithProof {
+                version,
+                transaction: txn.transaction,
+                events: fetch_events.then_some(txn.events),
+                proof: TransactionInfoWithProof::new(
+                    self.ledger_store
+                        .get_transaction_proof(version, ledger_version)?,
+                    txn.transaction_info,
+                ),
+            });
+        }
         self.error_if_ledger_pruned("Transaction", version)?;
 
         let proof = self
@@ -667,19 +744,20 @@ impl AptosDB {
         )
     }
 
-    /// Creates new physical DB checkpoint in directory specified by `path`.
+    /// Creates new physical DB checkpoint at `cp_paths`, honoring the root directories of the
+    /// individual DBs on both sides.
     pub fn create_checkpoint(
-        db_path: impl AsRef<Path>,
-        cp_path: impl AsRef<Path>,
+        db_paths: &StorageDirPaths,
+        cp_paths: &StorageDirPaths,
         use_sharded_state_merkle_db: bool,
     ) -> Result<()> {
         let start = Instant::now();
-        let ledger_db_path = db_path.as_ref().join(LEDGER_DB_NAME);
-        let ledger_cp_path = cp_path.as_ref().join(LEDGER_DB_NAME);
+        let ledger_db_path = db_paths.ledger_db_root_path().join(LEDGER_DB_NAME);
+        let ledger_cp_path = cp_paths.ledger_db_root_path().join(LEDGER_DB_NAME);
 
         info!("Creating ledger_db checkpoint at: {ledger_cp_path:?}");
 
-        std::fs::remove_dir_all(&ledger_cp_path).unwrap_or(());
+        prepare_checkpoint_path(&ledger_cp_path);
 
         // Weird enough, checkpoint doesn't work with readonly or secondary mode (gets stuck).
         // https://github.com/facebook/rocksdb/issues/11167
@@ -691,16 +769,12 @@ impl AptosDB {
         )?;
         ledger_db.create_checkpoint(ledger_cp_path)?;
 
-        StateKvDb::create_checkpoint(db_path.as_ref(), cp_path.as_ref())?;
-        StateMerkleDb::create_checkpoint(
-            db_path.as_ref(),
-            cp_path.as_ref(),
-            use_sharded_state_merkle_db,
-        )?;
+        StateKvDb::create_checkpoint(db_paths, cp_paths)?;
+        StateMerkleDb::create_checkpoint(db_paths, cp_paths, use_sharded_state_merkle_db)?;
 
         info!(
-            db_path = db_path.as_ref(),
-            cp_path = cp_path.as_ref(),
+            db_path = db_paths.default_root_path(),
+            cp_path = cp_paths.default_root_path(),
             time_ms = %start.elapsed().as_millis(),
             "Made AptosDB checkpoint."
         );
@@ -757,7 +831,7 @@ impl AptosDB {
         let mut events_with_version = event_indices
             .into_iter()
             .map(|(seq, ver, idx)| {
-                let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                let event = self.get_event_by_version_and_index(ver, idx)?;
                 ensure!(
                     seq == event.sequence_number(),
                     "Index broken, expected seq:{}, actual:{}",
@@ -1102,6 +1176,112 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Returns the ledger archive if `version` is pruned from the DB but kept in the archive.
+    fn archived(&self, version: Version) -> Option<&LedgerArchive> {
+        self.ledger_archive.as_deref().filter(|ledger_archive| {
+            version < self.ledger_pruner.get_min_readable_version()
+                && ledger_archive.contains(version)
+        })
+    }
+
+    /// Returns the archived data of the versions at the beginning of
+    /// `[start_version, start_version + limit)` that are already pruned from the DB.
+    fn get_archived_prefix(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Vec<ArchivedTransaction>> {
+        match self.archived(start_version) {
+            Some(ledger_archive) => {
+                let min_readable_version = self.ledger_pruner.get_min_readable_version();
+                let num_archived = std::cmp::min(limit, min_readable_version - start_version);
+                ledger_archive.get_transactions(start_version, num_archived as usize)
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn get_event_by_version_and_index(
+        &self,
+        version: Version,
+        index: u64,
+    ) -> Result<ContractEvent> {
+        match self.archived(version) {
+            Some(ledger_archive) => ledger_archive
+                .get_transaction(version)?
+                .events
+                .into_iter()
+                .nth(index as usize)
+                .ok_or_else(|| {
+                    AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)).into()
+                }),
+            None => self
+                .event_store
+                .get_event_by_version_and_index(version, index),
+        }
+    }
+
+    /// Like `EventStore::get_block_metadata`, but falls back to the ledger archive for pruned
+    /// block events.
+    fn get_block_metadata(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
+        let (first_version, event_index, _seq_num) = self
+            .event_store
+            .lookup_event_before_or_at_version(&new_block_event_key(), version)?
+            .ok_or_else(|| AptosDbError::NotFound("NewBlockEvent".to_string()))?;
+
+        let new_block_event = self.get_event_by_version_and_index(first_version, event_index)?;
+        let payload = bcs::from_bytes(new_block_event.event_data())?;
+        Ok((first_version, payload))
+    }
+
+    /// Serves `[start_version, start_version + limit)` from the DB, falling back to the ledger
+    /// archive (if any) for the versions that are already pruned from the DB.
+    fn get_archived_or_db_iter<'a, T: 'a>(
+        &'a self,
+        data_type: &str,
+        start_version: Version,
+        limit: u64,
+        from_archive: impl Fn(ArchivedTransaction) -> T,
+        from_db: impl FnOnce(Version, usize) -> Result<Box<dyn Iterator<Item = Result<T>> + 'a>>,
+    ) -> Result<Box<dyn Iterator<Item = Result<T>> + 'a>> {
+        if self.archived(start_version).is_none() {
+            self.error_if_ledger_pruned(data_type, start_version)?;
+            return from_db(start_version, limit as usize);
+        }
+
+        let archived = self.get_archived_prefix(start_version, limit)?;
+        let num_archived = archived.len() as u64;
+        let archived = archived.into_iter().map(from_archive).map(Ok);
+        if num_archived == limit {
+            return Ok(Box::new(archived));
+        }
+        Ok(Box::new(archived.chain(from_db(
+            start_version + num_archived,
+            (limit - num_archived) as usize,
+        )?)))
+    }
+
+    fn error_if_secondary_indexes_disabled(&self) -> Result<()> {
+        ensure!(
+            self.enable_secondary_indexes,
+            "Secondary indexes are not enabled.",
+        );
+        Ok(())
+    }
+
+    /// Like `error_if_ledger_pruned`, but tolerates versions kept in the ledger archive, for
+    /// which the transaction accumulator and the event indices are not pruned.
+    fn error_if_ledger_pruned_and_not_archived(
+        &self,
+        data_type: &str,
+        version: Version,
+    ) -> Result<()> {
+        if self.archived(version).is_some() {
+            return Ok(());
+        }
+        self.error_if_ledger_pruned(data_type, version)
+    }
+
     fn error_if_ledger_pruned(&self, data_type: &str, version: Version) -> Result<()> {
         let min_readable_version = self.ledger_pruner.get_min_readable_version();
         ensure!(
@@ -1285,20 +1465,41 @@ impl DbReader for AptosDB {
             if start_version > ledger_version || limit == 0 {
                 return Ok(TransactionListWithProof::new_empty());
             }
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_pruned_and_not_archived("Transaction", start_version)?;
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
-            let txns = (start_version..start_version + limit)
-                .map(|version| self.transaction_store.get_transaction(version))
+            // Versions already pruned from the DB are served by the ledger archive.
+            let archived = self.get_archived_prefix(start_version, limit)?;
+            let db_versions = start_version + archived.len() as Version..start_version + limit;
+
+            let txns = archived
+                .iter()
+                .map(|txn| Ok(txn.transaction.clone()))
+                .chain(
+                    db_versions
+                        .clone()
+                        .map(|version| self.transaction_store.get_transaction(version)),
+                )
                 .collect::<Result<Vec<_>>>()?;
-            let txn_infos = (start_version..start_version + limit)
-                .map(|version| self.ledger_store.get_transaction_info(version))
+            let txn_infos = archived
+                .iter()
+                .map(|txn| Ok(txn.transaction_info.clone()))
+                .chain(
+                    db_versions
+                        .clone()
+                        .map(|version| self.ledger_store.get_transaction_info(version)),
+                )
                 .collect::<Result<Vec<_>>>()?;
             let events = if fetch_events {
                 Some(
-                    (start_version..start_version + limit)
-                        .map(|version| self.event_store.get_events_by_version(version))
+                    archived
+                        .iter()
+                        .map(|txn| Ok(txn.events.clone()))
+                        .chain(
+                            db_versions
+                                .map(|version| self.event_store.get_events_by_version(version)),
+                        )
                         .collect::<Result<Vec<_>>>()?,
                 )
             } else {
@@ -1394,24 +1595,41 @@ impl DbReader for AptosDB {
                 return Ok(TransactionOutputListWithProof::new_empty());
             }
 
-            self.error_if_ledger_pruned("Transaction", start_version)?;
+            self.error_if_ledger_pruned_and_not_archived("Transaction", start_version)?;
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
-            let (txn_infos, txns_and_outputs) = (start_version..start_version + limit)
-                .map(|version| {
-                    let txn_info = self.ledger_store.get_transaction_info(version)?;
-                    let events = self.event_store.get_events_by_version(version)?;
-                    let write_set = self.transaction_store.get_write_set(version)?;
-                    let txn = self.transaction_store.get_transaction(version)?;
-                    let txn_output = TransactionOutput::new(
-                        write_set,
-                        events,
-                        txn_info.gas_used(),
-                        txn_info.status().clone().into(),
-                    );
-                    Ok((txn_info, (txn, txn_output)))
+            // Versions already pruned from the DB are served by the ledger archive.
+            let archived = self.get_archived_prefix(start_version, limit)?;
+            let db_versions = start_version + archived.len() as Version..start_version + limit;
+
+            let to_output = |txn_info: TransactionInfo, txn, write_set, events| {
+                let txn_output = TransactionOutput::new(
+                    write_set,
+                    events,
+                    txn_info.gas_used(),
+                    txn_info.status().clone().into(),
+                );
+                (txn_info, (txn, txn_output))
+            };
+            let (txn_infos, txns_and_outputs) = archived
+                .into_iter()
+                .map(|txn| {
+                    Ok(to_output(
+                        txn.transaction_info,
+                        txn.transaction,
+                        txn.write_set,
+                        txn.events,
+                    ))
                 })
+                .chain(db_versions.map(|version| -> Result<_> {
+                    Ok(to_output(
+                        self.ledger_store.get_transaction_info(version)?,
+                        self.transaction_store.get_transaction(version)?,
+                        self.transaction_store.get_write_set(version)?,
+                        self.event_store.get_events_by_version(version)?,
+                    ))
+                }))
                 .collect::<Result<Vec<_>>>()?
                 .into_iter()
                 .unzip();
@@ -1445,6 +1663,69 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        gauged_api("get_events_by_type", || {
+            self.error_if_secondary_indexes_disabled()?;
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+            self.event_store
+                .lookup_events_by_type(type_tag, start_version, start_index, limit, ledger_version)?
+                .into_iter()
+                .map(|(ver, idx)| {
+                    let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                    ensure!(
+                        event.type_tag() == type_tag,
+                        "Index broken, expected type:{}, actual:{}",
+                        type_tag,
+                        event.type_tag(),
+                    );
+                    Ok((idx, EventWithVersion::new(ver, event)))
+                })
+                .collect()
+        })
+    }
+
+    fn get_transactions_by_entry_function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>> {
+        gauged_api("get_transactions_by_entry_function", || {
+            self.error_if_secondary_indexes_disabled()?;
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+            self.transaction_store
+                .lookup_transactions_by_entry_function(
+                    module,
+                    function,
+                    start_version,
+                    limit,
+                    ledger_version,
+                )?
+                .into_iter()
+                .map(|txn_version| {
+                    self.get_transaction_with_proof(txn_version, ledger_version, include_events)
+                })
+                .collect()
+        })
+    }
+
+    /// Returns whether the event type and entry function indexes are maintained or not
+    fn secondary_indexes_enabled(&self) -> bool {
+        self.enable_secondary_indexes
+    }
+
     fn get_transaction_iterator(
         &self,
         start_version: Version,
@@ -1452,12 +1733,19 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<Transaction>> + '_>> {
         gauged_api("get_transaction_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
 
-            let iter = self
-                .transaction_store
-                .get_transaction_iter(start_version, limit as usize)?;
-            Ok(Box::new(iter) as Box<dyn Iterator<Item = Result<Transaction>> + '_>)
+            self.get_archived_or_db_iter(
+                "Transaction",
+                start_version,
+                limit,
+                |txn| txn.transaction,
+                |start_version, limit| {
+                    Ok(Box::new(
+                        self.transaction_store
+                            .get_transaction_iter(start_version, limit)?,
+                    ))
+                },
+            )
         })
     }
 
@@ -1468,12 +1756,19 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<TransactionInfo>> + '_>> {
         gauged_api("get_transaction_info_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
 
-            let iter = self
-                .ledger_store
-                .get_transaction_info_iter(start_version, limit as usize)?;
-            Ok(Box::new(iter) as Box<dyn Iterator<Item = Result<TransactionInfo>> + '_>)
+            self.get_archived_or_db_iter(
+                "Transaction",
+                start_version,
+                limit,
+                |txn| txn.transaction_info,
+                |start_version, limit| {
+                    Ok(Box::new(
+                        self.ledger_store
+                            .get_transaction_info_iter(start_version, limit)?,
+                    ))
+                },
+            )
         })
     }
 
@@ -1484,15 +1779,19 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<Vec<ContractEvent>>> + '_>> {
         gauged_api("get_events_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
 
-            let iter = self
-                .event_store
-                .get_events_by_version_iter(start_version, limit as usize)?;
-            Ok(Box::new(iter)
-                as Box<
-                    dyn Iterator<Item = Result<Vec<ContractEvent>>> + '_,
-                >)
+            self.get_archived_or_db_iter(
+                "Transaction",
+                start_version,
+                limit,
+                |txn| txn.events,
+                |start_version, limit| {
+                    Ok(Box::new(
+                        self.event_store
+                            .get_events_by_version_iter(start_version, limit)?,
+                    ))
+                },
+            )
         })
     }
 
@@ -1503,12 +1802,19 @@ impl DbReader for AptosDB {
     ) -> Result<Box<dyn Iterator<Item = Result<WriteSet>> + '_>> {
         gauged_api("get_write_set_iterator", || {
             error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
-            self.error_if_ledger_pruned("Transaction", start_version)?;
 
-            let iter = self
-                .transaction_store
-                .get_write_set_iter(start_version, limit as usize)?;
-            Ok(Box::new(iter) as Box<dyn Iterator<Item = Result<WriteSet>> + '_>)
+            self.get_archived_or_db_iter(
+                "Transaction",
+                start_version,
+                limit,
+                |txn| txn.write_set,
+                |start_version, limit| {
+                    Ok(Box::new(
+                        self.transaction_store
+                            .get_write_set_iter(start_version, limit)?,
+                    ))
+                },
+            )
         })
     }
 
@@ -1519,7 +1825,7 @@ impl DbReader for AptosDB {
         ledger_version: Version,
     ) -> Result<TransactionAccumulatorRangeProof> {
         gauged_api("get_transaction_accumulator_range_proof", || {
-            self.error_if_ledger_pruned("Transaction", first_version)?;
+            self.error_if_ledger_pruned_and_not_archived("Transaction", first_version)?;
 
             self.ledger_store.get_transaction_range_proof(
                 Some(first_version),
@@ -1610,6 +1916,20 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        gauged_api("get_state_values_with_multi_proof_by_version", || {
+            error_if_too_many_requested(state_keys.len() as u64, MAX_REQUEST_LIMIT)?;
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+
+            self.state_store
+                .get_state_values_with_multi_proof_by_version(state_keys, version)
+        })
+    }
+
     fn get_latest_epoch_state(&self) -> Result<EpochState> {
         gauged_api("get_latest_epoch_state", || {
             let latest_ledger_info = self.ledger_store.get_latest_ledger_info()?;
@@ -1643,22 +1963,22 @@ impl DbReader for AptosDB {
 
     fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         gauged_api("get_block_timestamp", || {
-            self.error_if_ledger_pruned("NewBlockEvent", version)?;
+            self.error_if_ledger_pruned_and_not_archived("NewBlockEvent", version)?;
             ensure!(version <= self.get_latest_version()?);
 
-            let (_first_version, new_block_event) = self.event_store.get_block_metadata(version)?;
+            let (_first_version, new_block_event) = self.get_block_metadata(version)?;
             Ok(new_block_event.proposed_time())
         })
     }
 
     fn get_next_block_event(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
         gauged_api("get_next_block_event", || {
-            self.error_if_ledger_pruned("NewBlockEvent", version)?;
+            self.error_if_ledger_pruned_and_not_archived("NewBlockEvent", version)?;
             if let Some((block_version, _, _)) = self
                 .event_store
                 .lookup_event_at_or_after_version(&new_block_event_key(), version)?
             {
-                self.event_store.get_block_metadata(block_version)
+                self.get_block_metadata(block_version)
             } else {
                 bail!(
                     "Failed to find a block event at or after version {}",
@@ -1673,7 +1993,7 @@ impl DbReader for AptosDB {
         version: Version,
     ) -> Result<(Version, Version, NewBlockEvent)> {
         gauged_api("get_block_info", || {
-            self.error_if_ledger_pruned("NewBlockEvent", version)?;
+            self.error_if_ledger_pruned_and_not_archived("NewBlockEvent", version)?;
 
             let latest_li = self.get_latest_ledger_info()?;
             let committed_version = latest_li.ledger_info().version();
@@ -1684,7 +2004,7 @@ impl DbReader for AptosDB {
                 committed_version
             );
 
-            let (first_version, new_block_event) = self.event_store.get_block_metadata(version)?;
+            let (first_version, new_block_event) = self.get_block_metadata(version)?;
 
             let last_version = self
                 .event_store
@@ -1701,9 +2021,11 @@ impl DbReader for AptosDB {
             let committed_version = latest_li.ledger_info().version();
 
             let event_key = new_block_event_key();
-            let (first_version, new_block_event) =
+            let (first_version, event_index) =
                 self.event_store
-                    .get_event_by_key(&event_key, height, committed_version)?;
+                    .lookup_event_by_key(&event_key, height, committed_version)?;
+            let new_block_event =
+                self.get_event_by_version_and_index(first_version, event_index)?;
             let last_version = self
                 .event_store
                 .lookup_event_after_version(&event_key, first_version)?
@@ -1756,7 +2078,7 @@ impl DbReader for AptosDB {
 
     fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
         gauged_api("get_accumulator_root_hash", || {
-            self.error_if_ledger_pruned("Transaction accumulator", version)?;
+            self.error_if_ledger_pruned_and_not_archived("Transaction accumulator", version)?;
             self.ledger_store.get_root_hash(version)
         })
     }
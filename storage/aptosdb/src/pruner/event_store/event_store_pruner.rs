@@ -7,6 +7,8 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct EventStorePruner {
     event_store: Arc<EventStore>,
+    /// Set when the events are archived before pruning.
+    keep_indices: bool,
 }
 
 impl DBSubPruner for EventStorePruner {
@@ -16,14 +18,21 @@ impl DBSubPruner for EventStorePruner {
         min_readable_version: u64,
         target_version: u64,
     ) -> anyhow::Result<()> {
-        self.event_store
-            .prune_events(min_readable_version, target_version, db_batch)?;
+        self.event_store.prune_events(
+            min_readable_version,
+            target_version,
+            self.keep_indices,
+            db_batch,
+        )?;
         Ok(())
     }
 }
 
 impl EventStorePruner {
-    pub(in crate::pruner) fn new(event_store: Arc<EventStore>) -> Self {
-        EventStorePruner { event_store }
+    pub(in crate::pruner) fn new(event_store: Arc<EventStore>, keep_indices: bool) -> Self {
+        EventStorePruner {
+            event_store,
+            keep_indices,
+        }
     }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    ledger_archive::LedgerArchive,
     metrics::{PRUNER_BATCH_SIZE, PRUNER_WINDOW},
     pruner::{
         db_pruner::DBPruner, ledger_pruner_worker::LedgerPrunerWorker,
@@ -99,8 +100,12 @@ impl PrunerManager for LedgerPrunerManager {
 
 impl LedgerPrunerManager {
     /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(ledger_rocksdb: Arc<DB>, ledger_pruner_config: LedgerPrunerConfig) -> Self {
-        let ledger_pruner = pruner_utils::create_ledger_pruner(ledger_rocksdb);
+    pub fn new(
+        ledger_rocksdb: Arc<DB>,
+        ledger_pruner_config: LedgerPrunerConfig,
+        ledger_archive: Option<Arc<LedgerArchive>>,
+    ) -> Self {
+        let ledger_pruner = pruner_utils::create_ledger_pruner(ledger_rocksdb, ledger_archive);
 
         if ledger_pruner_config.enable {
             PRUNER_WINDOW
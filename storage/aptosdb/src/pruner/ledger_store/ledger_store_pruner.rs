@@ -3,6 +3,7 @@
 
 use crate::{
     db_metadata::DbMetadataSchema,
+    ledger_archive::LedgerArchive,
     metrics::PRUNER_VERSIONS,
     pruner::{
         db_pruner::DBPruner,
@@ -37,6 +38,8 @@ pub(crate) struct LedgerPruner {
     version_data_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     event_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     write_set_pruner: Arc<dyn DBSubPruner + Send + Sync>,
+    /// If set, everything about to be pruned is archived here first.
+    ledger_archive: Option<Arc<LedgerArchive>>,
 }
 
 impl DBPruner for LedgerPruner {
@@ -140,17 +143,23 @@ impl LedgerPruner {
         db: Arc<DB>,
         transaction_store: Arc<TransactionStore>,
         event_store: Arc<EventStore>,
+        ledger_archive: Option<Arc<LedgerArchive>>,
     ) -> Self {
+        // The archive holds the pruned data, but not the transaction accumulator and the event
+        // indices needed to serve it with proofs and by event key, so they are kept in the DB.
+        let archived = ledger_archive.is_some();
         let pruner = LedgerPruner {
             db,
             target_version: AtomicVersion::new(0),
             min_readable_version: AtomicVersion::new(0),
             transaction_store_pruner: Arc::new(TransactionStorePruner::new(
                 transaction_store.clone(),
+                archived,
             )),
-            event_store_pruner: Arc::new(EventStorePruner::new(event_store)),
+            event_store_pruner: Arc::new(EventStorePruner::new(event_store, archived)),
             write_set_pruner: Arc::new(WriteSetPruner::new(transaction_store)),
             version_data_pruner: Arc::new(VersionDataPruner::new()),
+            ledger_archive,
         };
         pruner.initialize();
         pruner
@@ -161,7 +170,7 @@ impl LedgerPruner {
         let target_version = 1; // The genesis version is 0. Delete [0,1) (exclusive)
         let max_version = 1; // We should only be pruning a single version
 
-        let ledger_pruner = pruner_utils::create_ledger_pruner(ledger_db);
+        let ledger_pruner = pruner_utils::create_ledger_pruner(ledger_db, None);
         ledger_pruner.set_target_version(target_version);
         ledger_pruner.prune_inner(max_version, db_batch)?;
 
@@ -183,6 +192,11 @@ impl LedgerPruner {
             return Ok(min_readable_version);
         }
 
+        // The segment must be durable before the data is deleted from the DB.
+        if let Some(ledger_archive) = &self.ledger_archive {
+            ledger_archive.archive_range(&self.db, min_readable_version, current_target_version)?;
+        }
+
         self.transaction_store_pruner.prune(
             db_batch,
             min_readable_version,
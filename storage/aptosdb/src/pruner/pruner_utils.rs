@@ -4,6 +4,7 @@
 //! This module provides common utilities for the DB pruner.
 
 use crate::{
+    ledger_archive::LedgerArchive,
     pruner::{
         ledger_store::ledger_store_pruner::LedgerPruner,
         state_kv_pruner::StateKvPruner,
@@ -28,11 +29,22 @@ where
 }
 
 /// A utility function to instantiate the ledger pruner
-pub(crate) fn create_ledger_pruner(ledger_db: Arc<DB>) -> Arc<LedgerPruner> {
+pub(crate) fn create_ledger_pruner(
+    ledger_db: Arc<DB>,
+    ledger_archive: Option<Arc<LedgerArchive>>,
+) -> Arc<LedgerPruner> {
     Arc::new(LedgerPruner::new(
         Arc::clone(&ledger_db),
-        Arc::new(TransactionStore::new(Arc::clone(&ledger_db))),
-        Arc::new(EventStore::new(Arc::clone(&ledger_db))),
+        // Pruning always cleans up the secondary indexes, no need to enable them for writing.
+        Arc::new(TransactionStore::new(
+            Arc::clone(&ledger_db),
+            /*enable_secondary_indexes=*/ false,
+        )),
+        Arc::new(EventStore::new(
+            Arc::clone(&ledger_db),
+            /*enable_secondary_indexes=*/ false,
+        )),
+        ledger_archive,
     ))
 }
 
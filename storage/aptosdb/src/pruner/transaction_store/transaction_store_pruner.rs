@@ -8,6 +8,9 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct TransactionStorePruner {
     transaction_store: Arc<TransactionStore>,
+    /// Set when the transactions are archived before pruning, so that proofs can still be served
+    /// for them.
+    keep_accumulator: bool,
 }
 
 impl DBSubPruner for TransactionStorePruner {
@@ -26,28 +29,41 @@ impl DBSubPruner for TransactionStorePruner {
             .prune_transaction_by_hash(&candidate_transactions, db_batch)?;
         self.transaction_store
             .prune_transaction_by_account(&candidate_transactions, db_batch)?;
-        self.transaction_store.prune_transaction_schema(
+        self.transaction_store.prune_transaction_by_entry_function(
             min_readable_version,
-            target_version,
+            &candidate_transactions,
             db_batch,
         )?;
-        self.transaction_store.prune_transaction_info_schema(
+        self.transaction_store.prune_transaction_schema(
             min_readable_version,
             target_version,
             db_batch,
         )?;
-        self.transaction_store.prune_transaction_accumulator(
+        self.transaction_store.prune_transaction_info_schema(
             min_readable_version,
             target_version,
             db_batch,
         )?;
+        if !self.keep_accumulator {
+            self.transaction_store.prune_transaction_accumulator(
+                min_readable_version,
+                target_version,
+                db_batch,
+            )?;
+        }
         Ok(())
     }
 }
 
 impl TransactionStorePruner {
-    pub(in crate::pruner) fn new(transaction_store: Arc<TransactionStore>) -> Self {
-        TransactionStorePruner { transaction_store }
+    pub(in crate::pruner) fn new(
+        transaction_store: Arc<TransactionStore>,
+        keep_accumulator: bool,
+    ) -> Self {
+        TransactionStorePruner {
+            transaction_store,
+            keep_accumulator,
+        }
     }
 
     fn get_pruning_candidate_transactions(
@@ -0,0 +1,72 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an optional event index via which all the
+//! events of a given Move type can be found. The type is represented by the hash of its BCS
+//! serialized `TypeTag` (see `event_type_hash`), and each ContractEvent by a <txn_version,
+//! event_idx> tuple so that it can be fetched from `EventSchema`.
+//!
+//! ```text
+//! |<----------------key--------------->|<-value->|
+//! | event_type_hash | txn_ver | idx    |   ()    |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::language_storage::TypeTag;
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+/// Hashes the event type tag into the fixed length prefix used by `EventByTypeSchema` keys.
+pub(crate) fn event_type_hash(type_tag: &TypeTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(type_tag)?))
+}
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_hash, version, index) = *self;
+
+        let mut encoded = type_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const HASH_LEN: usize = HashValue::LENGTH;
+        const HASH_AND_VER_LEN: usize = size_of::<(HashValue, Version)>();
+        let type_hash = HashValue::from_slice(&data[..HASH_LEN])?;
+        let version = (&data[HASH_LEN..]).read_u64::<BigEndian>()?;
+        let index = (&data[HASH_AND_VER_LEN..]).read_u64::<BigEndian>()?;
+
+        Ok((type_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
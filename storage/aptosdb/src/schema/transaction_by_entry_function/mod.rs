@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an optional transaction index via which the
+//! versions of all the transactions calling a given entry function (`ModuleId::function`) can be
+//! found. The function is represented by the hash of its BCS serialized module id and name (see
+//! `entry_function_hash`). With the version one can resort to `TransactionSchema` for the
+//! transaction content.
+//!
+//! ```text
+//! |<-----------key----------->|<-value->|
+//! | function_hash | txn_ver   |   ()    |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, TRANSACTION_BY_ENTRY_FUNCTION_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use std::mem::size_of;
+
+define_schema!(
+    TransactionByEntryFunctionSchema,
+    Key,
+    (),
+    TRANSACTION_BY_ENTRY_FUNCTION_CF_NAME
+);
+
+type Key = (HashValue, Version);
+
+/// Hashes the entry function into the fixed length prefix used by
+/// `TransactionByEntryFunctionSchema` keys.
+pub(crate) fn entry_function_hash(module: &ModuleId, function: &IdentStr) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(&(
+        module,
+        function.as_str(),
+    ))?))
+}
+
+impl KeyCodec<TransactionByEntryFunctionSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref function_hash, version) = *self;
+
+        let mut encoded = function_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const HASH_LEN: usize = HashValue::LENGTH;
+        let function_hash = HashValue::from_slice(&data[..HASH_LEN])?;
+        let version = (&data[HASH_LEN..]).read_u64::<BigEndian>()?;
+
+        Ok((function_hash, version))
+    }
+}
+
+impl ValueCodec<TransactionByEntryFunctionSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
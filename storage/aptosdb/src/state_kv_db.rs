@@ -6,11 +6,14 @@
 use crate::{
     db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
     db_options::{gen_state_kv_cfds, state_kv_db_column_families},
-    utils::truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
+    utils::{
+        prepare_checkpoint_path,
+        truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
+    },
     COMMIT_POOL, NUM_STATE_SHARDS,
 };
 use anyhow::Result;
-use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
+use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
 use aptos_logger::prelude::info;
 use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{SchemaBatch, DB};
@@ -30,10 +33,8 @@ pub struct StateKvDb {
 }
 
 impl StateKvDb {
-    // TODO(grao): Support more flexible path to make it easier for people to put different shards
-    // on different disks.
-    pub(crate) fn new<P: AsRef<Path>>(
-        db_root_path: P,
+    pub(crate) fn new(
+        db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
         readonly: bool,
         ledger_db: Arc<DB>,
@@ -46,15 +47,16 @@ impl StateKvDb {
             });
         }
 
-        Self::open(db_root_path, rocksdb_configs.state_kv_db_config, readonly)
+        Self::open(db_paths, rocksdb_configs.state_kv_db_config, readonly)
     }
 
-    pub(crate) fn open<P: AsRef<Path>>(
-        db_root_path: P,
+    pub(crate) fn open(
+        db_paths: &StorageDirPaths,
         state_kv_db_config: RocksdbConfig,
         readonly: bool,
     ) -> Result<Self> {
-        let state_kv_metadata_db_path = Self::metadata_db_path(db_root_path.as_ref());
+        let state_kv_metadata_db_path =
+            Self::metadata_db_path(db_paths.state_kv_db_metadata_root_path());
 
         let state_kv_metadata_db = Arc::new(Self::open_db(
             state_kv_metadata_db_path.clone(),
@@ -74,7 +76,7 @@ impl StateKvDb {
             if sharding {
                 let mut shard_id: usize = 0;
                 arr![{
-                    let db = Self::open_shard(db_root_path.as_ref(), shard_id as u8, &state_kv_db_config, readonly)?;
+                    let db = Self::open_shard(db_paths.state_kv_db_shard_root_path(shard_id as u8), shard_id as u8, &state_kv_db_config, readonly)?;
                     shard_id += 1;
                     Arc::new(db)
                 }; 16]
@@ -142,30 +144,30 @@ impl StateKvDb {
     }
 
     pub(crate) fn create_checkpoint(
-        db_root_path: impl AsRef<Path>,
-        cp_root_path: impl AsRef<Path>,
+        db_paths: &StorageDirPaths,
+        cp_paths: &StorageDirPaths,
     ) -> Result<()> {
-        let state_kv_db = Self::open(db_root_path, RocksdbConfig::default(), false)?;
-        let cp_state_kv_db_path = cp_root_path.as_ref().join(STATE_KV_DB_FOLDER_NAME);
-
-        info!("Creating state_kv_db checkpoint at: {cp_state_kv_db_path:?}");
+        let state_kv_db = Self::open(db_paths, RocksdbConfig::default(), false)?;
+        let cp_metadata_db_path = Self::metadata_db_path(cp_paths.state_kv_db_metadata_root_path());
 
-        std::fs::remove_dir_all(&cp_state_kv_db_path).unwrap_or(());
-        std::fs::create_dir_all(&cp_state_kv_db_path).unwrap_or(());
+        info!("Creating state_kv_db checkpoint at: {cp_metadata_db_path:?}");
 
+        prepare_checkpoint_path(&cp_metadata_db_path);
         state_kv_db
             .metadata_db()
-            .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref()))?;
+            .create_checkpoint(cp_metadata_db_path)?;
 
         let sharding = false;
         if sharding {
             for shard_id in 0..NUM_STATE_SHARDS {
+                let cp_shard_path = Self::db_shard_path(
+                    cp_paths.state_kv_db_shard_root_path(shard_id as u8),
+                    shard_id as u8,
+                );
+                prepare_checkpoint_path(&cp_shard_path);
                 state_kv_db
                     .db_shard(shard_id as u8)
-                    .create_checkpoint(Self::db_shard_path(
-                        cp_root_path.as_ref(),
-                        shard_id as u8,
-                    ))?;
+                    .create_checkpoint(cp_shard_path)?;
             }
         }
 
@@ -193,7 +195,7 @@ impl StateKvDb {
         self.state_kv_db_shards[shard_id as usize].write_schemas(batch)
     }
 
-    fn open_shard<P: AsRef<Path>>(
+    pub(crate) fn open_shard<P: AsRef<Path>>(
         db_root_path: P,
         shard_id: u8,
         state_kv_db_config: &RocksdbConfig,
@@ -231,7 +233,7 @@ impl StateKvDb {
         })
     }
 
-    fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
+    pub(crate) fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
         let shard_sub_path = format!("shard_{}", shard_id);
         db_root_path
             .as_ref()
@@ -10,12 +10,15 @@ use crate::{
     schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
     stale_node_index::StaleNodeIndexSchema,
     stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
-    utils::truncation_helper::{get_state_merkle_commit_progress, truncate_state_merkle_db_shards},
+    utils::{
+        prepare_checkpoint_path,
+        truncation_helper::{get_state_merkle_commit_progress, truncate_state_merkle_db_shards},
+    },
     versioned_node_cache::VersionedNodeCache,
     ShardedStateMerkleSchemaBatch, NUM_STATE_SHARDS, OTHER_TIMERS_SECONDS,
 };
 use anyhow::Result;
-use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
+use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_jellyfish_merkle::{
     node_type::{NodeKey, NodeType},
@@ -26,7 +29,7 @@ use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{SchemaBatch, DB};
 use aptos_types::{
     nibble::{nibble_path::NibblePath, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof},
     state_store::state_key::StateKey,
     transaction::Version,
 };
@@ -68,8 +71,8 @@ pub struct StateMerkleDb {
 }
 
 impl StateMerkleDb {
-    pub(crate) fn new<P: AsRef<Path>>(
-        db_root_path: P,
+    pub(crate) fn new(
+        db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
         readonly: bool,
         max_nodes_per_lru_cache_shard: usize,
@@ -84,7 +87,9 @@ impl StateMerkleDb {
         let lru_cache = LruNodeCache::new(max_nodes_per_lru_cache_shard);
         if !rocksdb_configs.use_sharded_state_merkle_db {
             info!("Sharded state merkle DB is not enabled!");
-            let state_merkle_db_path = db_root_path.as_ref().join(STATE_MERKLE_DB_NAME);
+            let state_merkle_db_path = db_paths
+                .state_merkle_db_metadata_root_path()
+                .join(STATE_MERKLE_DB_NAME);
             let db = Arc::new(Self::open_db(
                 state_merkle_db_path,
                 STATE_MERKLE_DB_NAME,
@@ -101,7 +106,7 @@ impl StateMerkleDb {
         }
 
         Self::open(
-            db_root_path,
+            db_paths,
             state_merkle_db_config,
             readonly,
             enable_cache,
@@ -133,8 +138,8 @@ impl StateMerkleDb {
     }
 
     pub(crate) fn create_checkpoint(
-        db_root_path: impl AsRef<Path>,
-        cp_root_path: impl AsRef<Path>,
+        db_paths: &StorageDirPaths,
+        cp_paths: &StorageDirPaths,
         sharding: bool,
     ) -> Result<()> {
         let rocksdb_configs = RocksdbConfigs {
@@ -142,32 +147,31 @@ impl StateMerkleDb {
             ..Default::default()
         };
         let state_merkle_db = Self::new(
-            db_root_path,
+            db_paths,
             rocksdb_configs,
             /*readonly=*/ false,
             /*max_nodes_per_lru_cache_shard=*/ 0,
         )?;
-        let cp_state_merkle_db_path = cp_root_path.as_ref().join(STATE_MERKLE_DB_FOLDER_NAME);
-
-        info!("Creating state_merkle_db checkpoint at: {cp_state_merkle_db_path:?}");
+        let cp_metadata_db_path =
+            Self::metadata_db_path(cp_paths.state_merkle_db_metadata_root_path(), sharding);
 
-        std::fs::remove_dir_all(&cp_state_merkle_db_path).unwrap_or(());
-        if sharding {
-            std::fs::create_dir_all(&cp_state_merkle_db_path).unwrap_or(());
-        }
+        info!("Creating state_merkle_db checkpoint at: {cp_metadata_db_path:?}");
 
+        prepare_checkpoint_path(&cp_metadata_db_path);
         state_merkle_db
             .metadata_db()
-            .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
+            .create_checkpoint(cp_metadata_db_path)?;
 
         if sharding {
             for shard_id in 0..NUM_STATE_SHARDS {
+                let cp_shard_path = Self::db_shard_path(
+                    cp_paths.state_merkle_db_shard_root_path(shard_id as u8),
+                    shard_id as u8,
+                );
+                prepare_checkpoint_path(&cp_shard_path);
                 state_merkle_db
                     .db_shard(shard_id as u8)
-                    .create_checkpoint(Self::db_shard_path(
-                        cp_root_path.as_ref(),
-                        shard_id as u8,
-                    ))?;
+                    .create_checkpoint(cp_shard_path)?;
             }
         }
 
@@ -215,6 +219,17 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_with_proof_ext(state_key.hash(), version)
     }
 
+    pub fn get_with_merged_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (StateKey, Version))>>,
+        SparseMerkleMultiProof,
+    )> {
+        JellyfishMerkleTree::new(self).get_with_merged_proof(keys, version)
+    }
+
     pub fn get_range_proof(
         &self,
         rightmost_key: HashValue,
@@ -370,16 +385,18 @@ impl StateMerkleDb {
         }
     }
 
-    fn open<P: AsRef<Path>>(
-        db_root_path: P,
+    fn open(
+        db_paths: &StorageDirPaths,
         state_merkle_db_config: RocksdbConfig,
         readonly: bool,
         enable_cache: bool,
         version_cache: VersionedNodeCache,
         lru_cache: LruNodeCache,
     ) -> Result<Self> {
-        let state_merkle_metadata_db_path =
-            Self::metadata_db_path(db_root_path.as_ref(), /*sharding=*/ true);
+        let state_merkle_metadata_db_path = Self::metadata_db_path(
+            db_paths.state_merkle_db_metadata_root_path(),
+            /*sharding=*/ true,
+        );
 
         let state_merkle_metadata_db = Arc::new(Self::open_db(
             state_merkle_metadata_db_path.clone(),
@@ -395,7 +412,7 @@ impl StateMerkleDb {
 
         let mut shard_id: usize = 0;
         let state_merkle_db_shards = arr![{
-            let db = Self::open_shard(db_root_path.as_ref(), shard_id as u8, &state_merkle_db_config, readonly)?;
+            let db = Self::open_shard(db_paths.state_merkle_db_shard_root_path(shard_id as u8), shard_id as u8, &state_merkle_db_config, readonly)?;
             shard_id += 1;
             Arc::new(db)
         }; 16];
@@ -420,7 +437,7 @@ impl StateMerkleDb {
         Ok(state_merkle_db)
     }
 
-    fn open_shard<P: AsRef<Path>>(
+    pub(crate) fn open_shard<P: AsRef<Path>>(
         db_root_path: P,
         shard_id: u8,
         state_merkle_db_config: &RocksdbConfig,
@@ -458,7 +475,7 @@ impl StateMerkleDb {
         })
     }
 
-    fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
+    pub(crate) fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
         let shard_sub_path = format!("shard_{}", shard_id);
         db_root_path
             .as_ref()
@@ -39,7 +39,9 @@ use aptos_storage_interface::{
     sync_proof_fetcher::SyncProofFetcher, DbReader, StateSnapshotReceiver,
 };
 use aptos_types::{
-    proof::{definition::LeafCount, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        definition::LeafCount, SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{
         state_key::StateKey,
         state_key_prefix::StateKeyPrefix,
@@ -167,6 +169,24 @@ impl DbReader for StateDb {
         ))
     }
 
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        let keys: Vec<_> = state_keys.iter().map(CryptoHash::hash).collect();
+        let (leaf_data, proof) = self.state_merkle_db.get_with_merged_proof(&keys, version)?;
+        let values = leaf_data
+            .into_iter()
+            .map(|leaf_data| {
+                leaf_data
+                    .map(|(_, (key, version))| self.expect_value_by_version(&key, version))
+                    .transpose()
+            })
+            .collect::<Result<_>>()?;
+        Ok((values, proof))
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         version.map_or(Ok(StateStorageUsage::zero()), |version| {
             Ok(self
@@ -256,6 +276,15 @@ impl DbReader for StateStore {
         self.deref()
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
+
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        self.deref()
+            .get_state_values_with_multi_proof_by_version(state_keys, version)
+    }
 }
 
 impl StateDb {
@@ -491,8 +520,11 @@ impl StateStore {
                 buffered_state.current_state().current.clone(),
                 Arc::new(SyncProofFetcher::new(state_db.clone())),
             )?;
-            let write_sets = TransactionStore::new(Arc::clone(&state_db.ledger_db))
-                .get_write_sets(snapshot_next_version, num_transactions)?;
+            let write_sets = TransactionStore::new(
+                Arc::clone(&state_db.ledger_db),
+                /*enable_secondary_indexes=*/ false,
+            )
+            .get_write_sets(snapshot_next_version, num_transactions)?;
             let txn_info_iter =
                 ledger_store.get_transaction_info_iter(snapshot_next_version, write_sets.len())?;
             let last_checkpoint_index = txn_info_iter
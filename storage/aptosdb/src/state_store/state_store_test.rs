@@ -144,6 +144,39 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+#[test]
+fn test_state_store_multi_proof() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let kvs: Vec<_> = (0..10)
+        .map(|i| {
+            (
+                StateKey::raw(format!("test_key{}", i).into_bytes()),
+                StateValue::from(format!("test_val{}", i).into_bytes()),
+            )
+        })
+        .collect();
+    let root = put_value_set(store, kvs[..5].to_vec(), 0 /* version */, None);
+
+    let mut keys: Vec<_> = kvs.iter().map(|(key, _)| key.clone()).collect();
+    keys.sort_by_key(|key| key.hash());
+    let (values, proof) = store
+        .get_state_values_with_multi_proof_by_version(&keys, 0)
+        .unwrap();
+    for (key, value) in keys.iter().zip(values.iter()) {
+        verify_value_index_in_store(store, key.clone(), value.as_ref(), 0);
+    }
+    assert_eq!(values.iter().filter(|value| value.is_some()).count(), 5);
+
+    let elements: Vec<_> = keys.iter().map(CryptoHash::hash).zip(values).collect();
+    let elements: Vec<_> = elements
+        .iter()
+        .map(|(key, value)| (*key, value.as_ref()))
+        .collect();
+    proof.verify(root, &elements).unwrap();
+}
+
 fn traverse_values(
     store: &StateStore,
     prefix: &StateKeyPrefix,
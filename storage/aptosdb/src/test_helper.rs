@@ -540,7 +540,7 @@ fn get_events_by_event_key(
     Ok(ret)
 }
 
-fn verify_events_by_event_key(
+pub(crate) fn verify_events_by_event_key(
     db: &AptosDB,
     events: Vec<(EventKey, Vec<(Version, ContractEvent)>)>,
     ledger_info: &LedgerInfo,
@@ -589,7 +589,7 @@ fn verify_events_by_event_key(
         .unwrap();
 }
 
-fn group_events_by_event_key(
+pub(crate) fn group_events_by_event_key(
     first_version: Version,
     txns_to_commit: &[TransactionToCommit],
 ) -> Vec<(EventKey, Vec<(Version, ContractEvent)>)> {
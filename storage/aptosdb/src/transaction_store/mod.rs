@@ -7,8 +7,11 @@
 use crate::{
     errors::AptosDbError,
     schema::{
-        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
-        transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
+        transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_entry_function::{entry_function_hash, TransactionByEntryFunctionSchema},
+        transaction_by_hash::TransactionByHashSchema,
+        write_set::WriteSetSchema,
     },
     transaction_accumulator::TransactionAccumulatorSchema,
     transaction_info::TransactionInfoSchema,
@@ -20,9 +23,12 @@ use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_types::{
     account_address::AccountAddress,
     proof::position::Position,
-    transaction::{Transaction, Version},
+    transaction::{
+        EntryFunction, MultisigTransactionPayload, Transaction, TransactionPayload, Version,
+    },
     write_set::WriteSet,
 };
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
 use std::sync::Arc;
 
 #[cfg(test)]
@@ -31,11 +37,16 @@ mod test;
 #[derive(Clone, Debug)]
 pub struct TransactionStore {
     db: Arc<DB>,
+    /// Whether to maintain `TransactionByEntryFunctionSchema` when saving transactions.
+    enable_secondary_indexes: bool,
 }
 
 impl TransactionStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, enable_secondary_indexes: bool) -> Self {
+        Self {
+            db,
+            enable_secondary_indexes,
+        }
     }
 
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
@@ -96,6 +107,35 @@ impl TransactionStore {
         ))
     }
 
+    /// Gets the versions of up to `limit` transactions calling the entry function
+    /// `module::function`, committed at or after `start_version` and at or before
+    /// `ledger_version`, in ascending order.
+    pub fn lookup_transactions_by_entry_function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let function_hash = entry_function_hash(module, function)?;
+        let mut iter = self
+            .db
+            .iter::<TransactionByEntryFunctionSchema>(ReadOptions::default())?;
+        iter.seek(&(function_hash, start_version))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((hash, version), ()) = res?;
+            if hash != function_hash || version > ledger_version {
+                break;
+            }
+            result.push(version);
+        }
+
+        Ok(result)
+    }
+
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
         self.db
@@ -138,6 +178,17 @@ impl TransactionStore {
                 &version,
             )?;
         }
+        if self.enable_secondary_indexes {
+            if let Some(entry_function) = called_entry_function(transaction) {
+                batch.put::<TransactionByEntryFunctionSchema>(
+                    &(
+                        entry_function_hash(entry_function.module(), entry_function.function())?,
+                        version,
+                    ),
+                    &(),
+                )?;
+            }
+        }
         batch.put::<TransactionByHashSchema>(&transaction.hash(), &version)?;
         batch.put::<TransactionSchema>(&version, transaction)?;
 
@@ -227,6 +278,25 @@ impl TransactionStore {
         Ok(())
     }
 
+    /// Prune the transaction by entry function store given a list of transaction starting at
+    /// `begin`
+    pub fn prune_transaction_by_entry_function(
+        &self,
+        begin: Version,
+        transactions: &[Transaction],
+        db_batch: &SchemaBatch,
+    ) -> Result<()> {
+        for (version, transaction) in (begin..).zip(transactions) {
+            if let Some(entry_function) = called_entry_function(transaction) {
+                db_batch.delete::<TransactionByEntryFunctionSchema>(&(
+                    entry_function_hash(entry_function.module(), entry_function.function())?,
+                    version,
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Prune the transaction schema store between a range of version in [begin, end)
     pub fn prune_transaction_schema(
         &self,
@@ -325,3 +395,14 @@ impl TransactionStore {
         Ok(())
     }
 }
+
+/// Returns the entry function a user transaction calls, directly or through a multisig account.
+fn called_entry_function(transaction: &Transaction) -> Option<&EntryFunction> {
+    match transaction.try_as_signed_user_txn()?.payload() {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(multisig) => match multisig.transaction_payload.as_ref()? {
+            MultisigTransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        },
+        TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+    }
+}
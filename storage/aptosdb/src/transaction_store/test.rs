@@ -4,12 +4,15 @@
 
 use super::*;
 use crate::AptosDB;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::{
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
-    transaction::Transaction,
+    test_helpers::transaction_test_helpers::get_test_signed_txn,
+    transaction::{Multisig, Script, Transaction},
 };
+use move_core_types::{ident_str, identifier::Identifier};
 use proptest::{collection::vec, prelude::*};
 use std::collections::BTreeMap;
 
@@ -199,6 +202,85 @@ proptest! {
     }
 }
 
+#[test]
+fn test_lookup_transactions_by_entry_function() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_secondary_indexes(&tmp_dir);
+    let store = &db.transaction_store;
+
+    let module = ModuleId::new(AccountAddress::ONE, ident_str!("coin").to_owned());
+    let entry_function = |name: &str| {
+        EntryFunction::new(
+            module.clone(),
+            Identifier::new(name).unwrap(),
+            vec![],
+            vec![],
+        )
+    };
+    let multisig = |payload| {
+        TransactionPayload::Multisig(Multisig {
+            multisig_address: AccountAddress::TWO,
+            transaction_payload: payload,
+        })
+    };
+    let payloads = vec![
+        TransactionPayload::EntryFunction(entry_function("transfer")),
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        TransactionPayload::EntryFunction(entry_function("register")),
+        multisig(Some(MultisigTransactionPayload::EntryFunction(
+            entry_function("transfer"),
+        ))),
+        multisig(None),
+    ];
+
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let txns = payloads
+        .into_iter()
+        .enumerate()
+        .map(|(seq_num, payload)| {
+            Transaction::UserTransaction(get_test_signed_txn(
+                AccountAddress::ONE,
+                seq_num as u64,
+                &private_key,
+                private_key.public_key(),
+                Some(payload),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let batch = SchemaBatch::new();
+    for (ver, txn) in txns.iter().enumerate() {
+        store.put_transaction(ver as Version, txn, &batch).unwrap();
+    }
+    store.db.write_schemas(batch).unwrap();
+
+    let lookup = |function: &str, start_version, limit, ledger_version| {
+        store
+            .lookup_transactions_by_entry_function(
+                &module,
+                &Identifier::new(function).unwrap(),
+                start_version,
+                limit,
+                ledger_version,
+            )
+            .unwrap()
+    };
+    assert_eq!(lookup("transfer", 0, 10, 4), vec![0, 3]);
+    assert_eq!(lookup("transfer", 1, 10, 4), vec![3]);
+    assert_eq!(lookup("transfer", 0, 1, 4), vec![0]);
+    assert_eq!(lookup("transfer", 0, 10, 2), vec![0]);
+    assert_eq!(lookup("register", 0, 10, 4), vec![2]);
+    assert!(lookup("mint", 0, 10, 4).is_empty());
+
+    // Pruning the transactions cleans up the index as well.
+    let batch = SchemaBatch::new();
+    store
+        .prune_transaction_by_entry_function(0, &txns, &batch)
+        .unwrap();
+    store.db.write_schemas(batch).unwrap();
+    assert!(lookup("transfer", 0, 10, 4).is_empty());
+    assert!(lookup("register", 0, 10, 4).is_empty());
+}
+
 fn init_store(
     mut universe: AccountInfoUniverse,
     gens: Vec<(Index, SignatureCheckedTransactionGen)>,
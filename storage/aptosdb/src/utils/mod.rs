@@ -3,3 +3,14 @@
 
 pub mod iterators;
 pub(crate) mod truncation_helper;
+
+use std::path::Path;
+
+/// Removes any stale DB at `cp_path` and makes sure its parent directory exists, so that a
+/// checkpoint can be created there.
+pub(crate) fn prepare_checkpoint_path(cp_path: &Path) {
+    std::fs::remove_dir_all(cp_path).unwrap_or(());
+    if let Some(parent) = cp_path.parent() {
+        std::fs::create_dir_all(parent).unwrap_or(());
+    }
+}
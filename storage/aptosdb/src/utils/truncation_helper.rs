@@ -79,8 +79,14 @@ pub(crate) fn truncate_ledger_db(
 ) -> Result<()> {
     let status = StatusLine::new(Progress::new(target_version));
 
-    let event_store = EventStore::new(Arc::clone(&ledger_db));
-    let transaction_store = TransactionStore::new(Arc::clone(&ledger_db));
+    let event_store = EventStore::new(
+        Arc::clone(&ledger_db),
+        /*enable_secondary_indexes=*/ false,
+    );
+    let transaction_store = TransactionStore::new(
+        Arc::clone(&ledger_db),
+        /*enable_secondary_indexes=*/ false,
+    );
 
     let mut current_version = current_version;
     while current_version > target_version {
@@ -283,7 +289,12 @@ fn truncate_ledger_db_single_batch(
     delete_per_epoch_data(ledger_db, start_version, end_version, &batch)?;
     delete_per_version_data(start_version, end_version, &batch)?;
 
-    event_store.prune_events(start_version, end_version, &batch)?;
+    event_store.prune_events(
+        start_version,
+        end_version,
+        false, /* keep_indices */
+        &batch,
+    )?;
 
     truncate_transaction_accumulator(ledger_db, start_version, end_version, &batch)?;
 
@@ -305,6 +316,7 @@ fn delete_transaction_index_data(
         .collect::<Result<Vec<_>>>()?;
     transaction_store.prune_transaction_by_account(&transactions, batch)?;
     transaction_store.prune_transaction_by_hash(&transactions, batch)?;
+    transaction_store.prune_transaction_by_entry_function(start_version, &transactions, batch)?;
 
     Ok(())
 }
@@ -81,6 +81,7 @@ fn end_to_end() {
             EpochEndingRestoreOpt { manifest_handle },
             GlobalRestoreOpt {
                 db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                db_path_overrides: None,
                 dry_run: false,
                 target_version: Some(target_version),
                 trusted_waypoints: TrustedWaypointOpt::default(),
@@ -213,6 +214,7 @@ async fn test_trusted_waypoints_impl(
         manifests.clone(),
         GlobalRestoreOpt {
             db_dir: None,
+            db_path_overrides: None,
             dry_run: true,
             target_version: None,
             trusted_waypoints: TrustedWaypointOpt::default(),
@@ -232,6 +234,7 @@ async fn test_trusted_waypoints_impl(
         manifests,
         GlobalRestoreOpt {
             db_dir: None,
+            db_path_overrides: None,
             dry_run: true,
             target_version: None,
             trusted_waypoints: TrustedWaypointOpt {
@@ -82,6 +82,7 @@ fn end_to_end() {
             GlobalRestoreOpt {
                 dry_run: false,
                 db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                db_path_overrides: None,
                 target_version: None, // max
                 trusted_waypoints: TrustedWaypointOpt::default(),
                 rocksdb_opt: RocksdbOpt::default(),
@@ -0,0 +1,145 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeps the segments of the ledger archive (see `aptos_db::ledger_archive`) in a backup storage,
+//! e.g. a cloud bucket reached through the command adapter, instead of on local disk.
+//!
+//! Every segment is written as a file of its own "backup", and is then registered with a metadata
+//! line, so a segment only shows up in the listing once it's fully written. The metadata lines are
+//! not backup metadata, so the storage must be dedicated to the archive and not be shared with
+//! backups.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    storage::{
+        command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+        BackupStorage, FileHandle, ShellSafeName, TextLine,
+    },
+    utils::storage_ext::BackupStorageExt,
+};
+use anyhow::{format_err, Result};
+use aptos_db::ledger_archive::LedgerArchiveStorage;
+use aptos_infallible::Mutex;
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryInto, fs, future::Future, path::Path, sync::Arc};
+use tokio::{
+    io::AsyncWriteExt,
+    runtime::{Builder, Runtime},
+};
+
+const CONCURRENT_METADATA_DOWNLOADS: usize = 8;
+
+#[derive(Deserialize, Serialize)]
+struct SegmentMetadata {
+    name: String,
+    file_handle: FileHandle,
+}
+
+pub struct BackupStorageArchive {
+    storage: Arc<dyn BackupStorage>,
+    /// The storage is async, but the archive is accessed from the pruner and from DB reads.
+    runtime: Runtime,
+    /// Segment name -> file handle of every segment known to be in the storage.
+    file_handles: Mutex<HashMap<String, FileHandle>>,
+}
+
+impl BackupStorageArchive {
+    pub fn new(storage: Arc<dyn BackupStorage>) -> Result<Self> {
+        Ok(Self {
+            storage,
+            runtime: Builder::new_multi_thread()
+                .thread_name("ledger-archive")
+                .enable_all()
+                .build()?,
+            file_handles: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Keeps the segments in the storage the command adapter config at `config_path` talks to.
+    pub fn new_with_command_adapter_config(config_path: &Path) -> Result<Self> {
+        let config = CommandAdapterConfig::load_from_str(&fs::read_to_string(config_path)?)?;
+        Self::new(Arc::new(CommandAdapter::new(config)))
+    }
+
+    /// Runs `fut` on the archive's own runtime, which works whether or not the caller is in a
+    /// runtime itself.
+    fn block_on<T: Send + 'static>(
+        &self,
+        fut: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        futures::executor::block_on(self.runtime.spawn(fut))?
+    }
+}
+
+impl LedgerArchiveStorage for BackupStorageArchive {
+    fn put_segment(&self, name: &str, data: &[u8]) -> Result<()> {
+        // Segments are immutable, one left behind by an interrupted pruner run can be reused.
+        if self.file_handles.lock().contains_key(name) {
+            return Ok(());
+        }
+
+        let storage = Arc::clone(&self.storage);
+        let metadata_name: ShellSafeName = format!("{}.meta", name).try_into()?;
+        let file_name: ShellSafeName = name.parse()?;
+        let data = data.to_vec();
+        let file_handle = self.block_on(async move {
+            let backup_handle = storage.create_backup_with_random_suffix(&file_name).await?;
+            let (file_handle, mut file) =
+                storage.create_for_write(&backup_handle, &file_name).await?;
+            file.write_all(&data).await?;
+            file.shutdown().await?;
+
+            // Only register the segment once it's fully written.
+            let metadata = SegmentMetadata {
+                name: file_name.to_string(),
+                file_handle: file_handle.clone(),
+            };
+            storage
+                .save_metadata_line(
+                    &metadata_name,
+                    &TextLine::new(&serde_json::to_string(&metadata)?)?,
+                )
+                .await?;
+            Ok(file_handle)
+        })?;
+        self.file_handles
+            .lock()
+            .insert(name.to_string(), file_handle);
+        Ok(())
+    }
+
+    fn get_segment(&self, name: &str) -> Result<Vec<u8>> {
+        let file_handle = self
+            .file_handles
+            .lock()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format_err!("Ledger archive segment {} not found.", name))?;
+        let storage = Arc::clone(&self.storage);
+        self.block_on(async move { storage.read_all(&file_handle).await })
+    }
+
+    fn list_segments(&self) -> Result<Vec<String>> {
+        let storage = Arc::clone(&self.storage);
+        let metadata_files = self.block_on(async move {
+            let storage = &storage;
+            futures::stream::iter(storage.list_metadata_files().await?)
+                .map(|file_handle| async move { storage.read_all(&file_handle).await })
+                .buffer_unordered(CONCURRENT_METADATA_DOWNLOADS)
+                .try_collect::<Vec<_>>()
+                .await
+        })?;
+
+        let mut file_handles = self.file_handles.lock();
+        for content in metadata_files {
+            for line in std::str::from_utf8(&content)?.lines() {
+                let SegmentMetadata { name, file_handle } = serde_json::from_str(line)?;
+                file_handles.insert(name, file_handle);
+            }
+        }
+        Ok(file_handles.keys().cloned().collect())
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::local_fs::LocalFs;
+use aptos_db::ledger_archive::LedgerArchive;
+use aptos_temppath::TempPath;
+
+fn segment_name(first_version: u64, end_version: u64) -> String {
+    format!("{:020}-{:020}.segment", first_version, end_version)
+}
+
+#[test]
+fn test_put_get_list_segments() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let open =
+        || BackupStorageArchive::new(Arc::new(LocalFs::new(tmpdir.path().to_path_buf()))).unwrap();
+
+    let archive = open();
+    assert!(archive.list_segments().unwrap().is_empty());
+    archive.put_segment(&segment_name(0, 3), b"first").unwrap();
+    archive.put_segment(&segment_name(3, 5), b"second").unwrap();
+    // Putting a segment again is a no-op, segments are immutable.
+    archive.put_segment(&segment_name(0, 3), b"first").unwrap();
+    assert_eq!(archive.get_segment(&segment_name(3, 5)).unwrap(), b"second");
+
+    // The segments are found again through their metadata.
+    let reopened = open();
+    let mut names = reopened.list_segments().unwrap();
+    names.sort();
+    assert_eq!(names, vec![segment_name(0, 3), segment_name(3, 5)]);
+    assert_eq!(reopened.get_segment(&segment_name(0, 3)).unwrap(), b"first");
+    assert!(reopened.get_segment(&segment_name(5, 6)).is_err());
+
+    let ledger_archive = LedgerArchive::open(Box::new(open())).unwrap();
+    assert!(ledger_archive.contains(4));
+    assert!(!ledger_archive.contains(5));
+}
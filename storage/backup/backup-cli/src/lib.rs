@@ -6,6 +6,7 @@
 
 pub mod backup_types;
 pub mod coordinators;
+pub mod ledger_archive;
 pub mod metadata;
 pub mod metrics;
 pub mod storage;
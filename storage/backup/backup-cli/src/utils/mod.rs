@@ -13,7 +13,7 @@ pub mod test_utils;
 
 use anyhow::{anyhow, Result};
 use aptos_config::config::{
-    RocksdbConfig, RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS,
+    RocksdbConfig, RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
@@ -79,6 +79,8 @@ pub struct RocksdbOpt {
     index_db_max_total_wal_size: u64,
     #[clap(long, hidden(true), default_value = "16")]
     max_background_jobs: i32,
+    #[clap(long, hidden(true))]
+    enable_secondary_indexes: bool,
 }
 
 impl From<RocksdbOpt> for RocksdbConfigs {
@@ -110,6 +112,7 @@ impl From<RocksdbOpt> for RocksdbConfigs {
                 max_background_jobs: opt.max_background_jobs,
                 ..Default::default()
             },
+            enable_secondary_indexes: opt.enable_secondary_indexes,
         }
     }
 }
@@ -133,6 +136,15 @@ pub struct GlobalRestoreOpt {
     )]
     pub db_dir: Option<PathBuf>,
 
+    #[clap(
+        long,
+        parse(from_os_str),
+        requires = "db-dir",
+        help = "YAML file with the root directories of the DBs (or DB shards) that are not stored \
+        under the target DB dir, in the format of `db_path_overrides` in the node's storage config."
+    )]
+    pub db_path_overrides: Option<PathBuf>,
+
     #[clap(
         long,
         help = "Content newer than this version will not be recovered to DB, \
@@ -269,14 +281,19 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
         let concurrent_downloads = opt.concurrent_downloads.get();
         let replay_concurrency_level = opt.replay_concurrency_level.get();
         let run_mode = if let Some(db_dir) = &opt.db_dir {
-            let restore_handler = Arc::new(AptosDB::open(
+            let db_paths = StorageDirPaths::from_path_and_overrides_file(
                 db_dir,
+                opt.db_path_overrides.as_deref(),
+            )?;
+            let restore_handler = Arc::new(AptosDB::open_with_dir_paths(
+                &db_paths,
                 false,                       /* read_only */
                 NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
                 opt.rocksdb_opt.into(),
                 false,
                 BUFFERED_STATE_TARGET_ITEMS,
                 DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+                None, /* ledger_archive */
             )?)
             .get_restore_handler();
             RestoreRunMode::Restore { restore_handler }
@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use aptos_db::db_debugger::{checkpoint, ledger, state_tree, truncate};
+use aptos_db::db_debugger::{checkpoint, ledger, move_shard, state_tree, truncate};
 use clap::Parser;
 
-/// List snapshots, print nodes, make DB checkpoints and validate ledger hash
+/// List snapshots, print nodes, make DB checkpoints, validate ledger hash and move DB shards
 #[derive(Parser)]
 pub enum Command {
     #[clap(subcommand)]
@@ -14,6 +14,7 @@ pub enum Command {
     #[clap(subcommand)]
     Ledger(ledger::Cmd),
     Truncate(truncate::Cmd),
+    MoveShard(move_shard::Cmd),
 }
 
 impl Command {
@@ -23,6 +24,7 @@ impl Command {
             Command::Checkpoint(cmd) => cmd.run(),
             Command::Ledger(cmd) => cmd.run(),
             Command::Truncate(cmd) => cmd.run(),
+            Command::MoveShard(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,864 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use aptos_backup_cli::{
+    backup_types::{
+        state_snapshot::manifest::StateSnapshotBackup, transaction::manifest::TransactionBackup,
+    },
+    metadata::{cache, cache::MetadataCacheOpt, view::MetadataView},
+    storage::{BackupStorage, DBToolStorageOpt},
+    utils::{
+        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, ConcurrentDownloadsOpt,
+        RocksdbOpt,
+    },
+};
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_db::AptosDB;
+use aptos_logger::{info, warn};
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_state_view::{StateView, TStateView};
+use aptos_storage_interface::{
+    state_view::LatestDbStateCheckpointView, DbReader, MAX_REQUEST_LIMIT,
+};
+use aptos_types::{
+    access_path::Path,
+    contract_event::ContractEvent,
+    state_store::{
+        state_key::{StateKey, StateKeyInner},
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+    },
+    transaction::{Transaction, TransactionInfo, TransactionPayload, Version},
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::MoveResolverExt};
+use arrow::{
+    array::{ArrayRef, BooleanArray, StringArray, UInt64Array},
+    record_batch::RecordBatch,
+};
+use clap::{Parser, Subcommand};
+use itertools::izip;
+use parquet::{
+    arrow::ArrowWriter,
+    file::{
+        metadata::KeyValue,
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+    },
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path as FsPath, PathBuf},
+    sync::Arc,
+};
+
+const TRANSACTIONS_DIR: &str = "transactions";
+const EVENTS_DIR: &str = "events";
+const WRITE_SET_CHANGES_DIR: &str = "write_set_changes";
+const STATE_DIR: &str = "state";
+
+/// Keys of the Parquet metadata recording the range of versions (or state item indices) a file
+/// holds, which can be less than its partition.
+const RANGE_BEGIN_KEY: &str = "aptos.export.begin";
+const RANGE_END_KEY: &str = "aptos.export.end";
+
+/// Export transactions, events and write set changes in a version range, and optionally a
+/// state snapshot, from a local DB or a backup into Parquet files.
+///
+/// Each table is partitioned into files covering `--versions-per-file` versions (or
+/// `--state-items-per-file` state items), named after their partition. Files are written
+/// atomically along with the range they actually hold. A file already holding the range that
+/// would be written to it is skipped, otherwise it's overwritten, e.g. the last partition of an
+/// export that stopped at the latest version. So an interrupted export can be resumed, or a
+/// finished one extended, by running the command again with the same partition sizes.
+#[derive(Subcommand)]
+pub enum Command {
+    #[clap(about = "Export from a local DB.")]
+    Db(DbOpt),
+    #[clap(
+        about = "Export from a backup, without restoring it into a DB. Values are decoded with \
+        the modules in the latest state snapshot of the backup not newer than the end version, \
+        which is read in full before exporting."
+    )]
+    Backup(BackupOpt),
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Command::Db(opt) => opt.run().await,
+            Command::Backup(opt) => opt.run().await,
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct DbOpt {
+    #[clap(long = "db-dir", parse(from_os_str))]
+    db_dir: PathBuf,
+    #[clap(
+        long,
+        parse(from_os_str),
+        help = "YAML file with the root directories of the DBs (or DB shards) that are not stored \
+        under the DB dir, in the format of `db_path_overrides` in the node's storage config."
+    )]
+    db_path_overrides: Option<PathBuf>,
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+    #[clap(flatten)]
+    export: ExportOpt,
+}
+
+impl DbOpt {
+    async fn run(self) -> Result<()> {
+        let db_paths = StorageDirPaths::from_path_and_overrides_file(
+            &self.db_dir,
+            self.db_path_overrides.as_deref(),
+        )?;
+        let db: Arc<dyn DbReader> = Arc::new(AptosDB::open_with_dir_paths(
+            &db_paths,
+            true,                        /* read_only */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+            self.rocksdb_opt.clone().into(),
+            false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None, /* ledger_archive */
+        )?);
+        // Values are decoded with the latest module definitions, which is fine since module
+        // upgrades keep struct layouts compatible.
+        let state_view = db.latest_state_checkpoint_view()?;
+        self.export.run(Source::Db(db), &state_view).await
+    }
+}
+
+#[derive(Parser)]
+pub struct BackupOpt {
+    #[clap(flatten)]
+    metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    concurrent_downloads: ConcurrentDownloadsOpt,
+    #[clap(flatten)]
+    storage: DBToolStorageOpt,
+    #[clap(flatten)]
+    export: ExportOpt,
+}
+
+impl BackupOpt {
+    async fn run(self) -> Result<()> {
+        let storage = self.storage.init_storage().await?;
+        let metadata_view = cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&storage),
+            self.concurrent_downloads.get(),
+        )
+        .await?;
+        let backup = BackupSource {
+            storage,
+            metadata_view,
+        };
+        let state_view = backup
+            .load_modules(self.export.end_version.unwrap_or(Version::MAX))
+            .await?;
+        self.export.run(Source::Backup(backup), &state_view).await
+    }
+}
+
+#[derive(Parser)]
+pub(crate) struct ExportOpt {
+    #[clap(long, parse(from_os_str))]
+    output_dir: PathBuf,
+    #[clap(long, default_value = "0")]
+    start_version: Version,
+    #[clap(
+        long,
+        help = "The version to stop the export at, exclusive. [Defaults to the latest version + 1]"
+    )]
+    end_version: Option<Version>,
+    #[clap(long, default_value = "100000")]
+    versions_per_file: u64,
+    #[clap(
+        long,
+        help = "If set, also export the state snapshot at this version, which must exist in the \
+        DB or the backup."
+    )]
+    state_snapshot_version: Option<Version>,
+    #[clap(long, default_value = "1000000")]
+    state_items_per_file: usize,
+}
+
+impl ExportOpt {
+    pub(crate) async fn run<S: StateView>(self, source: Source, state_view: &S) -> Result<()> {
+        ensure!(
+            self.versions_per_file > 0,
+            "--versions-per-file must be positive."
+        );
+        ensure!(
+            self.state_items_per_file > 0,
+            "--state-items-per-file must be positive."
+        );
+
+        let end_version = match self.end_version {
+            Some(end_version) => end_version,
+            None => source.latest_version()? + 1,
+        };
+        let resolver = state_view.as_move_resolver();
+        let exporter = Exporter {
+            source,
+            annotator: AptosValueAnnotator::new(&resolver),
+            output_dir: self.output_dir.clone(),
+        };
+
+        let mut begin = self.start_version;
+        while begin < end_version {
+            let partition_begin = begin / self.versions_per_file * self.versions_per_file;
+            let partition_end = partition_begin + self.versions_per_file;
+            let end = std::cmp::min(end_version, partition_end);
+            exporter
+                .export_ledger(partition_begin, partition_end, begin, end)
+                .await?;
+            begin = end;
+        }
+
+        if let Some(version) = self.state_snapshot_version {
+            let num_items = exporter.source.get_state_item_count(version).await?;
+            let mut begin = 0;
+            while begin < num_items {
+                let partition_end = begin + self.state_items_per_file;
+                let end = std::cmp::min(num_items, partition_end);
+                exporter
+                    .export_state(version, begin, partition_end, end)
+                    .await?;
+                begin = end;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+type LedgerItem = (Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet);
+
+/// Where the exported data is read from.
+pub(crate) enum Source {
+    Db(Arc<dyn DbReader>),
+    Backup(BackupSource),
+}
+
+impl Source {
+    fn latest_version(&self) -> Result<Version> {
+        match self {
+            Source::Db(db) => db.get_latest_version(),
+            Source::Backup(backup) => backup
+                .metadata_view
+                .max_transaction_version()?
+                .ok_or_else(|| format_err!("No transaction backup found.")),
+        }
+    }
+
+    /// Gets the transactions and their outputs in `[begin, end)`.
+    async fn get_ledger_items(&self, begin: Version, end: Version) -> Result<Vec<LedgerItem>> {
+        match self {
+            Source::Db(db) => {
+                let mut items = Vec::with_capacity((end - begin) as usize);
+                let mut version = begin;
+                while version < end {
+                    let limit = std::cmp::min(end - version, MAX_REQUEST_LIMIT);
+                    for (txn, txn_info, events, write_set) in izip!(
+                        db.get_transaction_iterator(version, limit)?,
+                        db.get_transaction_info_iterator(version, limit)?,
+                        db.get_events_iterator(version, limit)?,
+                        db.get_write_set_iterator(version, limit)?,
+                    ) {
+                        items.push((txn?, txn_info?, events?, write_set?));
+                    }
+                    version += limit;
+                }
+                Ok(items)
+            },
+            Source::Backup(backup) => backup.get_ledger_items(begin, end).await,
+        }
+    }
+
+    async fn get_state_item_count(&self, version: Version) -> Result<usize> {
+        match self {
+            Source::Db(db) => db.get_state_leaf_count(version),
+            Source::Backup(backup) => Ok(backup
+                .get_state_snapshot(version)
+                .await?
+                .chunks
+                .last()
+                .map_or(0, |chunk| chunk.last_idx + 1)),
+        }
+    }
+
+    /// Gets the items with indices in `[begin, end)` of the state snapshot at `version`.
+    async fn get_state_items(
+        &self,
+        version: Version,
+        begin: usize,
+        end: usize,
+    ) -> Result<Vec<(StateKey, StateValue)>> {
+        let mut items = Vec::with_capacity(end - begin);
+        match self {
+            Source::Db(db) => {
+                let mut index = begin;
+                while index < end {
+                    let chunk_size = std::cmp::min(end - index, MAX_REQUEST_LIMIT as usize);
+                    let chunk = db.get_state_value_chunk_with_proof(version, index, chunk_size)?;
+                    items.extend(chunk.raw_values);
+                    index += chunk_size;
+                }
+            },
+            Source::Backup(backup) => {
+                backup
+                    .visit_state_items(version, begin, end, |state_key, state_value| {
+                        items.push((state_key, state_value))
+                    })
+                    .await?
+            },
+        }
+        Ok(items)
+    }
+}
+
+/// Reads the backup files directly. Nothing is verified against the signatures in the backup,
+/// `db-tool backup verify` does that.
+pub(crate) struct BackupSource {
+    storage: Arc<dyn BackupStorage>,
+    metadata_view: MetadataView,
+}
+
+impl BackupSource {
+    async fn get_ledger_items(&self, begin: Version, end: Version) -> Result<Vec<LedgerItem>> {
+        let mut items = Vec::with_capacity((end - begin) as usize);
+        for backup in self
+            .metadata_view
+            .select_transaction_backups(begin, end - 1)?
+        {
+            let manifest: TransactionBackup = self.storage.load_json_file(&backup.manifest).await?;
+            for chunk in manifest
+                .chunks
+                .iter()
+                .filter(|chunk| chunk.last_version >= begin && chunk.first_version < end)
+            {
+                let mut file = self.storage.open_for_read(&chunk.transactions).await?;
+                let mut version = chunk.first_version;
+                while let Some(record_bytes) = file.read_record_bytes().await? {
+                    if version >= end {
+                        break;
+                    }
+                    if version >= begin {
+                        items.push(bcs::from_bytes(&record_bytes)?);
+                    }
+                    version += 1;
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    async fn get_state_snapshot(&self, version: Version) -> Result<StateSnapshotBackup> {
+        let backup = self.metadata_view.expect_state_snapshot(version)?;
+        self.storage.load_json_file(&backup.manifest).await
+    }
+
+    /// Calls `visit` on the items with indices in `[begin, end)` of the state snapshot at
+    /// `version`, in order.
+    async fn visit_state_items(
+        &self,
+        version: Version,
+        begin: usize,
+        end: usize,
+        mut visit: impl FnMut(StateKey, StateValue),
+    ) -> Result<()> {
+        let manifest = self.get_state_snapshot(version).await?;
+        for chunk in manifest
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.last_idx >= begin && chunk.first_idx < end)
+        {
+            let mut file = self.storage.open_for_read(&chunk.blobs).await?;
+            let mut index = chunk.first_idx;
+            while let Some(record_bytes) = file.read_record_bytes().await? {
+                if index >= end {
+                    break;
+                }
+                if index >= begin {
+                    let (state_key, state_value) = bcs::from_bytes(&record_bytes)?;
+                    visit(state_key, state_value);
+                }
+                index += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the modules in the latest state snapshot before `end_version`.
+    async fn load_modules(&self, end_version: Version) -> Result<ModuleStateView> {
+        let mut modules = HashMap::new();
+        match self
+            .metadata_view
+            .select_state_snapshot(end_version.saturating_sub(1))?
+        {
+            Some(snapshot) => {
+                info!(
+                    version = snapshot.version,
+                    "Loading modules from the state snapshot."
+                );
+                self.visit_state_items(
+                    snapshot.version,
+                    0,
+                    usize::MAX,
+                    |state_key, state_value| {
+                        if let StateKeyInner::AccessPath(access_path) = state_key.inner() {
+                            if access_path.is_code() {
+                                modules.insert(state_key, state_value);
+                            }
+                        }
+                    },
+                )
+                .await?;
+            },
+            None => warn!("No state snapshot found in the backup, values won't be decoded."),
+        }
+        Ok(ModuleStateView { modules })
+    }
+}
+
+/// The modules in a state snapshot, which is all the annotator needs to decode values.
+struct ModuleStateView {
+    modules: HashMap<StateKey, StateValue>,
+}
+
+impl TStateView for ModuleStateView {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        Ok(self.modules.get(state_key).cloned())
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+struct Exporter<'a, R> {
+    source: Source,
+    annotator: AptosValueAnnotator<'a, R>,
+    output_dir: PathBuf,
+}
+
+impl<'a, R: MoveResolverExt> Exporter<'a, R> {
+    /// Exports the ledger data in `[begin, end)`, within the partition
+    /// `[partition_begin, partition_end)`, unless already exported.
+    async fn export_ledger(
+        &self,
+        partition_begin: Version,
+        partition_end: Version,
+        begin: Version,
+        end: Version,
+    ) -> Result<()> {
+        let file_name = format!("{:020}-{:020}.parquet", partition_begin, partition_end);
+        let paths = [TRANSACTIONS_DIR, EVENTS_DIR, WRITE_SET_CHANGES_DIR]
+            .map(|dir| self.output_dir.join(dir).join(&file_name));
+        if paths.iter().all(|path| is_exported(path, begin, end)) {
+            info!(begin = begin, end = end, "Already exported, skipping.");
+            return Ok(());
+        }
+
+        let mut transactions = TransactionRows::default();
+        let mut events = EventRows::default();
+        let mut write_set_changes = StateRows::default();
+        for (txn_version, (txn, txn_info, txn_events, write_set)) in
+            (begin..end).zip(self.source.get_ledger_items(begin, end).await?)
+        {
+            transactions.push(txn_version, &txn, &txn_info);
+            for (index, event) in txn_events.iter().enumerate() {
+                events.push(txn_version, index as u64, event, &self.annotator);
+            }
+            self.push_write_set(&mut write_set_changes, txn_version, &write_set);
+        }
+        ensure!(
+            transactions.version.len() as u64 == end - begin,
+            "Expecting {} transactions in [{}, {}), got {}.",
+            end - begin,
+            begin,
+            end,
+            transactions.version.len(),
+        );
+
+        write_parquet(&paths[0], transactions.into_record_batch()?, begin, end)?;
+        write_parquet(&paths[1], events.into_record_batch()?, begin, end)?;
+        write_parquet(
+            &paths[2],
+            write_set_changes.into_record_batch()?,
+            begin,
+            end,
+        )?;
+        info!(begin = begin, end = end, "Exported ledger data.");
+        Ok(())
+    }
+
+    fn push_write_set(&self, rows: &mut StateRows, version: Version, write_set: &WriteSet) {
+        for (state_key, write_op) in write_set.iter() {
+            let (op, value) = match write_op {
+                WriteOp::Creation(data) | WriteOp::CreationWithMetadata { data, .. } => {
+                    ("creation", Some(data.as_slice()))
+                },
+                WriteOp::Modification(data) | WriteOp::ModificationWithMetadata { data, .. } => {
+                    ("modification", Some(data.as_slice()))
+                },
+                WriteOp::Deletion | WriteOp::DeletionWithMetadata { .. } => ("deletion", None),
+            };
+            rows.push(Some(version), Some(op), state_key, value, &self.annotator);
+        }
+    }
+
+    /// Exports the state items with indices in `[begin, end)` of the snapshot at `version`,
+    /// within the partition `[begin, partition_end)`, unless already exported.
+    async fn export_state(
+        &self,
+        version: Version,
+        begin: usize,
+        partition_end: usize,
+        end: usize,
+    ) -> Result<()> {
+        let path = self
+            .output_dir
+            .join(STATE_DIR)
+            .join(format!("{:020}", version))
+            .join(format!("{:020}-{:020}.parquet", begin, partition_end));
+        if is_exported(&path, begin as u64, end as u64) {
+            info!(
+                version = version,
+                begin = begin,
+                end = end,
+                "Already exported, skipping."
+            );
+            return Ok(());
+        }
+
+        let mut rows = StateRows::default();
+        for (state_key, state_value) in self.source.get_state_items(version, begin, end).await? {
+            rows.push(
+                None,
+                None,
+                &state_key,
+                Some(state_value.bytes()),
+                &self.annotator,
+            );
+        }
+
+        write_parquet(&path, rows.into_record_batch()?, begin as u64, end as u64)?;
+        info!(
+            version = version,
+            begin = begin,
+            end = end,
+            "Exported state."
+        );
+        Ok(())
+    }
+}
+
+/// Returns whether the Parquet file at `path` exists and holds exactly `[begin, end)`.
+fn is_exported(path: &FsPath, begin: u64, end: u64) -> bool {
+    exported_range(path).ok().flatten() == Some((begin, end))
+}
+
+/// Reads the range recorded in the Parquet file at `path`, if it exists.
+fn exported_range(path: &FsPath) -> Result<Option<(u64, u64)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    let get = |key: &str| -> Option<u64> {
+        reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()?
+            .iter()
+            .find(|kv| kv.key == key)?
+            .value
+            .as_ref()?
+            .parse()
+            .ok()
+    };
+    Ok(get(RANGE_BEGIN_KEY).zip(get(RANGE_END_KEY)))
+}
+
+/// Writes `batch`, holding `[begin, end)`, into a new Parquet file at `path`, atomically.
+fn write_parquet(path: &FsPath, batch: RecordBatch, begin: u64, end: u64) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("parquet.tmp");
+    let props = WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![
+            KeyValue::new(RANGE_BEGIN_KEY.to_string(), begin.to_string()),
+            KeyValue::new(RANGE_END_KEY.to_string(), end.to_string()),
+        ]))
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(&tmp_path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct TransactionRows {
+    version: Vec<u64>,
+    hash: Vec<String>,
+    transaction_type: Vec<String>,
+    sender: Vec<Option<String>>,
+    sequence_number: Vec<Option<u64>>,
+    entry_function: Vec<Option<String>>,
+    success: Vec<bool>,
+    vm_status: Vec<String>,
+    gas_used: Vec<u64>,
+    state_change_hash: Vec<String>,
+    event_root_hash: Vec<String>,
+}
+
+impl TransactionRows {
+    fn push(&mut self, version: Version, txn: &Transaction, txn_info: &TransactionInfo) {
+        self.version.push(version);
+        self.hash.push(txn.hash().to_hex_literal());
+        self.transaction_type.push(
+            match txn {
+                Transaction::UserTransaction(_) => "user_transaction",
+                Transaction::GenesisTransaction(_) => "genesis_transaction",
+                Transaction::BlockMetadata(_) => "block_metadata_transaction",
+                Transaction::StateCheckpoint(_) => "state_checkpoint_transaction",
+            }
+            .to_string(),
+        );
+        let user_txn = txn.try_as_signed_user_txn();
+        self.sender
+            .push(user_txn.map(|txn| txn.sender().to_hex_literal()));
+        self.sequence_number
+            .push(user_txn.map(|txn| txn.sequence_number()));
+        self.entry_function
+            .push(user_txn.and_then(|txn| match txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => Some(format!(
+                    "{}::{}::{}",
+                    entry_function.module().address().to_hex_literal(),
+                    entry_function.module().name(),
+                    entry_function.function(),
+                )),
+                _ => None,
+            }));
+        self.success.push(txn_info.status().is_success());
+        self.vm_status.push(format!("{:?}", txn_info.status()));
+        self.gas_used.push(txn_info.gas_used());
+        self.state_change_hash
+            .push(txn_info.state_change_hash().to_hex_literal());
+        self.event_root_hash
+            .push(txn_info.event_root_hash().to_hex_literal());
+    }
+
+    fn into_record_batch(self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_from_iter_with_nullable(vec![
+            ("version", u64_array(self.version), false),
+            ("hash", string_array(self.hash), false),
+            ("type", string_array(self.transaction_type), false),
+            ("sender", optional_string_array(self.sender), true),
+            (
+                "sequence_number",
+                optional_u64_array(self.sequence_number),
+                true,
+            ),
+            (
+                "entry_function",
+                optional_string_array(self.entry_function),
+                true,
+            ),
+            ("success", Arc::new(BooleanArray::from(self.success)), false),
+            ("vm_status", string_array(self.vm_status), false),
+            ("gas_used", u64_array(self.gas_used), false),
+            (
+                "state_change_hash",
+                string_array(self.state_change_hash),
+                false,
+            ),
+            ("event_root_hash", string_array(self.event_root_hash), false),
+        ])?)
+    }
+}
+
+#[derive(Default)]
+struct EventRows {
+    version: Vec<u64>,
+    event_index: Vec<u64>,
+    account_address: Vec<String>,
+    creation_number: Vec<u64>,
+    sequence_number: Vec<u64>,
+    type_tag: Vec<String>,
+    data: Vec<String>,
+    decoded_data: Vec<Option<String>>,
+}
+
+impl EventRows {
+    fn push<R: MoveResolverExt>(
+        &mut self,
+        version: Version,
+        event_index: u64,
+        event: &ContractEvent,
+        annotator: &AptosValueAnnotator<R>,
+    ) {
+        self.version.push(version);
+        self.event_index.push(event_index);
+        self.account_address
+            .push(event.key().get_creator_address().to_hex_literal());
+        self.creation_number.push(event.key().get_creation_number());
+        self.sequence_number.push(event.sequence_number());
+        self.type_tag.push(event.type_tag().to_string());
+        self.data.push(hex::encode(event.event_data()));
+        self.decoded_data.push(
+            annotator
+                .view_contract_event(event)
+                .ok()
+                .map(|value| value.to_string()),
+        );
+    }
+
+    fn into_record_batch(self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_from_iter_with_nullable(vec![
+            ("version", u64_array(self.version), false),
+            ("event_index", u64_array(self.event_index), false),
+            ("account_address", string_array(self.account_address), false),
+            ("creation_number", u64_array(self.creation_number), false),
+            ("sequence_number", u64_array(self.sequence_number), false),
+            ("type", string_array(self.type_tag), false),
+            ("data", string_array(self.data), false),
+            (
+                "decoded_data",
+                optional_string_array(self.decoded_data),
+                true,
+            ),
+        ])?)
+    }
+}
+
+/// Rows of state items, either write set changes (with `version` and `op`) or the items of a
+/// state snapshot.
+#[derive(Default)]
+struct StateRows {
+    version: Vec<Option<u64>>,
+    op: Vec<Option<String>>,
+    key_type: Vec<String>,
+    address: Vec<Option<String>>,
+    resource_type: Vec<Option<String>>,
+    table_handle: Vec<Option<String>>,
+    key: Vec<String>,
+    value: Vec<Option<String>>,
+    decoded_value: Vec<Option<String>>,
+}
+
+impl StateRows {
+    fn push<R: MoveResolverExt>(
+        &mut self,
+        version: Option<Version>,
+        op: Option<&str>,
+        state_key: &StateKey,
+        value: Option<&[u8]>,
+        annotator: &AptosValueAnnotator<R>,
+    ) {
+        let (key_type, address, resource_type, table_handle, key) = match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                let (key_type, resource_type) = match access_path.get_path() {
+                    Path::Code(module_id) => ("module", module_id.to_string()),
+                    Path::Resource(struct_tag) => ("resource", struct_tag.to_string()),
+                    Path::ResourceGroup(struct_tag) => ("resource_group", struct_tag.to_string()),
+                };
+                (
+                    key_type,
+                    Some(access_path.address.to_hex_literal()),
+                    Some(resource_type),
+                    None,
+                    hex::encode(&access_path.path),
+                )
+            },
+            StateKeyInner::TableItem { handle, key } => (
+                "table_item",
+                None,
+                None,
+                Some(handle.0.to_hex_literal()),
+                hex::encode(key),
+            ),
+            StateKeyInner::Raw(bytes) => ("raw", None, None, None, hex::encode(bytes)),
+        };
+        let decoded_value = match (state_key.inner(), value) {
+            (StateKeyInner::AccessPath(access_path), Some(value)) if key_type == "resource" => {
+                annotator
+                    .view_access_path(access_path.clone(), value)
+                    .ok()
+                    .map(|value| value.to_string())
+            },
+            _ => None,
+        };
+
+        self.version.push(version);
+        self.op.push(op.map(str::to_string));
+        self.key_type.push(key_type.to_string());
+        self.address.push(address);
+        self.resource_type.push(resource_type);
+        self.table_handle.push(table_handle);
+        self.key.push(key);
+        self.value.push(value.map(hex::encode));
+        self.decoded_value.push(decoded_value);
+    }
+
+    fn into_record_batch(self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_from_iter_with_nullable(vec![
+            ("version", optional_u64_array(self.version), true),
+            ("op", optional_string_array(self.op), true),
+            ("key_type", string_array(self.key_type), false),
+            ("address", optional_string_array(self.address), true),
+            (
+                "resource_type",
+                optional_string_array(self.resource_type),
+                true,
+            ),
+            (
+                "table_handle",
+                optional_string_array(self.table_handle),
+                true,
+            ),
+            ("key", string_array(self.key), false),
+            ("value", optional_string_array(self.value), true),
+            (
+                "decoded_value",
+                optional_string_array(self.decoded_value),
+                true,
+            ),
+        ])?)
+    }
+}
+
+fn u64_array(values: Vec<u64>) -> ArrayRef {
+    Arc::new(UInt64Array::from(values))
+}
+
+fn optional_u64_array(values: Vec<Option<u64>>) -> ArrayRef {
+    Arc::new(UInt64Array::from(values))
+}
+
+fn string_array(values: Vec<String>) -> ArrayRef {
+    Arc::new(StringArray::from(values))
+}
+
+fn optional_string_array(values: Vec<Option<String>>) -> ArrayRef {
+    Arc::new(StringArray::from(values))
+}
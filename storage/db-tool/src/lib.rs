@@ -6,6 +6,7 @@ extern crate core;
 mod backup;
 mod backup_maintenance;
 mod debugger;
+mod export;
 mod replay_verify;
 mod restore;
 #[cfg(test)]
@@ -27,6 +28,8 @@ pub enum DBTool {
     Debug(debugger::Command),
     #[clap(subcommand)]
     BackupMaintenance(backup_maintenance::Command),
+    #[clap(subcommand)]
+    Export(export::Command),
 }
 
 impl DBTool {
@@ -37,6 +40,7 @@ impl DBTool {
             DBTool::ReplayVerify(cmd) => cmd.run().await,
             DBTool::BackupMaintenance(cmd) => cmd.run().await,
             DBTool::Debug(cmd) => cmd.run(),
+            DBTool::Export(cmd) => cmd.run().await,
         }
     }
 }
@@ -9,7 +9,7 @@ use aptos_backup_cli::{
     utils::{ConcurrentDownloadsOpt, ReplayConcurrencyLevelOpt, RocksdbOpt, TrustedWaypointOpt},
 };
 use aptos_config::config::{
-    BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
     NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_db::{AptosDB, GetRestoreHandler};
@@ -33,6 +33,13 @@ pub struct Opt {
     replay_concurrency_level: ReplayConcurrencyLevelOpt,
     #[clap(long = "target-db-dir", parse(from_os_str))]
     pub db_dir: PathBuf,
+    #[clap(
+        long,
+        parse(from_os_str),
+        help = "YAML file with the root directories of the DBs (or DB shards) that are not stored \
+        under the target DB dir, in the format of `db_path_overrides` in the node's storage config."
+    )]
+    pub db_path_overrides: Option<PathBuf>,
     #[clap(flatten)]
     pub rocksdb_opt: RocksdbOpt,
     #[clap(
@@ -60,14 +67,19 @@ pub struct Opt {
 
 impl Opt {
     pub async fn run(self) -> Result<()> {
-        let restore_handler = Arc::new(AptosDB::open(
+        let db_paths = StorageDirPaths::from_path_and_overrides_file(
             self.db_dir,
+            self.db_path_overrides.as_deref(),
+        )?;
+        let restore_handler = Arc::new(AptosDB::open_with_dir_paths(
+            &db_paths,
             false,                       /* read_only */
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
             self.rocksdb_opt.into(),
             false,
             BUFFERED_STATE_TARGET_ITEMS,
             DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None, /* ledger_archive */
         )?)
         .get_restore_handler();
         ReplayVerifyCoordinator::new(
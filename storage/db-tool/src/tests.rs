@@ -253,3 +253,247 @@ mod compaction_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 }
+
+#[cfg(test)]
+mod export_tests {
+    use crate::{
+        export::{ExportOpt, Source},
+        DBTool,
+    };
+    use aptos_backup_cli::{
+        metadata::{cache, cache::MetadataCacheOpt},
+        storage::{local_fs::LocalFs, BackupStorage},
+    };
+    use aptos_backup_service::start_backup_service;
+    use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+    use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
+    use aptos_temppath::TempPath;
+    use aptos_types::transaction::Version;
+    use arrow::{array::UInt64Array, record_batch::RecordBatch};
+    use clap::Parser;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::{
+        fs::{self, File},
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    };
+
+    fn export_opt(output_dir: &Path, args: &[&str]) -> ExportOpt {
+        ExportOpt::try_parse_from(
+            ["export", "--output-dir", output_dir.to_str().unwrap()]
+                .iter()
+                .chain(args),
+        )
+        .unwrap()
+    }
+
+    fn file_name(begin: Version, end: Version) -> String {
+        format!("{:020}-{:020}.parquet", begin, end)
+    }
+
+    fn read_parquet(path: &Path) -> Vec<RecordBatch> {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn versions(path: &Path) -> Vec<Version> {
+        read_parquet(path)
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column_by_name("version")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    /// Lists the files under `dir`, relative to it.
+    fn list_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files = vec![];
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(
+                    list_files(&path)
+                        .into_iter()
+                        .map(|file| path.strip_prefix(dir).unwrap().join(file)),
+                );
+            } else {
+                files.push(path.strip_prefix(dir).unwrap().to_path_buf());
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_export_and_resume() {
+        let db = test_execution_with_storage_impl();
+        let db: Arc<dyn DbReader> = db;
+        let state_view = db.latest_state_checkpoint_view().unwrap();
+        let output_dir = TempPath::new();
+        let transactions_dir = output_dir.path().join("transactions");
+
+        // Stop in the middle of a partition.
+        export_opt(output_dir.path(), &[
+            "--end-version",
+            "10",
+            "--versions-per-file",
+            "4",
+        ])
+        .run(Source::Db(Arc::clone(&db)), &state_view)
+        .await
+        .unwrap();
+        assert_eq!(versions(&transactions_dir.join(file_name(0, 4))), vec![
+            0, 1, 2, 3
+        ]);
+        assert_eq!(versions(&transactions_dir.join(file_name(8, 12))), vec![
+            8, 9
+        ]);
+        let first_file = transactions_dir.join(file_name(0, 4));
+        let first_file_modified = fs::metadata(&first_file).unwrap().modified().unwrap();
+
+        // Resuming skips the complete partitions and overwrites the partial one.
+        export_opt(output_dir.path(), &[
+            "--end-version",
+            "15",
+            "--versions-per-file",
+            "4",
+        ])
+        .run(Source::Db(Arc::clone(&db)), &state_view)
+        .await
+        .unwrap();
+        assert_eq!(
+            fs::metadata(&first_file).unwrap().modified().unwrap(),
+            first_file_modified
+        );
+        let expected_files: Vec<_> = (0..16)
+            .step_by(4)
+            .map(|begin| PathBuf::from(file_name(begin, begin + 4)))
+            .collect();
+        for dir in ["transactions", "events", "write_set_changes"] {
+            assert_eq!(list_files(&output_dir.path().join(dir)), expected_files);
+        }
+        let all_versions: Vec<_> = expected_files
+            .iter()
+            .flat_map(|file| versions(&transactions_dir.join(file)))
+            .collect();
+        assert_eq!(all_versions, (0..15).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_export_from_backup() {
+        let db = test_execution_with_storage_impl();
+        let latest_version = db.get_latest_version().unwrap();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+        let rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 6187),
+            Arc::clone(&db),
+        );
+        let run_cmd = |args: &[&str]| {
+            rt.block_on(
+                DBTool::try_parse_from(["aptos-db-tool"].iter().chain(args))
+                    .unwrap()
+                    .run(),
+            )
+            .unwrap()
+        };
+
+        let backup_dir_str = backup_dir.path().to_str().unwrap();
+        run_cmd(&[
+            "backup",
+            "oneoff",
+            "--backup-service-address",
+            "http://localhost:6187",
+            "state-snapshot",
+            "--state-snapshot-epoch",
+            "1",
+            "--local-fs-dir",
+            backup_dir_str,
+        ]);
+        run_cmd(&[
+            "backup",
+            "oneoff",
+            "--backup-service-address",
+            "http://localhost:6187",
+            "transaction",
+            "--start-version",
+            "0",
+            "--num_transactions",
+            &(latest_version + 1).to_string(),
+            "--local-fs-dir",
+            backup_dir_str,
+        ]);
+        let snapshot_version = rt
+            .block_on(cache::sync_and_load(
+                &MetadataCacheOpt::new(None::<PathBuf>),
+                store,
+                1,
+            ))
+            .unwrap()
+            .select_state_snapshot(Version::MAX)
+            .unwrap()
+            .unwrap()
+            .version
+            .to_string();
+        let export_args = [
+            "--versions-per-file",
+            "8",
+            "--state-snapshot-version",
+            &snapshot_version,
+            "--state-items-per-file",
+            "100",
+        ];
+
+        let backup_output_dir = TempPath::new();
+        run_cmd(
+            &[
+                "export",
+                "backup",
+                "--local-fs-dir",
+                backup_dir_str,
+                "--output-dir",
+                backup_output_dir.path().to_str().unwrap(),
+            ]
+            .iter()
+            .chain(&export_args)
+            .copied()
+            .collect::<Vec<_>>(),
+        );
+
+        let db: Arc<dyn DbReader> = db;
+        let db_output_dir = TempPath::new();
+        rt.block_on(export_opt(db_output_dir.path(), &export_args).run(
+            Source::Db(Arc::clone(&db)),
+            &db.latest_state_checkpoint_view().unwrap(),
+        ))
+        .unwrap();
+
+        // Both sources export the same data, decoded the same way.
+        let files = list_files(db_output_dir.path());
+        assert_eq!(list_files(backup_output_dir.path()), files);
+        assert!(files.iter().any(|file| file.starts_with("state")));
+        for file in files {
+            assert_eq!(
+                read_parquet(&backup_output_dir.path().join(&file)),
+                read_parquet(&db_output_dir.path().join(&file)),
+                "{:?} differs.",
+                file,
+            );
+        }
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+}
@@ -8,7 +8,8 @@ use crate::{
     test_helper::{
         arb_existent_kvs_and_nonexistent_keys, arb_kv_pair_with_distinct_last_nibble,
         arb_tree_with_index, gen_value, test_get_leaf_count, test_get_range_proof,
-        test_get_with_proof, test_get_with_proof_with_distinct_last_nibble, ValueBlob,
+        test_get_with_merged_proof, test_get_with_proof,
+        test_get_with_proof_with_distinct_last_nibble, ValueBlob,
     },
 };
 use aptos_crypto::HashValue;
@@ -787,6 +788,11 @@ proptest! {
         test_get_with_proof((existent_kvs, nonexistent_keys))
     }
 
+    #[test]
+    fn proptest_get_with_merged_proof((existent_kvs, nonexistent_keys) in arb_existent_kvs_and_nonexistent_keys::<ValueBlob>(1000, 100)) {
+        test_get_with_merged_proof((existent_kvs, nonexistent_keys))
+    }
+
     #[test]
     fn proptest_get_with_proof_with_distinct_last_nibble((kv1, kv2) in arb_kv_pair_with_distinct_last_nibble::<ValueBlob>()) {
         test_get_with_proof_with_distinct_last_nibble((kv1, kv2))
@@ -88,7 +88,9 @@ use aptos_crypto::{
 };
 use aptos_types::{
     nibble::{nibble_path::NibblePath, Nibble, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        SparseMerkleMultiProof, SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
 };
@@ -634,6 +636,30 @@ where
             .map(|(value, proof_ext)| (value, proof_ext.into()))
     }
 
+    /// Returns the values (if applicable) of `keys`, in the same order, and a single merkle proof
+    /// for all of them. `keys` must be in strictly ascending order.
+    ///
+    /// The tree is walked once per key, as in [`get_with_proof`](Self::get_with_proof), and the
+    /// resulting proofs are merged into a `SparseMerkleMultiProof`. Only the proof is
+    /// deduplicated: nodes shared by the paths of several keys are read once per key.
+    pub fn get_with_merged_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (K, Version))>>,
+        SparseMerkleMultiProof,
+    )> {
+        let mut values = Vec::with_capacity(keys.len());
+        let mut proofs = Vec::with_capacity(keys.len());
+        for key in keys {
+            let (value, proof) = self.get_with_proof(*key, version)?;
+            values.push(value);
+            proofs.push((*key, proof));
+        }
+        Ok((values, SparseMerkleMultiProof::from_proofs(&proofs)?))
+    }
+
     pub fn get_with_proof_ext(
         &self,
         key: HashValue,
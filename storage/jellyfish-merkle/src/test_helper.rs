@@ -20,7 +20,7 @@ use proptest::{
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::Bound,
 };
 
@@ -137,6 +137,39 @@ pub fn test_get_with_proof<V: TestKey>(
     test_nonexistent_keys_impl(&tree, version, &nonexistent_keys);
 }
 
+pub fn test_get_with_merged_proof<V: TestKey>(
+    (existent_kvs, nonexistent_keys): (HashMap<HashValue, (HashValue, V)>, Vec<HashValue>),
+) {
+    let (db, version) = init_mock_db(&existent_kvs);
+    let tree = JellyfishMerkleTree::new(&db);
+    let root_hash = tree.get_root_hash(version).unwrap();
+
+    let keys: Vec<_> = existent_kvs
+        .keys()
+        .chain(nonexistent_keys.iter())
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let (values, proof) = tree.get_with_merged_proof(&keys, version).unwrap();
+    let elements: Vec<_> = keys
+        .iter()
+        .zip(values.iter())
+        .map(|(key, value)| (*key, value.as_ref().map(|v| v.0)))
+        .collect();
+    for (key, value) in keys.iter().zip(values) {
+        assert_eq!(value.map(|v| (v.0, v.1 .0)).as_ref(), existent_kvs.get(key));
+    }
+    assert!(proof.verify_by_hash(root_hash, &elements).is_ok());
+
+    let mut tampered = elements;
+    tampered[0].1 = match tampered[0].1 {
+        Some(_) => None,
+        None => Some(HashValue::zero()),
+    };
+    assert!(proof.verify_by_hash(root_hash, &tampered).is_err());
+}
+
 pub fn arb_kv_pair_with_distinct_last_nibble<V: TestKey>(
 ) -> impl Strategy<Value = ((HashValue, (HashValue, V)), (HashValue, (HashValue, V)))> {
     (
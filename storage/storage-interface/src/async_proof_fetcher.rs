@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{metrics::TIMER, proof_fetcher::ProofFetcher, DbReader};
+use crate::{
+    metrics::TIMER, proof_fetcher::ProofFetcher, state_value_cache::StateValueCache, DbReader,
+};
 use anyhow::{anyhow, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_logger::{error, sample, sample::SampleRate};
@@ -40,6 +42,7 @@ pub struct AsyncProofFetcher {
     data_sender: Sender<Proof>,
     data_receiver: Receiver<Proof>,
     num_proofs_to_read: AtomicUsize,
+    state_value_cache: Option<Arc<StateValueCache>>,
 }
 
 impl AsyncProofFetcher {
@@ -51,6 +54,32 @@ impl AsyncProofFetcher {
             data_sender,
             data_receiver,
             num_proofs_to_read: AtomicUsize::new(0),
+            state_value_cache: None,
+        }
+    }
+
+    /// Serves the state values from `state_value_cache` when possible, populating it with the
+    /// values read from the DB otherwise. The proofs are always read from the DB.
+    pub fn with_state_value_cache(mut self, state_value_cache: Arc<StateValueCache>) -> Self {
+        self.state_value_cache = Some(state_value_cache);
+        self
+    }
+
+    fn get_state_value(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        match &self.state_value_cache {
+            Some(cache) => match cache.get(state_key, version) {
+                Some(value) => Ok(value),
+                None => {
+                    let value = self.reader.get_state_value_by_version(state_key, version)?;
+                    cache.maybe_insert(state_key, version, &value);
+                    Ok(value)
+                },
+            },
+            None => self.reader.get_state_value_by_version(state_key, version),
         }
     }
 
@@ -134,7 +163,7 @@ impl ProofFetcher for AsyncProofFetcher {
         let _timer = TIMER
             .with_label_values(&["async_proof_fetcher_fetch"])
             .start_timer();
-        let value = self.reader.get_state_value_by_version(state_key, version)?;
+        let value = self.get_state_value(state_key, version)?;
         self.schedule_proof_read(
             state_key.clone(),
             version,
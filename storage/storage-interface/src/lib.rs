@@ -16,8 +16,9 @@ use aptos_types::{
     move_resource::MoveStorage,
     on_chain_config::{access_path_for_config, ConfigID},
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleProofExt,
-        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        AccumulatorConsistencyProof, SparseMerkleMultiProof, SparseMerkleProof,
+        SparseMerkleProofExt, SparseMerkleRangeProof, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     state_store::{
@@ -33,6 +34,10 @@ use aptos_types::{
     },
     write_set::WriteSet,
 };
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
+};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -45,6 +50,7 @@ mod metrics;
 pub mod mock;
 pub mod proof_fetcher;
 pub mod state_delta;
+pub mod state_value_cache;
 pub mod state_view;
 pub mod sync_proof_fetcher;
 
@@ -207,6 +213,21 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the events of the given Move type starting at event `start_index` of
+    /// `start_version`, in ascending order, each with its index in the events of its
+    /// transaction. Will return no more than `limit` events and will ignore events emitted
+    /// at versions after `ledger_version`. Requires the secondary indexes to be enabled.
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        unimplemented!()
+    }
+
     fn get_transaction_iterator(
         &self,
         start_version: Version,
@@ -366,6 +387,23 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the list of transactions calling the entry function `module::function` (directly
+    /// or through a multisig account) starting at version `start_version`, in ascending order.
+    /// Will return no more than `limit` transactions. Will ignore transactions with
+    /// `txn.version > ledger_version`. Optionally fetch events for each transaction when
+    /// `include_events` is `true`. Requires the secondary indexes to be enabled.
+    fn get_transactions_by_entry_function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>> {
+        unimplemented!()
+    }
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -428,6 +466,18 @@ pub trait DbReader: Send + Sync {
             .map(|(value, proof_ext)| (value, proof_ext.into()))
     }
 
+    /// Gets the state values of a set of state keys along with a single proof for all of them,
+    /// out of the ledger state indicated by the state Merkle tree root at the given version.
+    /// `state_keys` must be in strictly ascending order of their hashes, and the values are
+    /// returned in the same order.
+    fn get_state_values_with_multi_proof_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        unimplemented!()
+    }
+
     /// Gets the latest ExecutedTrees no matter if db has been bootstrapped.
     /// Used by the Db-bootstrapper.
     fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
@@ -530,6 +580,11 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns whether the event type and entry function indexes have been enabled or not
+    fn secondary_indexes_enabled(&self) -> bool {
+        false
+    }
+
     /// Returns state storage usage at the end of an epoch.
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         unimplemented!()
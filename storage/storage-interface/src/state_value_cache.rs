@@ -0,0 +1,239 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::STATE_VALUE_CACHE;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+    write_set::{TransactionWrite, WriteSet},
+};
+use lru::LruCache;
+use parking_lot::Mutex;
+
+/// A bounded cache of the state values in the DB, persisting across blocks.
+///
+/// The cache tracks the latest committed version (the watermark) it knows about, and every entry
+/// is valid from its `valid_from` version up to the watermark, so a read at a version in between
+/// can be served without touching the DB. The entries are kept up to date by applying the
+/// committed write sets, and the cache is cleared if it misses any committed versions.
+///
+/// Every key written at or after `tracked_from` is in the cache, which is what allows to cache the
+/// value of a key read from the DB at a version in `[tracked_from, watermark]`: since the key was
+/// not written since, the value is valid up to the watermark. Values read at the watermark itself
+/// can always be cached.
+pub struct StateValueCache {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    entries: LruCache<StateKey, Entry>,
+    size_bytes: usize,
+    max_size_bytes: usize,
+    /// The latest version that is known to be committed, if any.
+    committed_version: Option<Version>,
+    /// The first version whose writes are all reflected in the cache.
+    tracked_from: Version,
+}
+
+struct Entry {
+    value: Option<StateValue>,
+    valid_from: Version,
+}
+
+impl StateValueCache {
+    /// Creates an empty cache on top of the DB committed up to `committed_version`, holding at
+    /// most `max_size_bytes` of keys and values.
+    pub fn new(max_size_bytes: usize, committed_version: Option<Version>) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: LruCache::unbounded(),
+                size_bytes: 0,
+                max_size_bytes,
+                committed_version,
+                tracked_from: next_version(committed_version),
+            }),
+        }
+    }
+
+    /// Returns the value of the key at `version`, if it's known.
+    pub fn get(&self, state_key: &StateKey, version: Version) -> Option<Option<StateValue>> {
+        let mut inner = self.inner.lock();
+        let hit = match inner.committed_version {
+            Some(committed_version) if version <= committed_version => inner
+                .entries
+                .get(state_key)
+                .filter(|entry| entry.valid_from <= version)
+                .map(|entry| entry.value.clone()),
+            _ => None,
+        };
+        STATE_VALUE_CACHE
+            .with_label_values(&[if hit.is_some() { "hit" } else { "miss" }])
+            .inc();
+        hit
+    }
+
+    /// Caches the value of the key read from the DB at `version`, if it's known to stay valid up
+    /// to the watermark.
+    pub fn maybe_insert(&self, state_key: &StateKey, version: Version, value: &Option<StateValue>) {
+        let mut inner = self.inner.lock();
+        let cacheable = match inner.committed_version {
+            Some(committed_version) => {
+                version == committed_version
+                    || (inner.tracked_from <= version && version < committed_version)
+            },
+            None => false,
+        };
+        // A key in the cache is either already up to date, or written after `version`.
+        if cacheable && !inner.entries.contains(state_key) {
+            let valid_from = inner.tracked_from.min(version);
+            inner.insert(state_key.clone(), value.clone(), valid_from);
+        }
+    }
+
+    /// Applies the write sets of the transactions committed starting at `first_version`.
+    pub fn apply_committed_write_sets<'a>(
+        &self,
+        first_version: Version,
+        write_sets: impl IntoIterator<Item = &'a WriteSet>,
+    ) {
+        let mut inner = self.inner.lock();
+        if first_version != next_version(inner.committed_version) {
+            // Some versions were committed elsewhere, e.g. by state sync, the entries can't be
+            // trusted anymore.
+            inner.clear();
+        }
+        let mut version = first_version;
+        for write_set in write_sets {
+            for (state_key, write_op) in write_set.iter() {
+                inner.insert(state_key.clone(), write_op.as_state_value(), version);
+            }
+            inner.committed_version = Some(version);
+            version += 1;
+        }
+        if inner.tracked_from > version {
+            // Entries were evicted, or the cache was cleared.
+            inner.tracked_from = version;
+        }
+    }
+}
+
+impl Inner {
+    fn insert(&mut self, state_key: StateKey, value: Option<StateValue>, valid_from: Version) {
+        let size = entry_size(&state_key, &value);
+        if let Some(old_entry) = self
+            .entries
+            .put(state_key.clone(), Entry { value, valid_from })
+        {
+            self.size_bytes -= entry_size(&state_key, &old_entry.value);
+        }
+        self.size_bytes += size;
+
+        while self.size_bytes > self.max_size_bytes {
+            match self.entries.pop_lru() {
+                Some((evicted_key, evicted_entry)) => {
+                    self.size_bytes -= entry_size(&evicted_key, &evicted_entry.value);
+                    STATE_VALUE_CACHE.with_label_values(&["evicted"]).inc();
+                },
+                None => break,
+            }
+            // Writes to the evicted keys are not tracked from now on.
+            self.tracked_from = Version::MAX;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.size_bytes = 0;
+        self.tracked_from = Version::MAX;
+        STATE_VALUE_CACHE.with_label_values(&["cleared"]).inc();
+    }
+}
+
+fn next_version(version: Option<Version>) -> Version {
+    version.map_or(0, |version| version + 1)
+}
+
+fn entry_size(state_key: &StateKey, value: &Option<StateValue>) -> usize {
+    state_key.size() + value.as_ref().map_or(0, |value| value.size())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::write_set::{WriteOp, WriteSetMut};
+
+    fn key(name: &str) -> StateKey {
+        StateKey::raw(name.as_bytes().to_vec())
+    }
+
+    fn value(bytes: &[u8]) -> Option<StateValue> {
+        Some(StateValue::from(bytes.to_vec()))
+    }
+
+    fn write_set(writes: Vec<(&str, &[u8])>) -> WriteSet {
+        WriteSetMut::new(
+            writes
+                .into_iter()
+                .map(|(name, bytes)| (key(name), WriteOp::Modification(bytes.to_vec()))),
+        )
+        .freeze()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_read_and_commit() {
+        let cache = StateValueCache::new(1 << 20, Some(9));
+        assert_eq!(cache.get(&key("a"), 9), None);
+        cache.maybe_insert(&key("a"), 9, &value(b"a0"));
+        assert_eq!(cache.get(&key("a"), 9), Some(value(b"a0")));
+        // Not committed yet.
+        assert_eq!(cache.get(&key("a"), 10), None);
+
+        cache.apply_committed_write_sets(10, &[write_set(vec![("a", b"a1")])]);
+        assert_eq!(cache.get(&key("a"), 9), None);
+        assert_eq!(cache.get(&key("a"), 10), Some(value(b"a1")));
+
+        // Reads at the versions before the commit can't be cached anymore, the key may have been
+        // written since.
+        cache.maybe_insert(&key("b"), 9, &value(b"b0"));
+        assert_eq!(cache.get(&key("b"), 10), None);
+        cache.maybe_insert(&key("b"), 10, &value(b"b0"));
+        assert_eq!(cache.get(&key("b"), 10), Some(value(b"b0")));
+    }
+
+    #[test]
+    fn test_version_gap_clears_cache() {
+        let cache = StateValueCache::new(1 << 20, Some(9));
+        cache.maybe_insert(&key("a"), 9, &value(b"a0"));
+        cache.apply_committed_write_sets(12, &[write_set(vec![("b", b"b1")])]);
+        assert_eq!(cache.get(&key("a"), 12), None);
+        assert_eq!(cache.get(&key("b"), 12), Some(value(b"b1")));
+
+        // The writes at versions 10 and 11 are unknown, the value read at 12 is only known to be
+        // valid at 12.
+        cache.maybe_insert(&key("a"), 12, &value(b"a2"));
+        cache.apply_committed_write_sets(13, &[write_set(vec![])]);
+        assert_eq!(cache.get(&key("a"), 11), None);
+        assert_eq!(cache.get(&key("a"), 13), Some(value(b"a2")));
+    }
+
+    #[test]
+    fn test_eviction() {
+        let entry_size = key("a").size() + 2;
+        let cache = StateValueCache::new(entry_size * 2, Some(9));
+        cache.apply_committed_write_sets(10, &[write_set(vec![]), write_set(vec![])]);
+        cache.maybe_insert(&key("a"), 10, &value(b"a0"));
+        cache.maybe_insert(&key("b"), 10, &value(b"b0"));
+        assert_eq!(cache.get(&key("a"), 10), Some(value(b"a0")));
+        cache.maybe_insert(&key("c"), 10, &value(b"c0"));
+
+        // 'b' is the least recently used.
+        assert_eq!(cache.get(&key("b"), 10), None);
+        assert_eq!(cache.get(&key("a"), 10), Some(value(b"a0")));
+        // Writes to 'b' are not tracked anymore, so only reads at the watermark can be cached.
+        cache.maybe_insert(&key("b"), 10, &value(b"b0"));
+        assert_eq!(cache.get(&key("b"), 10), None);
+        cache.maybe_insert(&key("b"), 11, &value(b"b0"));
+        assert_eq!(cache.get(&key("b"), 11), Some(value(b"b0")));
+    }
+}
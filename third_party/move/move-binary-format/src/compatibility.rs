@@ -8,8 +8,8 @@ use crate::{
     file_format_common::VERSION_5,
     normalized::Module,
 };
-use move_core_types::vm_status::StatusCode;
-use std::collections::BTreeSet;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId, vm_status::StatusCode};
+use std::{collections::BTreeSet, fmt};
 
 /// The result of a linking and layout compatibility check. Here is what the different combinations. NOTE that if `check_struct_layout` is false, type safety over a series of upgrades cannot be guaranteed.
 /// mean:
@@ -207,6 +207,268 @@ impl Compatibility {
 
         Ok(())
     }
+
+    /// Whether `incompatibility` is rejected under this compatibility configuration.
+    pub fn is_breaking(&self, incompatibility: &Incompatibility) -> bool {
+        (self.check_struct_and_pub_function_linking
+            && incompatibility.breaks_struct_and_pub_function_linking())
+            || (self.check_struct_layout && incompatibility.breaks_struct_layout())
+            || (self.check_friend_linking && incompatibility.breaks_friend_linking())
+    }
+
+    /// Lists every change from `old_module` to `new_module` that may be backward incompatible,
+    /// regardless of which checks are enabled. Use `is_breaking` to filter the list for a given
+    /// configuration. This is meant for reporting: `check` stops at the first breaking change
+    /// and is what the VM uses, while `incompatibilities` must agree with it for every
+    /// configuration.
+    pub fn incompatibilities(old_module: &Module, new_module: &Module) -> Vec<Incompatibility> {
+        let mut incompatibilities = vec![];
+
+        // module's name and address are unchanged
+        if old_module.address != new_module.address || old_module.name != new_module.name {
+            incompatibilities.push(Incompatibility::ModuleIdChanged);
+        }
+
+        // old module's structs are a subset of the new module's structs
+        for (name, old_struct) in &old_module.structs {
+            let new_struct = match new_module.structs.get(name) {
+                Some(new_struct) => new_struct,
+                None => {
+                    // Struct not present in new . Existing modules that depend on this struct will fail to link with the new version of the module.
+                    // Also, struct layout cannot be guaranteed transitively, because after
+                    // removing the struct, it could be re-added later with a different layout.
+                    incompatibilities.push(Incompatibility::StructMissing(name.clone()));
+                    continue;
+                },
+            };
+
+            if !struct_abilities_compatibile(old_struct.abilities, new_struct.abilities) {
+                incompatibilities.push(Incompatibility::StructAbilitiesChanged(name.clone()));
+            }
+            if !struct_type_parameters_compatibile(
+                &old_struct.type_parameters,
+                &new_struct.type_parameters,
+            ) {
+                incompatibilities.push(Incompatibility::StructTypeParametersChanged(name.clone()));
+            }
+            if new_struct.fields != old_struct.fields {
+                // Fields changed. Code in this module will fail at runtime if it tries to
+                // read a previously published struct value
+                // TODO: this is a stricter definition than required. We could in principle
+                // choose that changing the name (but not position or type) of a field is
+                // compatible. The VM does not care about the name of a field
+                // (it's purely informational), but clients presumably do.
+                incompatibilities.push(Incompatibility::StructLayoutChanged(name.clone()));
+            }
+        }
+
+        // The modules are considered as compatible function-wise when all the conditions are met:
+        //
+        // - old module's public functions are a subset of the new module's public functions
+        //   (i.e. we cannot remove or change public functions)
+        // - old module's script functions are a subset of the new module's script functions
+        //   (i.e. we cannot remove or change script functions)
+        // - for any friend function that is removed or changed in the old module
+        //   - if the function visibility is upgraded to public, it is OK
+        //   - otherwise, it is considered as incompatible.
+        //
+        // NOTE: it is possible to relax the compatibility checking for a friend function, i.e.,
+        // we can remove/change a friend function if the function is not used by any module in the
+        // friend list. But for simplicity, we decided to go to the more restrictive form now and
+        // we may revisit this in the future.
+        for (name, old_func) in &old_module.exposed_functions {
+            let new_func = match new_module.exposed_functions.get(name) {
+                Some(new_func) => new_func,
+                None => {
+                    incompatibilities.push(Incompatibility::FunctionMissing {
+                        name: name.clone(),
+                        old_visibility: old_func.visibility,
+                    });
+                    continue;
+                },
+            };
+            let is_vis_compatible = match (old_func.visibility, new_func.visibility) {
+                // public must remain public
+                (Visibility::Public, Visibility::Public) => true,
+                (Visibility::Public, _) => false,
+                // friend can become public or remain friend
+                (Visibility::Friend, Visibility::Public)
+                | (Visibility::Friend, Visibility::Friend) => true,
+                (Visibility::Friend, _) => false,
+                // private can become public or friend, or stay private
+                (Visibility::Private, _) => true,
+            };
+            let is_entry_compatible = if old_module.file_format_version < VERSION_5
+                && new_module.file_format_version < VERSION_5
+            {
+                // if it was public(script), it must remain pubic(script)
+                // if it was not public(script), it _cannot_ become public(script)
+                old_func.is_entry == new_func.is_entry
+            } else {
+                // If it was an entry function, it must remain one.
+                // If it was not an entry function, it is allowed to become one.
+                !old_func.is_entry || new_func.is_entry
+            };
+            if !is_vis_compatible {
+                incompatibilities.push(Incompatibility::FunctionVisibilityChanged {
+                    name: name.clone(),
+                    old_visibility: old_func.visibility,
+                    new_visibility: new_func.visibility,
+                });
+            }
+            if !is_entry_compatible {
+                incompatibilities.push(Incompatibility::FunctionEntryChanged {
+                    name: name.clone(),
+                    old_visibility: old_func.visibility,
+                });
+            }
+            if old_func.parameters != new_func.parameters
+                || old_func.return_ != new_func.return_
+                || !fun_type_parameters_compatibile(
+                    &old_func.type_parameters,
+                    &new_func.type_parameters,
+                )
+            {
+                incompatibilities.push(Incompatibility::FunctionSignatureChanged {
+                    name: name.clone(),
+                    old_visibility: old_func.visibility,
+                });
+            }
+        }
+
+        // check friend declarations compatibility
+        //
+        // - additions to the list are allowed
+        // - removals are not allowed
+        //
+        let new_friend_module_ids: BTreeSet<_> = new_module.friends.iter().collect();
+        for friend in &old_module.friends {
+            if !new_friend_module_ids.contains(friend) {
+                incompatibilities.push(Incompatibility::FriendMissing(friend.clone()));
+            }
+        }
+
+        incompatibilities
+    }
+}
+
+/// A change between two versions of a module that may break backward compatibility.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Incompatibility {
+    /// The address or name of the module changed
+    ModuleIdChanged,
+    /// A struct was removed
+    StructMissing(Identifier),
+    /// A struct lost some of its abilities
+    StructAbilitiesChanged(Identifier),
+    /// The type parameters of a struct changed incompatibly
+    StructTypeParametersChanged(Identifier),
+    /// The fields of a struct changed
+    StructLayoutChanged(Identifier),
+    /// A public, friend or entry function was removed
+    FunctionMissing {
+        name: Identifier,
+        old_visibility: Visibility,
+    },
+    /// The visibility of a function was restricted
+    FunctionVisibilityChanged {
+        name: Identifier,
+        old_visibility: Visibility,
+        new_visibility: Visibility,
+    },
+    /// An entry function is no longer an entry function
+    FunctionEntryChanged {
+        name: Identifier,
+        old_visibility: Visibility,
+    },
+    /// The parameters, return types or type parameters of a function changed incompatibly
+    FunctionSignatureChanged {
+        name: Identifier,
+        old_visibility: Visibility,
+    },
+    /// A friend declaration was removed
+    FriendMissing(ModuleId),
+}
+
+impl Incompatibility {
+    /// Whether modules depending on the public functions or structs of this module may fail to
+    /// link.
+    pub fn breaks_struct_and_pub_function_linking(&self) -> bool {
+        use Incompatibility::*;
+        match self {
+            ModuleIdChanged
+            | StructMissing(_)
+            | StructAbilitiesChanged(_)
+            | StructTypeParametersChanged(_) => true,
+            FunctionMissing { old_visibility, .. }
+            | FunctionVisibilityChanged { old_visibility, .. }
+            | FunctionEntryChanged { old_visibility, .. }
+            | FunctionSignatureChanged { old_visibility, .. } => {
+                !matches!(old_visibility, Visibility::Friend)
+            },
+            StructLayoutChanged(_) | FriendMissing(_) => false,
+        }
+    }
+
+    /// Whether previously published values of the structs of this module may no longer be
+    /// readable.
+    pub fn breaks_struct_layout(&self) -> bool {
+        matches!(
+            self,
+            Incompatibility::StructMissing(_) | Incompatibility::StructLayoutChanged(_)
+        )
+    }
+
+    /// Whether friend modules of this module may fail to link.
+    pub fn breaks_friend_linking(&self) -> bool {
+        use Incompatibility::*;
+        match self {
+            FunctionMissing { old_visibility, .. }
+            | FunctionVisibilityChanged { old_visibility, .. }
+            | FunctionEntryChanged { old_visibility, .. }
+            | FunctionSignatureChanged { old_visibility, .. } => {
+                matches!(old_visibility, Visibility::Friend)
+            },
+            FriendMissing(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Incompatibility::*;
+        match self {
+            ModuleIdChanged => write!(f, "module address or name changed"),
+            StructMissing(name) => write!(f, "struct `{}` was removed", name),
+            StructAbilitiesChanged(name) => {
+                write!(f, "struct `{}` no longer has all of its abilities", name)
+            },
+            StructTypeParametersChanged(name) => {
+                write!(f, "type parameters of struct `{}` changed", name)
+            },
+            StructLayoutChanged(name) => write!(f, "fields of struct `{}` changed", name),
+            FunctionMissing { name, .. } => write!(f, "function `{}` was removed", name),
+            FunctionVisibilityChanged {
+                name,
+                old_visibility,
+                new_visibility,
+            } => write!(
+                f,
+                "visibility of function `{}` changed from {:?} to {:?}",
+                name, old_visibility, new_visibility
+            ),
+            FunctionEntryChanged { name, .. } => {
+                write!(f, "function `{}` is no longer an entry function", name)
+            },
+            FunctionSignatureChanged { name, .. } => {
+                write!(f, "signature of function `{}` changed", name)
+            },
+            FriendMissing(module_id) => {
+                write!(f, "friend declaration of `{}` was removed", module_id)
+            },
+        }
+    }
 }
 
 // When upgrading, the new abilities must be a superset of the old abilities.
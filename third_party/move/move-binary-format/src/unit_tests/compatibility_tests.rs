@@ -1,9 +1,16 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{compatibility::Compatibility, file_format::*, normalized};
-use move_core_types::{account_address::AccountAddress, identifier::Identifier};
-use std::convert::TryFrom;
+use crate::{
+    compatibility::{Compatibility, Incompatibility},
+    file_format::*,
+    normalized,
+};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use proptest::{collection::vec, prelude::*, sample::subsequence};
+use std::{collections::BTreeMap, convert::TryFrom};
 
 fn mk_module(vis: u8) -> normalized::Module {
     let (visibility, is_entry) = if vis == Visibility::DEPRECATED_SCRIPT {
@@ -114,3 +121,183 @@ fn deprecated_add_script_visibility() {
         .check(&friend_module, &script_module)
         .is_err());
 }
+
+#[test]
+fn incompatibilities_report_restricted_visibility() {
+    let public_module = mk_module(Visibility::Public as u8);
+    let friend_module = mk_module(Visibility::Friend as u8);
+    let private_module = mk_module(Visibility::Private as u8);
+    let fn_name = Identifier::new("fn").unwrap();
+
+    // public -> friend, breaks linking
+    let incompatibilities = Compatibility::incompatibilities(&public_module, &friend_module);
+    assert_eq!(incompatibilities, vec![
+        Incompatibility::FunctionVisibilityChanged {
+            name: fn_name.clone(),
+            old_visibility: Visibility::Public,
+            new_visibility: Visibility::Friend,
+        }
+    ]);
+    assert!(Compatibility::full_check().is_breaking(&incompatibilities[0]));
+
+    // friend -> private, only breaks friend linking
+    let incompatibilities = Compatibility::incompatibilities(&friend_module, &private_module);
+    assert_eq!(incompatibilities, vec![Incompatibility::FunctionMissing {
+        name: fn_name,
+        old_visibility: Visibility::Friend,
+    }]);
+    assert!(Compatibility::full_check().is_breaking(&incompatibilities[0]));
+    assert!(!Compatibility::new(true, true, false).is_breaking(&incompatibilities[0]));
+    assert!(Compatibility::new(true, true, false)
+        .check(&friend_module, &private_module)
+        .is_ok());
+
+    // no changes, nothing reported
+    assert!(Compatibility::incompatibilities(&public_module, &public_module).is_empty());
+}
+
+// Strategies over small domains, so that two generated modules often share some of their
+// structs and functions.
+
+fn abilities() -> impl Strategy<Value = AbilitySet> {
+    (0u8..16).prop_map(|byte| AbilitySet::from_u8(byte).unwrap())
+}
+
+fn types() -> impl Strategy<Value = Vec<normalized::Type>> {
+    vec(
+        prop_oneof![Just(normalized::Type::Bool), Just(normalized::Type::U64)],
+        0..2,
+    )
+}
+
+fn struct_() -> impl Strategy<Value = normalized::Struct> {
+    (
+        abilities(),
+        vec(
+            (abilities(), any::<bool>()).prop_map(|(constraints, is_phantom)| {
+                StructTypeParameter {
+                    constraints,
+                    is_phantom,
+                }
+            }),
+            0..2,
+        ),
+        types(),
+    )
+        .prop_map(
+            |(abilities, type_parameters, field_types)| normalized::Struct {
+                abilities,
+                type_parameters,
+                fields: field_types
+                    .into_iter()
+                    .map(|type_| normalized::Field {
+                        name: Identifier::new("f").unwrap(),
+                        type_,
+                    })
+                    .collect(),
+            },
+        )
+}
+
+fn function() -> impl Strategy<Value = normalized::Function> {
+    (
+        prop_oneof![
+            Just(Visibility::Public),
+            Just(Visibility::Friend),
+            Just(Visibility::Private)
+        ],
+        any::<bool>(),
+        vec(abilities(), 0..2),
+        types(),
+        types(),
+    )
+        .prop_map(
+            |(visibility, is_entry, type_parameters, parameters, return_)| normalized::Function {
+                visibility,
+                is_entry,
+                type_parameters,
+                parameters,
+                return_,
+            },
+        )
+}
+
+fn members<T: std::fmt::Debug>(
+    names: &'static [&'static str],
+    member: impl Strategy<Value = T>,
+) -> impl Strategy<Value = BTreeMap<Identifier, T>> {
+    vec(proptest::option::of(member), names.len()).prop_map(move |members| {
+        names
+            .iter()
+            .zip(members)
+            .filter_map(|(name, member)| Some((Identifier::new(*name).unwrap(), member?)))
+            .collect()
+    })
+}
+
+fn module() -> impl Strategy<Value = normalized::Module> {
+    let friends = vec!["A", "B"]
+        .into_iter()
+        .map(|name| ModuleId::new(AccountAddress::ZERO, Identifier::new(name).unwrap()))
+        .collect::<Vec<_>>();
+    (
+        prop_oneof![
+            Just(crate::file_format_common::VERSION_4),
+            Just(crate::file_format_common::VERSION_6)
+        ],
+        prop_oneof![Just("M"), Just("N")],
+        subsequence(friends, 0..=2),
+        members(&["S", "T"], struct_()),
+        members(&["f", "g", "h"], function()),
+    )
+        .prop_map(
+            |(file_format_version, name, friends, structs, exposed_functions)| normalized::Module {
+                file_format_version,
+                address: AccountAddress::ZERO,
+                name: Identifier::new(name).unwrap(),
+                friends,
+                structs,
+                exposed_functions,
+            },
+        )
+}
+
+/// A module and an upgrade of it, which keeps some parts of the old module unchanged.
+fn module_upgrade() -> impl Strategy<Value = (normalized::Module, normalized::Module)> {
+    (module(), module(), vec(any::<bool>(), 4)).prop_map(|(old, mut new, keep)| {
+        if keep[0] {
+            new.name = old.name.clone();
+        }
+        if keep[1] {
+            new.friends = old.friends.clone();
+        }
+        if keep[2] {
+            new.structs = old.structs.clone();
+        }
+        if keep[3] {
+            new.exposed_functions = old.exposed_functions.clone();
+        }
+        (old, new)
+    })
+}
+
+proptest! {
+    // `check` is what the VM runs when publishing, while `incompatibilities` only reports
+    // the breaking changes. They have to agree for every configuration.
+    #[test]
+    fn incompatibilities_agree_with_check((old, new) in module_upgrade()) {
+        let incompatibilities = Compatibility::incompatibilities(&old, &new);
+        for bits in 0u8..8 {
+            let compatibility = Compatibility::new(bits & 1 != 0, bits & 2 != 0, bits & 4 != 0);
+            prop_assert_eq!(
+                compatibility.check(&old, &new).is_ok(),
+                !incompatibilities
+                    .iter()
+                    .any(|incompatibility| compatibility.is_breaking(incompatibility)),
+                "{:?}: {:?}",
+                compatibility,
+                incompatibilities
+            );
+        }
+    }
+}
@@ -15,6 +15,7 @@ use std::{
     str::FromStr,
 };
 
+const DELEGATION_POOL_DOMAIN_SEPARATOR: &[u8] = b"aptos_framework::delegation_pool";
 const MULTISIG_ACCOUNT_DOMAIN_SEPARATOR: &[u8] = b"aptos_framework::multisig_account";
 const STAKING_CONTRACT_DOMAIN_SEPARATOR: &[u8] = b"aptos_framework::staking_contract";
 const VESTING_POOL_DOMAIN_SEPARATOR: &[u8] = b"aptos_framework::vesting";
@@ -236,6 +237,13 @@ pub fn create_resource_address(address: AccountAddress, seed: &[u8]) -> AccountA
     AccountAddress::from_bytes(hash.as_ref()).unwrap()
 }
 
+pub fn create_delegation_pool_address(owner: AccountAddress, seed: &[u8]) -> AccountAddress {
+    let mut full_seed = vec![];
+    full_seed.extend(DELEGATION_POOL_DOMAIN_SEPARATOR);
+    full_seed.extend(seed);
+    create_resource_address(owner, &full_seed)
+}
+
 pub fn create_multisig_account_address(
     creator: AccountAddress,
     creator_nonce: u64,
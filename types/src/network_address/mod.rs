@@ -123,6 +123,9 @@ pub enum Protocol {
     // probably need to move network wire into its own crate to avoid circular
     // dependency b/w network and types.
     Handshake(u8),
+    Udp(u16),
+    // QUIC over the preceding `/udp/<port>`
+    Quic,
 }
 
 /// A minimally parsed DNS name. We don't really do any checking other than
@@ -193,11 +196,11 @@ pub enum ParseError {
     NetworkLayerMissing,
 
     #[error(
-        "NetworkAddress must start with one of Protocol::Ip4/Ip6/Dns/Dns4/Dns6 followed by TCP"
+        "NetworkAddress must start with one of Protocol::Ip4/Ip6/Dns/Dns4/Dns6 followed by TCP or UDP/QUIC"
     )]
     TransportLayerMissing,
 
-    #[error("NetworkAddress must have a NoiseIK protocol following the TCP protocol")]
+    #[error("NetworkAddress must have a NoiseIK protocol following the transport protocol")]
     SessionLayerMissing,
 
     #[error("NetworkAddress must have a Handshake protocol following the NoiseIK protocol")]
@@ -237,7 +240,7 @@ fn is_network_layer(p: Option<&Protocol>) -> bool {
 fn is_transport_layer(p: Option<&Protocol>) -> bool {
     use Protocol::*;
 
-    matches!(p, Some(Tcp(_)))
+    matches!(p, Some(Tcp(_)) | Some(Udp(_)))
 }
 
 fn is_session_layer(p: Option<&Protocol>, allow_empty: bool) -> bool {
@@ -282,6 +285,17 @@ impl NetworkAddress {
             if !is_transport_layer(p) {
                 return Err(ParseError::TransportLayerMissing);
             }
+
+            // UDP is only supported as the base of a QUIC transport
+            if matches!(p, Some(Udp(_))) {
+                p = iter.next();
+                if p.is_none() {
+                    return Ok(Self(protocols));
+                }
+                if !matches!(p, Some(Quic)) {
+                    return Err(ParseError::TransportLayerMissing);
+                }
+            }
         }
 
         p = iter.next();
@@ -356,6 +370,9 @@ impl NetworkAddress {
     /// `"/dns4/<domain>/tcp/<port>"` or
     /// `"/dns6/<domain>/tcp/<port>"` or
     /// `"/dns/<domain>/tcp/<port>"` or
+    /// `"/ip4/<addr>/udp/<port>/quic"` or
+    /// `"/ip6/<addr>/udp/<port>/quic"` or
+    /// `"/dns/<domain>/udp/<port>/quic"` (and `dns4`/`dns6`) or
     /// cfg!(test) `"/memory/<port>"`
     ///
     /// followed by transport upgrade handshake protocols:
@@ -388,7 +405,7 @@ impl NetworkAddress {
     /// Retrieves the port from the network address
     pub fn find_port(&self) -> Option<u16> {
         self.0.iter().find_map(|proto| match proto {
-            Protocol::Tcp(port) => Some(*port),
+            Protocol::Tcp(port) | Protocol::Udp(port) => Some(*port),
             _ => None,
         })
     }
@@ -613,6 +630,8 @@ impl fmt::Display for Protocol {
                     .expect("ValidCryptoMaterialStringExt::to_encoded_string is infallible")
             ),
             Handshake(version) => write!(f, "/handshake/{}", version),
+            Udp(port) => write!(f, "/udp/{}", port),
+            Quic => write!(f, "/quic"),
         }
     }
 }
@@ -643,6 +662,8 @@ impl Protocol {
                 args.next().ok_or(ParseError::UnexpectedEnd)?,
             )?),
             "handshake" => Protocol::Handshake(parse_one(args)?),
+            "udp" => Protocol::Udp(parse_one(args)?),
+            "quic" => Protocol::Quic,
             unknown => return Err(ParseError::UnknownProtocolType(unknown.to_string())),
         };
         Ok(protocol)
@@ -837,6 +858,42 @@ pub fn parse_tcp(protos: &[Protocol]) -> Option<((String, u16), &[Protocol])> {
     }
 }
 
+/// parse the `&[Protocol]` into the `"/ip4/<addr>/udp/<port>/quic"` or
+/// `"/ip6/<addr>/udp/<port>/quic"` prefix and unparsed `&[Protocol]` suffix.
+pub fn parse_ip_quic(protos: &[Protocol]) -> Option<((IpAddr, u16), &[Protocol])> {
+    use Protocol::*;
+
+    if protos.len() < 3 {
+        return None;
+    }
+
+    let (prefix, suffix) = protos.split_at(3);
+    match prefix {
+        [Ip4(ip), Udp(port), Quic] => Some(((IpAddr::V4(*ip), *port), suffix)),
+        [Ip6(ip), Udp(port), Quic] => Some(((IpAddr::V6(*ip), *port), suffix)),
+        _ => None,
+    }
+}
+
+/// parse the `&[Protocol]` into the `"/dns/<domain>/udp/<port>/quic"`,
+/// `"/dns4/<domain>/udp/<port>/quic"`, or `"/dns6/<domain>/udp/<port>/quic"`
+/// prefix and unparsed `&[Protocol]` suffix.
+pub fn parse_dns_quic(protos: &[Protocol]) -> Option<((IpFilter, &DnsName, u16), &[Protocol])> {
+    use Protocol::*;
+
+    if protos.len() < 3 {
+        return None;
+    }
+
+    let (prefix, suffix) = protos.split_at(3);
+    match prefix {
+        [Dns(name), Udp(port), Quic] => Some(((IpFilter::Any, name, *port), suffix)),
+        [Dns4(name), Udp(port), Quic] => Some(((IpFilter::OnlyIp4, name, *port), suffix)),
+        [Dns6(name), Udp(port), Quic] => Some(((IpFilter::OnlyIp6, name, *port), suffix)),
+        _ => None,
+    }
+}
+
 /// parse the `&[Protocol]` into the `"/noise-ik/<pubkey>"` prefix and
 /// unparsed `&[Protocol]` suffix.
 pub fn parse_noise_ik(protos: &[Protocol]) -> Option<(&x25519::PublicKey, &[Protocol])> {
@@ -863,11 +920,15 @@ fn parse_aptosnet_protos(protos: &[Protocol]) -> Option<&[Protocol]> {
     // ---
     // parse_ip_tcp
     // <or> parse_dns_tcp
+    // <or> parse_ip_quic
+    // <or> parse_dns_quic
     // <or> cfg!(test) parse_memory
 
     let transport_suffix = parse_ip_tcp(protos)
         .map(|x| x.1)
         .or_else(|| parse_dns_tcp(protos).map(|x| x.1))
+        .or_else(|| parse_ip_quic(protos).map(|x| x.1))
+        .or_else(|| parse_dns_quic(protos).map(|x| x.1))
         .or_else(|| {
             if cfg!(test) {
                 parse_memory(protos).map(|x| x.1)
@@ -965,6 +1026,24 @@ mod test {
                 NoiseIK(pubkey),
                 Handshake(5),
             ]),
+            ("/ip4/12.34.56.78/udp/6180/quic", vec![
+                Ip4(Ipv4Addr::new(12, 34, 56, 78)),
+                Udp(6180),
+                Quic,
+            ]),
+            (
+                &(format!(
+                    "/dns/example.com/udp/6180/quic/noise-ik/{}/handshake/0",
+                    pubkey_str
+                )),
+                vec![
+                    Dns(DnsName("example.com".to_owned())),
+                    Udp(6180),
+                    Quic,
+                    NoiseIK(pubkey),
+                    Handshake(0),
+                ],
+            ),
         ];
 
         for (addr_str, expected_address) in &test_cases {
@@ -992,6 +1071,11 @@ mod test {
             "/ip4/1.1.1.1.",
             "/ip4/1.1.1.1.1",
             "/ip4/1.1.1.999.1",
+            "/quic",
+            "/ip4/1.1.1.1/quic",
+            "/ip4/1.1.1.1/tcp/80/quic",
+            "/ip4/1.1.1.1/udp/80/tcp/80",
+            "/ip4/1.1.1.1/udp/80/quic/quic",
         ];
 
         for &addr_str in &test_cases {
@@ -1063,6 +1147,59 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_ip_quic() {
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/udp/123/quic").unwrap();
+        let expected_suffix: &[Protocol] = &[];
+        assert_eq!(
+            parse_ip_quic(addr.as_slice()).unwrap(),
+            ((IpAddr::from_str("1.2.3.4").unwrap(), 123), expected_suffix)
+        );
+        assert_eq!(addr.find_port(), Some(123));
+        assert!(parse_ip_tcp(addr.as_slice()).is_none());
+
+        let addr = NetworkAddress::from_str("/ip6/::1/udp/123/quic").unwrap();
+        let expected_suffix: &[Protocol] = &[];
+        assert_eq!(
+            parse_ip_quic(addr.as_slice()).unwrap(),
+            ((IpAddr::from_str("::1").unwrap(), 123), expected_suffix)
+        );
+
+        // UDP without QUIC is not a supported transport
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/udp/123").unwrap();
+        assert!(parse_ip_quic(addr.as_slice()).is_none());
+    }
+
+    #[test]
+    fn test_parse_dns_quic() {
+        let dns_name = DnsName::from_str("example.com").unwrap();
+        let addr = NetworkAddress::from_str("/dns4/example.com/udp/123/quic").unwrap();
+        let expected_suffix: &[Protocol] = &[];
+        assert_eq!(
+            parse_dns_quic(addr.as_slice()).unwrap(),
+            ((IpFilter::OnlyIp4, &dns_name, 123), expected_suffix)
+        );
+        assert!(parse_dns_tcp(addr.as_slice()).is_none());
+    }
+
+    #[test]
+    fn test_is_aptosnet_addr_quic() {
+        let pubkey_str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+        let addr = NetworkAddress::from_str(&format!(
+            "/ip4/1.2.3.4/udp/6180/quic/noise-ik/{}/handshake/0",
+            pubkey_str
+        ))
+        .unwrap();
+        assert!(addr.is_aptosnet_addr());
+        assert_eq!(
+            addr.to_string(),
+            format!(
+                "/ip4/1.2.3.4/udp/6180/quic/noise-ik/0x{}/handshake/0",
+                pubkey_str
+            )
+        );
+    }
+
     #[test]
     fn test_find_noise_proto() {
         let pubkey_str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
@@ -26,6 +26,10 @@ pub enum FeatureFlag {
     PERIODICAL_REWARD_RATE_DECREASE = 16,
     PARTIAL_GOVERNANCE_VOTING = 17,
     SIGNATURE_CHECKER_V2 = 18,
+    FEE_PAYER_ENABLED = 19,
+    SECP256K1_ECDSA_AUTHENTICATOR = 20,
+    WEBAUTHN_AUTHENTICATOR = 21,
+    BN254_STRUCTURES = 22,
 }
 
 /// Representation of features on chain as a bitset.
@@ -38,7 +42,7 @@ pub struct Features {
 impl Default for Features {
     fn default() -> Self {
         Features {
-            features: vec![0b00100000, 0b00100000, 0b00000100],
+            features: vec![0b00100000, 0b00100000, 0b01000100],
         }
     }
 }
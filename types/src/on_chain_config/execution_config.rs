@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub enum OnChainExecutionConfig {
     V1(ExecutionConfigV1),
+    V2(ExecutionConfigV2),
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -17,6 +18,15 @@ impl OnChainExecutionConfig {
     pub fn transaction_shuffler_type(&self) -> TransactionShufflerType {
         match &self {
             OnChainExecutionConfig::V1(config) => config.transaction_shuffler_type.clone(),
+            OnChainExecutionConfig::V2(config) => config.transaction_shuffler_type.clone(),
+        }
+    }
+
+    /// The gas limit of a block, the transactions after the limit is reached are retried.
+    pub fn block_gas_limit(&self) -> Option<u64> {
+        match &self {
+            OnChainExecutionConfig::V1(_config) => None,
+            OnChainExecutionConfig::V2(config) => config.block_gas_limit,
         }
     }
 }
@@ -59,11 +69,27 @@ impl Default for ExecutionConfigV1 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ExecutionConfigV2 {
+    pub transaction_shuffler_type: TransactionShufflerType,
+    pub block_gas_limit: Option<u64>,
+}
+
+impl Default for ExecutionConfigV2 {
+    fn default() -> Self {
+        Self {
+            transaction_shuffler_type: TransactionShufflerType::NoShuffling,
+            block_gas_limit: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
 pub enum TransactionShufflerType {
     NoShuffling,
     SenderAwareV1(u32),
+    ConflictAwareV1(u32),
 }
 
 #[cfg(test)]
@@ -102,6 +128,23 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_config_block_gas_limit() {
+        let config = OnChainExecutionConfig::V2(ExecutionConfigV2 {
+            transaction_shuffler_type: TransactionShufflerType::SenderAwareV1(32),
+            block_gas_limit: Some(100),
+        });
+
+        let s = bcs::to_bytes(&config).unwrap();
+        let result = bcs::from_bytes::<OnChainExecutionConfig>(&s).unwrap();
+        assert_eq!(result.block_gas_limit(), Some(100));
+        assert!(matches!(
+            result.transaction_shuffler_type(),
+            TransactionShufflerType::SenderAwareV1(32)
+        ));
+        assert_eq!(OnChainExecutionConfig::default().block_gas_limit(), None);
+    }
+
     #[test]
     fn test_config_onchain_payload() {
         let execution_config = OnChainExecutionConfig::V1(ExecutionConfigV1 {
@@ -38,7 +38,9 @@ pub use self::{
         ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerAndVoterConfig,
         ProposerElectionType,
     },
-    execution_config::{ExecutionConfigV1, OnChainExecutionConfig, TransactionShufflerType},
+    execution_config::{
+        ExecutionConfigV1, ExecutionConfigV2, OnChainExecutionConfig, TransactionShufflerType,
+    },
     gas_schedule::{GasSchedule, GasScheduleV2, StorageGasSchedule},
     timed_features::{TimedFeatureFlag, TimedFeatureOverride, TimedFeatures},
     validator_set::{ConsensusScheme, ValidatorSet},
@@ -25,7 +25,7 @@ use aptos_crypto::{
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
-use std::{any::type_name, marker::PhantomData};
+use std::{any::type_name, iter::Peekable, marker::PhantomData};
 
 /// A proof that can be used authenticate an element in an accumulator given trusted root hash. For
 /// example, both `LedgerInfoToTransactionInfoProof` and `TransactionInfoToEventProof` can be
@@ -373,6 +373,264 @@ impl SparseMerkleProof {
     }
 }
 
+/// A proof that authenticates a set of keys in a Sparse Merkle Tree at once. It carries the same
+/// information as one `SparseMerkleProof` per key, except that siblings shared by the paths of
+/// several keys, or that lie on the path of another key, are included only once or not at all.
+///
+/// The keys are always given in ascending order. Conceptually the proof describes the smallest
+/// subtree of the Sparse Merkle Tree containing the paths from the root to every key: every path
+/// ends either at a leaf or at an empty subtree, and every internal node on the paths that has
+/// only one child on the paths takes the other child from `siblings`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMerkleMultiProof {
+    /// The subtrees the paths end at, from left to right. Several keys can end up in the same
+    /// subtree.
+    subtrees: Vec<MultiProofSubtree>,
+
+    /// The siblings not on any of the paths, in the order they are visited by a depth-first,
+    /// left-to-right traversal of the paths starting from the root.
+    siblings: Vec<HashValue>,
+}
+
+/// The subtree a path in a `SparseMerkleMultiProof` ends at. It's either a single leaf or empty.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+struct MultiProofSubtree {
+    /// The depth of the root of the subtree, the root of the tree being at depth 0.
+    depth: u16,
+    leaf: Option<SparseMerkleLeafNode>,
+}
+
+impl MultiProofSubtree {
+    fn hash(&self) -> HashValue {
+        self.leaf
+            .map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash())
+    }
+
+    /// Verifies that `element_key`, which shares the path to this subtree, exists with
+    /// `element_hash` (or doesn't exist if `element_hash` is `None`).
+    fn verify_element(
+        &self,
+        element_key: HashValue,
+        element_hash: Option<HashValue>,
+    ) -> Result<()> {
+        match (element_hash, self.leaf) {
+            (Some(hash), Some(leaf)) => {
+                ensure!(
+                    element_key == leaf.key,
+                    "Keys do not match. Key in proof: {:x}. Expected key: {:x}.",
+                    leaf.key,
+                    element_key,
+                );
+                ensure!(
+                    hash == leaf.value_hash,
+                    "Value hashes do not match for key {:x}. Value hash in proof: {:x}. \
+                     Expected value hash: {:x}.",
+                    element_key,
+                    leaf.value_hash,
+                    hash,
+                );
+            },
+            (Some(hash), None) => {
+                bail!(
+                    "Expected inclusion proof for key {:x}, value hash: {:x}. Found non-inclusion \
+                     proof.",
+                    element_key,
+                    hash,
+                )
+            },
+            (None, Some(leaf)) => {
+                ensure!(
+                    element_key != leaf.key,
+                    "Expected non-inclusion proof, but key exists in proof. Key: {:x}.",
+                    element_key,
+                );
+                ensure!(
+                    element_key.common_prefix_bits_len(leaf.key) >= self.depth as usize,
+                    "Key would not have ended up in the subtree where the provided key in proof \
+                     is the only existing key, if it existed. So this is not a valid \
+                     non-inclusion proof. Key: {:x}. Key in proof: {:x}.",
+                    element_key,
+                    leaf.key,
+                );
+            },
+            (None, None) => {},
+        }
+        Ok(())
+    }
+}
+
+impl SparseMerkleMultiProof {
+    /// Combines the proofs of individual keys, all against the same root hash, into a
+    /// multi-proof. The keys must be in strictly ascending order.
+    pub fn from_proofs(proofs: &[(HashValue, SparseMerkleProof)]) -> Result<Self> {
+        ensure!(!proofs.is_empty(), "No proof to combine.");
+        ensure!(
+            proofs.windows(2).all(|w| w[0].0 < w[1].0),
+            "Keys must be in strictly ascending order."
+        );
+        for (key, proof) in proofs {
+            ensure!(
+                proof.siblings.len() <= HashValue::LENGTH_IN_BITS,
+                "Sparse Merkle Tree proof for key {:x} has more than {} ({}) siblings.",
+                key,
+                HashValue::LENGTH_IN_BITS,
+                proof.siblings.len(),
+            );
+        }
+
+        let mut multi_proof = Self {
+            subtrees: vec![],
+            siblings: vec![],
+        };
+        multi_proof.add_proofs(proofs, 0)?;
+        Ok(multi_proof)
+    }
+
+    fn add_proofs(
+        &mut self,
+        proofs: &[(HashValue, SparseMerkleProof)],
+        depth: usize,
+    ) -> Result<()> {
+        // All the keys share the same path down to `depth`, so the first proof tells whether the
+        // path ends here and what the sibling at this level is.
+        let (_, proof) = &proofs[0];
+        let proof_depth = proof.siblings.len();
+        if proof_depth == depth {
+            ensure!(
+                proofs
+                    .iter()
+                    .all(|(_, p)| p.siblings.len() == depth && p.leaf == proof.leaf),
+                "Proofs disagree on the subtree at depth {}.",
+                depth,
+            );
+            self.subtrees.push(MultiProofSubtree {
+                depth: depth as u16,
+                leaf: proof.leaf,
+            });
+            return Ok(());
+        }
+        ensure!(
+            depth < proof_depth,
+            "Proofs disagree on the subtree at depth {}.",
+            proof_depth,
+        );
+
+        let (left, right) = proofs.split_at(proofs.partition_point(|(key, _)| !key.bit(depth)));
+        if left.is_empty() || right.is_empty() {
+            self.siblings.push(proof.siblings[proof_depth - 1 - depth]);
+        }
+        if !left.is_empty() {
+            self.add_proofs(left, depth + 1)?;
+        }
+        if !right.is_empty() {
+            self.add_proofs(right, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    pub fn verify<V: CryptoHash>(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<&V>)],
+    ) -> Result<()> {
+        self.verify_by_hash(
+            expected_root_hash,
+            &elements
+                .iter()
+                .map(|(key, value)| (*key, value.map(|v| v.hash())))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Verifies, for each `(element_key, element_hash)` in `elements`, that the element exists
+    /// in the Sparse Merkle Tree if `element_hash` is present, or that `element_key` doesn't
+    /// exist in the tree otherwise. The keys must be in strictly ascending order.
+    pub fn verify_by_hash(
+        &self,
+        expected_root_hash: HashValue,
+        elements: &[(HashValue, Option<HashValue>)],
+    ) -> Result<()> {
+        ensure!(!elements.is_empty(), "No element to verify.");
+        ensure!(
+            elements.windows(2).all(|w| w[0].0 < w[1].0),
+            "Keys must be in strictly ascending order."
+        );
+
+        let mut subtrees = self.subtrees.iter().peekable();
+        let mut siblings = self.siblings.iter();
+        let actual_root_hash = Self::compute_root_hash(elements, 0, &mut subtrees, &mut siblings)?;
+        ensure!(
+            subtrees.next().is_none() && siblings.next().is_none(),
+            "Sparse Merkle multi-proof has unused subtrees or siblings.",
+        );
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "{}: Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            type_name::<Self>(),
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+
+    fn compute_root_hash<'a>(
+        elements: &[(HashValue, Option<HashValue>)],
+        depth: usize,
+        subtrees: &mut Peekable<impl Iterator<Item = &'a MultiProofSubtree>>,
+        siblings: &mut impl Iterator<Item = &'a HashValue>,
+    ) -> Result<HashValue> {
+        let subtree = **subtrees
+            .peek()
+            .ok_or_else(|| format_err!("Sparse Merkle multi-proof has too few subtrees."))?;
+        ensure!(
+            subtree.depth as usize >= depth,
+            "Sparse Merkle multi-proof has a subtree at unexpected depth {}.",
+            subtree.depth,
+        );
+        if subtree.depth as usize == depth {
+            subtrees.next();
+            for (element_key, element_hash) in elements {
+                subtree.verify_element(*element_key, *element_hash)?;
+            }
+            return Ok(subtree.hash());
+        }
+        ensure!(
+            depth < HashValue::LENGTH_IN_BITS,
+            "Sparse Merkle multi-proof has a subtree deeper than {}.",
+            HashValue::LENGTH_IN_BITS,
+        );
+
+        let (left, right) = elements.split_at(elements.partition_point(|(key, _)| !key.bit(depth)));
+        let (left_hash, right_hash) = if left.is_empty() {
+            let left_hash = Self::next_sibling(siblings)?;
+            (
+                left_hash,
+                Self::compute_root_hash(right, depth + 1, subtrees, siblings)?,
+            )
+        } else if right.is_empty() {
+            let right_hash = Self::next_sibling(siblings)?;
+            (
+                Self::compute_root_hash(left, depth + 1, subtrees, siblings)?,
+                right_hash,
+            )
+        } else {
+            (
+                Self::compute_root_hash(left, depth + 1, subtrees, siblings)?,
+                Self::compute_root_hash(right, depth + 1, subtrees, siblings)?,
+            )
+        };
+        Ok(SparseMerkleInternalNode::new(left_hash, right_hash).hash())
+    }
+
+    fn next_sibling<'a>(siblings: &mut impl Iterator<Item = &'a HashValue>) -> Result<HashValue> {
+        siblings
+            .next()
+            .copied()
+            .ok_or_else(|| format_err!("Sparse Merkle multi-proof has too few siblings."))
+    }
+}
+
 /// An in-memory accumulator for storing a summary of the core transaction info
 /// accumulator. It is a summary in the sense that it only stores maximally
 /// frozen subtree nodes rather than storing all leaves and internal nodes.
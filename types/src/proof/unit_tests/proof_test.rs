@@ -8,18 +8,23 @@ use crate::{
     block_metadata::BlockMetadata,
     contract_event::ContractEvent,
     event::EventKey,
-    ledger_info::LedgerInfo,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo},
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorExtensionProof, AccumulatorRangeProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, TestAccumulatorInternalNode,
-        TestAccumulatorProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleMultiProof,
+        TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
+        TransactionAccumulatorProof, TransactionInfoListWithProof, TransactionInfoWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueListWithProof},
     },
-    state_store::state_value::StateValue,
     transaction::{
         ExecutionStatus, Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
         TransactionOutputListWithProof, TransactionStatus,
     },
+    validator_signer::ValidatorSigner,
+    validator_verifier::generate_validator_verifier,
     write_set::WriteSet,
 };
 use aptos_crypto::{
@@ -266,6 +271,241 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_three_element_sparse_merkle_multi_proof() {
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+    assert_eq!(key1[0], 0b0011_0011);
+    assert_eq!(non_existing_key1[0], 0b0011_1010);
+    assert_eq!(key2[0], 0b0100_0010);
+    assert_eq!(key3[0], 0b0110_1001);
+    assert_eq!(non_existing_key2[0], 0b1000_1110);
+
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let blob3 = StateValue::from(b"3".to_vec());
+
+    let leaf1 = SparseMerkleLeafNode::new(key1, blob1.hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, blob2.hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, blob3.hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let proof1 = SparseMerkleProof::new(Some(leaf1), vec![
+        internal_b_hash,
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let proof2 = SparseMerkleProof::new(Some(leaf2), vec![
+        leaf3.hash(),
+        leaf1.hash(),
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let proof3 = SparseMerkleProof::new(Some(leaf3), vec![
+        leaf2.hash(),
+        leaf1.hash(),
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let default_proof = SparseMerkleProof::new(None, vec![internal_a_hash]);
+
+    {
+        // Construct a proof of all the keys, existing or not.
+        let proof = SparseMerkleMultiProof::from_proofs(&[
+            (key1, proof1.clone()),
+            (non_existing_key1, proof1.clone()),
+            (key2, proof2.clone()),
+            (key3, proof3.clone()),
+            (non_existing_key2, default_proof.clone()),
+        ])
+        .unwrap();
+        let elements = [
+            (key1, Some(&blob1)),
+            (non_existing_key1, None),
+            (key2, Some(&blob2)),
+            (key3, Some(&blob3)),
+            (non_existing_key2, None),
+        ];
+        assert!(proof.verify(root_hash, &elements).is_ok());
+
+        // Trying to show that a key has another value.
+        let mut tampered = elements;
+        tampered[2].1 = Some(&blob3);
+        assert!(proof.verify(root_hash, &tampered).is_err());
+        // Trying to show that an existing key doesn't exist.
+        let mut tampered = elements;
+        tampered[3].1 = None;
+        assert!(proof.verify(root_hash, &tampered).is_err());
+        // Trying to show that a non-existing key exists.
+        let mut tampered = elements;
+        tampered[1].1 = Some(&blob1);
+        assert!(proof.verify(root_hash, &tampered).is_err());
+        // Keys out of order.
+        let mut tampered = elements;
+        tampered.swap(0, 1);
+        assert!(proof.verify(root_hash, &tampered).is_err());
+        // Missing keys leave parts of the proof unused.
+        assert!(proof.verify(root_hash, &elements[..4]).is_err());
+        // Another root hash.
+        assert!(proof.verify(internal_a_hash, &elements).is_err());
+    }
+
+    {
+        // A proof of a single key needs the same siblings as the `SparseMerkleProof`.
+        let proof = SparseMerkleMultiProof::from_proofs(&[(key1, proof1.clone())]).unwrap();
+        assert!(proof.verify(root_hash, &[(key1, Some(&blob1))]).is_ok());
+        assert!(proof
+            .verify::<StateValue>(root_hash, &[(non_existing_key1, None)])
+            .is_ok());
+        assert!(proof.verify(root_hash, &[(key1, Some(&blob2))]).is_err());
+        // This proof can't be used to show anything about key2 or non_existing_key2.
+        assert!(proof
+            .verify(root_hash, &[(key1, Some(&blob1)), (key2, Some(&blob2))])
+            .is_err());
+        assert!(proof
+            .verify::<StateValue>(root_hash, &[(non_existing_key2, None)])
+            .is_err());
+    }
+
+    {
+        // A proof with a tampered sibling.
+        let tampered_proof1 = SparseMerkleProof::new(Some(leaf1), vec![
+            leaf2.hash(),
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        ]);
+        let proof = SparseMerkleMultiProof::from_proofs(&[
+            (key1, tampered_proof1),
+            (non_existing_key2, default_proof.clone()),
+        ])
+        .unwrap();
+        assert!(proof
+            .verify(root_hash, &[
+                (key1, Some(&blob1)),
+                (non_existing_key2, None)
+            ])
+            .is_err());
+    }
+
+    // Proofs of keys in the same subtree must agree on it.
+    assert!(SparseMerkleMultiProof::from_proofs(&[
+        (key1, proof1.clone()),
+        (
+            non_existing_key1,
+            SparseMerkleProof::new(
+                None,
+                vec![internal_b_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH,]
+            )
+        ),
+    ])
+    .is_err());
+    // Keys must be in ascending order.
+    assert!(SparseMerkleMultiProof::from_proofs(&[(key2, proof2), (key1, proof1)]).is_err());
+    assert!(SparseMerkleMultiProof::from_proofs(&[]).is_err());
+}
+
+#[test]
+fn test_state_value_list_with_proof() {
+    // A state with a single key, so the proof of every key is the leaf itself.
+    let key1 = StateKey::raw(b"hello".to_vec());
+    let key2 = StateKey::raw(b"world".to_vec());
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let leaf1 = SparseMerkleLeafNode::new(key1.hash(), blob1.hash());
+    let state_root_hash = leaf1.hash();
+    let leaf_proof = SparseMerkleProof::new(Some(leaf1), vec![]);
+
+    let mut state_values = vec![(key1, Some(blob1)), (key2, None)];
+    state_values.sort_by_key(|(key, _)| key.hash());
+    let proof = SparseMerkleMultiProof::from_proofs(
+        &state_values
+            .iter()
+            .map(|(key, _)| (key.hash(), leaf_proof.clone()))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(state_root_hash),
+        0,
+        ExecutionStatus::Success,
+    );
+    let signers = vec![ValidatorSigner::random([0; 32])];
+    let validator_verifier = generate_validator_verifier(&signers);
+    let list_with_proof = StateValueListWithProof {
+        ledger_info_with_signatures: generate_ledger_info_with_sig(
+            &signers,
+            create_ledger_info_at_version0(transaction_info.hash()),
+        ),
+        version: 0,
+        transaction_info_with_proof: TransactionInfoWithProof::new(
+            TransactionAccumulatorProof::new(vec![]),
+            transaction_info.clone(),
+        ),
+        state_values,
+        proof,
+    };
+    assert!(list_with_proof.verify(&validator_verifier).is_ok());
+
+    // Signed by someone else.
+    let other_verifier = generate_validator_verifier(&[ValidatorSigner::random([1; 32])]);
+    assert!(list_with_proof.verify(&other_verifier).is_err());
+
+    // A tampered value.
+    let mut tampered = list_with_proof.clone();
+    for (_, value) in tampered.state_values.iter_mut() {
+        *value = value.is_none().then(|| blob2.clone());
+    }
+    assert!(tampered.verify(&validator_verifier).is_err());
+
+    // A transaction info that is not in the ledger.
+    let mut tampered = list_with_proof.clone();
+    tampered.transaction_info_with_proof = TransactionInfoWithProof::new(
+        TransactionAccumulatorProof::new(vec![]),
+        create_transaction_info(None, None, None),
+    );
+    assert!(tampered.verify(&validator_verifier).is_err());
+
+    // A version the transaction info is not at.
+    let mut tampered = list_with_proof.clone();
+    tampered.version = 1;
+    assert!(tampered.verify(&validator_verifier).is_err());
+
+    // No state checkpoint at the version.
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        None,
+        0,
+        ExecutionStatus::Success,
+    );
+    let tampered = StateValueListWithProof {
+        ledger_info_with_signatures: generate_ledger_info_with_sig(
+            &signers,
+            create_ledger_info_at_version0(transaction_info.hash()),
+        ),
+        transaction_info_with_proof: TransactionInfoWithProof::new(
+            TransactionAccumulatorProof::new(vec![]),
+            transaction_info,
+        ),
+        ..list_with_proof
+    };
+    assert!(tampered.verify(&validator_verifier).is_err());
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    proof::SparseMerkleRangeProof, state_store::state_key::StateKey, transaction::Version,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleMultiProof, SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::state_key::StateKey,
+    transaction::Version,
+    validator_verifier::ValidatorVerifier,
 };
+use anyhow::{format_err, Result};
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -178,6 +183,43 @@ impl StateValueChunkWithProof {
     }
 }
 
+/// A set of state values at a state checkpoint, with everything needed to authenticate them
+/// against a signed ledger info.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateValueListWithProof {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    /// The version of the state checkpoint, not newer than the ledger info.
+    pub version: Version,
+    /// The transaction info at `version`, which carries the state root hash.
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+    /// The state keys in ascending order of their hashes, and their values if they exist.
+    pub state_values: Vec<(StateKey, Option<StateValue>)>,
+    pub proof: SparseMerkleMultiProof,
+}
+
+impl StateValueListWithProof {
+    /// Verifies the state values, trusting `validator_verifier` for the epoch of the ledger info.
+    pub fn verify(&self, validator_verifier: &ValidatorVerifier) -> Result<()> {
+        self.ledger_info_with_signatures
+            .verify_signatures(validator_verifier)?;
+        let ledger_info = self.ledger_info_with_signatures.ledger_info();
+        self.transaction_info_with_proof
+            .verify(ledger_info, self.version)?;
+        let state_root_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .state_checkpoint_hash()
+            .ok_or_else(|| format_err!("No state checkpoint at version {}.", self.version))?;
+
+        let elements: Vec<_> = self
+            .state_values
+            .iter()
+            .map(|(key, value)| (key.hash(), value.as_ref().map(|v| v.hash())))
+            .collect();
+        self.proof.verify_by_hash(state_root_hash, &elements)
+    }
+}
+
 /// Indicates a state value becomes stale since `stale_since_version`.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
@@ -4,13 +4,17 @@
 
 use crate::{
     account_address::AccountAddress,
-    transaction::{RawTransaction, RawTransactionWithData},
+    transaction::{
+        webauthn::PartialAuthenticatorAssertionResponse, RawTransaction, RawTransactionWithData,
+    },
 };
 use anyhow::{ensure, Error, Result};
 use aptos_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    secp256k1_ecdsa::{Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature},
+    secp256r1_ecdsa::Secp256r1EcdsaPublicKey,
     traits::Signature,
     CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
@@ -57,6 +61,24 @@ pub enum TransactionAuthenticator {
         secondary_signer_addresses: Vec<AccountAddress>,
         secondary_signers: Vec<AccountAuthenticator>,
     },
+    /// Multi-agent transaction whose gas is paid by a separate fee payer account.
+    FeePayer {
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+        fee_payer_address: AccountAddress,
+        fee_payer_signer: AccountAuthenticator,
+    },
+    /// Single secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn assertion, signed by a secp256r1 ECDSA passkey
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
 }
 
 impl TransactionAuthenticator {
@@ -92,6 +114,45 @@ impl TransactionAuthenticator {
         }
     }
 
+    /// Create a fee payer authenticator
+    pub fn fee_payer(
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+        fee_payer_address: AccountAddress,
+        fee_payer_signer: AccountAuthenticator,
+    ) -> Self {
+        Self::FeePayer {
+            sender,
+            secondary_signer_addresses,
+            secondary_signers,
+            fee_payer_address,
+            fee_payer_signer,
+        }
+    }
+
+    /// Create a single-signature secp256k1 ECDSA authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single-signature WebAuthn authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
     /// Return Ok if all AccountAuthenticator's public keys match their signatures, Err otherwise
     pub fn verify(&self, raw_txn: &RawTransaction) -> Result<()> {
         let num_sigs: usize = self.sender().number_of_signatures()
@@ -99,7 +160,10 @@ impl TransactionAuthenticator {
                 .secondary_signers()
                 .iter()
                 .map(|auth| auth.number_of_signatures())
-                .sum::<usize>();
+                .sum::<usize>()
+            + self
+                .fee_payer_signer()
+                .map_or(0, |auth| auth.number_of_signatures());
         if num_sigs > MAX_NUM_OF_SIGS {
             return Err(Error::new(AuthenticationError::MaxSignaturesExceeded));
         }
@@ -127,6 +191,32 @@ impl TransactionAuthenticator {
                 }
                 Ok(())
             },
+            Self::FeePayer {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                fee_payer_signer,
+            } => {
+                let message = RawTransactionWithData::new_multi_agent_with_fee_payer(
+                    raw_txn.clone(),
+                    secondary_signer_addresses.clone(),
+                    *fee_payer_address,
+                );
+                sender.verify(&message)?;
+                for signer in secondary_signers {
+                    signer.verify(&message)?;
+                }
+                fee_payer_signer.verify(&message)
+            },
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
         }
     }
 
@@ -143,7 +233,15 @@ impl TransactionAuthenticator {
                 public_key,
                 signature,
             } => AccountAuthenticator::multi_ed25519(public_key.clone(), signature.clone()),
-            Self::MultiAgent { sender, .. } => sender.clone(),
+            Self::MultiAgent { sender, .. } | Self::FeePayer { sender, .. } => sender.clone(),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => AccountAuthenticator::secp256k1_ecdsa(public_key.clone(), signature.clone()),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => AccountAuthenticator::webauthn(public_key.clone(), signature.clone()),
         }
     }
 
@@ -153,11 +251,17 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. } => vec![],
             Self::MultiAgent {
                 sender: _,
                 secondary_signer_addresses,
                 ..
+            }
+            | Self::FeePayer {
+                secondary_signer_addresses,
+                ..
             } => secondary_signer_addresses.to_vec(),
         }
     }
@@ -168,14 +272,47 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. } => vec![],
             Self::MultiAgent {
                 sender: _,
                 secondary_signer_addresses: _,
                 secondary_signers,
+            }
+            | Self::FeePayer {
+                secondary_signers, ..
             } => secondary_signers.to_vec(),
         }
     }
+
+    /// The address of the account paying for the gas, if it's not the sender.
+    pub fn fee_payer_address(&self) -> Option<AccountAddress> {
+        match self {
+            Self::FeePayer {
+                fee_payer_address, ..
+            } => Some(*fee_payer_address),
+            _ => None,
+        }
+    }
+
+    pub fn fee_payer_signer(&self) -> Option<AccountAuthenticator> {
+        match self {
+            Self::FeePayer {
+                fee_payer_signer, ..
+            } => Some(fee_payer_signer.clone()),
+            _ => None,
+        }
+    }
+
+    /// All the account authenticators of the transaction: the sender's, the secondary signers'
+    /// and the fee payer's, if any.
+    pub fn all_signers(&self) -> Vec<AccountAuthenticator> {
+        let mut signers = vec![self.sender()];
+        signers.extend(self.secondary_signers());
+        signers.extend(self.fee_payer_signer());
+        signers
+    }
 }
 
 impl fmt::Display for TransactionAuthenticator {
@@ -224,6 +361,53 @@ impl fmt::Display for TransactionAuthenticator {
                     sender, sec_addrs, sec_signers,
                 )
             },
+            Self::FeePayer {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                fee_payer_signer,
+            } => {
+                let mut sec_addrs: String = "".to_string();
+                for sec_addr in secondary_signer_addresses {
+                    sec_addrs = format!("{}\n\t\t\t{:#?},", sec_addrs, sec_addr);
+                }
+                let mut sec_signers: String = "".to_string();
+                for sec_signer in secondary_signers {
+                    sec_signers = format!("{}\n\t\t\t{:#?},", sec_signers, sec_signer);
+                }
+                write!(
+                    f,
+                    "TransactionAuthenticator[\n\
+                        \tscheme: FeePayer, \n\
+                        \tsender: {}\n\
+                        \tsecondary signer addresses: {}\n\
+                        \tsecondary signers: {}\n\
+                        \tfee payer address: {}\n\
+                        \tfee payer signer: {}]",
+                    sender, sec_addrs, sec_signers, fee_payer_address, fee_payer_signer,
+                )
+            },
+            Self::Secp256k1Ecdsa {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: Secp256k1Ecdsa, sender: {}]",
+                    self.sender()
+                )
+            },
+            Self::WebAuthn {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: WebAuthn, sender: {}]",
+                    self.sender()
+                )
+            },
         }
     }
 }
@@ -241,6 +425,8 @@ impl fmt::Display for TransactionAuthenticator {
 pub enum Scheme {
     Ed25519 = 0,
     MultiEd25519 = 1,
+    Secp256k1Ecdsa = 2,
+    WebAuthn = 3,
     // ... add more schemes here
     /// Scheme identifier used to derive addresses (not the authentication key) of objects and
     /// resources accounts. This application serves to domain separate hashes. Without such
@@ -257,6 +443,8 @@ impl fmt::Display for Scheme {
         let display = match self {
             Scheme::Ed25519 => "Ed25519",
             Scheme::MultiEd25519 => "MultiEd25519",
+            Scheme::Secp256k1Ecdsa => "Secp256k1Ecdsa",
+            Scheme::WebAuthn => "WebAuthn",
             Scheme::DeriveObjectAddressFromObject => "DeriveObjectAddressFromObject",
             Scheme::DeriveObjectAddressFromGuid => "DeriveObjectAddressFromGuid",
             Scheme::DeriveObjectAddressFromSeed => "DeriveObjectAddressFromSeed",
@@ -278,6 +466,16 @@ pub enum AccountAuthenticator {
         public_key: MultiEd25519PublicKey,
         signature: MultiEd25519Signature,
     },
+    /// Single secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn assertion, signed by a secp256r1 ECDSA passkey
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
     // ... add more schemes here
 }
 
@@ -287,6 +485,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => Scheme::Ed25519,
             Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::Secp256k1Ecdsa { .. } => Scheme::Secp256k1Ecdsa,
+            Self::WebAuthn { .. } => Scheme::WebAuthn,
         }
     }
 
@@ -309,6 +509,28 @@ impl AccountAuthenticator {
         }
     }
 
+    /// Create a single-signature secp256k1 ECDSA authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single-signature WebAuthn authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
     /// Return Ok if the authenticator's public key matches its signature, Err otherwise
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
@@ -320,6 +542,14 @@ impl AccountAuthenticator {
                 public_key,
                 signature,
             } => signature.verify(message, public_key),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
         }
     }
 
@@ -328,6 +558,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
             Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::Secp256k1Ecdsa { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::WebAuthn { public_key, .. } => public_key.to_bytes().to_vec(),
         }
     }
 
@@ -336,6 +568,9 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
             Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::Secp256k1Ecdsa { signature, .. } => signature.to_bytes().to_vec(),
+            Self::WebAuthn { signature, .. } => bcs::to_bytes(signature)
+                .expect("Serialization of a WebAuthn assertion should never fail"),
         }
     }
 
@@ -354,6 +589,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => 1,
             Self::MultiEd25519 { signature, .. } => signature.signatures().len(),
+            Self::Secp256k1Ecdsa { .. } => 1,
+            Self::WebAuthn { .. } => 1,
         }
     }
 }
@@ -406,6 +643,16 @@ impl AuthenticationKey {
         Self::from_preimage(&AuthenticationKeyPreimage::multi_ed25519(public_key))
     }
 
+    /// Create an authentication key from a secp256k1 ECDSA public key
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::secp256k1_ecdsa(public_key))
+    }
+
+    /// Create an authentication key from the secp256r1 ECDSA public key of a WebAuthn passkey
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::webauthn(public_key))
+    }
+
     /// Return an address derived from the last `AccountAddress::LENGTH` bytes of this
     /// authentication key.
     pub fn derived_address(&self) -> AccountAddress {
@@ -461,6 +708,16 @@ impl AuthenticationKeyPreimage {
         Self::new(public_key.to_bytes(), Scheme::MultiEd25519)
     }
 
+    /// Construct a preimage from a secp256k1 ECDSA public key
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::Secp256k1Ecdsa)
+    }
+
+    /// Construct a preimage from the secp256r1 ECDSA public key of a WebAuthn passkey
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::WebAuthn)
+    }
+
     /// Construct a vector from this authentication key
     pub fn into_vec(self) -> Vec<u8> {
         self.0
@@ -535,11 +792,163 @@ impl fmt::Display for AuthenticationKey {
 
 #[cfg(test)]
 mod tests {
-    use crate::transaction::authenticator::AuthenticationKey;
+    use crate::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{
+            authenticator::{AuthenticationKey, Scheme, TransactionAuthenticator},
+            webauthn::PartialAuthenticatorAssertionResponse,
+            RawTransaction, Script, SignedTransaction,
+        },
+    };
+    use aptos_crypto::{
+        ed25519::Ed25519PrivateKey,
+        secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+        secp256r1_ecdsa::{Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey},
+        PrivateKey, Uniform,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
     use std::str::FromStr;
 
     #[test]
     fn test_from_str_should_not_panic_by_given_empty_string() {
         assert!(AuthenticationKey::from_str("").is_err());
     }
+
+    #[test]
+    fn test_fee_payer_signatures_cover_fee_payer_address() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let sender_key = Ed25519PrivateKey::generate(&mut rng);
+        let fee_payer_key = Ed25519PrivateKey::generate(&mut rng);
+        let raw_txn = RawTransaction::new_script(
+            AccountAddress::random(),
+            0,
+            Script::new(vec![], vec![], vec![]),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        let fee_payer_address = AccountAddress::random();
+        let txn = raw_txn
+            .clone()
+            .sign_fee_payer(
+                &sender_key,
+                vec![],
+                vec![],
+                fee_payer_address,
+                &fee_payer_key,
+            )
+            .unwrap()
+            .into_inner();
+        assert!(txn.signature_is_valid());
+        assert_eq!(txn.fee_payer(), fee_payer_address);
+
+        // The same signatures don't authorize another fee payer.
+        let (sender, fee_payer_signer) = match txn.authenticator() {
+            TransactionAuthenticator::FeePayer {
+                sender,
+                fee_payer_signer,
+                ..
+            } => (sender, fee_payer_signer),
+            authenticator => panic!("Unexpected authenticator {:?}", authenticator),
+        };
+        let txn = SignedTransaction::new_fee_payer(
+            raw_txn,
+            sender,
+            vec![],
+            vec![],
+            AccountAddress::random(),
+            fee_payer_signer,
+        );
+        assert!(!txn.signature_is_valid());
+    }
+
+    #[test]
+    fn test_secp256k1_ecdsa_authenticator() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let private_key = Secp256k1EcdsaPrivateKey::generate(&mut rng);
+        let public_key: Secp256k1EcdsaPublicKey = private_key.public_key();
+        let raw_txn = RawTransaction::new_script(
+            AuthenticationKey::secp256k1_ecdsa(&public_key).derived_address(),
+            0,
+            Script::new(vec![], vec![], vec![]),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        let txn = raw_txn
+            .clone()
+            .sign_secp256k1_ecdsa(&private_key, public_key.clone())
+            .unwrap()
+            .into_inner();
+        assert!(txn.signature_is_valid());
+
+        let sender = txn.authenticator().sender();
+        assert!(matches!(sender.scheme(), Scheme::Secp256k1Ecdsa));
+        assert_eq!(
+            sender.authentication_key(),
+            AuthenticationKey::secp256k1_ecdsa(&public_key)
+        );
+        let preimage = sender.authentication_key_preimage().into_vec();
+        assert_eq!(preimage.last(), Some(&(Scheme::Secp256k1Ecdsa as u8)));
+
+        // The signature doesn't authorize another transaction.
+        let other_txn = SignedTransaction::new_with_authenticator(
+            RawTransaction::new_script(
+                raw_txn.sender(),
+                1,
+                Script::new(vec![], vec![], vec![]),
+                0,
+                0,
+                0,
+                ChainId::test(),
+            ),
+            txn.authenticator(),
+        );
+        assert!(!other_txn.signature_is_valid());
+    }
+
+    #[test]
+    fn test_webauthn_authenticator() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let private_key = Secp256r1EcdsaPrivateKey::generate(&mut rng);
+        let public_key: Secp256r1EcdsaPublicKey = private_key.public_key();
+        let raw_txn = RawTransaction::new_script(
+            AuthenticationKey::webauthn(&public_key).derived_address(),
+            0,
+            Script::new(vec![], vec![], vec![]),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        let assertion =
+            PartialAuthenticatorAssertionResponse::sign_for_testing(&private_key, &raw_txn);
+        let txn = SignedTransaction::new_webauthn(raw_txn.clone(), public_key.clone(), assertion);
+        assert!(txn.signature_is_valid());
+
+        let sender = txn.authenticator().sender();
+        assert!(matches!(sender.scheme(), Scheme::WebAuthn));
+        assert_eq!(
+            sender.authentication_key(),
+            AuthenticationKey::webauthn(&public_key)
+        );
+
+        // The assertion doesn't authorize another transaction.
+        let other_txn = SignedTransaction::new_with_authenticator(
+            RawTransaction::new_script(
+                raw_txn.sender(),
+                1,
+                Script::new(vec![], vec![], vec![]),
+                0,
+                0,
+                0,
+                ChainId::test(),
+            ),
+            txn.authenticator(),
+        );
+        assert!(!other_txn.signature_is_valid());
+    }
 }
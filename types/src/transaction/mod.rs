@@ -11,7 +11,10 @@ use crate::{
     proof::{
         accumulator::InMemoryAccumulator, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
-    transaction::authenticator::{AccountAuthenticator, TransactionAuthenticator},
+    transaction::{
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        webauthn::PartialAuthenticatorAssertionResponse,
+    },
     vm_status::{DiscardedVMStatus, KeptVMStatus, StatusCode, StatusType, VMStatus},
     write_set::WriteSet,
 };
@@ -20,6 +23,8 @@ use aptos_crypto::{
     ed25519::*,
     hash::{CryptoHash, EventAccumulatorHasher},
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature},
+    secp256r1_ecdsa::Secp256r1EcdsaPublicKey,
     traits::{signing_message, SigningKey},
     CryptoMaterialError, HashValue,
 };
@@ -41,6 +46,7 @@ mod module;
 mod multisig;
 mod script;
 mod transaction_argument;
+pub mod webauthn;
 
 use crate::state_store::{state_key::StateKey, state_value::StateValue};
 #[cfg(any(test, feature = "fuzzing"))]
@@ -241,6 +247,19 @@ impl RawTransaction {
         )))
     }
 
+    /// Signs the given `RawTransaction` with a secp256k1 ECDSA key. Note that this consumes the
+    /// `RawTransaction` and turns it into a `SignatureCheckedTransaction`.
+    pub fn sign_secp256k1_ecdsa(
+        self,
+        private_key: &Secp256k1EcdsaPrivateKey,
+        public_key: Secp256k1EcdsaPublicKey,
+    ) -> Result<SignatureCheckedTransaction> {
+        let signature = private_key.sign(&self)?;
+        Ok(SignatureCheckedTransaction(
+            SignedTransaction::new_secp256k1_ecdsa(self, public_key, signature),
+        ))
+    }
+
     /// Signs the given multi-agent `RawTransaction`, which is a transaction with secondary
     /// signers in addition to a sender. The private keys of the sender and the
     /// secondary signers are used to sign the transaction.
@@ -285,6 +304,63 @@ impl RawTransaction {
         ))
     }
 
+    /// Signs the given `RawTransaction` whose gas is paid by `fee_payer_address` rather than the
+    /// sender. The sender, the secondary signers and the fee payer all sign the transaction along
+    /// with the addresses of the secondary signers and the fee payer.
+    ///
+    /// The order and length of the secondary keys provided here have to match the order and
+    /// length of the `secondary_signers`.
+    pub fn sign_fee_payer(
+        self,
+        sender_private_key: &Ed25519PrivateKey,
+        secondary_signers: Vec<AccountAddress>,
+        secondary_private_keys: Vec<&Ed25519PrivateKey>,
+        fee_payer_address: AccountAddress,
+        fee_payer_private_key: &Ed25519PrivateKey,
+    ) -> Result<SignatureCheckedTransaction> {
+        let message = RawTransactionWithData::new_multi_agent_with_fee_payer(
+            self.clone(),
+            secondary_signers.clone(),
+            fee_payer_address,
+        );
+        let sender_signature = sender_private_key.sign(&message)?;
+        let sender_authenticator = AccountAuthenticator::ed25519(
+            Ed25519PublicKey::from(sender_private_key),
+            sender_signature,
+        );
+
+        if secondary_private_keys.len() != secondary_signers.len() {
+            return Err(format_err!(
+                "number of secondary private keys and number of secondary signers don't match"
+            ));
+        }
+        let mut secondary_authenticators = vec![];
+        for priv_key in secondary_private_keys {
+            let signature = priv_key.sign(&message)?;
+            secondary_authenticators.push(AccountAuthenticator::ed25519(
+                Ed25519PublicKey::from(priv_key),
+                signature,
+            ));
+        }
+
+        let fee_payer_signature = fee_payer_private_key.sign(&message)?;
+        let fee_payer_authenticator = AccountAuthenticator::ed25519(
+            Ed25519PublicKey::from(fee_payer_private_key),
+            fee_payer_signature,
+        );
+
+        Ok(SignatureCheckedTransaction(
+            SignedTransaction::new_fee_payer(
+                self,
+                sender_authenticator,
+                secondary_signers,
+                secondary_authenticators,
+                fee_payer_address,
+                fee_payer_authenticator,
+            ),
+        ))
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn multi_sign_for_testing(
         self,
@@ -368,6 +444,11 @@ pub enum RawTransactionWithData {
         raw_txn: RawTransaction,
         secondary_signer_addresses: Vec<AccountAddress>,
     },
+    MultiAgentWithFeePayer {
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: AccountAddress,
+    },
 }
 
 impl RawTransactionWithData {
@@ -380,6 +461,18 @@ impl RawTransactionWithData {
             secondary_signer_addresses,
         }
     }
+
+    pub fn new_multi_agent_with_fee_payer(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: AccountAddress,
+    ) -> Self {
+        Self::MultiAgentWithFeePayer {
+            raw_txn,
+            secondary_signer_addresses,
+            fee_payer_address,
+        }
+    }
 }
 
 /// Different kinds of transactions.
@@ -524,6 +617,32 @@ impl SignedTransaction {
         }
     }
 
+    pub fn new_secp256k1_ecdsa(
+        raw_txn: RawTransaction,
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> SignedTransaction {
+        let authenticator = TransactionAuthenticator::secp256k1_ecdsa(public_key, signature);
+        SignedTransaction {
+            raw_txn,
+            authenticator,
+            size: OnceCell::new(),
+        }
+    }
+
+    pub fn new_webauthn(
+        raw_txn: RawTransaction,
+        public_key: Secp256r1EcdsaPublicKey,
+        assertion: PartialAuthenticatorAssertionResponse,
+    ) -> SignedTransaction {
+        let authenticator = TransactionAuthenticator::webauthn(public_key, assertion);
+        SignedTransaction {
+            raw_txn,
+            authenticator,
+            size: OnceCell::new(),
+        }
+    }
+
     pub fn new_multi_agent(
         raw_txn: RawTransaction,
         sender: AccountAuthenticator,
@@ -541,6 +660,27 @@ impl SignedTransaction {
         }
     }
 
+    pub fn new_fee_payer(
+        raw_txn: RawTransaction,
+        sender: AccountAuthenticator,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        secondary_signers: Vec<AccountAuthenticator>,
+        fee_payer_address: AccountAddress,
+        fee_payer_signer: AccountAuthenticator,
+    ) -> Self {
+        SignedTransaction {
+            raw_txn,
+            authenticator: TransactionAuthenticator::fee_payer(
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                fee_payer_signer,
+            ),
+            size: OnceCell::new(),
+        }
+    }
+
     pub fn new_with_authenticator(
         raw_txn: RawTransaction,
         authenticator: TransactionAuthenticator,
@@ -556,6 +696,10 @@ impl SignedTransaction {
         self.authenticator.clone()
     }
 
+    pub fn authenticator_ref(&self) -> &TransactionAuthenticator {
+        &self.authenticator
+    }
+
     pub fn sender(&self) -> AccountAddress {
         self.raw_txn.sender
     }
@@ -630,10 +774,17 @@ impl SignedTransaction {
     pub fn is_multi_agent(&self) -> bool {
         matches!(
             self.authenticator,
-            TransactionAuthenticator::MultiAgent { .. }
+            TransactionAuthenticator::MultiAgent { .. } | TransactionAuthenticator::FeePayer { .. }
         )
     }
 
+    /// The address of the account paying for the gas, the sender unless a fee payer is set.
+    pub fn fee_payer(&self) -> AccountAddress {
+        self.authenticator
+            .fee_payer_address()
+            .unwrap_or_else(|| self.sender())
+    }
+
     /// Returns the hash when the transaction is commited onchain.
     pub fn committed_hash(self) -> HashValue {
         Transaction::UserTransaction(self).hash()
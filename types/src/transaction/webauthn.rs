@@ -0,0 +1,208 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebAuthn assertions, the signatures produced by platform authenticators such as passkeys.
+//!
+//! An authenticator doesn't sign the transaction directly: it signs the concatenation of its
+//! `authenticatorData` and of the SHA2-256 hash of the `clientDataJSON` built by the client
+//! (e.g. the browser), whose `challenge` field is the base64url encoding of the signing message of
+//! the transaction. Both are therefore part of the assertion, and verified on chain.
+//!
+//! The relying party (i.e. the origin the passkey was created for) is not checked: the passkey
+//! public key, and hence the account, is specific to it already.
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature},
+    traits::{signing_message, Signature},
+};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+/// The `type` of the client data of assertions, as opposed to credential creations.
+const WEBAUTHN_GET_TYPE: &str = "webauthn.get";
+/// The length of the authenticator data: the relying party ID hash, the flags and the counter.
+/// Extensions may follow.
+const MIN_AUTHENTICATOR_DATA_LENGTH: usize = 37;
+/// The index of the flags in the authenticator data.
+const FLAGS_INDEX: usize = 32;
+/// The flag set by the authenticator if the user was present, e.g. touched the device.
+const USER_PRESENT_FLAG: u8 = 0x01;
+
+/// The fields of a WebAuthn `AuthenticatorAssertionResponse` needed to verify its signature.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PartialAuthenticatorAssertionResponse {
+    /// The signature, with its s-component normalized to the lower half of the curve order.
+    signature: Secp256r1EcdsaSignature,
+    #[serde(with = "serde_bytes")]
+    authenticator_data: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    client_data_json: Vec<u8>,
+}
+
+/// The fields of the `clientDataJSON` that are checked, others are ignored.
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+}
+
+impl PartialAuthenticatorAssertionResponse {
+    pub fn new(
+        signature: Secp256r1EcdsaSignature,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        Self {
+            signature,
+            authenticator_data,
+            client_data_json,
+        }
+    }
+
+    pub fn signature(&self) -> &Secp256r1EcdsaSignature {
+        &self.signature
+    }
+
+    pub fn authenticator_data(&self) -> &[u8] {
+        &self.authenticator_data
+    }
+
+    pub fn client_data_json(&self) -> &[u8] {
+        &self.client_data_json
+    }
+
+    /// Return Ok if the assertion is a signature of `message` by `public_key`, Err otherwise
+    pub fn verify<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        let client_data: CollectedClientData = serde_json::from_slice(&self.client_data_json)
+            .map_err(|e| anyhow!("Malformed WebAuthn client data: {}", e))?;
+        ensure!(
+            client_data.ty == WEBAUTHN_GET_TYPE,
+            "Unexpected WebAuthn client data type {}",
+            client_data.ty
+        );
+        let challenge = base64::decode_config(&client_data.challenge, base64::URL_SAFE_NO_PAD)
+            .map_err(|e| anyhow!("Malformed WebAuthn challenge: {}", e))?;
+        ensure!(
+            challenge == signing_message(message)?,
+            "The WebAuthn challenge is not the signed message"
+        );
+
+        ensure!(
+            self.authenticator_data.len() >= MIN_AUTHENTICATOR_DATA_LENGTH,
+            "WebAuthn authenticator data is too short"
+        );
+        ensure!(
+            self.authenticator_data[FLAGS_INDEX] & USER_PRESENT_FLAG != 0,
+            "The WebAuthn user presence flag is not set"
+        );
+
+        self.signature.verify_arbitrary_msg(
+            &signed_data(&self.authenticator_data, &self.client_data_json),
+            public_key,
+        )
+    }
+
+    /// Creates the assertion a software authenticator would produce for `message`, with the
+    /// relying party `example.com`.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn sign_for_testing<T: Serialize + CryptoHash>(
+        private_key: &aptos_crypto::secp256r1_ecdsa::Secp256r1EcdsaPrivateKey,
+        message: &T,
+    ) -> Self {
+        let mut authenticator_data = sha2::Sha256::digest(b"example.com").to_vec();
+        authenticator_data.push(USER_PRESENT_FLAG);
+        authenticator_data.extend_from_slice(&0u32.to_be_bytes());
+        let client_data_json = serde_json::json!({
+            "type": WEBAUTHN_GET_TYPE,
+            "challenge": base64::encode_config(
+                signing_message(message).unwrap(),
+                base64::URL_SAFE_NO_PAD
+            ),
+            "origin": "https://example.com",
+            "crossOrigin": false,
+        })
+        .to_string()
+        .into_bytes();
+        Self::sign_data_for_testing(private_key, authenticator_data, client_data_json)
+    }
+
+    /// Signs arbitrary authenticator data and client data, e.g. to build malformed assertions.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn sign_data_for_testing(
+        private_key: &aptos_crypto::secp256r1_ecdsa::Secp256r1EcdsaPrivateKey,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        use aptos_crypto::traits::SigningKey;
+
+        let signature = private_key
+            .sign_arbitrary_message(&signed_data(&authenticator_data, &client_data_json));
+        Self::new(signature, authenticator_data, client_data_json)
+    }
+}
+
+/// The data signed by the authenticator: `authenticatorData || SHA2-256(clientDataJSON)`.
+fn signed_data(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    let mut data = authenticator_data.to_vec();
+    data.extend_from_slice(&sha2::Sha256::digest(client_data_json));
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{
+        secp256r1_ecdsa::Secp256r1EcdsaPrivateKey, test_utils::TestAptosCrypto, PrivateKey, Uniform,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_verify_assertion() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let private_key = Secp256r1EcdsaPrivateKey::generate(&mut rng);
+        let public_key = private_key.public_key();
+        let message = TestAptosCrypto("transaction".to_string());
+        let assertion =
+            PartialAuthenticatorAssertionResponse::sign_for_testing(&private_key, &message);
+        assert!(assertion.verify(&message, &public_key).is_ok());
+
+        let other_message = TestAptosCrypto("other transaction".to_string());
+        assert!(assertion.verify(&other_message, &public_key).is_err());
+        let other_public_key = Secp256r1EcdsaPrivateKey::generate(&mut rng).public_key();
+        assert!(assertion.verify(&message, &other_public_key).is_err());
+
+        // Validly signed, but for a credential creation.
+        let client_data_json = String::from_utf8(assertion.client_data_json().to_vec())
+            .unwrap()
+            .replace(WEBAUTHN_GET_TYPE, "webauthn.create")
+            .into_bytes();
+        let created = PartialAuthenticatorAssertionResponse::sign_data_for_testing(
+            &private_key,
+            assertion.authenticator_data().to_vec(),
+            client_data_json,
+        );
+        assert!(created.verify(&message, &public_key).is_err());
+
+        // Validly signed, but without the user presence.
+        let mut authenticator_data = assertion.authenticator_data().to_vec();
+        authenticator_data[FLAGS_INDEX] &= !USER_PRESENT_FLAG;
+        let absent = PartialAuthenticatorAssertionResponse::sign_data_for_testing(
+            &private_key,
+            authenticator_data,
+            assertion.client_data_json().to_vec(),
+        );
+        assert!(absent.verify(&message, &public_key).is_err());
+
+        // The signed data is covered by the signature.
+        let mut tampered = assertion;
+        tampered.authenticator_data.push(0);
+        assert!(tampered.verify(&message, &public_key).is_err());
+    }
+}